@@ -1,20 +1,169 @@
 use bitcoin::{consensus, hex::HexToArrayError};
 use thiserror::Error;
+use titan_types::{ErrorCode, ErrorResponse};
 
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("reqwest error")]
     ReqwestError(#[from] reqwest::Error),
 
+    /// An error response without a JSON body, e.g. from a proxy in front of
+    /// the server.
     #[error("titan error with status {0}: {1}")]
     TitanError(reqwest::StatusCode, String),
 
+    #[error("titan error: {0}")]
+    TxNotFound(ErrorResponse),
+
+    #[error("titan error: {0}")]
+    BlockNotFound(ErrorResponse),
+
+    #[error("titan error: {0}")]
+    OutputNotFound(ErrorResponse),
+
+    #[error("titan error: {0}")]
+    RuneNotFound(ErrorResponse),
+
+    #[error("titan error: {0}")]
+    InscriptionNotFound(ErrorResponse),
+
+    #[error("titan error: {0}")]
+    SubscriptionNotFound(ErrorResponse),
+
+    /// The node rejected a broadcast transaction, with its reason in
+    /// `details`.
+    #[error("titan error: {0}")]
+    TxRejected(ErrorResponse),
+
+    /// The server runs without `--index-addresses`.
+    #[error("titan error: {0}")]
+    AddressIndexDisabled(ErrorResponse),
+
+    #[error("titan error: {0}")]
+    SubscriptionsDisabled(ErrorResponse),
+
+    /// The server can't reach bitcoind. Worth retrying later.
+    #[error("titan error: {0}")]
+    RpcUnavailable(ErrorResponse),
+
+    /// bitcoind answered the server with an error.
+    #[error("titan error: {0}")]
+    RpcError(ErrorResponse),
+
+    /// Any other error response, such as invalid input. See `code()`.
+    #[error("titan error: {0}")]
+    Api(ErrorResponse),
+
     #[error("serde error")]
     SerdeError(#[from] serde_json::Error),
 
+    #[error("borsh error: {0}")]
+    BorshError(#[from] std::io::Error),
+
+    /// A response that decoded but holds something other than expected.
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+
     #[error("hex error: {0}")]
     HexToArrayError(#[from] HexToArrayError),
 
     #[error("bitcoin consensus error: {0}")]
     BitcoinConsensusError(#[from] consensus::encode::Error),
 }
+
+impl Error {
+    pub(crate) fn from_response(status: reqwest::StatusCode, body: String) -> Self {
+        let Ok(error) = serde_json::from_str::<ErrorResponse>(&body) else {
+            return Self::TitanError(status, body);
+        };
+
+        match error.code {
+            ErrorCode::TxNotFound => Self::TxNotFound(error),
+            ErrorCode::BlockNotFound => Self::BlockNotFound(error),
+            ErrorCode::OutputNotFound => Self::OutputNotFound(error),
+            ErrorCode::RuneNotFound => Self::RuneNotFound(error),
+            ErrorCode::InscriptionNotFound => Self::InscriptionNotFound(error),
+            ErrorCode::SubscriptionNotFound => Self::SubscriptionNotFound(error),
+            ErrorCode::TxRejected => Self::TxRejected(error),
+            ErrorCode::AddressIndexDisabled => Self::AddressIndexDisabled(error),
+            ErrorCode::SubscriptionsDisabled => Self::SubscriptionsDisabled(error),
+            ErrorCode::RpcUnavailable => Self::RpcUnavailable(error),
+            ErrorCode::RpcError => Self::RpcError(error),
+            _ => Self::Api(error),
+        }
+    }
+
+    /// The error response the server sent, if any.
+    pub fn response(&self) -> Option<&ErrorResponse> {
+        match self {
+            Self::TxNotFound(error)
+            | Self::BlockNotFound(error)
+            | Self::OutputNotFound(error)
+            | Self::RuneNotFound(error)
+            | Self::InscriptionNotFound(error)
+            | Self::SubscriptionNotFound(error)
+            | Self::TxRejected(error)
+            | Self::AddressIndexDisabled(error)
+            | Self::SubscriptionsDisabled(error)
+            | Self::RpcUnavailable(error)
+            | Self::RpcError(error)
+            | Self::Api(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// The server's error code, if it sent one.
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.response().map(|error| error.code)
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::TitanError(status, _) => *status == reqwest::StatusCode::NOT_FOUND,
+            error => error.code().is_some_and(|code| code.is_not_found()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_code(code: ErrorCode) -> Error {
+        let body = serde_json::to_string(&ErrorResponse::new(code, "message")).unwrap();
+        Error::from_response(code.status(), body)
+    }
+
+    #[test]
+    fn codes_map_to_variants() {
+        assert!(matches!(
+            from_code(ErrorCode::TxNotFound),
+            Error::TxNotFound(_)
+        ));
+        assert!(matches!(
+            from_code(ErrorCode::AddressIndexDisabled),
+            Error::AddressIndexDisabled(_)
+        ));
+        assert!(matches!(
+            from_code(ErrorCode::RpcUnavailable),
+            Error::RpcUnavailable(_)
+        ));
+
+        let invalid = from_code(ErrorCode::InvalidOutpoint);
+        assert!(matches!(invalid, Error::Api(_)));
+        assert_eq!(invalid.code(), Some(ErrorCode::InvalidOutpoint));
+        assert!(!invalid.is_not_found());
+
+        let not_found = from_code(ErrorCode::RuneNotFound);
+        assert_eq!(not_found.response().unwrap().message, "message");
+        assert!(not_found.is_not_found());
+    }
+
+    #[test]
+    fn bodies_without_json_keep_the_status() {
+        let error = Error::from_response(reqwest::StatusCode::NOT_FOUND, "<html>".into());
+        assert!(matches!(error, Error::TitanError(_, _)));
+        assert_eq!(error.code(), None);
+        assert!(error.is_not_found());
+    }
+}
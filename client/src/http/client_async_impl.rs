@@ -1,11 +1,22 @@
 use bitcoin::{OutPoint, Txid};
-use reqwest::{header::HeaderMap, Client as AsyncReqwestClient};
+use borsh::BorshDeserialize;
+use futures::Stream;
+use ordinals::RuneId;
+use reqwest::{
+    header::{HeaderMap, ACCEPT},
+    Client as AsyncReqwestClient, RequestBuilder,
+};
+use serde::{de::DeserializeOwned, Serialize};
 use std::{collections::HashMap, str::FromStr};
 use titan_types::*;
 
 use crate::Error;
 
-use super::TitanApiAsync;
+use super::{
+    encoding::{decode, parse_items, parse_keys, parse_page, Encoding},
+    pages::paginate,
+    TitanApiAsync,
+};
 
 #[derive(Clone)]
 pub struct AsyncClient {
@@ -13,6 +24,8 @@ pub struct AsyncClient {
     http_client: AsyncReqwestClient,
     /// The base URL for all endpoints (e.g. http://localhost:3030).
     base_url: String,
+    /// The encoding asked for in data requests.
+    encoding: Encoding,
 }
 
 impl AsyncClient {
@@ -21,16 +34,99 @@ impl AsyncClient {
         Self {
             http_client: AsyncReqwestClient::new(),
             base_url: base_url.trim_end_matches('/').to_string(),
+            encoding: Encoding::default(),
         }
     }
 
+    /// Asks for Borsh encoded responses instead of JSON. They're smaller and
+    /// cheaper to decode. Errors are still JSON.
+    pub fn with_borsh(mut self) -> Self {
+        self.encoding = Encoding::Borsh;
+        self
+    }
+
+    /// Streams every rune, newest first, fetching pages as they're consumed.
+    pub fn stream_runes(&self) -> impl Stream<Item = Result<RuneResponse, Error>> + '_ {
+        paginate(move |pagination| async move { self.get_page("/runes", Some(&pagination)).await })
+    }
+
+    /// Streams every transaction of a given `rune`, newest first.
+    pub fn stream_rune_transactions(
+        &self,
+        rune: &query::Rune,
+    ) -> impl Stream<Item = Result<Txid, Error>> + '_ {
+        let path = format!("/rune/{}/transactions", rune);
+        paginate(move |pagination| {
+            let path = path.clone();
+            async move { parse_page(self.get_page(&path, Some(&pagination)).await?) }
+        })
+    }
+
+    /// Streams the whole activity feed of a given `rune`, newest first.
+    pub fn stream_rune_activity(
+        &self,
+        rune: &query::Rune,
+    ) -> impl Stream<Item = Result<RuneActivity, Error>> + '_ {
+        self.stream_pages(format!("/rune/{}/activity", rune))
+    }
+
+    fn stream_pages<T: DeserializeOwned + BorshDeserialize + 'static>(
+        &self,
+        path: String,
+    ) -> impl Stream<Item = Result<T, Error>> + '_ {
+        paginate(move |pagination| {
+            let path = path.clone();
+            async move { self.get_page(&path, Some(&pagination)).await }
+        })
+    }
+
+    async fn get_page<T: DeserializeOwned + BorshDeserialize>(
+        &self,
+        path: &str,
+        pagination: Option<&Pagination>,
+    ) -> Result<PaginationResponse<T>, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut req = self.http_client.get(&url);
+        if let Some(p) = pagination {
+            req = req.query(p);
+        }
+        self.fetch(req).await
+    }
+
+    async fn get<T: DeserializeOwned + BorshDeserialize>(&self, path: &str) -> Result<T, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        self.fetch(self.http_client.get(&url)).await
+    }
+
+    /// Sends a data request, asking for the client's encoding.
+    async fn fetch<T: DeserializeOwned + BorshDeserialize>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, Error> {
+        let response = request
+            .header(ACCEPT, self.encoding.accept())
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(Error::from_response(
+                response.status(),
+                response.text().await?,
+            ));
+        }
+        let headers = response.headers().clone();
+        decode(&headers, &response.bytes().await?)
+    }
+
     async fn call_text(&self, path: &str) -> Result<String, Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.get(&url).send().await?;
         if response.status().is_success() {
             Ok(response.text().await?)
         } else {
-            Err(Error::TitanError(response.status(), response.text().await?))
+            Err(Error::from_response(
+                response.status(),
+                response.text().await?,
+            ))
         }
     }
 
@@ -40,7 +136,10 @@ impl AsyncClient {
         if response.status().is_success() {
             Ok(response.bytes().await?.to_vec())
         } else {
-            Err(Error::TitanError(response.status(), response.text().await?))
+            Err(Error::from_response(
+                response.status(),
+                response.text().await?,
+            ))
         }
     }
 
@@ -50,17 +149,32 @@ impl AsyncClient {
         if response.status().is_success() {
             Ok(response.text().await?)
         } else {
-            Err(Error::TitanError(response.status(), response.text().await?))
+            Err(Error::from_response(
+                response.status(),
+                response.text().await?,
+            ))
         }
     }
 
+    async fn post_json<T: DeserializeOwned + BorshDeserialize, B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        self.fetch(self.http_client.post(&url).json(body)).await
+    }
+
     async fn delete(&self, path: &str) -> Result<(), Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.delete(&url).send().await?;
         if response.status().is_success() {
             Ok(())
         } else {
-            Err(Error::TitanError(response.status(), response.text().await?))
+            Err(Error::from_response(
+                response.status(),
+                response.text().await?,
+            ))
         }
     }
 }
@@ -68,18 +182,15 @@ impl AsyncClient {
 #[async_trait::async_trait]
 impl TitanApiAsync for AsyncClient {
     async fn get_status(&self) -> Result<Status, Error> {
-        let text = self.call_text("/status").await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get("/status").await
     }
 
     async fn get_tip(&self) -> Result<BlockTip, Error> {
-        let text = self.call_text("/tip").await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get("/tip").await
     }
 
     async fn get_block(&self, query: &query::Block) -> Result<Block, Error> {
-        let text = self.call_text(&format!("/block/{}", query)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/block/{}", query)).await
     }
 
     async fn get_block_hash_by_height(&self, height: u64) -> Result<String, Error> {
@@ -87,18 +198,43 @@ impl TitanApiAsync for AsyncClient {
     }
 
     async fn get_block_txids(&self, query: &query::Block) -> Result<Vec<String>, Error> {
-        let text = self.call_text(&format!("/block/{}/txids", query)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/block/{}/txids", query)).await
+    }
+
+    async fn get_block_stats(&self, query: &query::Block) -> Result<BlockStats, Error> {
+        self.get(&format!("/block/{}/stats", query)).await
+    }
+
+    async fn get_block_rune_activity(
+        &self,
+        query: &query::Block,
+    ) -> Result<Vec<BlockRuneActivity>, Error> {
+        self.get(&format!("/block/{}/runes", query)).await
+    }
+
+    async fn get_blocks(&self, query: &BlocksQuery) -> Result<Vec<BlockStats>, Error> {
+        let url = format!("{}/blocks", self.base_url);
+        self.fetch(self.http_client.get(&url).query(query)).await
     }
 
     async fn get_address(&self, address: &str) -> Result<AddressData, Error> {
-        let text = self.call_text(&format!("/address/{}", address)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/address/{}", address)).await
+    }
+
+    async fn get_address_with_risk(&self, address: &str) -> Result<AddressData, Error> {
+        self.get(&format!("/address/{}?risk=true", address)).await
+    }
+
+    async fn get_wallet(&self, query: &WalletQuery) -> Result<WalletData, Error> {
+        self.post_json("/wallet", query).await
     }
 
     async fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error> {
-        let text = self.call_text(&format!("/tx/{}", txid)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/tx/{}", txid)).await
+    }
+
+    async fn get_transaction_detail(&self, txid: &Txid) -> Result<TransactionDetail, Error> {
+        self.get(&format!("/tx/{}/detail", txid)).await
     }
 
     async fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error> {
@@ -110,8 +246,31 @@ impl TitanApiAsync for AsyncClient {
     }
 
     async fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error> {
-        let text = self.call_text(&format!("/tx/{}/status", txid)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/tx/{}/status", txid)).await
+    }
+
+    async fn get_transaction_outspends(&self, txid: &Txid) -> Result<Vec<Outspend>, Error> {
+        self.get(&format!("/tx/{}/outspends", txid)).await
+    }
+
+    async fn get_transaction_statuses(
+        &self,
+        txids: &[Txid],
+    ) -> Result<HashMap<Txid, Option<TransactionStatus>>, Error> {
+        parse_keys(self.post_json("/txs/status", txids).await?)
+    }
+
+    async fn get_transaction_bump_info(
+        &self,
+        txid: &Txid,
+        query: &BumpInfoQuery,
+    ) -> Result<BumpInfo, Error> {
+        let url = format!("{}/tx/{}/bump-info", self.base_url, txid);
+        self.fetch(self.http_client.get(&url).query(query)).await
+    }
+
+    async fn get_transaction_replacement(&self, txid: &Txid) -> Result<TxReplacement, Error> {
+        self.get(&format!("/tx/{}/replacement", txid)).await
     }
 
     async fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error> {
@@ -120,8 +279,22 @@ impl TitanApiAsync for AsyncClient {
     }
 
     async fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutEntry, Error> {
-        let text = self.call_text(&format!("/output/{}", outpoint)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/output/{}", outpoint)).await
+    }
+
+    async fn get_output_risk(&self, outpoint: &OutPoint) -> Result<OutputRisk, Error> {
+        self.get(&format!("/output/{}/risk", outpoint)).await
+    }
+
+    async fn get_output_spender(&self, outpoint: &OutPoint) -> Result<Outspend, Error> {
+        self.get(&format!("/output/{}/spender", outpoint)).await
+    }
+
+    async fn get_outputs(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Option<TxOutEntry>>, Error> {
+        parse_keys(self.post_json("/outputs", outpoints).await?)
     }
 
     async fn get_inscription(
@@ -133,7 +306,7 @@ impl TitanApiAsync for AsyncClient {
         let status = resp.status();
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
-            return Err(Error::TitanError(status, body));
+            return Err(Error::from_response(status, body));
         }
         let headers = resp.headers().clone();
         let bytes = resp.bytes().await?.to_vec();
@@ -144,17 +317,31 @@ impl TitanApiAsync for AsyncClient {
         &self,
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<RuneResponse>, Error> {
-        let mut path = "/runes".to_string();
+        self.get_page("/runes", pagination.as_ref()).await
+    }
+
+    async fn search_runes(
+        &self,
+        search: &RuneSearch,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneResponse>, Error> {
+        let url = format!("{}/runes/search", self.base_url);
+        let mut req = self.http_client.get(&url).query(search);
         if let Some(p) = pagination {
-            path = format!("{}?skip={}&limit={}", path, p.skip, p.limit);
+            req = req.query(&[("skip", p.skip), ("limit", p.limit)]);
         }
-        let text = self.call_text(&path).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.fetch(req).await
     }
 
     async fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error> {
-        let text = self.call_text(&format!("/rune/{}", rune)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/rune/{}", rune)).await
+    }
+
+    async fn get_runes_by_ids(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, Option<RuneResponse>>, Error> {
+        parse_keys(self.post_json("/runes/batch", rune_ids).await?)
     }
 
     async fn get_rune_transactions(
@@ -162,54 +349,75 @@ impl TitanApiAsync for AsyncClient {
         rune: &query::Rune,
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<Txid>, Error> {
-        let mut path = format!("/rune/{}/transactions", rune);
-        if let Some(p) = pagination {
-            path = format!("{}?skip={}&limit={}", path, p.skip, p.limit);
-        }
-        let text = self.call_text(&path).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        let path = format!("/rune/{}/transactions", rune);
+        parse_page(self.get_page(&path, pagination.as_ref()).await?)
+    }
+
+    async fn get_rune_activity(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneActivity>, Error> {
+        let path = format!("/rune/{}/activity", rune);
+        self.get_page(&path, pagination.as_ref()).await
+    }
+
+    async fn get_rune_mints(
+        &self,
+        rune: &query::Rune,
+        range: &RuneMintsQuery,
+    ) -> Result<RuneMintsResponse, Error> {
+        let url = format!("{}/rune/{}/mints", self.base_url, rune);
+        self.fetch(self.http_client.get(&url).query(range)).await
+    }
+
+    async fn get_rune_supply(
+        &self,
+        rune: &query::Rune,
+        range: &RuneSupplyQuery,
+    ) -> Result<RuneSupplyResponse, Error> {
+        let url = format!("{}/rune/{}/supply", self.base_url, rune);
+        self.fetch(self.http_client.get(&url).query(range)).await
     }
 
     async fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error> {
-        let text = self.call_text("/mempool/txids").await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        parse_items(self.get("/mempool/txids").await?)
     }
 
     async fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry, Error> {
-        let text = self.call_text(&format!("/mempool/entry/{}", txid)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/mempool/entry/{}", txid)).await
     }
 
     async fn get_mempool_entries(
         &self,
         txids: &[Txid],
     ) -> Result<HashMap<Txid, Option<MempoolEntry>>, Error> {
-        let text = self
-            .post_text("/mempool/entries", serde_json::to_string(txids)?)
-            .await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        parse_keys(self.post_json("/mempool/entries", txids).await?)
     }
 
     async fn get_all_mempool_entries(&self) -> Result<HashMap<Txid, MempoolEntry>, Error> {
-        let text = self.call_text("/mempool/entries/all").await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        parse_keys(self.get("/mempool/entries/all").await?)
+    }
+
+    async fn get_mempool_fees(&self) -> Result<MempoolFees, Error> {
+        self.get("/mempool/fees").await
+    }
+
+    async fn get_mempool_blocks(&self, query: &MempoolBlocksQuery) -> Result<MempoolBlocks, Error> {
+        let url = format!("{}/mempool/blocks", self.base_url);
+        self.fetch(self.http_client.get(&url).query(query)).await
     }
 
     async fn get_subscription(&self, id: &str) -> Result<Subscription, Error> {
-        let text = self.call_text(&format!("/subscription/{}", id)).await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/subscription/{}", id)).await
     }
 
     async fn list_subscriptions(&self) -> Result<Vec<Subscription>, Error> {
-        let text = self.call_text("/subscriptions").await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get("/subscriptions").await
     }
 
     async fn add_subscription(&self, subscription: &Subscription) -> Result<Subscription, Error> {
-        let text = self
-            .post_text("/subscription", serde_json::to_string(subscription)?)
-            .await?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.post_json("/subscription", subscription).await
     }
 
     async fn delete_subscription(&self, id: &str) -> Result<(), Error> {
@@ -1,11 +1,21 @@
 use bitcoin::{OutPoint, Txid};
-use reqwest::{blocking::Client as BlockingReqwestClient, header::HeaderMap};
+use borsh::BorshDeserialize;
+use ordinals::RuneId;
+use reqwest::{
+    blocking::{Client as BlockingReqwestClient, RequestBuilder},
+    header::{HeaderMap, ACCEPT},
+};
+use serde::{de::DeserializeOwned, Serialize};
 use std::{collections::HashMap, str::FromStr};
 use titan_types::*;
 
 use crate::Error;
 
-use super::TitanApiSync;
+use super::{
+    encoding::{decode, parse_items, parse_keys, parse_page, Encoding},
+    pages::Pages,
+    TitanApiSync,
+};
 
 #[derive(Clone)]
 pub struct SyncClient {
@@ -13,6 +23,8 @@ pub struct SyncClient {
     http_client: BlockingReqwestClient,
     /// The base URL for all endpoints (e.g. http://localhost:3030).
     base_url: String,
+    /// The encoding asked for in data requests.
+    encoding: Encoding,
 }
 
 impl SyncClient {
@@ -21,16 +33,84 @@ impl SyncClient {
         Self {
             http_client: BlockingReqwestClient::new(),
             base_url: base_url.trim_end_matches('/').to_string(),
+            encoding: Encoding::default(),
         }
     }
 
+    /// Asks for Borsh encoded responses instead of JSON. They're smaller and
+    /// cheaper to decode. Errors are still JSON.
+    pub fn with_borsh(mut self) -> Self {
+        self.encoding = Encoding::Borsh;
+        self
+    }
+
+    /// Iterates over every rune, newest first, fetching pages as they're consumed.
+    pub fn iter_runes(&self) -> impl Iterator<Item = Result<RuneResponse, Error>> + '_ {
+        self.iter_pages("/runes".to_string())
+    }
+
+    /// Iterates over every transaction of a given rune, newest first.
+    pub fn iter_rune_transactions(
+        &self,
+        rune: &query::Rune,
+    ) -> impl Iterator<Item = Result<Txid, Error>> + '_ {
+        let path = format!("/rune/{}/transactions", rune);
+        Pages::new(move |pagination| parse_page(self.get_page(&path, Some(&pagination))?))
+    }
+
+    /// Iterates over the whole activity feed of a given rune, newest first.
+    pub fn iter_rune_activity(
+        &self,
+        rune: &query::Rune,
+    ) -> impl Iterator<Item = Result<RuneActivity, Error>> + '_ {
+        self.iter_pages(format!("/rune/{}/activity", rune))
+    }
+
+    fn iter_pages<T: DeserializeOwned + BorshDeserialize + 'static>(
+        &self,
+        path: String,
+    ) -> impl Iterator<Item = Result<T, Error>> + '_ {
+        Pages::new(move |pagination| self.get_page(&path, Some(&pagination)))
+    }
+
+    fn get_page<T: DeserializeOwned + BorshDeserialize>(
+        &self,
+        path: &str,
+        pagination: Option<&Pagination>,
+    ) -> Result<PaginationResponse<T>, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut req = self.http_client.get(&url);
+        if let Some(p) = pagination {
+            req = req.query(p);
+        }
+        self.fetch(req)
+    }
+
+    fn get<T: DeserializeOwned + BorshDeserialize>(&self, path: &str) -> Result<T, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        self.fetch(self.http_client.get(&url))
+    }
+
+    /// Sends a data request, asking for the client's encoding.
+    fn fetch<T: DeserializeOwned + BorshDeserialize>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, Error> {
+        let response = request.header(ACCEPT, self.encoding.accept()).send()?;
+        if !response.status().is_success() {
+            return Err(Error::from_response(response.status(), response.text()?));
+        }
+        let headers = response.headers().clone();
+        decode(&headers, &response.bytes()?)
+    }
+
     fn call_text(&self, path: &str) -> Result<String, Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.get(&url).send()?;
         if response.status().is_success() {
             Ok(response.text()?)
         } else {
-            Err(Error::TitanError(response.status(), response.text()?))
+            Err(Error::from_response(response.status(), response.text()?))
         }
     }
 
@@ -40,7 +120,7 @@ impl SyncClient {
         if response.status().is_success() {
             Ok(response.bytes()?.to_vec())
         } else {
-            Err(Error::TitanError(response.status(), response.text()?))
+            Err(Error::from_response(response.status(), response.text()?))
         }
     }
 
@@ -52,35 +132,41 @@ impl SyncClient {
         if response.status().is_success() {
             Ok(response.text()?)
         } else {
-            Err(Error::TitanError(response.status(), response.text()?))
+            Err(Error::from_response(response.status(), response.text()?))
         }
     }
 
+    fn post_json<T: DeserializeOwned + BorshDeserialize, B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        self.fetch(self.http_client.post(&url).json(body))
+    }
+
     fn delete(&self, path: &str) -> Result<(), Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.delete(&url).send()?;
         if response.status().is_success() {
             Ok(())
         } else {
-            Err(Error::TitanError(response.status(), response.text()?))
+            Err(Error::from_response(response.status(), response.text()?))
         }
     }
 }
 
 impl TitanApiSync for SyncClient {
     fn get_status(&self) -> Result<Status, Error> {
-        let text = self.call_text("/status")?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get("/status")
     }
 
     fn get_tip(&self) -> Result<BlockTip, Error> {
-        let text = self.call_text("/tip")?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get("/tip")
     }
 
     fn get_block(&self, query: &query::Block) -> Result<Block, Error> {
-        let text = self.call_text(&format!("/block/{}", query))?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/block/{}", query))
     }
 
     fn get_block_hash_by_height(&self, height: u64) -> Result<String, Error> {
@@ -88,18 +174,43 @@ impl TitanApiSync for SyncClient {
     }
 
     fn get_block_txids(&self, query: &query::Block) -> Result<Vec<String>, Error> {
-        let text = self.call_text(&format!("/block/{}/txids", query))?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/block/{}/txids", query))
+    }
+
+    fn get_block_stats(&self, query: &query::Block) -> Result<BlockStats, Error> {
+        self.get(&format!("/block/{}/stats", query))
+    }
+
+    fn get_block_rune_activity(
+        &self,
+        query: &query::Block,
+    ) -> Result<Vec<BlockRuneActivity>, Error> {
+        self.get(&format!("/block/{}/runes", query))
+    }
+
+    fn get_blocks(&self, query: &BlocksQuery) -> Result<Vec<BlockStats>, Error> {
+        let url = format!("{}/blocks", self.base_url);
+        self.fetch(self.http_client.get(&url).query(query))
     }
 
     fn get_address(&self, address: &str) -> Result<AddressData, Error> {
-        let text = self.call_text(&format!("/address/{}", address))?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/address/{}", address))
+    }
+
+    fn get_address_with_risk(&self, address: &str) -> Result<AddressData, Error> {
+        self.get(&format!("/address/{}?risk=true", address))
+    }
+
+    fn get_wallet(&self, query: &WalletQuery) -> Result<WalletData, Error> {
+        self.post_json("/wallet", query)
     }
 
     fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error> {
-        let text = self.call_text(&format!("/tx/{}", txid))?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/tx/{}", txid))
+    }
+
+    fn get_transaction_detail(&self, txid: &Txid) -> Result<TransactionDetail, Error> {
+        self.get(&format!("/tx/{}/detail", txid))
     }
 
     fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error> {
@@ -111,8 +222,31 @@ impl TitanApiSync for SyncClient {
     }
 
     fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error> {
-        let text = self.call_text(&format!("/tx/{}/status", txid))?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/tx/{}/status", txid))
+    }
+
+    fn get_transaction_outspends(&self, txid: &Txid) -> Result<Vec<Outspend>, Error> {
+        self.get(&format!("/tx/{}/outspends", txid))
+    }
+
+    fn get_transaction_statuses(
+        &self,
+        txids: &[Txid],
+    ) -> Result<HashMap<Txid, Option<TransactionStatus>>, Error> {
+        parse_keys(self.post_json("/txs/status", txids)?)
+    }
+
+    fn get_transaction_bump_info(
+        &self,
+        txid: &Txid,
+        query: &BumpInfoQuery,
+    ) -> Result<BumpInfo, Error> {
+        let url = format!("{}/tx/{}/bump-info", self.base_url, txid);
+        self.fetch(self.http_client.get(&url).query(query))
+    }
+
+    fn get_transaction_replacement(&self, txid: &Txid) -> Result<TxReplacement, Error> {
+        self.get(&format!("/tx/{}/replacement", txid))
     }
 
     fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error> {
@@ -121,8 +255,22 @@ impl TitanApiSync for SyncClient {
     }
 
     fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutEntry, Error> {
-        let text = self.call_text(&format!("/output/{}", outpoint))?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/output/{}", outpoint))
+    }
+
+    fn get_output_risk(&self, outpoint: &OutPoint) -> Result<OutputRisk, Error> {
+        self.get(&format!("/output/{}/risk", outpoint))
+    }
+
+    fn get_output_spender(&self, outpoint: &OutPoint) -> Result<Outspend, Error> {
+        self.get(&format!("/output/{}/spender", outpoint))
+    }
+
+    fn get_outputs(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Option<TxOutEntry>>, Error> {
+        parse_keys(self.post_json("/outputs", outpoints)?)
     }
 
     fn get_inscription(
@@ -134,7 +282,7 @@ impl TitanApiSync for SyncClient {
         let status = resp.status();
         if !status.is_success() {
             let body = resp.text().unwrap_or_default();
-            return Err(Error::TitanError(status, body));
+            return Err(Error::from_response(status, body));
         }
         let headers = resp.headers().clone();
         let bytes = resp.bytes()?.to_vec();
@@ -145,19 +293,31 @@ impl TitanApiSync for SyncClient {
         &self,
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<RuneResponse>, Error> {
-        let url = format!("{}/runes", self.base_url);
-        let mut req = self.http_client.get(&url);
+        self.get_page("/runes", pagination.as_ref())
+    }
+
+    fn search_runes(
+        &self,
+        search: &RuneSearch,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneResponse>, Error> {
+        let url = format!("{}/runes/search", self.base_url);
+        let mut req = self.http_client.get(&url).query(search);
         if let Some(ref p) = pagination {
             req = req.query(&[("skip", p.skip), ("limit", p.limit)]);
         }
-        let resp = req.send()?;
-        Ok(resp.json()?)
+        self.fetch(req)
     }
 
     fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error> {
-        let url = format!("{}/rune/{}", self.base_url, rune);
-        let resp = self.http_client.get(&url).send()?;
-        Ok(resp.json()?)
+        self.get(&format!("/rune/{}", rune))
+    }
+
+    fn get_runes_by_ids(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, Option<RuneResponse>>, Error> {
+        parse_keys(self.post_json("/runes/batch", rune_ids)?)
     }
 
     fn get_rune_transactions(
@@ -165,55 +325,75 @@ impl TitanApiSync for SyncClient {
         rune: &query::Rune,
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<Txid>, Error> {
-        let url = format!("{}/rune/{}/transactions", self.base_url, rune);
-        let mut req = self.http_client.get(&url);
-        if let Some(ref p) = pagination {
-            req = req.query(&[("skip", p.skip), ("limit", p.limit)]);
-        }
-        let resp = req.send()?;
-        Ok(resp.json()?)
+        let path = format!("/rune/{}/transactions", rune);
+        parse_page(self.get_page(&path, pagination.as_ref())?)
+    }
+
+    fn get_rune_activity(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneActivity>, Error> {
+        let path = format!("/rune/{}/activity", rune);
+        self.get_page(&path, pagination.as_ref())
+    }
+
+    fn get_rune_mints(
+        &self,
+        rune: &query::Rune,
+        range: &RuneMintsQuery,
+    ) -> Result<RuneMintsResponse, Error> {
+        let url = format!("{}/rune/{}/mints", self.base_url, rune);
+        self.fetch(self.http_client.get(&url).query(range))
+    }
+
+    fn get_rune_supply(
+        &self,
+        rune: &query::Rune,
+        range: &RuneSupplyQuery,
+    ) -> Result<RuneSupplyResponse, Error> {
+        let url = format!("{}/rune/{}/supply", self.base_url, rune);
+        self.fetch(self.http_client.get(&url).query(range))
     }
 
     fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error> {
-        let text = self.call_text("/mempool/txids")?;
-        serde_json::from_str(&text).map_err(Error::from)
+        parse_items(self.get("/mempool/txids")?)
     }
 
     fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry, Error> {
-        let text = self.call_text(&format!("/mempool/entry/{}", txid))?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/mempool/entry/{}", txid))
     }
 
     fn get_mempool_entries(
         &self,
         txids: &[Txid],
     ) -> Result<HashMap<Txid, Option<MempoolEntry>>, Error> {
-        let url = format!("{}/mempool/entries", self.base_url);
-        let body = serde_json::to_string(txids)?;
+        parse_keys(self.post_json("/mempool/entries", txids)?)
+    }
 
-        let response = self.post_text(&url, body)?;
+    fn get_all_mempool_entries(&self) -> Result<HashMap<Txid, MempoolEntry>, Error> {
+        parse_keys(self.get("/mempool/entries/all")?)
+    }
 
-        Ok(serde_json::from_str(&response).map_err(Error::from)?)
+    fn get_mempool_fees(&self) -> Result<MempoolFees, Error> {
+        self.get("/mempool/fees")
     }
 
-    fn get_all_mempool_entries(&self) -> Result<HashMap<Txid, MempoolEntry>, Error> {
-        let text = self.call_text("/mempool/entries/all")?;
-        serde_json::from_str(&text).map_err(Error::from)
+    fn get_mempool_blocks(&self, query: &MempoolBlocksQuery) -> Result<MempoolBlocks, Error> {
+        let url = format!("{}/mempool/blocks", self.base_url);
+        self.fetch(self.http_client.get(&url).query(query))
     }
 
     fn get_subscription(&self, id: &str) -> Result<Subscription, Error> {
-        let text = self.call_text(&format!("/subscription/{}", id))?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get(&format!("/subscription/{}", id))
     }
 
     fn list_subscriptions(&self) -> Result<Vec<Subscription>, Error> {
-        let text = self.call_text("/subscriptions")?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.get("/subscriptions")
     }
 
     fn add_subscription(&self, subscription: &Subscription) -> Result<Subscription, Error> {
-        let text = self.post_text("/subscription", serde_json::to_string(subscription)?)?;
-        serde_json::from_str(&text).map_err(Error::from)
+        self.post_json("/subscription", subscription)
     }
 
     fn delete_subscription(&self, id: &str) -> Result<(), Error> {
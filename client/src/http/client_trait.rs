@@ -3,11 +3,15 @@ use std::collections::HashMap;
 use crate::Error;
 use async_trait::async_trait;
 use bitcoin::{OutPoint, Txid};
+use ordinals::RuneId;
 use reqwest::header::HeaderMap;
 use titan_types::{
-    query, AddressData, Block, BlockTip, InscriptionId, MempoolEntry, Pagination,
-    PaginationResponse, RuneResponse, Status, Subscription, Transaction, TransactionStatus,
-    TxOutEntry,
+    query, AddressData, Block, BlockRuneActivity, BlockStats, BlockTip, BlocksQuery, BumpInfo,
+    BumpInfoQuery, InscriptionId, MempoolBlocks, MempoolBlocksQuery, MempoolEntry, MempoolFees,
+    OutputRisk, Outspend, Pagination, PaginationResponse, RuneActivity, RuneMintsQuery,
+    RuneMintsResponse, RuneResponse, RuneSearch, RuneSupplyQuery, RuneSupplyResponse, Status,
+    Subscription, Transaction, TransactionDetail, TransactionStatus, TxOutEntry, TxReplacement,
+    WalletData, WalletQuery,
 };
 
 /// Trait for all **async** methods.
@@ -28,12 +32,34 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Returns a list of transaction IDs in a particular block.
     async fn get_block_txids(&self, query: &query::Block) -> Result<Vec<String>, Error>;
 
+    /// Returns the stats of a block.
+    async fn get_block_stats(&self, query: &query::Block) -> Result<BlockStats, Error>;
+
+    /// Returns every rune etching, mint, burn and transfer in a block.
+    async fn get_block_rune_activity(
+        &self,
+        query: &query::Block,
+    ) -> Result<Vec<BlockRuneActivity>, Error>;
+
+    /// Returns the stats of blocks, newest first.
+    async fn get_blocks(&self, query: &BlocksQuery) -> Result<Vec<BlockStats>, Error>;
+
     /// Fetches address data (balance, transactions, etc.).
     async fn get_address(&self, address: &str) -> Result<AddressData, Error>;
 
+    /// Fetches address data with a risk report on each unconfirmed output.
+    async fn get_address_with_risk(&self, address: &str) -> Result<AddressData, Error>;
+
+    /// Scans the addresses of an xpub or descriptor for outputs.
+    async fn get_wallet(&self, query: &WalletQuery) -> Result<WalletData, Error>;
+
     /// Returns a higher-level transaction object (including Runes info) by `txid`.
     async fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error>;
 
+    /// Returns a transaction with the outputs its inputs spend, its fee and
+    /// the runes it moves.
+    async fn get_transaction_detail(&self, txid: &Txid) -> Result<TransactionDetail, Error>;
+
     /// Returns raw transaction bytes (binary).
     async fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error>;
 
@@ -43,12 +69,43 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Returns the status of a transaction by `txid`.
     async fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error>;
 
+    /// Returns whether and by what each output of a transaction is spent.
+    async fn get_transaction_outspends(&self, txid: &Txid) -> Result<Vec<Outspend>, Error>;
+
+    /// Returns the status of each of `txids`, `None` for unknown transactions.
+    async fn get_transaction_statuses(
+        &self,
+        txids: &[Txid],
+    ) -> Result<HashMap<Txid, Option<TransactionStatus>>, Error>;
+
+    /// Returns what it takes to bump a mempool transaction with RBF or CPFP.
+    async fn get_transaction_bump_info(
+        &self,
+        txid: &Txid,
+        query: &BumpInfoQuery,
+    ) -> Result<BumpInfo, Error>;
+
+    /// Returns which transactions `txid` replaced in the mempool and what replaced it.
+    async fn get_transaction_replacement(&self, txid: &Txid) -> Result<TxReplacement, Error>;
+
     /// Broadcasts a transaction (raw hex) to the network and returns the resulting `Txid`.
     async fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error>;
 
     /// Fetches a specific output by outpoint (`<txid>:<vout>`).
     async fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutEntry, Error>;
 
+    /// Returns the zero-conf risk of an output.
+    async fn get_output_risk(&self, outpoint: &OutPoint) -> Result<OutputRisk, Error>;
+
+    /// Returns the transaction spending an output and its confirmation status.
+    async fn get_output_spender(&self, outpoint: &OutPoint) -> Result<Outspend, Error>;
+
+    /// Fetches many outputs at once, `None` for outpoints that aren't indexed.
+    async fn get_outputs(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Option<TxOutEntry>>, Error>;
+
     /// Returns `(HTTP Headers, Bytes)` for an inscription by its `inscription_id`.
     async fn get_inscription(
         &self,
@@ -61,9 +118,22 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<RuneResponse>, Error>;
 
+    /// Searches runes by name prefix or symbol, filtered and sorted by `search`.
+    async fn search_runes(
+        &self,
+        search: &RuneSearch,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneResponse>, Error>;
+
     /// Fetches data about a specific rune.
     async fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error>;
 
+    /// Fetches many runes at once, `None` for ids without a rune.
+    async fn get_runes_by_ids(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, Option<RuneResponse>>, Error>;
+
     /// Returns a paginated list of `Txid` for all transactions involving a given `rune`.
     async fn get_rune_transactions(
         &self,
@@ -71,6 +141,28 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<Txid>, Error>;
 
+    /// Returns the activity feed of a given `rune`, newest first. Pass the
+    /// returned `next_cursor` as `pagination.cursor` to get the next page.
+    async fn get_rune_activity(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneActivity>, Error>;
+
+    /// Returns per-block mint counts for a given `rune` and an estimate of when its cap is reached.
+    async fn get_rune_mints(
+        &self,
+        rune: &query::Rune,
+        range: &RuneMintsQuery,
+    ) -> Result<RuneMintsResponse, Error>;
+
+    /// Returns the supply breakdown of a given `rune` and how it changed over a range of blocks.
+    async fn get_rune_supply(
+        &self,
+        rune: &query::Rune,
+        range: &RuneSupplyQuery,
+    ) -> Result<RuneSupplyResponse, Error>;
+
     /// Returns a list of all txids currently in the mempool.
     async fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error>;
 
@@ -86,6 +178,13 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Returns all mempool entries.
     async fn get_all_mempool_entries(&self) -> Result<HashMap<Txid, MempoolEntry>, Error>;
 
+    /// Returns the mempool fee-rate histogram and recommended fee rates.
+    async fn get_mempool_fees(&self) -> Result<MempoolFees, Error>;
+
+    /// Returns the blocks the mempool is projected to produce, and where
+    /// `query.txid` lands in them.
+    async fn get_mempool_blocks(&self, query: &MempoolBlocksQuery) -> Result<MempoolBlocks, Error>;
+
     /// Fetches a single subscription by `id`.
     async fn get_subscription(&self, id: &str) -> Result<Subscription, Error>;
 
@@ -116,12 +215,34 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     /// Returns txids for a block in a **blocking** manner.
     fn get_block_txids(&self, query: &query::Block) -> Result<Vec<String>, Error>;
 
+    /// Returns the stats of a block in a **blocking** manner.
+    fn get_block_stats(&self, query: &query::Block) -> Result<BlockStats, Error>;
+
+    /// Returns the rune activity of a block in a **blocking** manner.
+    fn get_block_rune_activity(
+        &self,
+        query: &query::Block,
+    ) -> Result<Vec<BlockRuneActivity>, Error>;
+
+    /// Returns the stats of blocks, newest first, in a **blocking** manner.
+    fn get_blocks(&self, query: &BlocksQuery) -> Result<Vec<BlockStats>, Error>;
+
     /// Returns address data in a **blocking** manner.
     fn get_address(&self, address: &str) -> Result<AddressData, Error>;
 
+    /// Returns address data with per-output risk in a **blocking** manner.
+    fn get_address_with_risk(&self, address: &str) -> Result<AddressData, Error>;
+
+    /// Scans the addresses of an xpub or descriptor in a **blocking** manner.
+    fn get_wallet(&self, query: &WalletQuery) -> Result<WalletData, Error>;
+
     /// Returns a transaction (with runic info) by `txid` in a **blocking** manner.
     fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error>;
 
+    /// Returns a transaction with resolved inputs, fee and rune flows in a
+    /// **blocking** manner.
+    fn get_transaction_detail(&self, txid: &Txid) -> Result<TransactionDetail, Error>;
+
     /// Returns raw tx bytes in a **blocking** manner.
     fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error>;
 
@@ -131,12 +252,44 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     /// Returns the status of a transaction by `txid` in a **blocking** manner.
     fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error>;
 
+    /// Returns the spent status of each output of a transaction in a
+    /// **blocking** manner.
+    fn get_transaction_outspends(&self, txid: &Txid) -> Result<Vec<Outspend>, Error>;
+
+    /// Returns the status of each of `txids` in a **blocking** manner.
+    fn get_transaction_statuses(
+        &self,
+        txids: &[Txid],
+    ) -> Result<HashMap<Txid, Option<TransactionStatus>>, Error>;
+
+    /// Returns what it takes to bump a mempool transaction with RBF or CPFP.
+    fn get_transaction_bump_info(
+        &self,
+        txid: &Txid,
+        query: &BumpInfoQuery,
+    ) -> Result<BumpInfo, Error>;
+
+    /// Returns the mempool replacement history of `txid` in a **blocking** manner.
+    fn get_transaction_replacement(&self, txid: &Txid) -> Result<TxReplacement, Error>;
+
     /// Broadcasts a raw-hex transaction in a **blocking** manner.
     fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error>;
 
     /// Fetches a specific output (outpoint) in a **blocking** manner.
     fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutEntry, Error>;
 
+    /// Returns the zero-conf risk of an output in a **blocking** manner.
+    fn get_output_risk(&self, outpoint: &OutPoint) -> Result<OutputRisk, Error>;
+
+    /// Returns the spender of an output in a **blocking** manner.
+    fn get_output_spender(&self, outpoint: &OutPoint) -> Result<Outspend, Error>;
+
+    /// Fetches many outputs at once in a **blocking** manner.
+    fn get_outputs(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Option<TxOutEntry>>, Error>;
+
     /// Fetches an inscription (headers + bytes) by `inscription_id`, blocking.
     fn get_inscription(
         &self,
@@ -149,9 +302,22 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<RuneResponse>, Error>;
 
+    /// Searches runes by name prefix or symbol in a **blocking** manner.
+    fn search_runes(
+        &self,
+        search: &RuneSearch,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneResponse>, Error>;
+
     /// Fetches data for a specific rune in a **blocking** manner.
     fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error>;
 
+    /// Fetches many runes at once in a **blocking** manner.
+    fn get_runes_by_ids(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, Option<RuneResponse>>, Error>;
+
     /// Returns transactions for a given rune in a **blocking** manner.
     fn get_rune_transactions(
         &self,
@@ -159,6 +325,27 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<Txid>, Error>;
 
+    /// Returns the activity feed of a given rune in a **blocking** manner.
+    fn get_rune_activity(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneActivity>, Error>;
+
+    /// Returns per-block mint counts for a given rune in a **blocking** manner.
+    fn get_rune_mints(
+        &self,
+        rune: &query::Rune,
+        range: &RuneMintsQuery,
+    ) -> Result<RuneMintsResponse, Error>;
+
+    /// Returns the supply breakdown of a given rune in a **blocking** manner.
+    fn get_rune_supply(
+        &self,
+        rune: &query::Rune,
+        range: &RuneSupplyQuery,
+    ) -> Result<RuneSupplyResponse, Error>;
+
     /// Returns mempool txids in a **blocking** manner.
     fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error>;
 
@@ -174,6 +361,13 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     /// Returns all mempool entries.
     fn get_all_mempool_entries(&self) -> Result<HashMap<Txid, MempoolEntry>, Error>;
 
+    /// Returns the mempool fee-rate histogram and recommended fee rates.
+    fn get_mempool_fees(&self) -> Result<MempoolFees, Error>;
+
+    /// Returns the blocks the mempool is projected to produce, and where
+    /// `query.txid` lands in them.
+    fn get_mempool_blocks(&self, query: &MempoolBlocksQuery) -> Result<MempoolBlocks, Error>;
+
     /// Fetches a single subscription by `id`, blocking.
     fn get_subscription(&self, id: &str) -> Result<Subscription, Error>;
 
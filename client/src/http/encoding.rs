@@ -0,0 +1,73 @@
+use std::{collections::HashMap, hash::Hash, str::FromStr};
+
+use borsh::BorshDeserialize;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::de::DeserializeOwned;
+use titan_types::{PaginationResponse, BORSH_CONTENT_TYPE};
+
+use crate::Error;
+
+/// Encoding a client asks for in the `Accept` header of data requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) enum Encoding {
+    #[default]
+    Json,
+    Borsh,
+}
+
+impl Encoding {
+    pub(crate) fn accept(self) -> HeaderValue {
+        match self {
+            Self::Json => HeaderValue::from_static("application/json"),
+            Self::Borsh => HeaderValue::from_static(BORSH_CONTENT_TYPE),
+        }
+    }
+}
+
+/// Decodes a response body by its `Content-Type`, so servers that don't
+/// speak Borsh still work.
+pub(crate) fn decode<T: DeserializeOwned + BorshDeserialize>(
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<T, Error> {
+    let borsh = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(BORSH_CONTENT_TYPE));
+
+    if borsh {
+        Ok(borsh::from_slice(body)?)
+    } else {
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+/// Map and txid responses are keyed by strings on the wire.
+pub(crate) fn parse_keys<K: FromStr + Eq + Hash, V>(
+    map: HashMap<String, V>,
+) -> Result<HashMap<K, V>, Error> {
+    map.into_iter()
+        .map(|(key, value)| Ok((parse(&key)?, value)))
+        .collect()
+}
+
+pub(crate) fn parse_items<T: FromStr>(items: Vec<String>) -> Result<Vec<T>, Error> {
+    items.iter().map(|item| parse(item)).collect()
+}
+
+pub(crate) fn parse_page<T: FromStr>(
+    page: PaginationResponse<String>,
+) -> Result<PaginationResponse<T>, Error> {
+    Ok(PaginationResponse {
+        items: parse_items(page.items)?,
+        offset: page.offset,
+        next_cursor: page.next_cursor,
+        prev_cursor: page.prev_cursor,
+    })
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::InvalidResponse(format!("unexpected value `{value}`")))
+}
@@ -1,6 +1,8 @@
 mod client_async_impl;
 mod client_sync_impl;
 mod client_trait;
+mod encoding;
+mod pages;
 
 pub use client_async_impl::*;
 pub use client_sync_impl::*;
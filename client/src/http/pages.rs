@@ -0,0 +1,124 @@
+use std::future::Future;
+
+use futures::{stream, Stream, TryStreamExt};
+use titan_types::{Pagination, PaginationResponse};
+
+use crate::Error;
+
+/// Walks every page of a cursor-paginated endpoint, starting at the first
+/// one and following `next_cursor` until it runs out.
+pub(crate) fn paginate<T, F, Fut>(fetch: F) -> impl Stream<Item = Result<T, Error>>
+where
+    F: FnMut(Pagination) -> Fut,
+    Fut: Future<Output = Result<PaginationResponse<T>, Error>>,
+{
+    stream::try_unfold(
+        (Some(Pagination::default()), fetch),
+        |(pagination, mut fetch)| async move {
+            let Some(pagination) = pagination else {
+                return Ok::<_, Error>(None);
+            };
+
+            let page = fetch(pagination).await?;
+            let items = stream::iter(page.items.into_iter().map(Ok));
+            Ok(Some((items, (next_page(page.next_cursor), fetch))))
+        },
+    )
+    .try_flatten()
+}
+
+/// Blocking counterpart of [`paginate`].
+pub(crate) struct Pages<T, F> {
+    fetch: F,
+    next: Option<Pagination>,
+    items: std::vec::IntoIter<T>,
+}
+
+impl<T, F> Pages<T, F>
+where
+    F: FnMut(Pagination) -> Result<PaginationResponse<T>, Error>,
+{
+    pub(crate) fn new(fetch: F) -> Self {
+        Self {
+            fetch,
+            next: Some(Pagination::default()),
+            items: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<T, F> Iterator for Pages<T, F>
+where
+    F: FnMut(Pagination) -> Result<PaginationResponse<T>, Error>,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            let pagination = self.next.take()?;
+            match (self.fetch)(pagination) {
+                Ok(page) => {
+                    self.next = next_page(page.next_cursor);
+                    self.items = page.items.into_iter();
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+fn next_page(next_cursor: Option<String>) -> Option<Pagination> {
+    next_cursor.map(|cursor| Pagination {
+        cursor: Some(cursor),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves `0..total` in pages of two, newest first, with the cursor being
+    /// the last item returned.
+    fn fetch(total: u64, pagination: Pagination) -> Result<PaginationResponse<u64>, Error> {
+        let end = match pagination.cursor {
+            Some(cursor) => cursor.parse().unwrap(),
+            None => total,
+        };
+        let items: Vec<u64> = (end.saturating_sub(2)..end).rev().collect();
+        let next_cursor = items
+            .last()
+            .filter(|last| **last > 0)
+            .map(|last| last.to_string());
+
+        Ok(PaginationResponse {
+            offset: total - end + items.len() as u64,
+            items,
+            next_cursor,
+            prev_cursor: None,
+        })
+    }
+
+    #[test]
+    fn pages_walk_every_item() {
+        let items: Vec<u64> = Pages::new(|pagination| fetch(5, pagination))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(items, vec![4, 3, 2, 1, 0]);
+    }
+
+    #[tokio::test]
+    async fn paginate_walks_every_item() {
+        let items: Vec<u64> = paginate(|pagination| async move { fetch(5, pagination) })
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(items, vec![4, 3, 2, 1, 0]);
+    }
+}
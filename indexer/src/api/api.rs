@@ -1,21 +1,35 @@
 use {
     super::{
+        bump,
         content::{content_response, AcceptEncoding, ContentError},
+        cursor::{Cursor, RuneTransactionKey},
         query::{to_hash, to_rune_id},
+        risk::RiskAnalyzer,
+        tx_detail, wallet_scan,
     },
     crate::{
         bitcoin_rpc::PooledClient,
-        index::{Index, IndexError},
+        index::{Index, IndexError, StoreError},
         subscription::{self, WebhookSubscriptionManager},
     },
-    bitcoin::{consensus, Address, OutPoint, Txid},
+    bitcoin::{
+        consensus, Address, BlockHash, Network, OutPoint, Transaction as BitcoinTransaction, Txid,
+    },
     bitcoincore_rpc::RpcApi,
     http::HeaderMap,
-    std::{collections::HashMap, sync::Arc},
+    ordinals::RuneId,
+    std::{
+        collections::{HashMap, HashSet, VecDeque},
+        sync::Arc,
+    },
     titan_types::{
-        query, AddressData, Block, BlockTip, InscriptionId, MempoolEntry, Pagination,
-        PaginationResponse, RuneResponse, Status, Subscription, Transaction, TransactionStatus,
-        TxOutEntry,
+        query, AddressData, Block, BlockRuneActivity, BlockStats, BlockTip, BlocksQuery, BumpInfo,
+        BumpInfoQuery, BumpOutput, InscriptionId, MempoolBlocks, MempoolBlocksQuery, MempoolEntry,
+        MempoolFees, OutputRisk, Outspend, Pagination, PaginationResponse, Readiness, RuneActivity,
+        RuneBlockMints, RuneMintsQuery, RuneMintsResponse, RuneResponse, RuneSearch,
+        RuneSupplyBlock, RuneSupplyQuery, RuneSupplyResponse, SpentStatus, Status, Subscription,
+        Transaction, TransactionDetail, TransactionStatus, TxOutEntry, TxReplacement, WalletData,
+        WalletQuery,
     },
     tracing::error,
     uuid::Uuid,
@@ -35,6 +49,33 @@ pub enum ApiError {
     HexError(#[from] hex::FromHexError),
     #[error("consensus error: {0}")]
     ConsensusError(#[from] consensus::encode::Error),
+    #[error("invalid cursor: {0}")]
+    InvalidCursor(String),
+    #[error("{0} ids requested, at most {MAX_BATCH_SIZE} are allowed")]
+    BatchTooLarge(usize),
+    #[error("invalid descriptor: {0}")]
+    InvalidDescriptor(String),
+    #[error("invalid range: {0}")]
+    InvalidRange(String),
+    #[error("invalid search: {0}")]
+    InvalidSearch(&'static str),
+    #[error("{0} isn't recorded for blocks indexed before this version, reindex to serve it")]
+    ReindexRequired(&'static str),
+}
+
+/// Most ids a batch lookup takes in one request.
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// Unconfirmed outputs of an address that get a risk report. Without the
+/// transaction index each report can take RPC calls.
+const MAX_RISK_OUTPUTS: usize = 100;
+
+fn check_batch_size<T>(ids: &[T]) -> Result<()> {
+    if ids.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::BatchTooLarge(ids.len()));
+    }
+
+    Ok(())
 }
 
 pub type Result<T> = std::result::Result<T, ApiError>;
@@ -49,6 +90,10 @@ pub fn tip(index: Arc<Index>) -> Result<BlockTip> {
     })
 }
 
+pub fn readiness(index: Arc<Index>) -> Result<Readiness> {
+    Ok(index.readiness()?)
+}
+
 pub fn status(index: Arc<Index>) -> Result<Status> {
     let block_count = index.get_block_count()?;
     let block_hash = index.get_block_hash(block_count - 1)?;
@@ -78,10 +123,156 @@ pub fn block_txids(index: Arc<Index>, block: &query::Block) -> Result<Vec<String
     Ok(block.tx_ids)
 }
 
+pub fn block_stats(index: Arc<Index>, block: &query::Block) -> Result<BlockStats> {
+    let hash = to_hash(block, &index)?;
+    match index.get_block_stats(&hash) {
+        Err(IndexError::StoreError(StoreError::NotFound(_))) => {
+            Err(missing_block_summary(&index, block, &hash))
+        }
+        stats => Ok(stats?),
+    }
+}
+
+pub fn block_rune_activity(
+    index: Arc<Index>,
+    block: &query::Block,
+) -> Result<Vec<BlockRuneActivity>> {
+    let hash = to_hash(block, &index)?;
+    match index.get_block_rune_activity(&hash) {
+        Err(IndexError::StoreError(StoreError::NotFound(_))) => {
+            Err(missing_block_summary(&index, block, &hash))
+        }
+        activity => Ok(activity?),
+    }
+}
+
+/// Why a block has no stats or rune activity: it was indexed before they
+/// were kept, or it isn't indexed at all.
+fn missing_block_summary(index: &Index, block: &query::Block, hash: &BlockHash) -> ApiError {
+    let height = match block {
+        query::Block::Height(height) => Ok(*height),
+        query::Block::Hash(_) => index.get_block_by_hash(hash).map(|block| block.height),
+    };
+
+    match (height, index.get_block_summaries_from()) {
+        (Ok(height), Ok(from)) if height < from => {
+            ApiError::ReindexRequired("block stats and rune activity")
+        }
+        (Err(error), _) | (_, Err(error)) => error.into(),
+        _ => IndexError::StoreError(StoreError::NotFound(format!("stats of block {hash}"))).into(),
+    }
+}
+
+/// Blocks `/blocks` lists unless told otherwise, and the most it lists.
+const DEFAULT_BLOCKS: u64 = 10;
+const MAX_BLOCKS: u64 = 100;
+
+/// Stats of the blocks from `query.from` down. Refused if some of them were
+/// indexed before the stats were kept, rather than listing fewer.
+pub fn blocks(index: Arc<Index>, query: BlocksQuery) -> Result<Vec<BlockStats>> {
+    let Some(tip) = index.get_block_count()?.checked_sub(1) else {
+        return Ok(vec![]);
+    };
+    let from = query.from.map_or(tip, |from| from.min(tip));
+    let limit = query.limit.unwrap_or(DEFAULT_BLOCKS).min(MAX_BLOCKS);
+
+    if limit > 0 && (from + 1).saturating_sub(limit) < index.get_block_summaries_from()? {
+        return Err(ApiError::ReindexRequired("block stats and rune activity"));
+    }
+
+    (0..=from)
+        .rev()
+        .take(limit as usize)
+        .map(|height| {
+            let hash = index.get_block_hash(height)?;
+            Ok(index.get_block_stats(&hash)?)
+        })
+        .collect()
+}
+
 pub fn output(index: Arc<Index>, outpoint: &OutPoint) -> Result<TxOutEntry> {
     Ok(index.get_tx_out(outpoint)?)
 }
 
+/// Every outpoint is in the result, `None` if it isn't indexed.
+pub fn outputs(
+    index: Arc<Index>,
+    outpoints: &Vec<OutPoint>,
+) -> Result<HashMap<OutPoint, Option<TxOutEntry>>> {
+    check_batch_size(outpoints)?;
+
+    let mut outputs: HashMap<OutPoint, Option<TxOutEntry>> =
+        outpoints.iter().map(|outpoint| (*outpoint, None)).collect();
+    for (outpoint, tx_out) in index.get_tx_outs(outpoints)? {
+        outputs.insert(outpoint, Some(tx_out));
+    }
+
+    Ok(outputs)
+}
+
+/// Whether and by what each output of `txid` is spent.
+pub fn outspends(index: Arc<Index>, client: PooledClient, txid: &Txid) -> Result<Vec<Outspend>> {
+    let transaction = bitcoin_transaction(&index, &client, txid)?;
+    let outpoints = (0..transaction.output.len())
+        .map(|vout| OutPoint::new(*txid, vout as u32))
+        .collect();
+
+    outspends_of(&index, &outpoints)
+}
+
+pub fn output_spender(index: Arc<Index>, outpoint: &OutPoint) -> Result<Outspend> {
+    let outspend = outspends_of(&index, &vec![*outpoint])?.remove(0);
+
+    // Not found for unknown transactions rather than an unknown status.
+    if outspend.spent.is_none() {
+        index.get_transaction_status(&outpoint.txid)?;
+    }
+
+    Ok(outspend)
+}
+
+pub(super) fn outspends_of(index: &Index, outpoints: &Vec<OutPoint>) -> Result<Vec<Outspend>> {
+    let spent = index.get_outspends(outpoints)?;
+
+    let spenders: Vec<Txid> = spent
+        .values()
+        .filter_map(|spent| match spent {
+            Some(SpentStatus::Spent(spender)) => Some(spender.txid),
+            _ => None,
+        })
+        .collect();
+    let statuses = index.get_transaction_statuses(&spenders)?;
+
+    Ok(outpoints
+        .iter()
+        .map(|outpoint| {
+            let spent = spent.get(outpoint).cloned().flatten();
+            let spender = match &spent {
+                Some(SpentStatus::Spent(spender)) => Some(spender.clone()),
+                _ => None,
+            };
+
+            Outspend {
+                vout: outpoint.vout,
+                spent: spent.map(|spent| matches!(spent, SpentStatus::Spent(_))),
+                status: spender
+                    .as_ref()
+                    .and_then(|spender| statuses.get(&spender.txid).cloned().flatten()),
+                spender,
+            }
+        })
+        .collect())
+}
+
+pub fn output_risk(
+    index: Arc<Index>,
+    client: PooledClient,
+    outpoint: &OutPoint,
+) -> Result<OutputRisk> {
+    let tx_out = index.get_tx_out(outpoint)?;
+    RiskAnalyzer::new(&index, &client).output_risk(*outpoint, tx_out.risky_runes)
+}
+
 pub fn inscription_content(
     index: Arc<Index>,
     inscription_id: &InscriptionId,
@@ -97,27 +288,288 @@ pub fn inscription_content(
 pub fn rune(index: Arc<Index>, rune_query: &query::Rune) -> Result<RuneResponse> {
     let rune_id = to_rune_id(rune_query, &index)?;
     let block_count = index.get_block_count()?;
-    let rune_response = index
-        .get_rune(&rune_id)?
-        .to_rune_response(rune_id, block_count - 1);
+    let utxo_counts = index.get_rune_utxo_counts(&vec![rune_id])?;
+    let rune_response = index.get_rune(&rune_id)?.to_rune_response(
+        rune_id,
+        block_count - 1,
+        utxo_counts.get(&rune_id).cloned().unwrap_or(0),
+    );
     Ok(rune_response)
 }
 
+/// Every id is in the result, `None` if there's no such rune.
+pub fn runes_by_ids(
+    index: Arc<Index>,
+    rune_ids: &Vec<RuneId>,
+) -> Result<HashMap<RuneId, Option<RuneResponse>>> {
+    check_batch_size(rune_ids)?;
+
+    let block_count = index.get_block_count()?;
+    let utxo_counts = index.get_rune_utxo_counts(rune_ids)?;
+
+    let mut runes: HashMap<RuneId, Option<RuneResponse>> =
+        rune_ids.iter().map(|rune_id| (*rune_id, None)).collect();
+    for (rune_id, rune_entry) in index.get_runes_by_ids(rune_ids)? {
+        let rune_response = rune_entry.to_rune_response(
+            rune_id,
+            block_count - 1,
+            utxo_counts.get(&rune_id).cloned().unwrap_or(0),
+        );
+        runes.insert(rune_id, Some(rune_response));
+    }
+
+    Ok(runes)
+}
+
 pub fn runes(
     index: Arc<Index>,
     pagination: Pagination,
 ) -> Result<PaginationResponse<RuneResponse>> {
-    let rune_entries = index.get_runes(pagination)?;
+    let cursor = Cursor::<u64>::from_pagination(pagination.cursor.as_deref())?;
+    let runes_count = index.get_runes_count()?;
+
+    // Rune numbers have no gaps, so a page is a range of them.
+    let (numbers, more) = match cursor {
+        None => {
+            let end = runes_count.saturating_sub(pagination.skip);
+            let start = end.saturating_sub(pagination.limit);
+            (start..end, start > 0)
+        }
+        Some(Cursor::Older(number)) => {
+            let end = number.min(runes_count);
+            let start = end.saturating_sub(pagination.limit);
+            (start..end, start > 0)
+        }
+        Some(Cursor::Newer(number)) => {
+            let start = number.saturating_add(1).min(runes_count);
+            let end = start.saturating_add(pagination.limit).min(runes_count);
+            (start..end, end < runes_count)
+        }
+    };
+    let offset = runes_count - numbers.start;
+
+    let rune_entries = index.get_runes_by_number(numbers)?;
+    let (next_cursor, prev_cursor) = Cursor::page(
+        cursor,
+        rune_entries
+            .first()
+            .map(|(_, rune_entry)| rune_entry.number),
+        rune_entries.last().map(|(_, rune_entry)| rune_entry.number),
+        more,
+        cursor.is_none() && pagination.skip > 0,
+    );
+
+    let block_count = index.get_block_count()?;
+    let utxo_counts =
+        index.get_rune_utxo_counts(&rune_entries.iter().map(|(rune_id, _)| *rune_id).collect())?;
+    let rune_responses: Vec<RuneResponse> = rune_entries
+        .into_iter()
+        .map(|(rune_id, rune_entry)| {
+            rune_entry.to_rune_response(
+                rune_id,
+                block_count,
+                utxo_counts.get(&rune_id).cloned().unwrap_or(0),
+            )
+        })
+        .collect();
+
+    Ok(PaginationResponse {
+        items: rune_responses,
+        offset,
+        next_cursor,
+        prev_cursor,
+    })
+}
+
+const DEFAULT_RUNE_RANGE_BLOCKS: u64 = 144;
+const MAX_RUNE_RANGE_BLOCKS: u64 = 2016;
+const TARGET_BLOCK_SECONDS: u64 = 600;
+
+/// Resolves an optional block range, defaulting to the last day of blocks and
+/// never reaching past the tip. Ranges longer than `MAX_RUNE_RANGE_BLOCKS`
+/// are refused rather than cut short.
+fn rune_block_range(from: Option<u64>, to: Option<u64>, tip: u64) -> Result<(u64, u64)> {
+    let to = to.unwrap_or(tip).min(tip);
+    let from = from.unwrap_or(to.saturating_sub(DEFAULT_RUNE_RANGE_BLOCKS - 1));
+
+    if from > to {
+        return Err(ApiError::InvalidRange(format!(
+            "from {from} is after to {to}"
+        )));
+    }
+
+    if to - from >= MAX_RUNE_RANGE_BLOCKS {
+        return Err(ApiError::InvalidRange(format!(
+            "{from}..={to} spans more than {MAX_RUNE_RANGE_BLOCKS} blocks"
+        )));
+    }
+
+    Ok((from, to))
+}
+
+/// Mints per block over a range of `blocks`, and the blocks left until the
+/// cap at that pace, if the rune can still be minted.
+fn mint_velocity(
+    minted_in_range: u64,
+    blocks: u64,
+    mints: u128,
+    cap: u128,
+    mintable: bool,
+) -> (f64, Option<u64>) {
+    let velocity = minted_in_range as f64 / blocks as f64;
+
+    let estimated_blocks_to_cap = if mintable && velocity > 0.0 {
+        let remaining = cap.saturating_sub(mints);
+        Some((remaining as f64 / velocity).ceil() as u64)
+    } else {
+        None
+    };
+
+    (velocity, estimated_blocks_to_cap)
+}
+
+/// Mints and supply per block are only complete if the index kept them from
+/// its first block.
+fn require_rune_history(index: &Index) -> Result<()> {
+    if !index.has_rune_history()? {
+        return Err(ApiError::ReindexRequired("rune mint and supply history"));
+    }
+
+    Ok(())
+}
+
+/// Scripthash lookups and wallet scans need every script that had an output,
+/// emptied ones included, or they'd report used scripts as unused.
+pub(super) fn require_script_history(index: &Index) -> Result<()> {
+    if !index.has_script_history()? {
+        return Err(ApiError::ReindexRequired(
+            "scripthash and spent output history",
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn rune_mints(
+    index: Arc<Index>,
+    rune_query: &query::Rune,
+    range: RuneMintsQuery,
+) -> Result<RuneMintsResponse> {
+    require_rune_history(&index)?;
+
+    let rune_id = to_rune_id(rune_query, &index)?;
+    let rune_entry = index.get_rune(&rune_id)?;
+    let block_count = index.get_block_count()?;
+    let (from, to) = rune_block_range(range.from, range.to, block_count.saturating_sub(1))?;
+
+    let blocks: Vec<RuneBlockMints> = index
+        .get_rune_mints(&rune_id, from, to)?
+        .into_iter()
+        .map(|(height, mints)| RuneBlockMints { height, mints })
+        .collect();
+
+    let cap = rune_entry
+        .terms
+        .and_then(|terms| terms.cap)
+        .unwrap_or_default();
+    let mintable = rune_entry.mintable(block_count).is_ok();
+
+    let (velocity, estimated_blocks_to_cap) = mint_velocity(
+        blocks.iter().map(|block| block.mints).sum(),
+        to - from + 1,
+        rune_entry.mints,
+        cap,
+        mintable,
+    );
+
+    Ok(RuneMintsResponse {
+        id: rune_id,
+        from,
+        to,
+        mints: rune_entry.mints,
+        cap,
+        mintable,
+        blocks,
+        velocity,
+        estimated_blocks_to_cap,
+        estimated_seconds_to_cap: estimated_blocks_to_cap
+            .map(|blocks| blocks.saturating_mul(TARGET_BLOCK_SECONDS)),
+    })
+}
+
+pub fn rune_supply(
+    index: Arc<Index>,
+    rune_query: &query::Rune,
+    range: RuneSupplyQuery,
+) -> Result<RuneSupplyResponse> {
+    require_rune_history(&index)?;
+
+    let rune_id = to_rune_id(rune_query, &index)?;
+    let rune_entry = index.get_rune(&rune_id)?;
+    let tip = index.get_block_count()?.saturating_sub(1);
+    let (from, to) = rune_block_range(range.from, range.to, tip)?;
+
+    let totals = index.get_rune_supply(&rune_id)?;
+    let supply = rune_entry.supply();
+    let burned = rune_entry.burned;
+    let unspendable = totals.unspendable;
+
+    let blocks = index
+        .get_rune_supply_totals(&rune_id, from, to)?
+        .into_iter()
+        .map(|(height, totals)| RuneSupplyBlock {
+            height,
+            supply: totals.issued,
+            burned: totals.burned,
+            unspendable: totals.unspendable,
+            circulating: totals
+                .issued
+                .saturating_sub(totals.burned)
+                .saturating_sub(totals.unspendable),
+        })
+        .collect();
+
+    Ok(RuneSupplyResponse {
+        id: rune_id,
+        premine: rune_entry.premine,
+        minted: supply.saturating_sub(rune_entry.premine),
+        supply,
+        max_supply: rune_entry.max_supply(),
+        burned,
+        unspendable,
+        circulating: supply.saturating_sub(burned).saturating_sub(unspendable),
+        pending: totals.pending,
+        from,
+        to,
+        blocks,
+    })
+}
+
+pub fn search_runes(
+    index: Arc<Index>,
+    search: &RuneSearch,
+    pagination: Pagination,
+) -> Result<PaginationResponse<RuneResponse>> {
+    // Name matches are walked in name order, which no sort can apply to.
+    if search.q.is_some() && search.sort.is_some() {
+        return Err(ApiError::InvalidSearch("`sort` can't be used with `q`"));
+    }
+
+    let rune_entries = index.search_runes(search, pagination)?;
     let block_count = index.get_block_count()?;
     let rune_responses: Vec<RuneResponse> = rune_entries
         .items
         .into_iter()
-        .map(|(rune_id, rune_entry)| rune_entry.to_rune_response(rune_id, block_count))
+        .map(|(rune_id, rune_entry, utxo_count)| {
+            rune_entry.to_rune_response(rune_id, block_count, utxo_count)
+        })
         .collect();
 
     Ok(PaginationResponse {
         items: rune_responses,
         offset: rune_entries.offset,
+        next_cursor: None,
+        prev_cursor: None,
     })
 }
 
@@ -127,8 +579,158 @@ pub fn last_rune_transactions(
     pagination: Option<Pagination>,
 ) -> Result<PaginationResponse<Txid>> {
     let rune_id = to_rune_id(rune_query, &index)?;
-    let transactions = index.get_last_rune_transactions(&rune_id, pagination, None)?;
-    Ok(transactions)
+    let pagination = pagination.unwrap_or_default();
+
+    // Skipping without a cursor is still supported, but such pages have no
+    // cursors.
+    if pagination.cursor.is_none() && pagination.skip > 0 {
+        return Ok(index.get_last_rune_transactions(&rune_id, Some(pagination), None)?);
+    }
+
+    let cursor = Cursor::from_pagination(pagination.cursor.as_deref())?;
+    let (transactions, more) = rune_transactions_page(&index, &rune_id, cursor, pagination.limit)?;
+    let (next_cursor, prev_cursor) = Cursor::page(
+        cursor,
+        transactions.first().map(|(key, _)| *key),
+        transactions.last().map(|(key, _)| *key),
+        more,
+        false,
+    );
+
+    Ok(PaginationResponse {
+        offset: rune_transactions_offset(&index, &rune_id, cursor, &transactions)?,
+        items: transactions.into_iter().map(|(_, txid)| txid).collect(),
+        next_cursor,
+        prev_cursor,
+    })
+}
+
+pub fn rune_activity(
+    index: Arc<Index>,
+    rune_query: &query::Rune,
+    pagination: Pagination,
+) -> Result<PaginationResponse<RuneActivity>> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    let cursor = Cursor::from_pagination(pagination.cursor.as_deref())?;
+
+    let (transactions, more) = rune_transactions_page(&index, &rune_id, cursor, pagination.limit)?;
+    let (next_cursor, prev_cursor) = Cursor::page(
+        cursor,
+        transactions.first().map(|(key, _)| *key),
+        transactions.last().map(|(key, _)| *key),
+        more,
+        false,
+    );
+    let offset = rune_transactions_offset(&index, &rune_id, cursor, &transactions)?;
+
+    let txids: Vec<(Txid, bool)> = transactions
+        .into_iter()
+        .map(|(key, txid)| (txid, key.mempool))
+        .collect();
+    let items = index.get_rune_activity(&rune_id, &txids)?;
+
+    Ok(PaginationResponse {
+        offset,
+        items,
+        next_cursor,
+        prev_cursor,
+    })
+}
+
+/// Walks a rune's transactions from `cursor`: mempool ones first, then
+/// confirmed ones, newest first. Returns up to `limit` of them and whether
+/// more follow in the cursor's direction.
+fn rune_transactions_page(
+    index: &Index,
+    rune_id: &RuneId,
+    cursor: Option<Cursor<RuneTransactionKey>>,
+    limit: u64,
+) -> Result<(Vec<(RuneTransactionKey, Txid)>, bool)> {
+    // Each segment is a walk over one column family, in the order they're
+    // visited.
+    let segments: Vec<(bool, u64)> = match cursor {
+        None => vec![(true, u64::MAX), (false, u64::MAX)],
+        Some(Cursor::Older(key)) if key.mempool => vec![(true, key.index), (false, u64::MAX)],
+        Some(Cursor::Older(key)) => vec![(false, key.index)],
+        Some(Cursor::Newer(key)) if key.mempool => vec![(true, key.index.saturating_add(1))],
+        Some(Cursor::Newer(key)) => vec![(false, key.index.saturating_add(1)), (true, 0)],
+    };
+    let newer = matches!(cursor, Some(Cursor::Newer(_)));
+
+    // One extra item tells whether there's more.
+    let wanted = limit.saturating_add(1);
+    let mut transactions = Vec::new();
+    for (mempool, bound) in segments {
+        let remaining = wanted - transactions.len() as u64;
+        if remaining == 0 {
+            break;
+        }
+
+        let segment = if newer {
+            index.get_rune_transactions_from(rune_id, bound, remaining, mempool)?
+        } else {
+            index.get_rune_transactions_before(rune_id, bound, remaining, mempool)?
+        };
+
+        transactions.extend(
+            segment
+                .into_iter()
+                .map(|(index, txid)| (RuneTransactionKey { mempool, index }, txid)),
+        );
+    }
+
+    let more = transactions.len() as u64 > limit;
+    transactions.truncate(limit as usize);
+    if newer {
+        transactions.reverse();
+    }
+
+    Ok((transactions, more))
+}
+
+/// How many of the rune's transactions, newest first, come up to the end of a
+/// page from `rune_transactions_page`.
+fn rune_transactions_offset(
+    index: &Index,
+    rune_id: &RuneId,
+    cursor: Option<Cursor<RuneTransactionKey>>,
+    transactions: &[(RuneTransactionKey, Txid)],
+) -> Result<u64> {
+    match (transactions.last(), cursor.as_ref()) {
+        (Some((key, _)), _) | (None, Some(Cursor::Older(key))) => {
+            rune_transaction_position(index, rune_id, key)
+        }
+        (None, Some(Cursor::Newer(key))) => {
+            Ok(rune_transaction_position(index, rune_id, key)?.saturating_sub(1))
+        }
+        (None, None) => Ok(0),
+    }
+}
+
+/// Position of `key` among the rune's transactions, newest first, counting
+/// from one. Mempool transactions are few, so they're counted. Confirmed ones
+/// are counted from their indexes, like skipping does, so transactions a reorg
+/// dropped still take up their place.
+fn rune_transaction_position(
+    index: &Index,
+    rune_id: &RuneId,
+    key: &RuneTransactionKey,
+) -> Result<u64> {
+    if key.mempool {
+        return Ok(index
+            .get_rune_transactions_from(rune_id, key.index, u64::MAX, true)?
+            .len() as u64);
+    }
+
+    let mempool = index
+        .get_rune_transactions_from(rune_id, 0, u64::MAX, true)?
+        .len() as u64;
+    let newest = index
+        .get_rune_transactions_before(rune_id, u64::MAX, 1, false)?
+        .first()
+        .map_or(key.index, |(newest, _)| *newest);
+
+    Ok(mempool + newest.saturating_sub(key.index) + 1)
 }
 
 pub fn broadcast_transaction(index: Arc<Index>, client: PooledClient, hex: &str) -> Result<Txid> {
@@ -215,10 +817,138 @@ pub fn transaction(index: Arc<Index>, client: PooledClient, txid: &Txid) -> Resu
     Ok(transaction)
 }
 
+/// `transaction` with the outputs its inputs spend, its fee and rune flows.
+pub fn transaction_detail(
+    index: Arc<Index>,
+    client: PooledClient,
+    txid: &Txid,
+) -> Result<TransactionDetail> {
+    tx_detail::build(&index, &client, txid)
+}
+
+pub fn transaction_replacement(index: Arc<Index>, txid: &Txid) -> Result<TxReplacement> {
+    Ok(index.get_transaction_replacement(txid)?)
+}
+
+pub fn bump_info(
+    index: Arc<Index>,
+    client: PooledClient,
+    txid: &Txid,
+    query: BumpInfoQuery,
+) -> Result<BumpInfo> {
+    let entry = index.get_mempool_entry(txid)?;
+    let ancestors = mempool_relatives(&index, &entry, |entry| &entry.depends)?;
+    let descendants = mempool_relatives(&index, &entry, |entry| &entry.spent_by)?;
+
+    let target_fee_rate = match query.target_fee_rate.filter(|rate| rate.is_finite()) {
+        Some(target_fee_rate) => target_fee_rate,
+        None => index.get_mempool_fees()?.recommended.fastest,
+    }
+    .max(bump::MIN_RELAY_FEE_RATE);
+    let cpfp_child_vsize = query.child_vsize.unwrap_or(bump::DEFAULT_CPFP_CHILD_VSIZE);
+
+    // Fetched before `transaction` takes the client.
+    let mut inherits_rbf = false;
+    for ancestor in &ancestors {
+        if bitcoin_transaction(&index, &client, ancestor)?.is_explicitly_rbf() {
+            inherits_rbf = true;
+            break;
+        }
+    }
+
+    let transaction = transaction(index.clone(), client, txid)?;
+    let signals_rbf = transaction
+        .input
+        .iter()
+        .any(|input| input.sequence.is_rbf());
+
+    let position = index.get_mempool_tx_position(txid)?;
+
+    Ok(BumpInfo {
+        txid: *txid,
+        vsize: entry.vsize,
+        fee: entry.fees.base,
+        fee_rate: entry.fees.base as f64 / entry.vsize as f64,
+        // Fall back to the ancestor package for transactions that came in
+        // after the last projection.
+        effective_fee_rate: position.as_ref().map_or(
+            entry.fees.ancestor as f64 / entry.ancestor_size as f64,
+            |position| position.fee_rate,
+        ),
+        projected_block: position.and_then(|position| position.block),
+        signals_rbf,
+        replaceable: signals_rbf || inherits_rbf,
+        ancestors,
+        descendants,
+        target_fee_rate,
+        rbf_fee: bump::rbf_fee(&entry, target_fee_rate),
+        cpfp_child_vsize,
+        cpfp_fee: bump::cpfp_fee(&entry, target_fee_rate, cpfp_child_vsize),
+        outputs: transaction
+            .output
+            .into_iter()
+            .enumerate()
+            .map(|(vout, output)| BumpOutput {
+                vout: vout as u32,
+                value: output.value,
+                spent: matches!(output.spent, SpentStatus::Spent(_)),
+                runes: output.runes,
+                risky_runes: output.risky_runes,
+            })
+            .collect(),
+    })
+}
+
+/// Mempool transactions reachable from `entry` by following `next`, nearest
+/// first.
+fn mempool_relatives(
+    index: &Index,
+    entry: &MempoolEntry,
+    next: impl Fn(&MempoolEntry) -> &Vec<Txid>,
+) -> Result<Vec<Txid>> {
+    let mut relatives = Vec::new();
+    let mut seen: HashSet<Txid> = HashSet::new();
+    let mut queue: VecDeque<Txid> = next(entry).iter().copied().collect();
+
+    while !queue.is_empty() {
+        let txids: Vec<Txid> = queue.drain(..).filter(|txid| seen.insert(*txid)).collect();
+
+        let entries = index.get_mempool_entries(&txids)?;
+        for txid in txids {
+            if let Some(Some(entry)) = entries.get(&txid) {
+                relatives.push(txid);
+                queue.extend(next(entry).iter().copied());
+            }
+        }
+    }
+
+    Ok(relatives)
+}
+
+pub(super) fn bitcoin_transaction(
+    index: &Index,
+    client: &PooledClient,
+    txid: &Txid,
+) -> Result<BitcoinTransaction> {
+    if index.is_indexing_bitcoin_transactions() {
+        Ok(consensus::deserialize(&index.get_transaction_raw(txid)?)?)
+    } else {
+        Ok(client.get_raw_transaction(txid, None)?)
+    }
+}
+
 pub fn transaction_status(index: Arc<Index>, txid: &Txid) -> Result<TransactionStatus> {
     Ok(index.get_transaction_status(txid)?)
 }
 
+pub fn transaction_statuses(
+    index: Arc<Index>,
+    txids: &Vec<Txid>,
+) -> Result<HashMap<Txid, Option<TransactionStatus>>> {
+    check_batch_size(txids)?;
+    Ok(index.get_transaction_statuses(txids)?)
+}
+
 pub fn mempool_txids(index: Arc<Index>) -> Result<Vec<Txid>> {
     Ok(index.get_mempool_txids()?)
 }
@@ -238,11 +968,50 @@ pub fn mempool_entries_all(index: Arc<Index>) -> Result<HashMap<Txid, MempoolEnt
     Ok(index.get_all_mempool_entries()?)
 }
 
+pub fn mempool_fees(index: Arc<Index>) -> Result<MempoolFees> {
+    Ok(index.get_mempool_fees()?)
+}
+
+pub fn mempool_blocks(index: Arc<Index>, query: MempoolBlocksQuery) -> Result<MempoolBlocks> {
+    // Every projected block unless told otherwise.
+    let max_blocks = query.blocks.map_or(usize::MAX, |blocks| blocks as usize);
+    Ok(index.get_mempool_blocks(max_blocks, query.txid.as_ref())?)
+}
+
 pub fn address(index: Arc<Index>, address: &Address) -> Result<AddressData> {
     let outpoints = index.get_script_pubkey_outpoints(&address)?;
     Ok(outpoints)
 }
 
+pub fn wallet(index: Arc<Index>, network: Network, query: &WalletQuery) -> Result<WalletData> {
+    wallet_scan::scan(&index, network, query)
+}
+
+/// Fills in `risk` for the first `MAX_RISK_OUTPUTS` unconfirmed outputs of
+/// `address_data`.
+pub fn add_address_risk(
+    index: Arc<Index>,
+    client: PooledClient,
+    address_data: &mut AddressData,
+) -> Result<()> {
+    let mut analyzer = RiskAnalyzer::new(&index, &client);
+    for output in address_data
+        .outputs
+        .iter_mut()
+        .filter(|output| !output.status.confirmed)
+        .take(MAX_RISK_OUTPUTS)
+    {
+        let outpoint = OutPoint {
+            txid: output.txid,
+            vout: output.vout,
+        };
+
+        output.risk = Some(analyzer.output_risk(outpoint, output.risky_runes.clone())?);
+    }
+
+    Ok(())
+}
+
 pub fn subscriptions(
     subscription_manager: Arc<WebhookSubscriptionManager>,
 ) -> Result<Vec<Subscription>> {
@@ -269,3 +1038,261 @@ pub fn get_subscription(
 ) -> Result<Subscription> {
     Ok(subscription_manager.get_subscription(&id)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::models::{BatchUpdate, BlockId, RuneEntry},
+        bitcoin::{hashes::Hash, BlockHash},
+        ordinals::{Rune, SpacedRune},
+        titan_types::{RuneSortBy, SpenderReference},
+    };
+
+    fn outpoint(n: u8) -> OutPoint {
+        OutPoint::new(Txid::from_byte_array([n; 32]), 0)
+    }
+
+    fn rune_entry() -> RuneEntry {
+        RuneEntry {
+            block: 840000,
+            burned: 0,
+            divisibility: 0,
+            etching: Txid::all_zeros(),
+            mints: 0,
+            number: 0,
+            premine: 1000,
+            spaced_rune: SpacedRune::new(Rune(0), 0),
+            symbol: None,
+            terms: None,
+            pending_burns: 0,
+            pending_mints: 0,
+            inscription_id: None,
+            timestamp: 0,
+            turbo: false,
+        }
+    }
+
+    #[test]
+    fn batches_answer_none_for_unknown_ids() {
+        let (_dir, db, index) = Index::open_temp();
+        let index = Arc::new(index);
+        let rune_id = RuneId::new(840000, 1).unwrap();
+
+        let mut update = BatchUpdate::new(1, 1, 0);
+        update.runes = HashMap::from([(rune_id, rune_entry())]);
+        update.txouts = HashMap::from([(
+            outpoint(1),
+            TxOutEntry {
+                runes: vec![],
+                risky_runes: vec![],
+                value: 1000,
+                spent: SpentStatus::Unspent,
+            },
+        )]);
+        db.batch_update(&update, false).unwrap();
+
+        let outputs = outputs(index.clone(), &vec![outpoint(1), outpoint(2)]).unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[&outpoint(1)].as_ref().unwrap().value, 1000);
+        assert!(outputs[&outpoint(2)].is_none());
+
+        let unknown = RuneId::new(840000, 2).unwrap();
+        let runes = runes_by_ids(index.clone(), &vec![rune_id, unknown]).unwrap();
+        assert_eq!(runes.len(), 2);
+        assert_eq!(runes[&rune_id].as_ref().unwrap().id, rune_id);
+        assert!(runes[&unknown].is_none());
+
+        let statuses = transaction_statuses(index, &vec![outpoint(2).txid]).unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert!(statuses[&outpoint(2).txid].is_none());
+    }
+
+    #[test]
+    fn outspends_prefer_mempool_spenders() {
+        let (_dir, db, index) = Index::open_temp();
+        let index = Arc::new(index);
+        let spender = SpenderReference {
+            txid: outpoint(9).txid,
+            vin: 0,
+        };
+        let unspent = TxOutEntry {
+            runes: vec![],
+            risky_runes: vec![],
+            value: 1000,
+            spent: SpentStatus::Unspent,
+        };
+
+        // 1 is spent in the mempool, 2 is unspent and 3 isn't tracked.
+        let mut update = BatchUpdate::new(0, 1, 0);
+        update.txouts = HashMap::from([(outpoint(1), unspent.clone()), (outpoint(2), unspent)]);
+        update.transaction_confirming_block = HashMap::from([(
+            outpoint(3).txid,
+            BlockId {
+                hash: BlockHash::all_zeros(),
+                height: 0,
+            },
+        )]);
+        db.batch_update(&update, false).unwrap();
+
+        let mut update = BatchUpdate::new(0, 1, 0);
+        update.spent_outpoints_in_mempool = HashMap::from([(outpoint(1), spender.clone())]);
+        db.batch_update(&update, true).unwrap();
+
+        let outspends = outspends_of(&index, &vec![outpoint(1), outpoint(2), outpoint(3)]).unwrap();
+
+        assert_eq!(outspends[0].spent, Some(true));
+        let mempool_spender = outspends[0].spender.as_ref().unwrap();
+        assert_eq!(
+            (mempool_spender.txid, mempool_spender.vin),
+            (spender.txid, spender.vin)
+        );
+        assert_eq!(outspends[1].spent, Some(false));
+        assert!(outspends[1].spender.is_none());
+        assert_eq!(outspends[2].spent, None);
+
+        assert_eq!(
+            output_spender(index.clone(), &outpoint(3)).unwrap().spent,
+            None
+        );
+        assert!(matches!(
+            output_spender(index, &outpoint(4)),
+            Err(ApiError::IndexError(IndexError::StoreError(
+                StoreError::NotFound(_)
+            )))
+        ));
+    }
+
+    #[test]
+    fn rune_block_ranges() {
+        assert_eq!(rune_block_range(None, None, 1000).unwrap(), (857, 1000));
+        assert_eq!(rune_block_range(None, None, 10).unwrap(), (0, 10));
+        assert_eq!(rune_block_range(Some(5), Some(2000), 10).unwrap(), (5, 10));
+        assert_eq!(
+            rune_block_range(Some(0), Some(2015), 5000).unwrap(),
+            (0, 2015)
+        );
+
+        assert!(matches!(
+            rune_block_range(Some(0), Some(2016), 5000),
+            Err(ApiError::InvalidRange(_))
+        ));
+        assert!(matches!(
+            rune_block_range(Some(0), None, 5000),
+            Err(ApiError::InvalidRange(_))
+        ));
+        assert!(matches!(
+            rune_block_range(Some(11), None, 10),
+            Err(ApiError::InvalidRange(_))
+        ));
+    }
+
+    #[test]
+    fn mint_velocity_and_estimate() {
+        // 20 mints over 10 blocks, 100 left.
+        assert_eq!(mint_velocity(20, 10, 900, 1000, true), (2.0, Some(50)));
+        // A partial block at the end still counts.
+        assert_eq!(mint_velocity(3, 2, 0, 10, true), (1.5, Some(7)));
+        // Nobody minted in the range.
+        assert_eq!(mint_velocity(0, 10, 900, 1000, true), (0.0, None));
+        // The cap was reached, so minting is closed.
+        assert_eq!(mint_velocity(20, 10, 1000, 1000, false), (2.0, None));
+        // Mints past the cap never make the estimate wrap around.
+        assert_eq!(mint_velocity(20, 10, 1001, 1000, true), (2.0, Some(0)));
+    }
+
+    #[test]
+    fn search_refuses_sort_with_name_prefix() {
+        let (_dir, _db, index) = Index::open_temp();
+        let index = Arc::new(index);
+
+        let search = RuneSearch {
+            q: Some("DOG".into()),
+            sort: Some(RuneSortBy::UtxoCount),
+            ..Default::default()
+        };
+        assert!(matches!(
+            search_runes(index.clone(), &search, Pagination::default()),
+            Err(ApiError::InvalidSearch(_))
+        ));
+
+        let search = RuneSearch {
+            sort: None,
+            ..search
+        };
+        assert!(search_runes(index, &search, Pagination::default())
+            .unwrap()
+            .items
+            .is_empty());
+    }
+
+    #[test]
+    fn rune_transaction_pages_count_from_the_newest() {
+        let (_dir, db, index) = Index::open_temp();
+        let index = Arc::new(index);
+        let rune_id = RuneId::new(840000, 1).unwrap();
+        let query = query::Rune::Id(rune_id);
+
+        let txids = |range: std::ops::RangeInclusive<u8>| -> Vec<Txid> {
+            range.map(|n| outpoint(n).txid).collect()
+        };
+        db.add_rune_transactions_batch(&HashMap::from([(rune_id, txids(1..=5))]), false)
+            .unwrap();
+        db.add_rune_transactions_batch(&HashMap::from([(rune_id, txids(6..=7))]), true)
+            .unwrap();
+
+        let page = |cursor: Option<String>| {
+            let pagination = Pagination {
+                skip: 0,
+                limit: 2,
+                cursor,
+            };
+            last_rune_transactions(index.clone(), &query, Some(pagination)).unwrap()
+        };
+
+        let mut offsets = Vec::new();
+        let mut cursor = None;
+        let last = loop {
+            let response = page(cursor);
+            offsets.push(response.offset);
+            match response.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break response,
+            }
+        };
+        assert_eq!(offsets, vec![2, 4, 6, 7]);
+
+        let previous = page(last.prev_cursor);
+        assert_eq!(previous.items, vec![outpoint(3).txid, outpoint(2).txid]);
+        assert_eq!(previous.offset, 6);
+    }
+
+    #[test]
+    fn batches_are_limited() {
+        let (_dir, _db, index) = Index::open_temp();
+        let index = Arc::new(index);
+
+        let outpoints: Vec<OutPoint> = (0..=MAX_BATCH_SIZE as u32)
+            .map(|vout| OutPoint::new(Txid::all_zeros(), vout))
+            .collect();
+        assert!(matches!(
+            outputs(index.clone(), &outpoints),
+            Err(ApiError::BatchTooLarge(n)) if n == MAX_BATCH_SIZE + 1
+        ));
+        assert!(outputs(index.clone(), &outpoints[..MAX_BATCH_SIZE].to_vec()).is_ok());
+
+        let rune_ids: Vec<RuneId> = (0..=MAX_BATCH_SIZE as u32)
+            .map(|tx| RuneId::new(840000, tx).unwrap())
+            .collect();
+        assert!(matches!(
+            runes_by_ids(index.clone(), &rune_ids),
+            Err(ApiError::BatchTooLarge(_))
+        ));
+
+        let txids: Vec<Txid> = outpoints.iter().map(|_| Txid::all_zeros()).collect();
+        assert!(matches!(
+            transaction_statuses(index, &txids),
+            Err(ApiError::BatchTooLarge(_))
+        ));
+    }
+}
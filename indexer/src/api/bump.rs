@@ -0,0 +1,67 @@
+use titan_types::MempoolEntry;
+
+/// Bitcoin Core's default `-minrelaytxfee`, in sat/vB.
+pub(super) const MIN_RELAY_FEE_RATE: f64 = 1.0;
+
+/// Bitcoin Core's default `-incrementalrelayfee`, in sat/vB. A replacement
+/// pays this on top of the fees of everything it evicts.
+const INCREMENTAL_RELAY_FEE_RATE: f64 = 1.0;
+
+/// A one-input taproot spend with a change output and a runestone.
+pub(super) const DEFAULT_CPFP_CHILD_VSIZE: u64 = 200;
+
+fn fee_at(fee_rate: f64, vsize: u64) -> u64 {
+    (fee_rate * vsize as f64).ceil() as u64
+}
+
+/// Fee a replacement of the same vsize as `entry` needs under BIP 125.
+pub(super) fn rbf_fee(entry: &MempoolEntry, target_fee_rate: f64) -> u64 {
+    let evicted = entry.fees.descendant + fee_at(INCREMENTAL_RELAY_FEE_RATE, entry.vsize);
+    fee_at(target_fee_rate, entry.vsize).max(evicted)
+}
+
+/// Fee a child of `child_vsize` needs so that `entry` and its unconfirmed
+/// ancestors, mined together with it, reach the target rate.
+pub(super) fn cpfp_fee(entry: &MempoolEntry, target_fee_rate: f64, child_vsize: u64) -> u64 {
+    fee_at(target_fee_rate, entry.ancestor_size + child_vsize)
+        .saturating_sub(entry.fees.ancestor)
+        .max(fee_at(MIN_RELAY_FEE_RATE, child_vsize))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, titan_types::MempoolEntryFee};
+
+    fn entry() -> MempoolEntry {
+        // 200 vB at 2 sat/vB with a 100 vB parent at 1 sat/vB and a 150 vB
+        // child at 2 sat/vB.
+        MempoolEntry {
+            vsize: 200,
+            weight: Some(800),
+            descendant_count: 2,
+            descendant_size: 350,
+            ancestor_count: 2,
+            ancestor_size: 300,
+            fees: MempoolEntryFee {
+                base: 400,
+                descendant: 700,
+                ancestor: 500,
+            },
+            depends: vec![],
+            spent_by: vec![],
+        }
+    }
+
+    #[test]
+    fn rbf_pays_for_evicted_descendants() {
+        assert_eq!(rbf_fee(&entry(), 2.5), 900);
+        assert_eq!(rbf_fee(&entry(), 10.0), 2_000);
+    }
+
+    #[test]
+    fn cpfp_lifts_ancestor_package() {
+        assert_eq!(cpfp_fee(&entry(), 10.0, 200), 4_500);
+        assert_eq!(cpfp_fee(&entry(), 1.5, 200), 250);
+        assert_eq!(cpfp_fee(&entry(), 1.0, 200), 200);
+    }
+}
@@ -0,0 +1,118 @@
+use {
+    super::{ApiError, Result},
+    borsh::{BorshDeserialize, BorshSerialize},
+};
+
+/// Position in a newest-first list, handed out as `next_cursor` and
+/// `prev_cursor`. Both variants exclude `key` itself, so items added while
+/// paging never shift the following pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub(super) enum Cursor<K> {
+    /// Items older than `key`, walking towards the end of the list.
+    Older(K),
+    /// Items newer than `key`, walking back towards the start of the list.
+    Newer(K),
+}
+
+impl<K: Copy + BorshSerialize + BorshDeserialize> Cursor<K> {
+    pub fn encode(&self) -> String {
+        hex::encode(borsh::to_vec(self).expect("cursor serialization"))
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self> {
+        hex::decode(cursor)
+            .ok()
+            .and_then(|bytes| borsh::from_slice(&bytes).ok())
+            .ok_or_else(|| ApiError::InvalidCursor(cursor.to_string()))
+    }
+
+    pub fn from_pagination(cursor: Option<&str>) -> Result<Option<Self>> {
+        cursor.map(Self::decode).transpose()
+    }
+
+    /// Returns `(next_cursor, prev_cursor)` for a page whose items, newest
+    /// first, go from `first` to `last`. `more` tells whether the walk in the
+    /// cursor's direction stopped before the end of the list, and `skipped`
+    /// whether a page fetched without a cursor didn't start at the top.
+    pub fn page(
+        cursor: Option<Self>,
+        first: Option<K>,
+        last: Option<K>,
+        more: bool,
+        skipped: bool,
+    ) -> (Option<String>, Option<String>) {
+        let older = |key: Option<K>| key.map(|key| Self::Older(key).encode());
+        let newer = |key: Option<K>| key.map(|key| Self::Newer(key).encode());
+
+        match cursor {
+            None => (
+                more.then(|| older(last)).flatten(),
+                skipped.then(|| newer(first)).flatten(),
+            ),
+            Some(Self::Older(key)) => (
+                more.then(|| older(last)).flatten(),
+                newer(first.or(Some(key))),
+            ),
+            Some(Self::Newer(key)) => (
+                older(last.or(Some(key))),
+                more.then(|| newer(first)).flatten(),
+            ),
+        }
+    }
+}
+
+/// Key of a rune transaction. Mempool transactions are newer than confirmed
+/// ones; within each, a higher index is newer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub(super) struct RuneTransactionKey {
+    pub mempool: bool,
+    pub index: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let cursor = Cursor::Older(RuneTransactionKey {
+            mempool: true,
+            index: 3456,
+        });
+
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(Cursor::<u64>::decode("zz").is_err());
+        assert!(Cursor::<u64>::decode("00").is_err());
+    }
+
+    #[test]
+    fn page_cursors() {
+        let decode = |cursor: Option<String>| cursor.map(|c| Cursor::<u64>::decode(&c).unwrap());
+
+        // First page with more items behind it.
+        let (next, prev) = Cursor::<u64>::page(None, Some(9), Some(5), true, false);
+        assert_eq!(decode(next), Some(Cursor::Older(5)));
+        assert_eq!(decode(prev), None);
+
+        // Last page, reached by walking forward.
+        let (next, prev) =
+            Cursor::<u64>::page(Some(Cursor::Older(5)), Some(4), Some(0), false, false);
+        assert_eq!(decode(next), None);
+        assert_eq!(decode(prev), Some(Cursor::Newer(4)));
+
+        // Walking back to the top.
+        let (next, prev) =
+            Cursor::<u64>::page(Some(Cursor::Newer(4)), Some(9), Some(5), false, false);
+        assert_eq!(decode(next), Some(Cursor::Older(5)));
+        assert_eq!(decode(prev), None);
+
+        // An empty page still leads back to where it started.
+        let (next, prev) = Cursor::<u64>::page(Some(Cursor::Older(0)), None, None, false, false);
+        assert_eq!(decode(next), None);
+        assert_eq!(decode(prev), Some(Cursor::Newer(0)));
+    }
+}
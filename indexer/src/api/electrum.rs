@@ -0,0 +1,605 @@
+//! The index in the shape of the Electrum protocol's `blockchain.*` results.
+//!
+//! Scripthashes are resolved through the scripthash index and history comes
+//! from the outputs the address index keeps, spent ones included. Both are
+//! only complete on an index that kept them from its first block, so
+//! scripthash methods are refused on older indexes until they're reindexed.
+//!
+//! Headers and merkle proofs come from the stored blocks, and fee estimates
+//! from the same mempool projection as the Esplora ones.
+
+use {
+    super::{
+        bump::MIN_RELAY_FEE_RATE, esplora, require_script_history, script_history::ScriptHistory,
+        ApiError, Result,
+    },
+    crate::index::{Index, IndexError, StoreError},
+    bitcoin::{
+        consensus,
+        hashes::{sha256, sha256d, Hash},
+        Address, ScriptBuf, Txid,
+    },
+    hex::FromHex,
+    serde::Serialize,
+    std::{collections::HashSet, str::FromStr},
+    titan_types::{AddressData, SpentStatus},
+};
+
+/// Most headers `blockchain.block.headers` returns at once, like ElectrumX.
+pub const MAX_HEADERS: u64 = 2016;
+
+/// Vsize of the first bin of `mempool.get_fee_histogram`. Each one after is
+/// 10% larger, like ElectrumX.
+const FEE_HISTOGRAM_BIN_VSIZE: f64 = 100_000.0;
+
+/// Sats per vbyte to BTC per kvbyte, the unit Electrum fee rates are in.
+const SAT_PER_VB_TO_BTC_PER_KVB: f64 = 100_000.0;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Header {
+    pub height: u64,
+    pub hex: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Headers {
+    pub count: u64,
+    /// The headers concatenated.
+    pub hex: String,
+    pub max: u64,
+    #[serde(flatten)]
+    pub proof: Option<HeaderProof>,
+}
+
+/// Proof that the last header requested is in the chain up to a checkpoint.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct HeaderProof {
+    pub branch: Vec<String>,
+    pub root: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ProvenHeader {
+    pub header: String,
+    #[serde(flatten)]
+    pub proof: HeaderProof,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MerkleProof {
+    pub block_height: u64,
+    pub merkle: Vec<String>,
+    pub pos: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Balance {
+    pub confirmed: u64,
+    /// Negative when the mempool spends more than it pays in.
+    pub unconfirmed: i64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct HistoryEntry {
+    pub tx_hash: Txid,
+    /// Zero for mempool transactions, -1 for those spending mempool outputs.
+    pub height: i64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Unspent {
+    pub tx_hash: Txid,
+    pub tx_pos: u32,
+    /// Zero for mempool outputs.
+    pub height: u64,
+    pub value: u64,
+}
+
+/// Electrum scripthashes are the sha256 of the script, hex encoded in
+/// reverse byte order.
+pub fn parse_script_hash(hex: &str) -> Result<[u8; 32]> {
+    let mut script_hash = <[u8; 32]>::from_hex(hex)?;
+    script_hash.reverse();
+    Ok(script_hash)
+}
+
+/// The scripthash of `address`, in the byte order `parse_script_hash` returns.
+pub fn address_script_hash(address: &str) -> Option<[u8; 32]> {
+    let address = Address::from_str(address).ok()?.assume_checked();
+    Some(sha256::Hash::hash(address.script_pubkey().as_bytes()).to_byte_array())
+}
+
+pub fn header(index: &Index) -> Result<Header> {
+    let height = index.get_block_count()?.saturating_sub(1);
+    Ok(Header {
+        height,
+        hex: block_header(index, height)?,
+    })
+}
+
+/// The header at `height`, hex encoded.
+pub fn block_header(index: &Index, height: u64) -> Result<String> {
+    let block = index.get_block_by_hash(&index.get_block_hash(height)?)?;
+    Ok(consensus::encode::serialize_hex(&block.header))
+}
+
+/// The header at `height`, with its proof up to the `cp_height` checkpoint.
+pub fn proven_block_header(index: &Index, height: u64, cp_height: u64) -> Result<ProvenHeader> {
+    Ok(ProvenHeader {
+        header: block_header(index, height)?,
+        proof: header_proof(index, height, cp_height)?,
+    })
+}
+
+/// Up to `MAX_HEADERS` headers from `start_height`, fewer past the tip. With a
+/// `cp_height` checkpoint, the last one is proven up to it.
+pub fn block_headers(
+    index: &Index,
+    start_height: u64,
+    count: u64,
+    cp_height: u64,
+) -> Result<Headers> {
+    let end = index
+        .get_block_count()?
+        .min(start_height.saturating_add(count.min(MAX_HEADERS)));
+
+    let mut hex = String::new();
+    for height in start_height..end {
+        hex.push_str(&block_header(index, height)?);
+    }
+
+    let count = end.saturating_sub(start_height);
+    let proof = match (cp_height, count) {
+        (0, _) | (_, 0) => None,
+        _ => Some(header_proof(index, end - 1, cp_height)?),
+    };
+
+    Ok(Headers {
+        count,
+        hex,
+        max: MAX_HEADERS,
+        proof,
+    })
+}
+
+/// The branch of the transaction `txid` in the block at `height`.
+pub fn merkle_proof(index: &Index, txid: &Txid, height: u64) -> Result<MerkleProof> {
+    let block = index.get_block_by_hash(&index.get_block_hash(height)?)?;
+    let leaves = block
+        .tx_ids
+        .iter()
+        .map(|tx_id| Ok(Txid::from_str(tx_id)?.to_raw_hash()))
+        .collect::<std::result::Result<Vec<_>, StoreError>>()
+        .map_err(IndexError::from)?;
+
+    let pos = leaves
+        .iter()
+        .position(|leaf| *leaf == txid.to_raw_hash())
+        .ok_or_else(|| {
+            IndexError::StoreError(StoreError::NotFound(format!(
+                "transaction {txid} in block {height}"
+            )))
+        })?;
+    let (branch, _) = merkle_branch(leaves, pos);
+
+    Ok(MerkleProof {
+        block_height: height,
+        merkle: branch.iter().map(ToString::to_string).collect(),
+        pos,
+    })
+}
+
+/// BTC per kvbyte to confirm within `target` blocks, the Esplora estimate for
+/// the nearest target at or after it.
+pub fn estimate_fee(index: &Index, target: u16) -> Result<f64> {
+    let estimates = esplora::fee_estimates(index)?;
+    let fee_rate = estimates
+        .range(target..)
+        .next()
+        .or(estimates.last_key_value())
+        .map_or(MIN_RELAY_FEE_RATE, |(_, fee_rate)| *fee_rate);
+
+    Ok(fee_rate / SAT_PER_VB_TO_BTC_PER_KVB)
+}
+
+/// The lowest fee rate relayed, in BTC per kvbyte.
+pub fn relay_fee() -> f64 {
+    MIN_RELAY_FEE_RATE / SAT_PER_VB_TO_BTC_PER_KVB
+}
+
+/// `[fee rate, vsize]` of the mempool in bins, highest fee rate first. Each
+/// bin's fee rate is the lowest in it.
+pub fn fee_histogram(index: &Index) -> Result<Vec<(f64, u64)>> {
+    let fee_rates = index
+        .get_all_mempool_entries()?
+        .into_values()
+        .filter(|entry| entry.vsize > 0)
+        .map(|entry| (entry.fees.base as f64 / entry.vsize as f64, entry.vsize))
+        .collect();
+
+    Ok(fee_histogram_of(fee_rates))
+}
+
+pub fn balance(index: &Index, script_hash: &[u8; 32]) -> Result<Balance> {
+    Ok(outputs(index, script_hash)?
+        .map(|data| balance_of(&data))
+        .unwrap_or_default())
+}
+
+pub fn history(index: &Index, script_hash: &[u8; 32]) -> Result<Vec<HistoryEntry>> {
+    let Some(script_pubkey) = script_pubkey(index, script_hash)? else {
+        return Ok(Vec::new());
+    };
+
+    let history = ScriptHistory::of(index, &script_pubkey)?;
+    let mempool_txids: Vec<Txid> = history
+        .transactions()
+        .into_iter()
+        .filter(|(_, status)| !status.confirmed)
+        .map(|(txid, _)| txid)
+        .collect();
+    let unconfirmed_parents = index
+        .get_mempool_entries(&mempool_txids)?
+        .into_iter()
+        .filter(|(_, entry)| matches!(entry, Some(entry) if !entry.depends.is_empty()))
+        .map(|(txid, _)| txid)
+        .collect();
+
+    Ok(history_of(&history, &unconfirmed_parents))
+}
+
+/// Leaves out the outputs a mempool transaction spends.
+pub fn unspent(index: &Index, script_hash: &[u8; 32]) -> Result<Vec<Unspent>> {
+    let Some(data) = outputs(index, script_hash)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut unspent: Vec<Unspent> = data
+        .outputs
+        .into_iter()
+        .filter(|output| matches!(output.spent, SpentStatus::Unspent))
+        .map(|output| Unspent {
+            tx_hash: output.txid,
+            tx_pos: output.vout,
+            height: output.status.block_height.unwrap_or_default(),
+            value: output.value,
+        })
+        .collect();
+
+    unspent.sort_by_key(|unspent| (unspent.height == 0, unspent.height, unspent.tx_hash));
+    Ok(unspent)
+}
+
+/// The hash `blockchain.scripthash.subscribe` reports, `None` without
+/// history.
+pub fn status(index: &Index, script_hash: &[u8; 32]) -> Result<Option<String>> {
+    Ok(status_of(&history(index, script_hash)?))
+}
+
+fn script_pubkey(index: &Index, script_hash: &[u8; 32]) -> Result<Option<ScriptBuf>> {
+    require_script_history(index)?;
+
+    match index.get_script_pubkey_by_hash(script_hash) {
+        Ok(script_pubkey) => Ok(Some(script_pubkey)),
+        Err(IndexError::StoreError(StoreError::NotFound(_))) => Ok(None),
+        Err(error) => Err(ApiError::IndexError(error)),
+    }
+}
+
+/// Current outputs and the mempool transactions spending them.
+fn outputs(index: &Index, script_hash: &[u8; 32]) -> Result<Option<AddressData>> {
+    script_pubkey(index, script_hash)?
+        .map(|script_pubkey| Ok(index.get_script_pubkey_outputs(&script_pubkey)?))
+        .transpose()
+}
+
+fn balance_of(data: &AddressData) -> Balance {
+    let mut balance = Balance::default();
+
+    for output in &data.outputs {
+        if output.status.confirmed {
+            balance.confirmed += output.value;
+        } else {
+            balance.unconfirmed += output.value as i64;
+        }
+
+        if let SpentStatus::Spent(_) = output.spent {
+            balance.unconfirmed -= output.value as i64;
+        }
+    }
+
+    balance
+}
+
+/// Confirmed transactions by height, then the mempool ones, those with
+/// `unconfirmed_parents` last.
+fn history_of(history: &ScriptHistory, unconfirmed_parents: &HashSet<Txid>) -> Vec<HistoryEntry> {
+    let mut history: Vec<HistoryEntry> = history
+        .transactions()
+        .into_iter()
+        .map(|(tx_hash, status)| HistoryEntry {
+            tx_hash,
+            height: match status.block_height {
+                Some(height) => height as i64,
+                None if unconfirmed_parents.contains(&tx_hash) => -1,
+                None => 0,
+            },
+        })
+        .collect();
+
+    history.sort_by_key(|entry| (entry.height <= 0, entry.height.abs(), entry.tx_hash));
+    history
+}
+
+fn header_proof(index: &Index, height: u64, cp_height: u64) -> Result<HeaderProof> {
+    if height > cp_height {
+        return Err(ApiError::InvalidRange(format!(
+            "height {height} is past checkpoint {cp_height}"
+        )));
+    }
+
+    let leaves = (0..=cp_height)
+        .map(|height| Ok(index.get_block_hash(height)?.to_raw_hash()))
+        .collect::<Result<Vec<_>>>()?;
+    let (branch, root) = merkle_branch(leaves, height as usize);
+
+    Ok(HeaderProof {
+        branch: branch.iter().map(ToString::to_string).collect(),
+        root: root.to_string(),
+    })
+}
+
+/// The hashes `leaves[position]` is paired with on the way up to the merkle
+/// root, and the root, for a tree built like a block's transaction tree.
+fn merkle_branch(
+    mut level: Vec<sha256d::Hash>,
+    mut position: usize,
+) -> (Vec<sha256d::Hash>, sha256d::Hash) {
+    let mut branch = Vec::new();
+
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            level.push(level[level.len() - 1]);
+        }
+
+        branch.push(level[position ^ 1]);
+        position /= 2;
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                sha256d::Hash::hash(&[pair[0].to_byte_array(), pair[1].to_byte_array()].concat())
+            })
+            .collect();
+    }
+
+    (branch, level[0])
+}
+
+fn fee_histogram_of(mut fee_rates: Vec<(f64, u64)>) -> Vec<(f64, u64)> {
+    fee_rates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut histogram = Vec::new();
+    let mut bin_vsize = FEE_HISTOGRAM_BIN_VSIZE;
+    let mut vsize = 0;
+    let mut last_fee_rate = 0.0;
+
+    for (fee_rate, tx_vsize) in fee_rates {
+        vsize += tx_vsize;
+        last_fee_rate = fee_rate;
+
+        if vsize as f64 >= bin_vsize {
+            histogram.push((fee_rate, vsize));
+            vsize = 0;
+            bin_vsize *= 1.1;
+        }
+    }
+
+    if vsize > 0 {
+        histogram.push((last_fee_rate, vsize));
+    }
+
+    histogram
+}
+
+fn status_of(history: &[HistoryEntry]) -> Option<String> {
+    if history.is_empty() {
+        return None;
+    }
+
+    let status: String = history
+        .iter()
+        .map(|entry| format!("{}:{}:", entry.tx_hash, entry.height))
+        .collect();
+
+    Some(sha256::Hash::hash(status.as_bytes()).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::api::script_history::tests::{confirmed, output, txid},
+        bitcoin::TxMerkleNode,
+        std::collections::HashMap,
+        titan_types::TransactionStatus,
+    };
+
+    #[test]
+    fn balance_follows_the_mempool() {
+        let data = AddressData {
+            value: 0,
+            runes: Vec::new(),
+            outputs: vec![
+                output(txid(2), 500, TransactionStatus::unconfirmed(), None),
+                output(txid(1), 1000, confirmed(100), Some(txid(3))),
+            ],
+        };
+
+        assert_eq!(
+            balance_of(&data),
+            Balance {
+                confirmed: 1000,
+                unconfirmed: -500,
+            }
+        );
+    }
+
+    #[test]
+    fn history_includes_spent_outputs() {
+        let (funding, spent, received, spender) = (txid(1), txid(2), txid(3), txid(4));
+
+        let history = ScriptHistory {
+            outputs: vec![
+                output(received, 500, TransactionStatus::unconfirmed(), None),
+                output(funding, 1000, confirmed(100), Some(spent)),
+                output(spent, 700, confirmed(101), Some(spender)),
+            ],
+            spenders: HashMap::from([
+                (spent, confirmed(101)),
+                (spender, TransactionStatus::unconfirmed()),
+            ]),
+        };
+
+        let history = history_of(&history, &HashSet::new());
+        assert_eq!(
+            history,
+            vec![
+                HistoryEntry {
+                    tx_hash: funding,
+                    height: 100,
+                },
+                HistoryEntry {
+                    tx_hash: spent,
+                    height: 101,
+                },
+                HistoryEntry {
+                    tx_hash: received,
+                    height: 0,
+                },
+                HistoryEntry {
+                    tx_hash: spender,
+                    height: 0,
+                },
+            ]
+        );
+
+        // A fully spent script still has a status.
+        let fully_spent = ScriptHistory {
+            outputs: vec![output(funding, 1000, confirmed(100), Some(spent))],
+            spenders: HashMap::from([(spent, confirmed(101))]),
+        };
+        assert!(status_of(&history_of(&fully_spent, &HashSet::new())).is_some());
+
+        assert_eq!(status_of(&[]), None);
+        assert_eq!(
+            status_of(&history),
+            Some(
+                sha256::Hash::hash(
+                    format!("{funding}:100:{spent}:101:{received}:0:{spender}:0:").as_bytes()
+                )
+                .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn mempool_transactions_spending_the_mempool_come_last() {
+        let (received, child) = (txid(3), txid(1));
+
+        let history = ScriptHistory {
+            outputs: vec![output(
+                received,
+                500,
+                TransactionStatus::unconfirmed(),
+                Some(child),
+            )],
+            spenders: HashMap::from([(child, TransactionStatus::unconfirmed())]),
+        };
+
+        let history = history_of(&history, &HashSet::from([child]));
+        assert_eq!(
+            history,
+            vec![
+                HistoryEntry {
+                    tx_hash: received,
+                    height: 0,
+                },
+                HistoryEntry {
+                    tx_hash: child,
+                    height: -1,
+                },
+            ]
+        );
+        assert_eq!(
+            status_of(&history),
+            Some(sha256::Hash::hash(format!("{received}:0:{child}:-1:").as_bytes()).to_string())
+        );
+    }
+
+    #[test]
+    fn merkle_branches_lead_to_the_merkle_root() {
+        for count in 1..=7u8 {
+            let txids: Vec<Txid> = (1..=count).map(txid).collect();
+            let merkle_root = bitcoin::merkle_tree::calculate_root(
+                txids
+                    .iter()
+                    .map(|txid| TxMerkleNode::from_raw_hash(txid.to_raw_hash())),
+            )
+            .unwrap();
+
+            for position in 0..txids.len() {
+                let leaves = txids.iter().map(|txid| txid.to_raw_hash()).collect();
+                let (branch, root) = merkle_branch(leaves, position);
+                assert_eq!(root, merkle_root.to_raw_hash());
+
+                let folded = branch.iter().enumerate().fold(
+                    txids[position].to_raw_hash(),
+                    |hash, (depth, sibling)| {
+                        let (left, right) = match (position >> depth) % 2 {
+                            0 => (hash, *sibling),
+                            _ => (*sibling, hash),
+                        };
+                        sha256d::Hash::hash(&[left.to_byte_array(), right.to_byte_array()].concat())
+                    },
+                );
+                assert_eq!(folded, root);
+            }
+        }
+    }
+
+    #[test]
+    fn fee_histogram_bins_grow() {
+        let mut fee_rates = vec![(1.0, 50_000)];
+        fee_rates.extend((0..10).map(|n| (20.0 - n as f64, 30_000)));
+
+        assert_eq!(
+            fee_histogram_of(fee_rates),
+            vec![(17.0, 120_000), (13.0, 120_000), (1.0, 110_000),]
+        );
+        assert!(fee_histogram_of(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn script_hashes_are_reversed() {
+        let script_hash =
+            parse_script_hash("0100000000000000000000000000000000000000000000000000000000000002")
+                .unwrap();
+        assert_eq!(script_hash[0], 2);
+        assert_eq!(script_hash[31], 1);
+        assert!(parse_script_hash("01").is_err());
+    }
+
+    #[test]
+    fn address_script_hash_matches_the_protocol_example() {
+        assert_eq!(
+            address_script_hash("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+            Some(
+                parse_script_hash(
+                    "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161"
+                )
+                .unwrap()
+            )
+        );
+        assert_eq!(address_script_hash("not an address"), None);
+    }
+}
@@ -0,0 +1,644 @@
+//! The index in the Esplora REST dialect, for wallets and sync libraries that
+//! speak it.
+//!
+//! Address and scripthash endpoints need `--index-addresses`. Their history
+//! comes from the outputs the address index keeps, spent ones included.
+//! Outputs spent in a block before the index kept spent outputs are missing,
+//! along with their spending transactions.
+
+use {
+    super::{
+        api::{bitcoin_transaction, outspends_of},
+        bump::MIN_RELAY_FEE_RATE,
+        script_history::ScriptHistory,
+        tx_detail, Result,
+    },
+    crate::{
+        bitcoin_rpc::PooledClient,
+        index::{Index, IndexError, StoreError},
+    },
+    bitcoin::{
+        consensus, Address, BlockHash, OutPoint, Script, ScriptBuf,
+        Transaction as BitcoinTransaction, TxMerkleNode, Txid,
+    },
+    bitcoincore_rpc::RpcApi,
+    serde::Serialize,
+    std::{
+        cmp::Reverse,
+        collections::{BTreeMap, HashMap, HashSet},
+    },
+    titan_types::{MempoolBlock, SpentStatus, TransactionStatus},
+};
+
+/// Mempool and confirmed transactions `/address/{address}/txs` returns, like
+/// Esplora. `/txs/chain/{last_seen_txid}` pages through the rest.
+const MAX_MEMPOOL_TXS: usize = 50;
+const MAX_CHAIN_TXS: usize = 25;
+
+/// Blocks `/blocks` lists at once, like Esplora.
+const MAX_BLOCKS: u64 = 10;
+
+/// Confirmation targets `/fee-estimates` answers for, like Esplora.
+const FEE_ESTIMATE_TARGETS: [u16; 28] = [
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 144,
+    504, 1008,
+];
+
+#[derive(Debug, Serialize)]
+pub struct Block {
+    pub id: BlockHash,
+    pub height: u64,
+    pub version: i32,
+    pub timestamp: u32,
+    pub tx_count: u64,
+    pub size: u64,
+    pub weight: u64,
+    pub merkle_root: TxMerkleNode,
+    pub previousblockhash: Option<BlockHash>,
+    pub mediantime: u64,
+    pub nonce: u32,
+    pub bits: u32,
+    pub difficulty: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockStatus {
+    pub in_best_chain: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
+    pub next_best: Option<BlockHash>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TxStatus {
+    pub confirmed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<BlockHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_time: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tx {
+    pub txid: Txid,
+    pub version: i32,
+    pub locktime: u32,
+    pub vin: Vec<Vin>,
+    pub vout: Vec<Vout>,
+    pub size: u64,
+    pub weight: u64,
+    /// Zero for coinbase transactions and when an input can't be resolved.
+    pub fee: u64,
+    pub status: TxStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Vin {
+    pub txid: Txid,
+    pub vout: u32,
+    pub prevout: Option<Vout>,
+    pub scriptsig: String,
+    pub scriptsig_asm: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub witness: Vec<String>,
+    pub is_coinbase: bool,
+    pub sequence: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Vout {
+    pub scriptpubkey: String,
+    pub scriptpubkey_asm: String,
+    pub scriptpubkey_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scriptpubkey_address: Option<String>,
+    pub value: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Outspend {
+    pub spent: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txid: Option<Txid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vin: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<TxStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AddressStats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scripthash: Option<String>,
+    pub chain_stats: Stats,
+    pub mempool_stats: Stats,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub funded_txo_count: u64,
+    pub funded_txo_sum: u64,
+    pub spent_txo_count: u64,
+    pub spent_txo_sum: u64,
+    pub tx_count: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Utxo {
+    pub txid: Txid,
+    pub vout: u32,
+    pub status: TxStatus,
+    pub value: u64,
+}
+
+pub fn block(index: &Index, client: &PooledClient, hash: &BlockHash) -> Result<Block> {
+    let block = index.get_block_by_hash(hash)?;
+    let info = client.get_block_info(hash)?;
+    let header = block.header;
+
+    Ok(Block {
+        id: *hash,
+        height: block.height,
+        version: header.version.to_consensus(),
+        timestamp: header.time,
+        tx_count: block.tx_ids.len() as u64,
+        size: info.size as u64,
+        weight: info.weight as u64,
+        merkle_root: header.merkle_root,
+        previousblockhash: (block.height > 0).then_some(header.prev_blockhash),
+        mediantime: info.mediantime.unwrap_or_default() as u64,
+        nonce: header.nonce,
+        bits: header.bits.to_consensus(),
+        difficulty: header.difficulty_float(),
+    })
+}
+
+/// The `MAX_BLOCKS` newest blocks at or below `start_height`, by default the
+/// tip. Empty past the tip.
+pub fn blocks(
+    index: &Index,
+    client: &PooledClient,
+    start_height: Option<u64>,
+) -> Result<Vec<Block>> {
+    let Some(tip) = index.get_block_count()?.checked_sub(1) else {
+        return Ok(Vec::new());
+    };
+
+    let start_height = start_height.unwrap_or(tip);
+    if start_height > tip {
+        return Ok(Vec::new());
+    }
+
+    (start_height.saturating_sub(MAX_BLOCKS - 1)..=start_height)
+        .rev()
+        .map(|height| block(index, client, &index.get_block_hash(height)?))
+        .collect()
+}
+
+pub fn block_header(index: &Index, hash: &BlockHash) -> Result<String> {
+    let block = index.get_block_by_hash(hash)?;
+    Ok(consensus::encode::serialize_hex(&block.header))
+}
+
+/// Blocks the index doesn't know are reported as out of the best chain, like
+/// Esplora does for stale blocks.
+pub fn block_status(index: &Index, hash: &BlockHash) -> Result<BlockStatus> {
+    let Ok(block) = index.get_block_by_hash(hash) else {
+        return Ok(BlockStatus {
+            in_best_chain: false,
+            height: None,
+            next_best: None,
+        });
+    };
+
+    Ok(BlockStatus {
+        in_best_chain: index.get_block_hash(block.height).ok() == Some(*hash),
+        height: Some(block.height),
+        next_best: index.get_block_hash(block.height + 1).ok(),
+    })
+}
+
+pub fn tx(index: &Index, client: &PooledClient, txid: &Txid) -> Result<Tx> {
+    let transaction = bitcoin_transaction(index, client, txid)?;
+    let status = index.get_transaction_status(txid)?;
+    to_tx(index, client, &transaction, status, &mut HashMap::new())
+}
+
+pub fn tx_status(index: &Index, txid: &Txid) -> Result<TxStatus> {
+    let status = index.get_transaction_status(txid)?;
+    to_tx_status(index, status, &mut HashMap::new())
+}
+
+pub fn outspends(index: &Index, client: &PooledClient, txid: &Txid) -> Result<Vec<Outspend>> {
+    let transaction = bitcoin_transaction(index, client, txid)?;
+    let outpoints = (0..transaction.output.len())
+        .map(|vout| OutPoint::new(*txid, vout as u32))
+        .collect();
+
+    to_outspends(index, &outpoints)
+}
+
+pub fn outspend(index: &Index, outpoint: &OutPoint) -> Result<Outspend> {
+    Ok(to_outspends(index, &vec![*outpoint])?.remove(0))
+}
+
+/// The script of an address or scripthash endpoint. Scripthashes are the
+/// sha256 of the script, hex encoded in byte order unlike Electrum's.
+pub enum ScriptQuery {
+    Address(Address),
+    ScriptHash([u8; 32]),
+}
+
+impl ScriptQuery {
+    /// `None` for a scripthash the index has never seen an output for.
+    fn script_pubkey(&self, index: &Index) -> Result<Option<ScriptBuf>> {
+        match self {
+            Self::Address(address) => Ok(Some(address.script_pubkey())),
+            Self::ScriptHash(script_hash) => match index.get_script_pubkey_by_hash(script_hash) {
+                Ok(script_pubkey) => Ok(Some(script_pubkey)),
+                Err(IndexError::StoreError(StoreError::NotFound(_))) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+        }
+    }
+
+    fn history(&self, index: &Index) -> Result<ScriptHistory> {
+        match self.script_pubkey(index)? {
+            Some(script_pubkey) => ScriptHistory::of(index, &script_pubkey),
+            None => Ok(ScriptHistory::default()),
+        }
+    }
+}
+
+pub fn address_stats(index: &Index, query: &ScriptQuery) -> Result<AddressStats> {
+    let (chain_stats, mempool_stats) = stats_of(&query.history(index)?);
+
+    let (address, scripthash) = match query {
+        ScriptQuery::Address(address) => (Some(address.to_string()), None),
+        ScriptQuery::ScriptHash(script_hash) => (None, Some(hex::encode(script_hash))),
+    };
+
+    Ok(AddressStats {
+        address,
+        scripthash,
+        chain_stats,
+        mempool_stats,
+    })
+}
+
+/// Unspent outputs, leaving out those a mempool transaction spends.
+pub fn address_utxos(index: &Index, query: &ScriptQuery) -> Result<Vec<Utxo>> {
+    let Some(script_pubkey) = query.script_pubkey(index)? else {
+        return Ok(Vec::new());
+    };
+
+    let data = index.get_script_pubkey_outputs(&script_pubkey)?;
+    let mut block_times = HashMap::new();
+
+    let mut utxos = data
+        .outputs
+        .into_iter()
+        .filter(|output| matches!(output.spent, SpentStatus::Unspent))
+        .map(|output| {
+            Ok(Utxo {
+                txid: output.txid,
+                vout: output.vout,
+                status: to_tx_status(index, output.status, &mut block_times)?,
+                value: output.value,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    utxos.sort_by_key(|utxo| (utxo.status.block_height.is_some(), utxo.txid, utxo.vout));
+    Ok(utxos)
+}
+
+/// Mempool transactions first, then confirmed ones newest first. Given the
+/// last confirmed transaction seen, the confirmed ones after it.
+pub fn address_txs(
+    index: &Index,
+    client: &PooledClient,
+    query: &ScriptQuery,
+    last_seen: Option<&Txid>,
+) -> Result<Vec<Tx>> {
+    let transactions = query.history(index)?.transactions();
+
+    let mut block_times = HashMap::new();
+    txs_page(&transactions, last_seen)
+        .into_iter()
+        .map(|txid| {
+            let transaction = bitcoin_transaction(index, client, &txid)?;
+            to_tx(
+                index,
+                client,
+                &transaction,
+                transactions[&txid].clone(),
+                &mut block_times,
+            )
+        })
+        .collect()
+}
+
+/// Confirmed and mempool stats. A spend counts where its spending
+/// transaction is, so confirmed outputs spent in the mempool count in both.
+fn stats_of(history: &ScriptHistory) -> (Stats, Stats) {
+    let mut chain_stats = Stats::default();
+    let mut mempool_stats = Stats::default();
+    let mut chain_txids = HashSet::new();
+    let mut mempool_txids = HashSet::new();
+
+    for output in &history.outputs {
+        let (stats, txids) = if output.status.confirmed {
+            (&mut chain_stats, &mut chain_txids)
+        } else {
+            (&mut mempool_stats, &mut mempool_txids)
+        };
+        stats.funded_txo_count += 1;
+        stats.funded_txo_sum += output.value;
+        txids.insert(output.txid);
+
+        if let (SpentStatus::Spent(spender), Some(status)) =
+            (&output.spent, history.spender_status(output))
+        {
+            let (stats, txids) = if status.confirmed {
+                (&mut chain_stats, &mut chain_txids)
+            } else {
+                (&mut mempool_stats, &mut mempool_txids)
+            };
+            stats.spent_txo_count += 1;
+            stats.spent_txo_sum += output.value;
+            txids.insert(spender.txid);
+        }
+    }
+
+    chain_stats.tx_count = chain_txids.len() as u64;
+    mempool_stats.tx_count = mempool_txids.len() as u64;
+
+    (chain_stats, mempool_stats)
+}
+
+fn txs_page(
+    transactions: &HashMap<Txid, TransactionStatus>,
+    last_seen: Option<&Txid>,
+) -> Vec<Txid> {
+    let mut mempool: Vec<Txid> = Vec::new();
+    let mut chain: Vec<(Reverse<u64>, Txid)> = Vec::new();
+    for (txid, status) in transactions {
+        match status.block_height {
+            Some(height) if status.confirmed => chain.push((Reverse(height), *txid)),
+            _ => mempool.push(*txid),
+        }
+    }
+
+    mempool.sort();
+    chain.sort();
+    let chain = chain.into_iter().map(|(_, txid)| txid);
+
+    match last_seen {
+        Some(last_seen) => chain
+            .skip_while(|txid| txid != last_seen)
+            .skip(1)
+            .take(MAX_CHAIN_TXS)
+            .collect(),
+        None => mempool
+            .into_iter()
+            .take(MAX_MEMPOOL_TXS)
+            .chain(chain.take(MAX_CHAIN_TXS))
+            .collect(),
+    }
+}
+
+pub fn fee_estimates(index: &Index) -> Result<BTreeMap<u16, f64>> {
+    let blocks = index.get_mempool_blocks(usize::MAX, None)?.blocks;
+    Ok(fee_estimates_for(&blocks))
+}
+
+/// A target of `n` blocks gets the median fee rate of the `n`th projected
+/// block. Targets past the projection only need to relay.
+fn fee_estimates_for(blocks: &[MempoolBlock]) -> BTreeMap<u16, f64> {
+    FEE_ESTIMATE_TARGETS
+        .iter()
+        .map(|target| {
+            let fee_rate = blocks
+                .get(*target as usize - 1)
+                .map_or(MIN_RELAY_FEE_RATE, |block| block.median_fee_rate);
+            (*target, fee_rate.max(MIN_RELAY_FEE_RATE))
+        })
+        .collect()
+}
+
+fn to_tx(
+    index: &Index,
+    client: &PooledClient,
+    transaction: &BitcoinTransaction,
+    status: TransactionStatus,
+    block_times: &mut HashMap<BlockHash, u32>,
+) -> Result<Tx> {
+    let detail = tx_detail::from_transaction(index, client, transaction, status.clone())?;
+    let is_coinbase = transaction.is_coinbase();
+
+    let vin = transaction
+        .input
+        .iter()
+        .zip(detail.input)
+        .map(|(tx_in, input)| Vin {
+            txid: tx_in.previous_output.txid,
+            vout: tx_in.previous_output.vout,
+            prevout: input
+                .value
+                .zip(input.script_pubkey)
+                .map(|(value, script_pubkey)| to_vout(&script_pubkey, input.address, value)),
+            scriptsig: tx_in.script_sig.to_hex_string(),
+            scriptsig_asm: tx_in.script_sig.to_asm_string(),
+            witness: tx_in.witness.iter().map(hex::encode).collect(),
+            is_coinbase,
+            sequence: tx_in.sequence.0,
+        })
+        .collect();
+
+    let vout = detail
+        .output
+        .into_iter()
+        .map(|output| to_vout(&output.script_pubkey, output.address, output.value))
+        .collect();
+
+    Ok(Tx {
+        txid: detail.txid,
+        version: detail.version,
+        locktime: detail.lock_time,
+        vin,
+        vout,
+        size: detail.size,
+        weight: detail.weight,
+        fee: detail.fee.unwrap_or_default(),
+        status: to_tx_status(index, status, block_times)?,
+    })
+}
+
+fn to_vout(script_pubkey: &Script, address: Option<String>, value: u64) -> Vout {
+    Vout {
+        scriptpubkey: script_pubkey.to_hex_string(),
+        scriptpubkey_asm: script_pubkey.to_asm_string(),
+        scriptpubkey_type: script_type(script_pubkey),
+        scriptpubkey_address: address,
+        value,
+    }
+}
+
+/// Esplora's name for the template of `script`.
+fn script_type(script: &Script) -> &'static str {
+    if script.is_empty() {
+        "empty"
+    } else if script.is_op_return() {
+        "op_return"
+    } else if script.is_p2pk() {
+        "p2pk"
+    } else if script.is_p2pkh() {
+        "p2pkh"
+    } else if script.is_p2sh() {
+        "p2sh"
+    } else if script.is_p2wpkh() {
+        "v0_p2wpkh"
+    } else if script.is_p2wsh() {
+        "v0_p2wsh"
+    } else if script.is_p2tr() {
+        "v1_p2tr"
+    } else if script.is_multisig() {
+        "multisig"
+    } else {
+        "unknown"
+    }
+}
+
+/// Block times are looked up once per block.
+fn to_tx_status(
+    index: &Index,
+    status: TransactionStatus,
+    block_times: &mut HashMap<BlockHash, u32>,
+) -> Result<TxStatus> {
+    let block_time = match status.block_hash {
+        Some(hash) => Some(match block_times.get(&hash) {
+            Some(time) => *time,
+            None => {
+                let time = index.get_block_by_hash(&hash)?.header.time;
+                block_times.insert(hash, time);
+                time
+            }
+        }),
+        None => None,
+    };
+
+    Ok(TxStatus {
+        confirmed: status.confirmed,
+        block_height: status.block_height,
+        block_hash: status.block_hash,
+        block_time,
+    })
+}
+
+/// Outputs the index doesn't track are reported unspent, as Esplora has no
+/// way to say it doesn't know.
+fn to_outspends(index: &Index, outpoints: &Vec<OutPoint>) -> Result<Vec<Outspend>> {
+    let outspends = outspends_of(index, outpoints)?;
+    let mut block_times = HashMap::new();
+
+    outspends
+        .into_iter()
+        .map(|outspend| {
+            Ok(Outspend {
+                spent: outspend.spent.unwrap_or_default(),
+                txid: outspend.spender.as_ref().map(|spender| spender.txid),
+                vin: outspend.spender.map(|spender| spender.vin),
+                status: outspend
+                    .status
+                    .map(|status| to_tx_status(index, status, &mut block_times))
+                    .transpose()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::api::script_history::tests::{confirmed, output, txid},
+    };
+
+    fn block(median_fee_rate: f64) -> MempoolBlock {
+        MempoolBlock {
+            txids: Vec::new(),
+            tx_count: 0,
+            vsize: 0,
+            weight: 0,
+            total_fee: 0,
+            min_fee_rate: 0.0,
+            max_fee_rate: 0.0,
+            median_fee_rate,
+        }
+    }
+
+    #[test]
+    fn fee_estimates_follow_projected_blocks() {
+        let estimates = fee_estimates_for(&[block(20.0), block(8.5), block(0.5)]);
+
+        assert_eq!(estimates.len(), FEE_ESTIMATE_TARGETS.len());
+        assert_eq!(estimates[&1], 20.0);
+        assert_eq!(estimates[&2], 8.5);
+        assert_eq!(estimates[&3], MIN_RELAY_FEE_RATE);
+        assert_eq!(estimates[&1008], MIN_RELAY_FEE_RATE);
+    }
+
+    #[test]
+    fn stats_count_spends_where_the_spender_is() {
+        let history = ScriptHistory {
+            outputs: vec![
+                output(txid(1), 1000, confirmed(100), Some(txid(2))),
+                output(txid(3), 500, confirmed(105), Some(txid(4))),
+                output(txid(4), 200, TransactionStatus::unconfirmed(), None),
+            ],
+            spenders: HashMap::from([
+                (txid(2), confirmed(101)),
+                (txid(4), TransactionStatus::unconfirmed()),
+            ]),
+        };
+
+        let (chain, mempool) = stats_of(&history);
+
+        assert_eq!(chain.funded_txo_count, 2);
+        assert_eq!(chain.funded_txo_sum, 1500);
+        assert_eq!(chain.spent_txo_count, 1);
+        assert_eq!(chain.spent_txo_sum, 1000);
+        assert_eq!(chain.tx_count, 3);
+
+        assert_eq!(mempool.funded_txo_count, 1);
+        assert_eq!(mempool.funded_txo_sum, 200);
+        assert_eq!(mempool.spent_txo_count, 1);
+        assert_eq!(mempool.spent_txo_sum, 500);
+        assert_eq!(mempool.tx_count, 1);
+    }
+
+    #[test]
+    fn txs_page_through_confirmed_history() {
+        let mut transactions: HashMap<Txid, TransactionStatus> = (1..=30)
+            .map(|n| (txid(n), confirmed(u64::from(n))))
+            .collect();
+        transactions.insert(txid(100), TransactionStatus::unconfirmed());
+
+        let first = txs_page(&transactions, None);
+        assert_eq!(first.len(), 1 + MAX_CHAIN_TXS);
+        assert_eq!(first[0], txid(100));
+        assert_eq!(first[1], txid(30));
+        assert_eq!(first[MAX_CHAIN_TXS], txid(6));
+
+        let rest = txs_page(&transactions, Some(&txid(6)));
+        assert_eq!(rest, (1..=5).rev().map(txid).collect::<Vec<_>>());
+
+        assert!(txs_page(&transactions, Some(&txid(1))).is_empty());
+        assert!(txs_page(&transactions, Some(&txid(200))).is_empty());
+    }
+}
@@ -1,6 +1,14 @@
 pub use api::*;
 
 mod api;
+mod bump;
 pub mod content;
+mod cursor;
+pub mod electrum;
+pub mod esplora;
 pub mod query;
 mod re;
+mod risk;
+mod script_history;
+mod tx_detail;
+mod wallet_scan;
@@ -0,0 +1,219 @@
+use {
+    super::{api::bitcoin_transaction, Result},
+    crate::{bitcoin_rpc::PooledClient, index::Index},
+    bitcoin::{OutPoint, Txid},
+    std::collections::{HashMap, HashSet, VecDeque},
+    titan_types::{MempoolEntry, OutputRisk, RuneAmount},
+};
+
+/// Works out the zero-conf risk of outputs. Lookups are cached, so outputs
+/// sharing unconfirmed ancestors, like those of one address, only fetch them
+/// once.
+pub(super) struct RiskAnalyzer<'a> {
+    index: &'a Index,
+    client: &'a PooledClient,
+    entries: HashMap<Txid, Option<MempoolEntry>>,
+    signals_rbf: HashMap<Txid, bool>,
+}
+
+impl<'a> RiskAnalyzer<'a> {
+    pub fn new(index: &'a Index, client: &'a PooledClient) -> Self {
+        Self {
+            index,
+            client,
+            entries: HashMap::new(),
+            signals_rbf: HashMap::new(),
+        }
+    }
+
+    pub fn output_risk(
+        &mut self,
+        outpoint: OutPoint,
+        risky_runes: Vec<RuneAmount>,
+    ) -> Result<OutputRisk> {
+        // Outputs are only indexed for mempool transactions and confirmed
+        // ones.
+        let Some(entry) = self.entries(&[outpoint.txid])?.remove(&outpoint.txid) else {
+            return Ok(OutputRisk {
+                outpoint,
+                confirmed: true,
+                unconfirmed_ancestor_depth: 0,
+                unconfirmed_ancestors: vec![],
+                signals_rbf: false,
+                replaced_txs: vec![],
+                lowest_ancestor_fee_rate: None,
+                risky_runes,
+            });
+        };
+
+        let (unconfirmed_ancestors, mut chain) = self.ancestors(&entry)?;
+        chain.insert(outpoint.txid, entry);
+
+        let mut signals_rbf = false;
+        let mut replaced_txs = Vec::new();
+        for txid in chain.keys() {
+            signals_rbf |= self.signals_rbf(txid)?;
+            replaced_txs.extend(self.index.get_transaction_replacement(txid)?.replaces);
+        }
+        replaced_txs.sort();
+        replaced_txs.dedup();
+
+        Ok(OutputRisk {
+            outpoint,
+            confirmed: false,
+            unconfirmed_ancestor_depth: unconfirmed_depth(&outpoint.txid, &chain),
+            unconfirmed_ancestors,
+            signals_rbf,
+            replaced_txs,
+            lowest_ancestor_fee_rate: lowest_fee_rate(&chain),
+            risky_runes,
+        })
+    }
+
+    /// Mempool entries of `txids`, leaving out the ones no longer in the
+    /// mempool.
+    fn entries(&mut self, txids: &[Txid]) -> Result<HashMap<Txid, MempoolEntry>> {
+        let missing: Vec<Txid> = txids
+            .iter()
+            .filter(|txid| !self.entries.contains_key(*txid))
+            .copied()
+            .collect();
+
+        if !missing.is_empty() {
+            self.entries
+                .extend(self.index.get_mempool_entries(&missing)?);
+        }
+
+        Ok(txids
+            .iter()
+            .filter_map(|txid| {
+                self.entries
+                    .get(txid)
+                    .cloned()
+                    .flatten()
+                    .map(|entry| (*txid, entry))
+            })
+            .collect())
+    }
+
+    /// Unconfirmed ancestors of `entry`, nearest first, along with their
+    /// mempool entries.
+    fn ancestors(
+        &mut self,
+        entry: &MempoolEntry,
+    ) -> Result<(Vec<Txid>, HashMap<Txid, MempoolEntry>)> {
+        let mut ancestors = Vec::new();
+        let mut entries = HashMap::new();
+        let mut seen: HashSet<Txid> = HashSet::new();
+        let mut queue: VecDeque<Txid> = entry.depends.iter().copied().collect();
+
+        while !queue.is_empty() {
+            let txids: Vec<Txid> = queue.drain(..).filter(|txid| seen.insert(*txid)).collect();
+
+            let mut found = self.entries(&txids)?;
+            for txid in txids {
+                if let Some(entry) = found.remove(&txid) {
+                    ancestors.push(txid);
+                    queue.extend(entry.depends.iter().copied());
+                    entries.insert(txid, entry);
+                }
+            }
+        }
+
+        Ok((ancestors, entries))
+    }
+
+    /// Whether `txid` signals replaceability. Without the transaction index
+    /// this takes an RPC call.
+    fn signals_rbf(&mut self, txid: &Txid) -> Result<bool> {
+        if let Some(signals_rbf) = self.signals_rbf.get(txid) {
+            return Ok(*signals_rbf);
+        }
+
+        let signals_rbf = bitcoin_transaction(self.index, self.client, txid)?.is_explicitly_rbf();
+        self.signals_rbf.insert(*txid, signals_rbf);
+
+        Ok(signals_rbf)
+    }
+}
+
+/// Length of the longest chain of unconfirmed transactions ending at `txid`,
+/// `txid` included. `chain` holds the mempool entries of `txid` and of all its
+/// unconfirmed ancestors.
+fn unconfirmed_depth(txid: &Txid, chain: &HashMap<Txid, MempoolEntry>) -> u64 {
+    fn depth(
+        txid: &Txid,
+        chain: &HashMap<Txid, MempoolEntry>,
+        memo: &mut HashMap<Txid, u64>,
+    ) -> u64 {
+        let Some(entry) = chain.get(txid) else {
+            return 0;
+        };
+
+        if let Some(depth) = memo.get(txid) {
+            return *depth;
+        }
+
+        let parent_depth = entry
+            .depends
+            .iter()
+            .map(|parent| depth(parent, chain, memo))
+            .max()
+            .unwrap_or(0);
+
+        memo.insert(*txid, parent_depth + 1);
+        parent_depth + 1
+    }
+
+    depth(txid, chain, &mut HashMap::new())
+}
+
+fn lowest_fee_rate(chain: &HashMap<Txid, MempoolEntry>) -> Option<f64> {
+    chain
+        .values()
+        .map(|entry| entry.fees.base as f64 / entry.vsize as f64)
+        .min_by(f64::total_cmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::hashes::Hash, titan_types::MempoolEntryFee};
+
+    fn txid(n: u8) -> Txid {
+        Txid::from_byte_array([n; 32])
+    }
+
+    fn entry(fee: u64, vsize: u64, depends: Vec<Txid>) -> MempoolEntry {
+        MempoolEntry {
+            vsize,
+            weight: Some(vsize * 4),
+            descendant_count: 1,
+            descendant_size: vsize,
+            ancestor_count: 1,
+            ancestor_size: vsize,
+            fees: MempoolEntryFee {
+                base: fee,
+                descendant: fee,
+                ancestor: fee,
+            },
+            depends,
+            spent_by: vec![],
+        }
+    }
+
+    #[test]
+    fn depth_follows_longest_chain() {
+        // 1 <- 2 <- 4 and 3 <- 4
+        let chain = HashMap::from([
+            (txid(1), entry(100, 100, vec![])),
+            (txid(2), entry(300, 100, vec![txid(1)])),
+            (txid(3), entry(500, 100, vec![])),
+            (txid(4), entry(200, 100, vec![txid(2), txid(3)])),
+        ]);
+
+        assert_eq!(unconfirmed_depth(&txid(4), &chain), 3);
+        assert_eq!(unconfirmed_depth(&txid(3), &chain), 1);
+        assert_eq!(unconfirmed_depth(&txid(5), &chain), 0);
+        assert_eq!(lowest_fee_rate(&chain), Some(1.0));
+    }
+}
@@ -0,0 +1,139 @@
+use {
+    super::Result,
+    crate::index::Index,
+    bitcoin::{ScriptBuf, Txid},
+    std::collections::HashMap,
+    titan_types::{AddressTxOut, SpentStatus, TransactionStatus},
+};
+
+/// Every output a script has had, spent ones included, and the status of the
+/// transactions spending them. What the Esplora and Electrum APIs list a
+/// script's transactions from.
+#[derive(Debug, Default)]
+pub(super) struct ScriptHistory {
+    pub outputs: Vec<AddressTxOut>,
+    pub spenders: HashMap<Txid, TransactionStatus>,
+}
+
+impl ScriptHistory {
+    pub(super) fn of(index: &Index, script_pubkey: &ScriptBuf) -> Result<Self> {
+        let outputs = index.get_script_pubkey_history(script_pubkey)?;
+
+        let mut spender_txids: Vec<Txid> = outputs
+            .iter()
+            .filter_map(|output| match &output.spent {
+                SpentStatus::Spent(spender) => Some(spender.txid),
+                SpentStatus::Unspent => None,
+            })
+            .collect();
+        spender_txids.sort();
+        spender_txids.dedup();
+
+        // A spender the index can't place is in the mempool: confirmed ones
+        // always have their block.
+        let spenders = index
+            .get_transaction_statuses(&spender_txids)?
+            .into_iter()
+            .map(|(txid, status)| (txid, status.unwrap_or_else(TransactionStatus::unconfirmed)))
+            .collect();
+
+        Ok(Self { outputs, spenders })
+    }
+
+    /// Status of the transaction spending `output`, `None` if it's unspent.
+    pub(super) fn spender_status(&self, output: &AddressTxOut) -> Option<TransactionStatus> {
+        match &output.spent {
+            SpentStatus::Spent(spender) => Some(
+                self.spenders
+                    .get(&spender.txid)
+                    .cloned()
+                    .unwrap_or_else(TransactionStatus::unconfirmed),
+            ),
+            SpentStatus::Unspent => None,
+        }
+    }
+
+    /// Every transaction that funded or spent the script, with its status.
+    pub(super) fn transactions(&self) -> HashMap<Txid, TransactionStatus> {
+        let mut transactions = HashMap::new();
+
+        for output in &self.outputs {
+            transactions.insert(output.txid, output.status.clone());
+
+            if let (SpentStatus::Spent(spender), Some(status)) =
+                (&output.spent, self.spender_status(output))
+            {
+                transactions.insert(spender.txid, status);
+            }
+        }
+
+        transactions
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use {super::*, bitcoin::hashes::Hash, titan_types::SpenderReference};
+
+    pub(in crate::api) fn txid(n: u8) -> Txid {
+        Txid::from_byte_array([n; 32])
+    }
+
+    pub(in crate::api) fn confirmed(height: u64) -> TransactionStatus {
+        TransactionStatus {
+            confirmed: true,
+            block_height: Some(height),
+            block_hash: None,
+        }
+    }
+
+    /// An output of `txid`, spent by `spender` if given.
+    pub(in crate::api) fn output(
+        txid: Txid,
+        value: u64,
+        status: TransactionStatus,
+        spender: Option<Txid>,
+    ) -> AddressTxOut {
+        AddressTxOut {
+            txid,
+            vout: 0,
+            value,
+            runes: Vec::new(),
+            risky_runes: Vec::new(),
+            spent: match spender {
+                Some(txid) => SpentStatus::Spent(SpenderReference { txid, vin: 0 }),
+                None => SpentStatus::Unspent,
+            },
+            status,
+            risk: None,
+        }
+    }
+
+    #[test]
+    fn transactions_include_spenders() {
+        let history = ScriptHistory {
+            outputs: vec![
+                output(txid(1), 1000, confirmed(100), Some(txid(2))),
+                output(txid(3), 500, confirmed(105), Some(txid(4))),
+                output(txid(4), 200, TransactionStatus::unconfirmed(), None),
+            ],
+            spenders: HashMap::from([(txid(2), confirmed(101))]),
+        };
+
+        let transactions = history.transactions();
+
+        let heights: HashMap<Txid, Option<u64>> = transactions
+            .into_iter()
+            .map(|(txid, status)| (txid, status.block_height))
+            .collect();
+        assert_eq!(
+            heights,
+            HashMap::from([
+                (txid(1), Some(100)),
+                (txid(2), Some(101)),
+                (txid(3), Some(105)),
+                (txid(4), None),
+            ])
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use {
+    super::{api::bitcoin_transaction, ApiError, Result},
+    crate::{
+        bitcoin_rpc::{BitcoinCoreRpcResultExt, PooledClient},
+        index::{Index, IndexError, StoreError},
+        models::TransactionStateChange,
+    },
+    bitcoin::{OutPoint, Transaction as BitcoinTransaction, Txid},
+    bitcoincore_rpc::RpcApi,
+    ordinals::RuneId,
+    std::collections::{hash_map, BTreeMap, HashMap},
+    titan_types::{RuneFlow, TransactionDetail, TransactionStatus, TxInDetail, TxOutDetail},
+};
+
+/// Builds the detail of `txid`. Inputs are resolved from the index first and
+/// from the transactions they spend otherwise.
+pub(super) fn build(
+    index: &Index,
+    client: &PooledClient,
+    txid: &Txid,
+) -> Result<TransactionDetail> {
+    let status = index.get_transaction_status(txid)?;
+    let transaction = bitcoin_transaction(index, client, txid)?;
+    from_transaction(index, client, &transaction, status)
+}
+
+/// `build` for a transaction that has already been fetched.
+pub(super) fn from_transaction(
+    index: &Index,
+    client: &PooledClient,
+    transaction: &BitcoinTransaction,
+    status: TransactionStatus,
+) -> Result<TransactionDetail> {
+    let txid = &transaction.compute_txid();
+    let is_coinbase = transaction.is_coinbase();
+
+    let prevouts: Vec<OutPoint> = if is_coinbase {
+        Vec::new()
+    } else {
+        transaction
+            .input
+            .iter()
+            .map(|tx_in| tx_in.previous_output)
+            .collect()
+    };
+
+    let spent_entries = index.get_tx_outs(&prevouts)?;
+    let mut script_pubkeys = index.get_script_pubkeys(&prevouts)?;
+    let mut values: HashMap<OutPoint, u64> = spent_entries
+        .iter()
+        .map(|(outpoint, entry)| (*outpoint, entry.value))
+        .collect();
+
+    let mut previous_transactions: HashMap<Txid, Option<BitcoinTransaction>> = HashMap::new();
+    for prevout in &prevouts {
+        if values.contains_key(prevout) && script_pubkeys.contains_key(prevout) {
+            continue;
+        }
+
+        let previous_transaction = match previous_transactions.entry(prevout.txid) {
+            hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(previous_transaction(index, client, &prevout.txid)?)
+            }
+        };
+
+        if let Some(tx_out) = previous_transaction
+            .as_ref()
+            .and_then(|transaction| transaction.output.get(prevout.vout as usize))
+        {
+            values.insert(*prevout, tx_out.value.to_sat());
+            script_pubkeys.insert(*prevout, tx_out.script_pubkey.clone());
+        }
+    }
+
+    let input: Vec<TxInDetail> = transaction
+        .input
+        .iter()
+        .map(|tx_in| {
+            let prevout = &tx_in.previous_output;
+            let script_pubkey = script_pubkeys.get(prevout).cloned();
+
+            TxInDetail {
+                previous_output: *prevout,
+                sequence: tx_in.sequence.0,
+                value: values.get(prevout).copied(),
+                address: script_pubkey
+                    .as_ref()
+                    .and_then(|script_pubkey| index.get_address(script_pubkey))
+                    .map(|address| address.to_string()),
+                script_pubkey,
+                runes: spent_entries
+                    .get(prevout)
+                    .map(|entry| entry.runes.clone())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    // Purged for confirmed transactions past the retention window, in which
+    // case the output entries still carry the runes.
+    let state_change = match index.get_transaction_state_change(txid) {
+        Ok(state_change) => Some(state_change),
+        Err(IndexError::StoreError(StoreError::NotFound(_))) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    let outpoints: Vec<OutPoint> = (0..transaction.output.len())
+        .map(|vout| OutPoint::new(*txid, vout as u32))
+        .collect();
+    let output_entries = index.get_tx_outs(&outpoints)?;
+
+    let output: Vec<TxOutDetail> = transaction
+        .output
+        .iter()
+        .zip(&outpoints)
+        .map(|(tx_out, outpoint)| {
+            let entry = output_entries.get(outpoint);
+            let runes_entry = entry.or_else(|| {
+                state_change
+                    .as_ref()
+                    .and_then(|state_change| state_change.outputs.get(outpoint.vout as usize))
+            });
+
+            TxOutDetail {
+                value: tx_out.value.to_sat(),
+                script_pubkey: tx_out.script_pubkey.clone(),
+                address: index
+                    .get_address(&tx_out.script_pubkey)
+                    .map(|address| address.to_string()),
+                runes: runes_entry
+                    .map(|entry| entry.runes.clone())
+                    .unwrap_or_default(),
+                risky_runes: runes_entry
+                    .map(|entry| entry.risky_runes.clone())
+                    .unwrap_or_default(),
+                spent: entry.map(|entry| entry.spent.clone()),
+            }
+        })
+        .collect();
+
+    let vsize = transaction.vsize() as u64;
+    let fee = if is_coinbase {
+        None
+    } else {
+        let output_value: u64 = output.iter().map(|tx_out| tx_out.value).sum();
+        input
+            .iter()
+            .map(|tx_in| tx_in.value)
+            .sum::<Option<u64>>()
+            .map(|input_value| input_value.saturating_sub(output_value))
+    };
+
+    Ok(TransactionDetail {
+        txid: *txid,
+        version: transaction.version.0,
+        lock_time: transaction.lock_time.to_consensus_u32(),
+        size: transaction.total_size() as u64,
+        vsize,
+        weight: transaction.weight().to_wu(),
+        fee,
+        fee_rate: fee.map(|fee| fee as f64 / vsize as f64),
+        rune_flows: rune_flows(&input, &output, state_change.as_ref()),
+        input,
+        output,
+        status,
+    })
+}
+
+/// `None` when neither the index nor bitcoind has `txid`, like confirmed
+/// transactions on a node without `-txindex`.
+fn previous_transaction(
+    index: &Index,
+    client: &PooledClient,
+    txid: &Txid,
+) -> Result<Option<BitcoinTransaction>> {
+    if !index.is_indexing_bitcoin_transactions() {
+        return Ok(client.get_raw_transaction(txid, None).into_option()?);
+    }
+
+    match bitcoin_transaction(index, client, txid) {
+        Ok(transaction) => Ok(Some(transaction)),
+        Err(ApiError::IndexError(IndexError::StoreError(StoreError::NotFound(_)))) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+fn rune_flows(
+    input: &[TxInDetail],
+    output: &[TxOutDetail],
+    state_change: Option<&TransactionStateChange>,
+) -> Vec<RuneFlow> {
+    fn flow(flows: &mut BTreeMap<RuneId, RuneFlow>, rune_id: RuneId) -> &mut RuneFlow {
+        flows.entry(rune_id).or_insert_with(|| RuneFlow {
+            rune_id,
+            input: 0,
+            output: 0,
+            minted: 0,
+            burned: 0,
+            etched: false,
+        })
+    }
+
+    let mut flows = BTreeMap::new();
+
+    for rune in input.iter().flat_map(|tx_in| &tx_in.runes) {
+        flow(&mut flows, rune.rune_id).input += rune.amount;
+    }
+
+    for rune in output.iter().flat_map(|tx_out| &tx_out.runes) {
+        flow(&mut flows, rune.rune_id).output += rune.amount;
+    }
+
+    if let Some(state_change) = state_change {
+        if let Some((rune_id, _)) = state_change.etched {
+            flow(&mut flows, rune_id).etched = true;
+        }
+
+        if let Some(minted) = &state_change.minted {
+            flow(&mut flows, minted.rune_id).minted += minted.amount;
+        }
+
+        for (rune_id, amount) in &state_change.burned {
+            flow(&mut flows, *rune_id).burned += amount.n();
+        }
+    }
+
+    flows.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::models::Lot,
+        bitcoin::ScriptBuf,
+        titan_types::{RuneAmount, SpentStatus},
+    };
+
+    #[test]
+    fn rune_flows_sum_inputs_outputs_and_state_change() {
+        let a = RuneId { block: 1, tx: 0 };
+        let b = RuneId { block: 2, tx: 0 };
+
+        let input = vec![TxInDetail {
+            previous_output: OutPoint::null(),
+            sequence: 0,
+            value: Some(1_000),
+            script_pubkey: None,
+            address: None,
+            runes: vec![RuneAmount::from((b, 100))],
+        }];
+        let output = |runes: Vec<RuneAmount>| TxOutDetail {
+            value: 546,
+            script_pubkey: ScriptBuf::new(),
+            address: None,
+            runes,
+            risky_runes: Vec::new(),
+            spent: Some(SpentStatus::Unspent),
+        };
+        let output = vec![
+            output(vec![RuneAmount::from((a, 10)), RuneAmount::from((b, 40))]),
+            output(vec![RuneAmount::from((b, 20))]),
+        ];
+        let state_change = TransactionStateChange {
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            etched: None,
+            minted: Some(RuneAmount::from((a, 10))),
+            burned: HashMap::from([(b, Lot(40))]),
+            is_coinbase: false,
+        };
+
+        assert_eq!(
+            rune_flows(&input, &output, Some(&state_change)),
+            vec![
+                RuneFlow {
+                    rune_id: a,
+                    input: 0,
+                    output: 10,
+                    minted: 10,
+                    burned: 0,
+                    etched: false,
+                },
+                RuneFlow {
+                    rune_id: b,
+                    input: 100,
+                    output: 60,
+                    minted: 0,
+                    burned: 40,
+                    etched: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+//! Gap-limit discovery of the addresses an HD wallet has used, so clients
+//! don't have to query every derived address themselves.
+//!
+//! An address counts as used once it has had an output, spent or not. That's
+//! only known on an index that kept spent outputs from its first block, so
+//! scans are refused on older indexes until they're reindexed.
+
+use {
+    super::{require_script_history, ApiError, Result},
+    crate::index::Index,
+    bitcoin::{
+        base58,
+        bip32::{ChildNumber, Xpub},
+        secp256k1::{Secp256k1, Verification},
+        Address, CompressedPublicKey, Network, NetworkKind, PublicKey, ScriptBuf,
+    },
+    std::{collections::HashMap, str::FromStr},
+    titan_types::{RuneAmount, WalletAddress, WalletData, WalletQuery},
+};
+
+const DEFAULT_GAP_LIMIT: u32 = 20;
+const MAX_GAP_LIMIT: u32 = 1000;
+
+/// Chains a descriptor may have, usually receive and change.
+const MAX_CHAINS: usize = 2;
+
+/// Addresses derived per chain at most. Wallets that need more are refused
+/// rather than reported incompletely, which bounds a scan to
+/// `MAX_CHAINS * MAX_CHAIN_LENGTH` derivations.
+const MAX_CHAIN_LENGTH: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScriptType {
+    Pkh,
+    ShWpkh,
+    Wpkh,
+    Tr,
+}
+
+/// An extended public key, the script its keys are used in and the paths of
+/// the chains derived from it.
+#[derive(Debug, PartialEq)]
+struct Descriptor {
+    script_type: ScriptType,
+    xpub: Xpub,
+    chains: Vec<Vec<ChildNumber>>,
+}
+
+impl Descriptor {
+    fn script_pubkey<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+        chain: &Xpub,
+        index: u32,
+    ) -> Result<ScriptBuf> {
+        let key = chain
+            .ckd_pub(secp, ChildNumber::Normal { index })
+            .map_err(|e| ApiError::InvalidDescriptor(e.to_string()))?
+            .public_key;
+
+        Ok(match self.script_type {
+            ScriptType::Pkh => ScriptBuf::new_p2pkh(&PublicKey::new(key).pubkey_hash()),
+            ScriptType::ShWpkh => ScriptBuf::new_p2sh(
+                &ScriptBuf::new_p2wpkh(&CompressedPublicKey(key).wpubkey_hash()).script_hash(),
+            ),
+            ScriptType::Wpkh => ScriptBuf::new_p2wpkh(&CompressedPublicKey(key).wpubkey_hash()),
+            ScriptType::Tr => ScriptBuf::new_p2tr(secp, key.x_only_public_key().0, None),
+        })
+    }
+}
+
+impl FromStr for Descriptor {
+    type Err = String;
+
+    /// The checksum after `#` isn't verified.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.split_once('#').map_or(s, |(descriptor, _)| descriptor);
+
+        let (script_type, key) = if let Some(key) = unwrap(s, "sh(wpkh(", "))") {
+            (ScriptType::ShWpkh, key)
+        } else if let Some(key) = unwrap(s, "wpkh(", ")") {
+            (ScriptType::Wpkh, key)
+        } else if let Some(key) = unwrap(s, "pkh(", ")") {
+            (ScriptType::Pkh, key)
+        } else if let Some(key) = unwrap(s, "tr(", ")") {
+            (ScriptType::Tr, key)
+        } else {
+            let (script_type, xpub) = parse_extended_key(s)?;
+            return Ok(Self {
+                script_type,
+                xpub,
+                chains: vec![
+                    vec![ChildNumber::Normal { index: 0 }],
+                    vec![ChildNumber::Normal { index: 1 }],
+                ],
+            });
+        };
+
+        // The key origin only matters to signers.
+        let key = key.rsplit_once(']').map_or(key, |(_, key)| key);
+
+        let mut steps = key.split('/');
+        let (_, xpub) = parse_extended_key(steps.next().unwrap_or_default())?;
+        let steps: Vec<&str> = steps.collect();
+
+        let Some((&"*", steps)) = steps.split_last() else {
+            return Err("the key has to end in an unhardened `/*`".into());
+        };
+
+        let mut chains = vec![Vec::new()];
+        for step in steps {
+            if let Some(indexes) = step.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+                if chains.len() > 1 {
+                    return Err("only one `<a;b>` step is supported".into());
+                }
+
+                chains = indexes
+                    .split(';')
+                    .map(|index| Ok(vec![parse_step(index)?]))
+                    .collect::<std::result::Result<_, String>>()?;
+
+                if chains.len() > MAX_CHAINS {
+                    return Err(format!("at most {MAX_CHAINS} chains are supported"));
+                }
+            } else {
+                let child = parse_step(step)?;
+                for chain in chains.iter_mut() {
+                    chain.push(child);
+                }
+            }
+        }
+
+        Ok(Self {
+            script_type,
+            xpub,
+            chains,
+        })
+    }
+}
+
+fn unwrap<'a>(s: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    s.strip_prefix(prefix)?.strip_suffix(suffix)
+}
+
+fn parse_step(step: &str) -> std::result::Result<ChildNumber, String> {
+    let index = step
+        .parse()
+        .map_err(|_| format!("`{step}` isn't an unhardened derivation step"))?;
+    ChildNumber::from_normal_idx(index).map_err(|e| e.to_string())
+}
+
+/// Reads xpub and tpub, and the SLIP-132 versions that imply a script type.
+fn parse_extended_key(key: &str) -> std::result::Result<(ScriptType, Xpub), String> {
+    let mut data = base58::decode_check(key).map_err(|e| e.to_string())?;
+    if data.len() < 4 {
+        return Err("invalid extended public key".into());
+    }
+
+    let (script_type, version): (ScriptType, [u8; 4]) = match data[..4] {
+        [0x04, 0x88, 0xb2, 0x1e] => (ScriptType::Pkh, [0x04, 0x88, 0xb2, 0x1e]),
+        [0x04, 0x9d, 0x7c, 0xb2] => (ScriptType::ShWpkh, [0x04, 0x88, 0xb2, 0x1e]),
+        [0x04, 0xb2, 0x47, 0x46] => (ScriptType::Wpkh, [0x04, 0x88, 0xb2, 0x1e]),
+        [0x04, 0x35, 0x87, 0xcf] => (ScriptType::Pkh, [0x04, 0x35, 0x87, 0xcf]),
+        [0x04, 0x4a, 0x52, 0x62] => (ScriptType::ShWpkh, [0x04, 0x35, 0x87, 0xcf]),
+        [0x04, 0x5f, 0x1c, 0xf6] => (ScriptType::Wpkh, [0x04, 0x35, 0x87, 0xcf]),
+        _ => return Err("not an extended public key".into()),
+    };
+    data[..4].copy_from_slice(&version);
+
+    let xpub = Xpub::decode(&data).map_err(|e| e.to_string())?;
+    Ok((script_type, xpub))
+}
+
+/// Scans every chain of `query.descriptor` until `gap_limit` unused addresses
+/// in a row, adding up what the used ones hold.
+pub(super) fn scan(index: &Index, network: Network, query: &WalletQuery) -> Result<WalletData> {
+    require_script_history(index)?;
+
+    let descriptor: Descriptor = query
+        .descriptor
+        .parse()
+        .map_err(ApiError::InvalidDescriptor)?;
+
+    if descriptor.xpub.network != NetworkKind::from(network) {
+        return Err(ApiError::InvalidDescriptor(format!(
+            "the key isn't for {network}"
+        )));
+    }
+
+    let gap_limit = query
+        .gap_limit
+        .unwrap_or(DEFAULT_GAP_LIMIT)
+        .clamp(1, MAX_GAP_LIMIT);
+    let secp = Secp256k1::verification_only();
+
+    let mut value = 0;
+    let mut runes: HashMap<_, u128> = HashMap::new();
+    let mut addresses = Vec::new();
+    let mut next_index = Vec::new();
+
+    for (chain, path) in descriptor.chains.iter().enumerate() {
+        let chain_key = descriptor
+            .xpub
+            .derive_pub(&secp, path)
+            .map_err(|e| ApiError::InvalidDescriptor(e.to_string()))?;
+
+        let mut next: u32 = 0;
+        let mut index_in_chain = 0;
+        while index_in_chain < next.saturating_add(gap_limit) {
+            if index_in_chain == MAX_CHAIN_LENGTH {
+                return Err(ApiError::InvalidDescriptor(format!(
+                    "chain {chain} has used addresses beyond the first {MAX_CHAIN_LENGTH}"
+                )));
+            }
+
+            let script_pubkey = descriptor.script_pubkey(&secp, &chain_key, index_in_chain)?;
+
+            if index.is_script_pubkey_used(&script_pubkey)? {
+                next = index_in_chain + 1;
+
+                let address = Address::from_script(&script_pubkey, network)
+                    .map_err(|e| ApiError::InvalidDescriptor(e.to_string()))?;
+                let data = index.get_script_pubkey_outpoints(&address)?;
+
+                value += data.value;
+                for rune in data.runes {
+                    *runes.entry(rune.rune_id).or_default() += rune.amount;
+                }
+
+                addresses.push(WalletAddress {
+                    address: address.to_string(),
+                    chain: chain as u32,
+                    index: index_in_chain,
+                    value: data.value,
+                    outputs: data.outputs,
+                });
+            }
+
+            index_in_chain += 1;
+        }
+
+        next_index.push(next);
+    }
+
+    Ok(WalletData {
+        value,
+        runes: runes
+            .into_iter()
+            .map(|(rune_id, amount)| RuneAmount::from((rune_id, amount)))
+            .collect(),
+        addresses,
+        next_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::models::BatchUpdate,
+        bitcoin::{hashes::Hash, OutPoint, Txid},
+        titan_types::{SpentStatus, TxOutEntry},
+    };
+
+    // BIP 84's test account.
+    const ZPUB: &str = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+
+    #[test]
+    fn descriptors_and_extended_keys() {
+        let zpub: Descriptor = ZPUB.parse().unwrap();
+        let xpub = zpub.xpub.to_string();
+        assert!(xpub.starts_with("xpub"));
+
+        assert_eq!(zpub.script_type, ScriptType::Wpkh);
+        assert_eq!(zpub.chains.len(), 2);
+
+        let descriptor: Descriptor = format!("wpkh([73c5da0a/84h/0h/0h]{xpub}/<0;1>/*)#checksum")
+            .parse()
+            .unwrap();
+        assert_eq!(descriptor, zpub);
+
+        let receive: Descriptor = format!("wpkh({xpub}/0/*)").parse().unwrap();
+        assert_eq!(receive.chains, vec![vec![ChildNumber::Normal { index: 0 }]]);
+
+        let secp = Secp256k1::verification_only();
+        let chain = receive.xpub.derive_pub(&secp, &receive.chains[0]).unwrap();
+        let address = Address::from_script(
+            &receive.script_pubkey(&secp, &chain, 0).unwrap(),
+            Network::Bitcoin,
+        )
+        .unwrap();
+        assert_eq!(
+            address.to_string(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+
+        assert!(format!("wpkh({xpub}/0/*')").parse::<Descriptor>().is_err());
+        assert!(format!("wpkh({xpub}/0h/*)").parse::<Descriptor>().is_err());
+        assert!(format!("wsh({xpub}/0/*)").parse::<Descriptor>().is_err());
+        assert!(format!("wpkh({xpub}/<0;1;2>/*)")
+            .parse::<Descriptor>()
+            .is_err());
+        assert!(format!("wpkh({xpub}/<0;1>/<0;1>/*)")
+            .parse::<Descriptor>()
+            .is_err());
+    }
+
+    #[test]
+    fn scan_stops_after_gap_limit_unused_addresses() {
+        let (_dir, db, index) = Index::open_temp();
+
+        let descriptor: Descriptor = ZPUB.parse().unwrap();
+        let secp = Secp256k1::verification_only();
+        let receive = descriptor
+            .xpub
+            .derive_pub(&secp, &descriptor.chains[0])
+            .unwrap();
+        let script_pubkey = |index| descriptor.script_pubkey(&secp, &receive, index).unwrap();
+        let outpoint = |n: u8| OutPoint {
+            txid: Txid::from_byte_array([n; 32]),
+            vout: 0,
+        };
+
+        // Receive address 0 was funded and emptied, 3 still holds an output
+        // after a gap of two unused addresses.
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.script_pubkeys = HashMap::from([
+            (script_pubkey(0), (vec![outpoint(1)], vec![outpoint(1)])),
+            (script_pubkey(3), (vec![outpoint(2)], vec![])),
+        ]);
+        update.txouts = HashMap::from([(
+            outpoint(2),
+            TxOutEntry {
+                runes: vec![],
+                risky_runes: vec![],
+                value: 1000,
+                spent: SpentStatus::Unspent,
+            },
+        )]);
+        db.batch_update(&update, false).unwrap();
+
+        let scan_with = |gap_limit| {
+            scan(
+                &index,
+                Network::Bitcoin,
+                &WalletQuery {
+                    descriptor: ZPUB.into(),
+                    gap_limit: Some(gap_limit),
+                },
+            )
+            .unwrap()
+        };
+
+        let wallet = scan_with(3);
+        assert_eq!(wallet.value, 1000);
+        assert_eq!(
+            wallet
+                .addresses
+                .iter()
+                .map(|address| (address.chain, address.index, address.value))
+                .collect::<Vec<_>>(),
+            vec![(0, 0, 0), (0, 3, 1000)]
+        );
+        assert_eq!(wallet.next_index, vec![4, 0]);
+
+        // Two unused addresses in a row end the scan before address 3.
+        let wallet = scan_with(2);
+        assert_eq!(wallet.value, 0);
+        assert_eq!(wallet.addresses.len(), 1);
+        assert_eq!(wallet.next_index, vec![1, 0]);
+    }
+}
@@ -1,4 +1,3 @@
-
 use {
     bitcoincore_rpc::{Client, RpcApi},
     std::{thread, time::Duration},
@@ -20,7 +19,11 @@ impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
                 bitcoincore_rpc::jsonrpc::error::RpcError {
                     code: -5, message, ..
                 },
-            ))) if message.starts_with("No such mempool or blockchain transaction") => Ok(None),
+            ))) if message.starts_with("No such mempool or blockchain transaction")
+                || message == "Transaction not in mempool" =>
+            {
+                Ok(None)
+            }
             Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
                 bitcoincore_rpc::jsonrpc::error::RpcError { message, .. },
             ))) if message.ends_with("not found") => Ok(None),
@@ -1,7 +1,13 @@
 use {
-    crate::models::{BlockId, Inscription, RuneEntry, TransactionStateChange, TxRuneIndexRef},
+    crate::models::{
+        BlockId, Inscription, Replacement, RuneEntry, RuneSupply, TransactionStateChange,
+        TxRuneIndexRef,
+    },
     borsh::{BorshDeserialize, BorshSerialize},
-    titan_types::{Block, MempoolEntry, SpenderReference, Subscription, TxOutEntry},
+    titan_types::{
+        Block, BlockRuneActivity, BlockStats, MempoolEntry, SpenderReference, Subscription,
+        TxOutEntry,
+    },
 };
 
 pub trait Entry: Sized + BorshDeserialize + BorshSerialize {
@@ -17,9 +23,12 @@ pub trait Entry: Sized + BorshDeserialize + BorshSerialize {
 }
 
 impl Entry for Block {}
+impl Entry for BlockStats {}
+impl Entry for Vec<BlockRuneActivity> {}
 impl Entry for BlockId {}
 impl Entry for Inscription {}
 impl Entry for RuneEntry {}
+impl Entry for RuneSupply {}
 impl Entry for TxRuneIndexRef {}
 impl Entry for Vec<TxRuneIndexRef> {}
 impl Entry for TransactionStateChange {}
@@ -27,3 +36,4 @@ impl Entry for TxOutEntry {}
 impl Entry for Subscription {}
 impl Entry for SpenderReference {}
 impl Entry for MempoolEntry {}
+impl Entry for Replacement {}
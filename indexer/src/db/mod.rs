@@ -1,4 +1,7 @@
-pub use {error::RocksDBError, rocks::RocksDB};
+pub use {
+    error::RocksDBError,
+    rocks::{RocksDB, SnapshotError},
+};
 
 mod entry;
 mod error;
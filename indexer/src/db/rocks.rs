@@ -2,31 +2,39 @@ use {
     super::{
         entry::Entry,
         util::{
-            parse_outpoint_from_script_pubkey_key, rune_id_from_bytes, rune_index_key,
-            rune_transaction_key, script_pubkey_outpoint_to_bytes, script_pubkey_search_key,
+            parse_height_from_rune_block_key, parse_index_from_rune_transaction_key,
+            parse_outpoint_from_script_pubkey_key, parse_replaced_txid_from_key,
+            parse_rune_id_from_ranking_key, parse_rune_id_from_symbol_key,
+            parse_script_pubkey_from_script_pubkey_key, replaced_tx_key, rune_block_key,
+            rune_block_search_key, rune_id_from_bytes, rune_index_key, rune_name_key,
+            rune_ranking_key, rune_ranking_search_key, rune_symbol_key, rune_symbol_search_key,
+            rune_transaction_key, rune_transaction_search_key, script_hash_key,
+            script_pubkey_outpoint_to_bytes, script_pubkey_search_key,
         },
         *,
     },
     crate::models::{
-        BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, RuneEntry,
-        TransactionStateChange, TxRuneIndexRef,
+        BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, Replacement, RuneEntry,
+        RuneOrder, RuneRanking, RuneSupply, RuneSupplyDelta, TransactionStateChange,
+        TxRuneIndexRef,
     },
     bitcoin::{consensus, hashes::Hash, BlockHash, OutPoint, ScriptBuf, Transaction, Txid},
     borsh::BorshDeserialize,
     mapper::DBResultMapper,
-    ordinals::RuneId,
+    ordinals::{Rune, RuneId},
     rocksdb::{
         BlockBasedOptions, BoundColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction,
         IteratorMode, MultiThreaded, Options, WriteBatch,
     },
     std::{
-        collections::HashMap,
+        collections::{BTreeMap, HashMap},
         sync::{Arc, RwLock},
     },
     titan_types::{
-        Block, InscriptionId, MempoolEntry, Pagination, PaginationResponse, SpenderReference,
-        Subscription, TxOutEntry,
+        Block, BlockRuneActivity, BlockStats, InscriptionId, MempoolEntry, Pagination,
+        PaginationResponse, SpenderReference, SpentStatus, Subscription, TxOutEntry,
     },
+    tracing::info,
     util::{
         inscription_id_to_bytes, outpoint_to_bytes, rune_id_to_bytes, txid_from_bytes,
         txid_to_bytes,
@@ -35,6 +43,10 @@ use {
     wrapper::RuneIdWrapper,
 };
 
+pub use snapshot::SnapshotError;
+
+mod snapshot;
+
 pub struct RocksDB {
     db: DBWithThreadMode<MultiThreaded>,
     mempool_cache: RwLock<HashMap<Txid, MempoolEntry>>,
@@ -42,8 +54,13 @@ pub struct RocksDB {
 
 pub type DBResult<T> = Result<T, RocksDBError>;
 
+/// Rune ids in the order of a [`RuneOrder`].
+pub type RuneIds<'a> = Box<dyn Iterator<Item = DBResult<RuneId>> + 'a>;
+
 const BLOCKS_CF: &str = "blocks";
 const BLOCK_HEIGHT_TO_HASH_CF: &str = "block_height_to_hash";
+const BLOCK_STATS_CF: &str = "block_stats";
+const BLOCK_RUNE_ACTIVITY_CF: &str = "block_rune_activity";
 
 const OUTPOINTS_CF: &str = "outpoints";
 const OUTPOINTS_MEMPOOL_CF: &str = "mempool_outpoints";
@@ -61,11 +78,23 @@ const RUNES_COUNT_KEY: &str = "runes_count";
 const RUNES_CF: &str = "runes";
 const RUNE_IDS_CF: &str = "rune_ids";
 const RUNE_NUMBER_CF: &str = "rune_number";
+const RUNE_NAMES_CF: &str = "rune_names";
+const RUNE_SYMBOLS_CF: &str = "rune_symbols";
+const RUNE_UTXO_COUNTS_CF: &str = "rune_utxo_counts";
+const RUNE_RANKINGS_CF: &str = "rune_rankings";
+const RUNE_MINTS_CF: &str = "rune_mints";
+const RUNE_SUPPLY_CF: &str = "rune_supply";
+const RUNE_SUPPLY_BLOCKS_CF: &str = "rune_supply_blocks";
+/// Confirmed supply totals after each block in `rune_supply_blocks`.
+const RUNE_SUPPLY_TOTALS_CF: &str = "rune_supply_totals";
 
 const INSCRIPTIONS_CF: &str = "inscriptions";
 
 const SCRIPT_PUBKEYS_CF: &str = "script_pubkeys";
 const SCRIPT_PUBKEYS_MEMPOOL_CF: &str = "script_pubkeys_mempool";
+/// Confirmed outputs that have been spent, keyed like `script_pubkeys`.
+const SCRIPT_PUBKEYS_SPENT_CF: &str = "script_pubkeys_spent";
+const SCRIPT_HASHES_CF: &str = "script_hashes";
 
 const OUTPOINT_TO_SCRIPT_PUBKEY_CF: &str = "outpoint_to_script_pubkey";
 const OUTPOINT_TO_SCRIPT_PUBKEY_MEMPOOL_CF: &str = "outpoint_to_script_pubkey_mempool";
@@ -77,6 +106,9 @@ const TRANSACTION_CONFIRMING_BLOCK_CF: &str = "transaction_confirming_block";
 
 const MEMPOOL_CF: &str = "mempool";
 
+const REPLACEMENTS_CF: &str = "replacements";
+const REPLACED_TXS_CF: &str = "replaced_txs";
+
 const STATS_CF: &str = "stats";
 
 const SETTINGS_CF: &str = "settings";
@@ -85,6 +117,12 @@ const SUBSCRIPTIONS_CF: &str = "subscriptions";
 
 const INDEX_ADDRESSES_KEY: &str = "index_addresses";
 const INDEX_BITCOIN_TRANSACTIONS_KEY: &str = "index_bitcoin_transactions";
+const RUNE_SEARCH_INDEX_KEY: &str = "rune_search_index";
+const RUNE_TRANSACTION_KEYS_BE_KEY: &str = "rune_transaction_keys_be";
+const RUNE_TRANSACTION_KEYS_PARKED_KEY: &str = "rune_transaction_keys_parked";
+const RUNE_HISTORY_KEY: &str = "rune_history";
+const SCRIPT_HISTORY_KEY: &str = "script_history";
+const BLOCK_SUMMARIES_FROM_KEY: &str = "block_summaries_from";
 
 const BLOCK_COUNT_KEY: &str = "block_count";
 const PURGED_BLOCKS_COUNT_KEY: &str = "purged_blocks_count";
@@ -99,6 +137,10 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(BLOCKS_CF, cf_opts.clone());
         let block_height_to_hash_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(BLOCK_HEIGHT_TO_HASH_CF, cf_opts.clone());
+        let block_stats_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(BLOCK_STATS_CF, cf_opts.clone());
+        let block_rune_activity_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(BLOCK_RUNE_ACTIVITY_CF, cf_opts.clone());
         let outpoints_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(OUTPOINTS_CF, cf_opts.clone());
         let outpoints_mempool_cfd: ColumnFamilyDescriptor =
@@ -113,6 +155,22 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(RUNE_IDS_CF, cf_opts.clone());
         let rune_number_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(RUNE_NUMBER_CF, cf_opts.clone());
+        let rune_names_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_NAMES_CF, cf_opts.clone());
+        let rune_symbols_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_SYMBOLS_CF, cf_opts.clone());
+        let rune_utxo_counts_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_UTXO_COUNTS_CF, cf_opts.clone());
+        let rune_rankings_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_RANKINGS_CF, cf_opts.clone());
+        let rune_mints_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_MINTS_CF, cf_opts.clone());
+        let rune_supply_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_SUPPLY_CF, cf_opts.clone());
+        let rune_supply_blocks_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_SUPPLY_BLOCKS_CF, cf_opts.clone());
+        let rune_supply_totals_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_SUPPLY_TOTALS_CF, cf_opts.clone());
         let inscriptions_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(INSCRIPTIONS_CF, cf_opts.clone());
         let mempool_cfd: ColumnFamilyDescriptor =
@@ -131,6 +189,10 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(SCRIPT_PUBKEYS_CF, cf_opts.clone());
         let script_pubkeys_mempool_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(SCRIPT_PUBKEYS_MEMPOOL_CF, cf_opts.clone());
+        let script_pubkeys_spent_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(SCRIPT_PUBKEYS_SPENT_CF, cf_opts.clone());
+        let script_hashes_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(SCRIPT_HASHES_CF, cf_opts.clone());
         let outpoint_to_script_pubkey_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(OUTPOINT_TO_SCRIPT_PUBKEY_CF, cf_opts.clone());
         let outpoint_to_script_pubkey_mempool_cfd: ColumnFamilyDescriptor =
@@ -143,6 +205,10 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(TRANSACTIONS_MEMPOOL_CF, cf_opts.clone());
         let transaction_confirming_block_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(TRANSACTION_CONFIRMING_BLOCK_CF, cf_opts.clone());
+        let replacements_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(REPLACEMENTS_CF, cf_opts.clone());
+        let replaced_txs_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(REPLACED_TXS_CF, cf_opts.clone());
         let settings_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(SETTINGS_CF, cf_opts.clone());
         let subscriptions_cfd: ColumnFamilyDescriptor =
@@ -171,6 +237,8 @@ impl RocksDB {
             vec![
                 blocks_cfd,
                 block_height_to_hash_cfd,
+                block_stats_cfd,
+                block_rune_activity_cfd,
                 outpoints_cfd,
                 outpoints_mempool_cfd,
                 transaction_state_change_cfd,
@@ -178,6 +246,14 @@ impl RocksDB {
                 runes_cfd,
                 rune_ids_cfd,
                 rune_number_cfd,
+                rune_names_cfd,
+                rune_symbols_cfd,
+                rune_utxo_counts_cfd,
+                rune_rankings_cfd,
+                rune_mints_cfd,
+                rune_supply_cfd,
+                rune_supply_blocks_cfd,
+                rune_supply_totals_cfd,
                 inscriptions_cfd,
                 mempool_cfd,
                 stats_cfd,
@@ -187,12 +263,16 @@ impl RocksDB {
                 transaction_rune_index_mempool_cfd,
                 script_pubkeys_cfd,
                 script_pubkeys_mempool_cfd,
+                script_pubkeys_spent_cfd,
+                script_hashes_cfd,
                 outpoint_to_script_pubkey_cfd,
                 outpoint_to_script_pubkey_mempool_cfd,
                 spent_outpoints_mempool_cfd,
                 transactions_cfd,
                 transactions_mempool_cfd,
                 transaction_confirming_block_cfd,
+                replacements_cfd,
+                replaced_txs_cfd,
                 settings_cfd,
                 subscriptions_cfd,
             ],
@@ -205,9 +285,295 @@ impl RocksDB {
             db: descriptors,
             mempool_cache: RwLock::new(mempool_cache),
         };
+
+        rocks_db.mark_complete_history()?;
+        rocks_db.mark_block_summaries_from()?;
+        rocks_db.build_rune_search_index()?;
+        rocks_db.migrate_rune_transaction_keys()?;
+
         Ok(rocks_db)
     }
 
+    /// Rune mints and supply, spent outputs and scripthashes are recorded as
+    /// blocks are indexed and can't be rebuilt from what older indexes kept,
+    /// outputs spent in purged blocks being gone, so they're only complete on
+    /// an index that had them from its first block. Marks a new index as
+    /// such.
+    fn mark_complete_history(&self) -> DBResult<()> {
+        if self.get_block_count()? != 0 {
+            return Ok(());
+        }
+
+        let settings_cf_handle = self.cf_handle(SETTINGS_CF)?;
+        self.db
+            .put_cf(&settings_cf_handle, RUNE_HISTORY_KEY, 1u64.to_le_bytes())?;
+        self.db
+            .put_cf(&settings_cf_handle, SCRIPT_HISTORY_KEY, 1u64.to_le_bytes())?;
+
+        Ok(())
+    }
+
+    /// Block stats and rune activity come from the transaction state changes,
+    /// which are purged past reorg depth, so they can't be worked out for
+    /// blocks indexed before they were kept. Records the first block that has
+    /// them, which is the first block on a new index.
+    fn mark_block_summaries_from(&self) -> DBResult<()> {
+        let settings_cf_handle = self.cf_handle(SETTINGS_CF)?;
+        if self
+            .get_option_vec_data(&settings_cf_handle, BLOCK_SUMMARIES_FROM_KEY)?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        self.db.put_cf(
+            &settings_cf_handle,
+            BLOCK_SUMMARIES_FROM_KEY,
+            self.get_block_count()?.to_le_bytes(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Fills the rune name, symbol, unspent output count and ranking indexes
+    /// for databases created before they existed. It only runs once, after
+    /// that `batch_update` and `batch_rollback` keep them up to date. Every
+    /// write is a plain put computed from data this doesn't change, so if it
+    /// stops midway it just starts over.
+    fn build_rune_search_index(&self) -> DBResult<()> {
+        let settings_cf_handle = self.cf_handle(SETTINGS_CF)?;
+        if self
+            .get_option_vec_data(&settings_cf_handle, RUNE_SEARCH_INDEX_KEY)?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        info!("Building rune search index");
+
+        // Unspent outputs holding each rune.
+        let mut utxo_counts: HashMap<RuneId, u64> = HashMap::new();
+        let outpoints_cf_handle = self.cf_handle(OUTPOINTS_CF)?;
+        for item in self
+            .db
+            .iterator_cf(&outpoints_cf_handle, IteratorMode::Start)
+        {
+            let (_, value) = item?;
+            let tx_out = TxOutEntry::load(value.to_vec());
+            if !matches!(tx_out.spent, SpentStatus::Unspent) {
+                continue;
+            }
+
+            for rune_amount in tx_out.runes.iter() {
+                *utxo_counts.entry(rune_amount.rune_id).or_default() += 1;
+            }
+        }
+
+        let mut batch = WriteBatch::default();
+        let mut runes_count = 0;
+
+        let runes_cf_handle = self.cf_handle(RUNES_CF)?;
+        let rune_names_cf_handle = self.cf_handle(RUNE_NAMES_CF)?;
+        let rune_symbols_cf_handle = self.cf_handle(RUNE_SYMBOLS_CF)?;
+        let rune_utxo_counts_cf_handle = self.cf_handle(RUNE_UTXO_COUNTS_CF)?;
+        let rune_rankings_cf_handle = self.cf_handle(RUNE_RANKINGS_CF)?;
+        for item in self.db.iterator_cf(&runes_cf_handle, IteratorMode::Start) {
+            let (key, value) = item?;
+            let rune_id = rune_id_from_bytes(&key).map_err(|_| RocksDBError::InvalidRuneId)?;
+            let rune_entry = RuneEntry::load(value.to_vec());
+            runes_count += 1;
+
+            batch.put_cf(
+                &rune_names_cf_handle,
+                rune_name_key(&rune_entry.spaced_rune.rune),
+                RuneIdWrapper(rune_id).store(),
+            );
+
+            if let Some(symbol) = rune_entry.symbol {
+                batch.put_cf(
+                    &rune_symbols_cf_handle,
+                    rune_symbol_key(symbol, &rune_id),
+                    vec![1],
+                );
+            }
+
+            let utxo_count = utxo_counts.get(&rune_id).cloned().unwrap_or(0);
+            batch.put_cf(
+                &rune_utxo_counts_cf_handle,
+                rune_id_to_bytes(&rune_id),
+                utxo_count.to_le_bytes(),
+            );
+
+            for (ranking, value) in [
+                (RuneRanking::Mints, rune_entry.mints),
+                (RuneRanking::Supply, rune_entry.supply()),
+                (RuneRanking::UtxoCount, utxo_count.into()),
+            ] {
+                batch.put_cf(
+                    &rune_rankings_cf_handle,
+                    rune_ranking_key(ranking, value, &rune_id),
+                    vec![1],
+                );
+            }
+
+            if batch.len() >= 100_000 {
+                self.db.write(std::mem::take(&mut batch))?;
+            }
+        }
+
+        batch.put_cf(
+            &settings_cf_handle,
+            RUNE_SEARCH_INDEX_KEY,
+            1u64.to_le_bytes(),
+        );
+
+        self.db.write(batch)?;
+
+        info!("Rune search index built for {} runes", runes_count);
+
+        Ok(())
+    }
+
+    /// Rune transaction keys used to end in a little-endian index, which
+    /// doesn't iterate in index order. Rewrites them once as big-endian.
+    fn migrate_rune_transaction_keys(&self) -> DBResult<()> {
+        let settings_cf_handle = self.cf_handle(SETTINGS_CF)?;
+        if self
+            .get_option_vec_data(&settings_cf_handle, RUNE_TRANSACTION_KEYS_BE_KEY)?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        info!("Migrating rune transaction keys");
+
+        // Converted keys are parked under their own prefix until every key is
+        // converted, so a restart never converts a key twice: until the parked
+        // flag is written, whatever is left under "rune:" is still little-endian.
+        if self
+            .get_option_vec_data(&settings_cf_handle, RUNE_TRANSACTION_KEYS_PARKED_KEY)?
+            .is_none()
+        {
+            self.move_rune_transaction_keys(
+                b"rune:",
+                b"rune_be:",
+                true,
+                RUNE_TRANSACTION_KEYS_PARKED_KEY,
+            )?;
+        }
+
+        let migrated = self.move_rune_transaction_keys(
+            b"rune_be:",
+            b"rune:",
+            false,
+            RUNE_TRANSACTION_KEYS_BE_KEY,
+        )?;
+
+        info!("Migrated {} rune transaction keys", migrated);
+
+        Ok(())
+    }
+
+    /// Moves the rune transaction keys starting with `from` to `to`, turning
+    /// their index big-endian if `to_big_endian`. `done_key` is set in the
+    /// settings with the last batch, so the move is recorded as done exactly
+    /// when nothing is left under `from`.
+    fn move_rune_transaction_keys(
+        &self,
+        from: &[u8],
+        to: &[u8],
+        to_big_endian: bool,
+        done_key: &str,
+    ) -> DBResult<u64> {
+        // The rune id and its separator, then the index.
+        let key_len = from.len() + 13 + 8;
+
+        let mut moved = 0;
+        let mut batch = WriteBatch::default();
+
+        for cf_name in [RUNE_TRANSACTIONS_CF, RUNE_TRANSACTIONS_MEMPOOL_CF] {
+            let cf_handle = self.cf_handle(cf_name)?;
+
+            let iter = self
+                .db
+                .iterator_cf(&cf_handle, IteratorMode::From(from, Direction::Forward));
+            for item in iter {
+                let (key, value) = item?;
+                if !key.starts_with(from) {
+                    break;
+                }
+
+                if key.len() != key_len {
+                    continue;
+                }
+
+                let (rune_id, index) = key[from.len()..].split_at(13);
+                let index: [u8; 8] = index.try_into().unwrap();
+                let index = if to_big_endian {
+                    u64::from_le_bytes(index).to_be_bytes()
+                } else {
+                    index
+                };
+
+                let mut new_key = to.to_vec();
+                new_key.extend_from_slice(rune_id);
+                new_key.extend_from_slice(&index);
+
+                batch.delete_cf(&cf_handle, &key);
+                batch.put_cf(&cf_handle, new_key, value);
+                moved += 1;
+
+                if batch.len() >= 100_000 {
+                    self.db.write(std::mem::take(&mut batch))?;
+                }
+            }
+        }
+
+        batch.put_cf(&self.cf_handle(SETTINGS_CF)?, done_key, 1u64.to_le_bytes());
+        self.db.write(batch)?;
+
+        Ok(moved)
+    }
+
+    /// Maps the hash of every script in the address index back to the script.
+    fn index_script_hashes(&self) -> DBResult<u64> {
+        let script_hashes_cf_handle = self.cf_handle(SCRIPT_HASHES_CF)?;
+        let mut scripts_count = 0;
+
+        for cf_name in [SCRIPT_PUBKEYS_CF, SCRIPT_PUBKEYS_MEMPOOL_CF] {
+            let cf_handle = self.cf_handle(cf_name)?;
+            let mut batch = WriteBatch::default();
+            let mut last_script_pubkey: Option<ScriptBuf> = None;
+
+            for item in self.db.iterator_cf(&cf_handle, IteratorMode::Start) {
+                let (key, _) = item?;
+                let script_pubkey = parse_script_pubkey_from_script_pubkey_key(&key)
+                    .map_err(|_| RocksDBError::InvalidOutpoint)?;
+
+                // Keys are sorted, so a script's outpoints are next to each other.
+                if last_script_pubkey.as_ref() == Some(&script_pubkey) {
+                    continue;
+                }
+
+                batch.put_cf(
+                    &script_hashes_cf_handle,
+                    script_hash_key(&script_pubkey),
+                    script_pubkey.as_bytes(),
+                );
+                scripts_count += 1;
+                last_script_pubkey = Some(script_pubkey);
+
+                if batch.len() >= 100_000 {
+                    self.db.write(std::mem::take(&mut batch))?;
+                }
+            }
+
+            self.db.write(batch)?;
+        }
+
+        Ok(scripts_count)
+    }
+
     fn cf_handle(&self, name: &str) -> DBResult<Arc<BoundColumnFamily>> {
         match self.db.cf_handle(name) {
             None => Err(RocksDBError::InvalidHandle(name.to_string())),
@@ -245,6 +611,27 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Whether rune mints and supply were recorded for every indexed block.
+    pub fn has_rune_history(&self) -> DBResult<bool> {
+        let cf_handle = self.cf_handle(SETTINGS_CF)?;
+        let val: Option<u64> = self
+            .get_option_vec_data(&cf_handle, RUNE_HISTORY_KEY)
+            .mapped()?;
+
+        Ok(val == Some(1))
+    }
+
+    /// Whether every script that had an output, spent or not, is in the
+    /// scripthash index and has its spent outputs kept.
+    pub fn has_script_history(&self) -> DBResult<bool> {
+        let cf_handle = self.cf_handle(SETTINGS_CF)?;
+        let val: Option<u64> = self
+            .get_option_vec_data(&cf_handle, SCRIPT_HISTORY_KEY)
+            .mapped()?;
+
+        Ok(val == Some(1))
+    }
+
     pub fn is_index_bitcoin_transactions(&self) -> DBResult<Option<bool>> {
         let cf_handle = self.cf_handle(SETTINGS_CF)?;
         let val: Option<u64> = self
@@ -312,13 +699,45 @@ impl RocksDB {
             .ok_or(RocksDBError::NotFound(format!("block not found: {}", hash)))?)
     }
 
+    /// Deletes the block along with the stats and rune activity worked out
+    /// when it was indexed.
     pub fn delete_block(&self, hash: &BlockHash) -> DBResult<()> {
-        let cf_handle = self.cf_handle(BLOCKS_CF)?;
-        self.db
-            .delete_cf(&cf_handle, hash.as_raw_hash().to_byte_array())?;
+        let key = hash.as_raw_hash().to_byte_array();
+        let mut batch = WriteBatch::default();
+        for cf in [BLOCKS_CF, BLOCK_STATS_CF, BLOCK_RUNE_ACTIVITY_CF] {
+            batch.delete_cf(&self.cf_handle(cf)?, key);
+        }
+        self.db.write(batch)?;
         Ok(())
     }
 
+    /// Height of the first block with stats and rune activity.
+    pub fn get_block_summaries_from(&self) -> DBResult<u64> {
+        let cf_handle = self.cf_handle(SETTINGS_CF)?;
+        let val: Option<u64> = self
+            .get_option_vec_data(&cf_handle, BLOCK_SUMMARIES_FROM_KEY)
+            .mapped()?;
+
+        Ok(val.unwrap_or_default())
+    }
+
+    pub fn get_block_stats(&self, hash: &BlockHash) -> DBResult<BlockStats> {
+        let cf_handle = self.cf_handle(BLOCK_STATS_CF)?;
+        self.get_option_vec_data(&cf_handle, hash.as_raw_hash().to_byte_array())
+            .mapped()?
+            .ok_or(RocksDBError::NotFound(format!("stats of block {}", hash)))
+    }
+
+    pub fn get_block_rune_activity(&self, hash: &BlockHash) -> DBResult<Vec<BlockRuneActivity>> {
+        let cf_handle = self.cf_handle(BLOCK_RUNE_ACTIVITY_CF)?;
+        self.get_option_vec_data(&cf_handle, hash.as_raw_hash().to_byte_array())
+            .mapped()?
+            .ok_or(RocksDBError::NotFound(format!(
+                "rune activity of block {}",
+                hash
+            )))
+    }
+
     pub fn get_rune(&self, rune_id: &RuneId) -> DBResult<RuneEntry> {
         let cf_handle = self.cf_handle(RUNES_CF)?;
         Ok(self
@@ -519,6 +938,209 @@ impl RocksDB {
         Ok(rune_id_wrapper.0)
     }
 
+    /// Rune ids in `order`. Only what's iterated is read, so a page costs
+    /// about the page size.
+    pub fn get_rune_ids(&self, order: &RuneOrder) -> DBResult<RuneIds<'_>> {
+        let rune_ids: RuneIds<'_> = match order {
+            RuneOrder::NamePrefix(prefix) => {
+                let cf_handle = self.cf_handle(RUNE_NAMES_CF)?;
+                let prefix = prefix.as_bytes().to_vec();
+                let iter = self
+                    .db
+                    .iterator_cf(&cf_handle, IteratorMode::From(&prefix, Direction::Forward));
+
+                Box::new(
+                    with_prefix(iter, prefix)
+                        .map(|item| item.map(|(_, value)| RuneIdWrapper::load(value.to_vec()).0)),
+                )
+            }
+            RuneOrder::Symbol(symbol) => {
+                let cf_handle = self.cf_handle(RUNE_SYMBOLS_CF)?;
+                let prefix = rune_symbol_search_key(*symbol);
+                let iter = self.db.iterator_cf(
+                    &cf_handle,
+                    IteratorMode::From(&last_key_with_prefix(&prefix), Direction::Reverse),
+                );
+
+                Box::new(with_prefix(iter, prefix).map(|item| {
+                    item.and_then(|(key, _)| {
+                        parse_rune_id_from_symbol_key(&key).map_err(|_| RocksDBError::InvalidRuneId)
+                    })
+                }))
+            }
+            RuneOrder::Newest => {
+                let runes_count = self.get_runes_count()?;
+                Box::new(
+                    (0..runes_count)
+                        .rev()
+                        .map(|number| self.get_rune_id_by_number(number)),
+                )
+            }
+            RuneOrder::Ranking(ranking) => {
+                let cf_handle = self.cf_handle(RUNE_RANKINGS_CF)?;
+                let prefix = rune_ranking_search_key(*ranking);
+                let iter = self.db.iterator_cf(
+                    &cf_handle,
+                    IteratorMode::From(&last_key_with_prefix(&prefix), Direction::Reverse),
+                );
+
+                Box::new(with_prefix(iter, prefix).map(|item| {
+                    item.and_then(|(key, _)| {
+                        parse_rune_id_from_ranking_key(&key)
+                            .map_err(|_| RocksDBError::InvalidRuneId)
+                    })
+                }))
+            }
+        };
+
+        Ok(rune_ids)
+    }
+
+    /// Returns `(height, mints)` for every block in `from..=to` where the rune was minted.
+    pub fn get_rune_mints(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> DBResult<Vec<(u64, u64)>> {
+        let cf_handle = self.cf_handle(RUNE_MINTS_CF)?;
+        let search_key = rune_block_search_key(rune_id);
+        let start_key = rune_block_key(rune_id, from);
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&start_key, Direction::Forward),
+        );
+
+        let mut mints = Vec::new();
+        for item in iter {
+            let (key, value) = item?;
+            if !key.starts_with(&search_key) {
+                break;
+            }
+
+            let height =
+                parse_height_from_rune_block_key(&key).map_err(|_| RocksDBError::InvalidU64)?;
+            if height > to {
+                break;
+            }
+
+            let count = u64::from_le_bytes(
+                value
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| RocksDBError::InvalidU64)?,
+            );
+            mints.push((height, count));
+        }
+
+        Ok(mints)
+    }
+
+    /// Running totals of every supply change recorded for the rune.
+    pub fn get_rune_supply(&self, rune_id: &RuneId) -> DBResult<RuneSupply> {
+        let cf_handle = self.cf_handle(RUNE_SUPPLY_CF)?;
+        let supply: Option<RuneSupply> = self
+            .get_option_vec_data(&cf_handle, rune_id_to_bytes(rune_id))
+            .mapped()?;
+
+        Ok(supply.unwrap_or_default())
+    }
+
+    /// Returns the supply changes for every block in `from..=to` where the rune's supply moved.
+    pub fn get_rune_supply_blocks(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> DBResult<Vec<(u64, RuneSupply)>> {
+        self.get_rune_supply_range(RUNE_SUPPLY_BLOCKS_CF, rune_id, from, to)
+    }
+
+    /// Returns the confirmed supply totals after every block in `from..=to`
+    /// where the rune's supply moved.
+    pub fn get_rune_supply_totals(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> DBResult<Vec<(u64, RuneSupply)>> {
+        self.get_rune_supply_range(RUNE_SUPPLY_TOTALS_CF, rune_id, from, to)
+    }
+
+    fn get_rune_supply_range(
+        &self,
+        cf_name: &str,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> DBResult<Vec<(u64, RuneSupply)>> {
+        let cf_handle = self.cf_handle(cf_name)?;
+        let search_key = rune_block_search_key(rune_id);
+        let start_key = rune_block_key(rune_id, from);
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&start_key, Direction::Forward),
+        );
+
+        let mut blocks = Vec::new();
+        for item in iter {
+            let (key, value) = item?;
+            if !key.starts_with(&search_key) {
+                break;
+            }
+
+            let height =
+                parse_height_from_rune_block_key(&key).map_err(|_| RocksDBError::InvalidU64)?;
+            if height > to {
+                break;
+            }
+
+            blocks.push((height, RuneSupply::load(value.to_vec())));
+        }
+
+        Ok(blocks)
+    }
+
+    /// Confirmed supply totals of the rune after the last block before `height`
+    /// that moved its supply.
+    fn get_rune_supply_totals_before(&self, rune_id: &RuneId, height: u64) -> DBResult<RuneSupply> {
+        let Some(before) = height.checked_sub(1) else {
+            return Ok(RuneSupply::default());
+        };
+
+        let cf_handle = self.cf_handle(RUNE_SUPPLY_TOTALS_CF)?;
+        let start_key = rune_block_key(rune_id, before);
+        let mut iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&start_key, Direction::Reverse),
+        );
+
+        match iter.next().transpose()? {
+            Some((key, value)) if key.starts_with(&rune_block_search_key(rune_id)) => {
+                Ok(RuneSupply::load(value.to_vec()))
+            }
+            _ => Ok(RuneSupply::default()),
+        }
+    }
+
+    pub fn get_rune_utxo_counts(&self, rune_ids: &Vec<RuneId>) -> DBResult<HashMap<RuneId, u64>> {
+        let cf_handle = self.cf_handle(RUNE_UTXO_COUNTS_CF)?;
+        let keys: Vec<_> = rune_ids
+            .iter()
+            .map(|id| (&cf_handle, rune_id_to_bytes(id)))
+            .collect();
+
+        let values = self.db.multi_get_cf(keys);
+
+        let mut result = HashMap::new();
+        for (i, value) in values.into_iter().enumerate() {
+            let utxo_count: Option<u64> = value.map_err(RocksDBError::from).mapped()?;
+            result.insert(rune_ids[i], utxo_count.unwrap_or(0));
+        }
+
+        Ok(result)
+    }
+
     pub fn get_inscription(&self, id: &InscriptionId) -> DBResult<Inscription> {
         let cf_handle = self.cf_handle(INSCRIPTIONS_CF)?;
         let inscription: Inscription = self
@@ -556,6 +1178,8 @@ impl RocksDB {
             return Ok(PaginationResponse {
                 items: vec![],
                 offset: 0,
+                next_cursor: None,
+                prev_cursor: None,
             });
         }
 
@@ -569,6 +1193,8 @@ impl RocksDB {
             return Ok(PaginationResponse {
                 items: vec![],
                 offset,
+                next_cursor: None,
+                prev_cursor: None,
             });
         }
 
@@ -579,15 +1205,7 @@ impl RocksDB {
         // We'll do it cleanly:
         let start_index = if start_index < 1 { 1 } else { start_index };
 
-        let prefix_bytes = {
-            let rune_id_bytes = rune_id_to_bytes(rune_id);
-            // "rune:<rune_id>:"
-            let mut p = Vec::with_capacity(5 + rune_id_bytes.len() + 1);
-            p.extend_from_slice(b"rune:");
-            p.extend_from_slice(&rune_id_bytes);
-            p.push(b':');
-            p
-        };
+        let prefix_bytes = rune_transaction_search_key(rune_id);
 
         // 3. Construct the 'seek_key' for end_index
         let seek_key = rune_transaction_key(rune_id, end_index);
@@ -607,12 +1225,10 @@ impl RocksDB {
             }
 
             // Extract the last 8 bytes for the big-endian index
-            if key_bytes.len() < prefix_bytes.len() + 8 {
+            let Ok(idx) = parse_index_from_rune_transaction_key(&key_bytes) else {
                 // Malformed key?
                 continue;
-            }
-            let idx_bytes = &key_bytes[key_bytes.len() - 8..];
-            let idx = u64::from_le_bytes(idx_bytes.try_into().unwrap());
+            };
 
             if idx < start_index {
                 // We've gone past the range
@@ -640,9 +1256,79 @@ impl RocksDB {
         Ok(PaginationResponse {
             items: results,
             offset,
+            next_cursor: None,
+            prev_cursor: None,
         })
     }
 
+    /// Returns up to `limit` `(index, txid)` pairs for the rune, newest first,
+    /// starting right below `before`.
+    pub fn get_rune_transactions_before(
+        &self,
+        rune_id: &RuneId,
+        before: u64,
+        limit: u64,
+        mempool: bool,
+    ) -> DBResult<Vec<(u64, Txid)>> {
+        let Some(start) = before.checked_sub(1) else {
+            return Ok(vec![]);
+        };
+
+        self.iterate_rune_transactions(rune_id, start, Direction::Reverse, limit, mempool)
+    }
+
+    /// Returns up to `limit` `(index, txid)` pairs for the rune, oldest first,
+    /// starting at `from`.
+    pub fn get_rune_transactions_from(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        limit: u64,
+        mempool: bool,
+    ) -> DBResult<Vec<(u64, Txid)>> {
+        self.iterate_rune_transactions(rune_id, from, Direction::Forward, limit, mempool)
+    }
+
+    fn iterate_rune_transactions(
+        &self,
+        rune_id: &RuneId,
+        start: u64,
+        direction: Direction,
+        limit: u64,
+        mempool: bool,
+    ) -> DBResult<Vec<(u64, Txid)>> {
+        let cf_handle = if mempool {
+            self.cf_handle(RUNE_TRANSACTIONS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(RUNE_TRANSACTIONS_CF)?
+        };
+
+        let search_key = rune_transaction_search_key(rune_id);
+        let start_key = rune_transaction_key(rune_id, start);
+        let iter = self
+            .db
+            .iterator_cf(&cf_handle, IteratorMode::From(&start_key, direction));
+
+        let mut transactions = Vec::new();
+        for item in iter {
+            if transactions.len() as u64 >= limit {
+                break;
+            }
+
+            let (key, value) = item?;
+            if !key.starts_with(&search_key) {
+                break;
+            }
+
+            let index = parse_index_from_rune_transaction_key(&key)
+                .map_err(|_| RocksDBError::InvalidU64)?;
+            let txid = txid_from_bytes(&value).map_err(|_| RocksDBError::InvalidTxid)?;
+            transactions.push((index, txid));
+        }
+
+        Ok(transactions)
+    }
+
     /// Batch-add multiple rune transactions.
     ///
     /// # Arguments
@@ -898,9 +1584,26 @@ impl RocksDB {
             self.cf_handle(SCRIPT_PUBKEYS_CF)?
         };
 
+        self.script_pubkey_outpoints_in(&cf_handle, script_pubkey)
+    }
+
+    /// Confirmed outputs of `script_pubkey` that have been spent in a block.
+    pub fn get_script_pubkey_spent_outpoints(
+        &self,
+        script_pubkey: &ScriptBuf,
+    ) -> DBResult<Vec<OutPoint>> {
+        let cf_handle = self.cf_handle(SCRIPT_PUBKEYS_SPENT_CF)?;
+        self.script_pubkey_outpoints_in(&cf_handle, script_pubkey)
+    }
+
+    fn script_pubkey_outpoints_in(
+        &self,
+        cf_handle: &Arc<BoundColumnFamily<'_>>,
+        script_pubkey: &ScriptBuf,
+    ) -> DBResult<Vec<OutPoint>> {
         let search_key = script_pubkey_search_key(script_pubkey);
         let iter = self.db.iterator_cf(
-            &cf_handle,
+            cf_handle,
             IteratorMode::From(&search_key, Direction::Forward),
         );
 
@@ -920,6 +1623,18 @@ impl RocksDB {
         Ok(outpoints)
     }
 
+    pub fn get_script_pubkey_by_hash(&self, script_hash: &[u8; 32]) -> DBResult<ScriptBuf> {
+        let cf_handle = self.cf_handle(SCRIPT_HASHES_CF)?;
+        let script_pubkey =
+            self.get_option_vec_data(&cf_handle, script_hash)?
+                .ok_or(RocksDBError::NotFound(format!(
+                    "script hash not found: {}",
+                    hex::encode(script_hash)
+                )))?;
+
+        Ok(ScriptBuf::from_bytes(script_pubkey))
+    }
+
     pub fn get_outpoints_to_script_pubkey(
         &self,
         outpoints: &Vec<OutPoint>,
@@ -1129,14 +1844,35 @@ impl RocksDB {
             }
         }
 
-        // 2. Update block_hashes
-        {
-            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(BLOCK_HEIGHT_TO_HASH_CF)?;
-            for (block_height, block_hash) in update.block_hashes.iter() {
+        // 2. Update block_hashes
+        {
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(BLOCK_HEIGHT_TO_HASH_CF)?;
+            for (block_height, block_hash) in update.block_hashes.iter() {
+                batch.put_cf(
+                    &cf_handle,
+                    block_height.to_le_bytes(),
+                    block_hash.as_raw_hash().to_byte_array(),
+                );
+            }
+        }
+
+        // 2b. Update block stats and rune activity
+        {
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(BLOCK_STATS_CF)?;
+            for (block_hash, stats) in update.block_stats.iter() {
+                batch.put_cf(
+                    &cf_handle,
+                    block_hash.as_raw_hash().to_byte_array(),
+                    stats.clone().store(),
+                );
+            }
+
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(BLOCK_RUNE_ACTIVITY_CF)?;
+            for (block_hash, activity) in update.block_rune_activity.iter() {
                 batch.put_cf(
                     &cf_handle,
-                    block_height.to_le_bytes(),
                     block_hash.as_raw_hash().to_byte_array(),
+                    activity.clone().store(),
                 );
             }
         }
@@ -1178,19 +1914,61 @@ impl RocksDB {
         // 5. Update runes
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNES_CF)?;
+            let symbols_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_SYMBOLS_CF)?;
+            let rankings_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNE_RANKINGS_CF)?;
+            let previous_entries =
+                self.get_runes_by_ids(&update.runes.keys().cloned().collect())?;
 
             for (rune_id, rune) in update.runes.iter() {
                 batch.put_cf(&cf_handle, rune_id_to_bytes(&rune_id), rune.clone().store());
+
+                let previous = previous_entries.get(rune_id);
+                add_rune_rankings_to_batch(
+                    &mut batch,
+                    &rankings_cf_handle,
+                    rune_id,
+                    previous,
+                    Some(rune),
+                );
+
+                // New runes are ranked with no unspent outputs until step 18
+                // moves them.
+                if previous.is_none() {
+                    batch.put_cf(
+                        &rankings_cf_handle,
+                        rune_ranking_key(RuneRanking::UtxoCount, 0, rune_id),
+                        vec![1],
+                    );
+                }
+
+                if let Some(symbol) = rune.symbol {
+                    batch.put_cf(
+                        &symbols_cf_handle,
+                        rune_symbol_key(symbol, rune_id),
+                        vec![1],
+                    );
+                }
             }
         }
 
         // 6. Update rune_ids
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_IDS_CF)?;
+            let names_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_NAMES_CF)?;
 
             for (rune, rune_id) in update.rune_ids.iter() {
                 let rune_id_wrapper = RuneIdWrapper(rune_id.clone());
-                batch.put_cf(&cf_handle, rune.to_le_bytes(), rune_id_wrapper.store());
+                batch.put_cf(
+                    &cf_handle,
+                    rune.to_le_bytes(),
+                    rune_id_wrapper.clone().store(),
+                );
+                batch.put_cf(
+                    &names_cf_handle,
+                    rune_name_key(&Rune(*rune)),
+                    rune_id_wrapper.store(),
+                );
             }
         }
 
@@ -1273,7 +2051,18 @@ impl RocksDB {
                 self.cf_handle(SCRIPT_PUBKEYS_CF)?
             };
 
+            let spent_cf_handle = self.cf_handle(SCRIPT_PUBKEYS_SPENT_CF)?;
+            let script_hashes_cf_handle = self.cf_handle(SCRIPT_HASHES_CF)?;
+
             for (script_pubkey, (new_ops, spent_ops)) in update.script_pubkeys.iter() {
+                if !new_ops.is_empty() || !spent_ops.is_empty() {
+                    batch.put_cf(
+                        &script_hashes_cf_handle,
+                        script_hash_key(script_pubkey),
+                        script_pubkey.as_bytes(),
+                    );
+                }
+
                 for outpoint in new_ops.iter() {
                     batch.put_cf(
                         &cf_handle,
@@ -1283,10 +2072,13 @@ impl RocksDB {
                 }
 
                 for outpoint in spent_ops.iter() {
-                    batch.delete_cf(
-                        &cf_handle,
-                        script_pubkey_outpoint_to_bytes(script_pubkey, outpoint),
-                    );
+                    let key = script_pubkey_outpoint_to_bytes(script_pubkey, outpoint);
+                    batch.delete_cf(&cf_handle, &key);
+
+                    // Spent outputs are kept for the script's history.
+                    if !mempool {
+                        batch.put_cf(&spent_cf_handle, &key, vec![1]);
+                    }
                 }
             }
         }
@@ -1348,6 +2140,24 @@ impl RocksDB {
             }
         }
 
+        // 18. Update rune_utxo_counts
+        if !mempool {
+            self.add_rune_utxo_counts_to_batch(&mut batch, &update.rune_utxo_counts, &[])?;
+        }
+
+        // 19. Update rune_mints
+        if !mempool {
+            self.add_rune_mints_to_batch(&mut batch, &update.rune_mints)?;
+        }
+
+        // 20. Update rune_supply
+        self.add_rune_supply_to_batch(&mut batch, &update.rune_supply)?;
+
+        // 21. Update rune_supply_blocks
+        if !mempool {
+            self.add_rune_supply_blocks_to_batch(&mut batch, &update.rune_supply_blocks)?;
+        }
+
         // Proceed with the actual write
         self.db.write(batch)?;
 
@@ -1424,12 +2234,25 @@ impl RocksDB {
         // 2. Update rune_entry
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNES_CF)?;
+            let rankings_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNE_RANKINGS_CF)?;
+            let previous_entries =
+                self.get_runes_by_ids(&rollback.rune_entry.keys().cloned().collect())?;
+
             for (rune_id, rune_entry) in rollback.rune_entry.iter() {
                 batch.put_cf(
                     &cf_handle,
                     rune_id_to_bytes(&rune_id),
                     rune_entry.clone().store(),
                 );
+
+                add_rune_rankings_to_batch(
+                    &mut batch,
+                    &rankings_cf_handle,
+                    rune_id,
+                    previous_entries.get(rune_id),
+                    Some(rune_entry),
+                );
             }
         }
 
@@ -1458,20 +2281,27 @@ impl RocksDB {
                 self.cf_handle(SCRIPT_PUBKEYS_CF)?
             };
 
+            let spent_cf_handle = self.cf_handle(SCRIPT_PUBKEYS_SPENT_CF)?;
+
             for (script_pubkey, (new_ops, spent_ops)) in rollback.script_pubkey_entry.iter() {
                 for outpoint in new_ops.iter() {
-                    batch.put_cf(
-                        &cf_handle,
-                        script_pubkey_outpoint_to_bytes(script_pubkey, outpoint),
-                        vec![1],
-                    );
+                    let key = script_pubkey_outpoint_to_bytes(script_pubkey, outpoint);
+                    batch.put_cf(&cf_handle, &key, vec![1]);
+
+                    // Unspent again.
+                    if !mempool {
+                        batch.delete_cf(&spent_cf_handle, &key);
+                    }
                 }
 
                 for outpoint in spent_ops.iter() {
-                    batch.delete_cf(
-                        &cf_handle,
-                        script_pubkey_outpoint_to_bytes(script_pubkey, outpoint),
-                    );
+                    let key = script_pubkey_outpoint_to_bytes(script_pubkey, outpoint);
+                    batch.delete_cf(&cf_handle, &key);
+
+                    // Gone altogether, even if the reverted block spent it too.
+                    if !mempool {
+                        batch.delete_cf(&spent_cf_handle, &key);
+                    }
                 }
             }
         }
@@ -1514,16 +2344,46 @@ impl RocksDB {
         // 8. Update runes_to_delete
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNES_CF)?;
+            let symbols_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_SYMBOLS_CF)?;
+            let rankings_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNE_RANKINGS_CF)?;
+            let utxo_counts_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNE_UTXO_COUNTS_CF)?;
+            let rune_entries = self.get_runes_by_ids(&rollback.runes_to_delete)?;
+            let utxo_counts = self.get_rune_utxo_counts(&rollback.runes_to_delete)?;
             for rune_id in rollback.runes_to_delete.iter() {
                 batch.delete_cf(&cf_handle, rune_id_to_bytes(rune_id));
+
+                // Step 18 leaves deleted runes alone.
+                let utxo_count = utxo_counts.get(rune_id).cloned().unwrap_or(0);
+                batch.delete_cf(&utxo_counts_cf_handle, rune_id_to_bytes(rune_id));
+                batch.delete_cf(
+                    &rankings_cf_handle,
+                    rune_ranking_key(RuneRanking::UtxoCount, utxo_count.into(), rune_id),
+                );
+
+                let rune_entry = rune_entries.get(rune_id);
+                if let Some(symbol) = rune_entry.and_then(|entry| entry.symbol) {
+                    batch.delete_cf(&symbols_cf_handle, rune_symbol_key(symbol, rune_id));
+                }
+
+                add_rune_rankings_to_batch(
+                    &mut batch,
+                    &rankings_cf_handle,
+                    rune_id,
+                    rune_entry,
+                    None,
+                );
             }
         }
 
         // 9. Update runes_ids_to_delete
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_IDS_CF)?;
+            let names_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_NAMES_CF)?;
             for rune in rollback.runes_ids_to_delete.iter() {
                 batch.delete_cf(&cf_handle, rune.0.to_le_bytes());
+                batch.delete_cf(&names_cf_handle, rune_name_key(rune));
             }
         }
 
@@ -1611,6 +2471,28 @@ impl RocksDB {
             }
         }
 
+        // 18. Update rune_utxo_counts
+        if !mempool {
+            self.add_rune_utxo_counts_to_batch(
+                &mut batch,
+                &rollback.rune_utxo_counts,
+                &rollback.runes_to_delete,
+            )?;
+        }
+
+        // 19. Update rune_mints
+        if !mempool {
+            self.add_rune_mints_to_batch(&mut batch, &rollback.rune_mints)?;
+        }
+
+        // 20. Update rune_supply
+        self.add_rune_supply_to_batch(&mut batch, &rollback.rune_supply)?;
+
+        // 21. Update rune_supply_blocks
+        if !mempool {
+            self.add_rune_supply_blocks_to_batch(&mut batch, &rollback.rune_supply_blocks)?;
+        }
+
         self.db.write(batch)?;
 
         self.delete_rune_transactions(&rollback.txs_to_delete, mempool)?;
@@ -1627,6 +2509,150 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Applies the unspent output count deltas, except to `deleted` runes,
+    /// and moves the runes in the unspent output count ranking.
+    fn add_rune_utxo_counts_to_batch(
+        &self,
+        batch: &mut WriteBatch,
+        rune_utxo_counts: &HashMap<RuneId, i64>,
+        deleted: &[RuneId],
+    ) -> DBResult<()> {
+        if rune_utxo_counts.is_empty() {
+            return Ok(());
+        }
+
+        let rune_ids: Vec<RuneId> = rune_utxo_counts.keys().cloned().collect();
+        let current = self.get_rune_utxo_counts(&rune_ids)?;
+
+        let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_UTXO_COUNTS_CF)?;
+        let rankings_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_RANKINGS_CF)?;
+        for (rune_id, delta) in rune_utxo_counts.iter() {
+            if deleted.contains(rune_id) {
+                continue;
+            }
+
+            let previous = current.get(rune_id).cloned().unwrap_or(0);
+            let utxo_count = previous
+                .checked_add_signed(*delta)
+                .ok_or(RocksDBError::Overflow)?;
+
+            batch.put_cf(
+                &cf_handle,
+                rune_id_to_bytes(rune_id),
+                utxo_count.to_le_bytes(),
+            );
+            batch.delete_cf(
+                &rankings_cf_handle,
+                rune_ranking_key(RuneRanking::UtxoCount, previous.into(), rune_id),
+            );
+            batch.put_cf(
+                &rankings_cf_handle,
+                rune_ranking_key(RuneRanking::UtxoCount, utxo_count.into(), rune_id),
+                vec![1],
+            );
+        }
+
+        Ok(())
+    }
+
+    fn add_rune_mints_to_batch(
+        &self,
+        batch: &mut WriteBatch,
+        rune_mints: &HashMap<(RuneId, u64), i64>,
+    ) -> DBResult<()> {
+        if rune_mints.is_empty() {
+            return Ok(());
+        }
+
+        let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_MINTS_CF)?;
+        let keys: Vec<_> = rune_mints
+            .keys()
+            .map(|(rune_id, height)| rune_block_key(rune_id, *height))
+            .collect();
+
+        let values = self
+            .db
+            .multi_get_cf(keys.iter().map(|key| (&cf_handle, key.clone())));
+
+        for ((key, delta), value) in keys.iter().zip(rune_mints.values()).zip(values) {
+            let current: Option<u64> = value.map_err(RocksDBError::from).mapped()?;
+            let mints = current
+                .unwrap_or(0)
+                .checked_add_signed(*delta)
+                .ok_or(RocksDBError::Overflow)?;
+
+            if mints == 0 {
+                batch.delete_cf(&cf_handle, key);
+            } else {
+                batch.put_cf(&cf_handle, key, mints.to_le_bytes());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_rune_supply_to_batch(
+        &self,
+        batch: &mut WriteBatch,
+        rune_supply: &HashMap<RuneId, RuneSupplyDelta>,
+    ) -> DBResult<()> {
+        let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_SUPPLY_CF)?;
+        for (rune_id, delta) in rune_supply.iter() {
+            let supply = self.get_rune_supply(rune_id)?.apply(delta);
+
+            if supply.is_zero() {
+                batch.delete_cf(&cf_handle, rune_id_to_bytes(rune_id));
+            } else {
+                batch.put_cf(&cf_handle, rune_id_to_bytes(rune_id), supply.store());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies per-block supply changes and rewrites the running totals from
+    /// the first changed block on. Blocks are only added or rolled back at the
+    /// tip, so no rune has records after the ones being changed.
+    fn add_rune_supply_blocks_to_batch(
+        &self,
+        batch: &mut WriteBatch,
+        rune_supply_blocks: &HashMap<(RuneId, u64), RuneSupplyDelta>,
+    ) -> DBResult<()> {
+        if rune_supply_blocks.is_empty() {
+            return Ok(());
+        }
+
+        let mut by_rune: HashMap<RuneId, BTreeMap<u64, &RuneSupplyDelta>> = HashMap::new();
+        for ((rune_id, height), delta) in rune_supply_blocks.iter() {
+            by_rune.entry(*rune_id).or_default().insert(*height, delta);
+        }
+
+        let blocks_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_SUPPLY_BLOCKS_CF)?;
+        let totals_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_SUPPLY_TOTALS_CF)?;
+        for (rune_id, deltas) in by_rune {
+            let first_height = *deltas.keys().next().unwrap();
+            let mut totals = self.get_rune_supply_totals_before(&rune_id, first_height)?;
+
+            for (height, delta) in deltas {
+                let key = rune_block_key(&rune_id, height);
+                let current: Option<RuneSupply> =
+                    self.get_option_vec_data(&blocks_cf_handle, &key).mapped()?;
+                let supply = current.unwrap_or_default().apply(delta);
+
+                if supply.is_zero() {
+                    batch.delete_cf(&blocks_cf_handle, &key);
+                    batch.delete_cf(&totals_cf_handle, &key);
+                } else {
+                    totals = totals.apply(&RuneSupplyDelta::credit(supply));
+                    batch.put_cf(&blocks_cf_handle, &key, supply.store());
+                    batch.put_cf(&totals_cf_handle, &key, totals.store());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn update_rune_numbers_after_revert(
         &self,
         rune_numbers_deleted: &Vec<u64>,
@@ -1689,6 +2715,86 @@ impl RocksDB {
         Ok(())
     }
 
+    pub fn get_replacement(&self, txid: &Txid) -> DBResult<Option<Replacement>> {
+        let cf_handle = self.cf_handle(REPLACEMENTS_CF)?;
+        self.get_option_vec_data(&cf_handle, txid_to_bytes(txid))
+            .mapped()
+    }
+
+    /// Returns the txids that `txid` replaced in the mempool.
+    pub fn get_replaced_txids(&self, txid: &Txid) -> DBResult<Vec<Txid>> {
+        let cf_handle = self.cf_handle(REPLACED_TXS_CF)?;
+        let search_key = txid_to_bytes(txid);
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&search_key, Direction::Forward),
+        );
+
+        let mut txids = Vec::new();
+        for item in iter {
+            let (key, _) = item?;
+            if !key.starts_with(&search_key) {
+                break;
+            }
+
+            txids.push(parse_replaced_txid_from_key(&key).map_err(|_| RocksDBError::InvalidTxid)?);
+        }
+
+        Ok(txids)
+    }
+
+    /// Stores `(replaced, replacing)` pairs noticed at `timestamp`.
+    pub fn set_replacements(&self, replacements: &[(Txid, Txid)], timestamp: u64) -> DBResult<()> {
+        let replacements_cf = self.cf_handle(REPLACEMENTS_CF)?;
+        let replaced_txs_cf = self.cf_handle(REPLACED_TXS_CF)?;
+
+        let mut batch = WriteBatch::default();
+        for (replaced, replacing) in replacements {
+            let replacement = Replacement {
+                replaced_by: *replacing,
+                timestamp,
+            };
+
+            batch.put_cf(
+                &replacements_cf,
+                txid_to_bytes(replaced),
+                replacement.store(),
+            );
+            batch.put_cf(&replaced_txs_cf, replaced_tx_key(replacing, replaced), []);
+        }
+
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// Deletes replacements noticed before `timestamp` and returns how many
+    /// were removed.
+    pub fn delete_replacements_before(&self, timestamp: u64) -> DBResult<u64> {
+        let replacements_cf = self.cf_handle(REPLACEMENTS_CF)?;
+        let replaced_txs_cf = self.cf_handle(REPLACED_TXS_CF)?;
+
+        let mut batch = WriteBatch::default();
+        let mut deleted = 0;
+        for item in self.db.iterator_cf(&replacements_cf, IteratorMode::Start) {
+            let (key, value) = item?;
+            let replacement = Replacement::load(value.to_vec());
+            if replacement.timestamp >= timestamp {
+                continue;
+            }
+
+            let replaced = txid_from_bytes(&key).map_err(|_| RocksDBError::InvalidTxid)?;
+            batch.delete_cf(&replacements_cf, &key);
+            batch.delete_cf(
+                &replaced_txs_cf,
+                replaced_tx_key(&replacement.replaced_by, &replaced),
+            );
+            deleted += 1;
+        }
+
+        self.db.write(batch)?;
+        Ok(deleted)
+    }
+
     pub fn set_subscription(&self, sub: &Subscription) -> DBResult<()> {
         let cf_handle = self.cf_handle(SUBSCRIPTIONS_CF)?;
         self.db
@@ -1753,3 +2859,379 @@ impl RocksDB {
         Ok(())
     }
 }
+
+/// Moves `rune_id` in the mint and supply rankings from its `previous` entry
+/// to its `current` one. `None` is a rune that doesn't exist.
+fn add_rune_rankings_to_batch(
+    batch: &mut WriteBatch,
+    cf_handle: &Arc<BoundColumnFamily<'_>>,
+    rune_id: &RuneId,
+    previous: Option<&RuneEntry>,
+    current: Option<&RuneEntry>,
+) {
+    if let Some(previous) = previous {
+        batch.delete_cf(
+            cf_handle,
+            rune_ranking_key(RuneRanking::Mints, previous.mints, rune_id),
+        );
+        batch.delete_cf(
+            cf_handle,
+            rune_ranking_key(RuneRanking::Supply, previous.supply(), rune_id),
+        );
+    }
+
+    if let Some(current) = current {
+        batch.put_cf(
+            cf_handle,
+            rune_ranking_key(RuneRanking::Mints, current.mints, rune_id),
+            vec![1],
+        );
+        batch.put_cf(
+            cf_handle,
+            rune_ranking_key(RuneRanking::Supply, current.supply(), rune_id),
+            vec![1],
+        );
+    }
+}
+
+type KeyValue = (Box<[u8]>, Box<[u8]>);
+
+/// The items of `iter` as long as their key starts with `prefix`.
+fn with_prefix<'a>(
+    iter: impl Iterator<Item = Result<KeyValue, rocksdb::Error>> + 'a,
+    prefix: Vec<u8>,
+) -> impl Iterator<Item = DBResult<KeyValue>> + 'a {
+    iter.map(|item| item.map_err(RocksDBError::from))
+        .take_while(move |item| match item {
+            Ok((key, _)) => key.starts_with(&prefix),
+            Err(_) => true,
+        })
+}
+
+/// Where a reverse iteration over the fixed-length keys starting with
+/// `prefix` starts.
+fn last_key_with_prefix(prefix: &[u8]) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.extend_from_slice(&[u8::MAX; 32]);
+    key
+}
+
+#[cfg(test)]
+impl RocksDB {
+    /// Opens an empty database in a temporary directory, which goes away with
+    /// the returned `TempDir`.
+    pub(crate) fn open_temp() -> (tempfile::TempDir, Self) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Self::open(dir.path().to_str().unwrap()).unwrap();
+        (dir, db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::models::BatchRollback, bitcoin::hashes::Hash};
+
+    pub(super) fn outpoint(n: u8) -> OutPoint {
+        OutPoint::new(Txid::from_byte_array([n; 32]), 0)
+    }
+
+    fn script_pubkey_entries(
+        script_pubkey: &ScriptBuf,
+        new: Vec<OutPoint>,
+        spent: Vec<OutPoint>,
+    ) -> HashMap<ScriptBuf, (Vec<OutPoint>, Vec<OutPoint>)> {
+        HashMap::from([(script_pubkey.clone(), (new, spent))])
+    }
+
+    #[test]
+    fn spent_outputs_are_kept_until_rolled_back() {
+        let (_dir, db) = RocksDB::open_temp();
+        let script_pubkey = ScriptBuf::from_bytes(vec![0x51]);
+
+        let mut update = BatchUpdate::new(0, 1, 0);
+        update.script_pubkeys =
+            script_pubkey_entries(&script_pubkey, vec![outpoint(1), outpoint(2)], vec![]);
+        db.batch_update(&update, false).unwrap();
+
+        let mut update = BatchUpdate::new(0, 2, 0);
+        update.script_pubkeys = script_pubkey_entries(&script_pubkey, vec![], vec![outpoint(1)]);
+        db.batch_update(&update, false).unwrap();
+
+        assert_eq!(
+            db.get_script_pubkey_outpoints(&script_pubkey, false)
+                .unwrap(),
+            vec![outpoint(2)]
+        );
+        assert_eq!(
+            db.get_script_pubkey_spent_outpoints(&script_pubkey)
+                .unwrap(),
+            vec![outpoint(1)]
+        );
+        assert_eq!(
+            db.get_script_pubkey_by_hash(&script_hash_key(&script_pubkey))
+                .unwrap(),
+            script_pubkey
+        );
+
+        // Reverting the spending block makes the output unspent again.
+        let mut rollback = BatchRollback::new(0);
+        rollback.script_pubkey_entry =
+            script_pubkey_entries(&script_pubkey, vec![outpoint(1)], vec![]);
+        db.batch_rollback(&rollback, false).unwrap();
+
+        let mut outpoints = db
+            .get_script_pubkey_outpoints(&script_pubkey, false)
+            .unwrap();
+        outpoints.sort();
+        assert_eq!(outpoints, vec![outpoint(1), outpoint(2)]);
+        assert!(db
+            .get_script_pubkey_spent_outpoints(&script_pubkey)
+            .unwrap()
+            .is_empty());
+    }
+
+    /// The rune transaction keys in the primary column family, and their values.
+    fn rune_transaction_keys(db: &RocksDB) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let cf_handle = db.cf_handle(RUNE_TRANSACTIONS_CF).unwrap();
+        db.db
+            .iterator_cf(&cf_handle, IteratorMode::Start)
+            .map(|item| {
+                let (key, value) = item.unwrap();
+                (key.to_vec(), value.to_vec())
+            })
+            .filter(|(key, _)| !key.starts_with(b"rune_index:"))
+            .collect()
+    }
+
+    #[test]
+    fn rune_transaction_key_migration_resumes() {
+        let (_dir, db) = RocksDB::open_temp();
+        let rune_id = RuneId::new(840000, 1).unwrap();
+        let settings_cf_handle = db.cf_handle(SETTINGS_CF).unwrap();
+        let cf_handle = db.cf_handle(RUNE_TRANSACTIONS_CF).unwrap();
+
+        let little_endian = |index: u64| {
+            let mut key = rune_transaction_key(&rune_id, index)[..18].to_vec();
+            key.extend_from_slice(&index.to_le_bytes());
+            key
+        };
+        let parked = |index: u64| {
+            let mut key = b"rune_be:".to_vec();
+            key.extend_from_slice(&rune_transaction_key(&rune_id, index)[5..]);
+            key
+        };
+
+        // Stopped while parking converted keys: 1 was converted, 256 wasn't.
+        for key in [
+            RUNE_TRANSACTION_KEYS_PARKED_KEY,
+            RUNE_TRANSACTION_KEYS_BE_KEY,
+        ] {
+            db.db.delete_cf(&settings_cf_handle, key).unwrap();
+        }
+        db.db.put_cf(&cf_handle, parked(1), [1]).unwrap();
+        db.db.put_cf(&cf_handle, little_endian(256), [2]).unwrap();
+
+        db.migrate_rune_transaction_keys().unwrap();
+        db.migrate_rune_transaction_keys().unwrap();
+
+        assert_eq!(
+            rune_transaction_keys(&db),
+            vec![
+                (rune_transaction_key(&rune_id, 1), vec![1]),
+                (rune_transaction_key(&rune_id, 256), vec![2]),
+            ]
+        );
+
+        // Stopped while moving parked keys back: the keys already under
+        // "rune:" are big-endian and must stay as they are.
+        db.db
+            .delete_cf(&settings_cf_handle, RUNE_TRANSACTION_KEYS_BE_KEY)
+            .unwrap();
+        db.db.put_cf(&cf_handle, parked(2), [3]).unwrap();
+
+        db.migrate_rune_transaction_keys().unwrap();
+        db.migrate_rune_transaction_keys().unwrap();
+
+        assert_eq!(
+            rune_transaction_keys(&db),
+            vec![
+                (rune_transaction_key(&rune_id, 1), vec![1]),
+                (rune_transaction_key(&rune_id, 2), vec![3]),
+                (rune_transaction_key(&rune_id, 256), vec![2]),
+            ]
+        );
+    }
+
+    pub(super) fn rune_entry(name: u128, number: u64, mints: u128) -> RuneEntry {
+        RuneEntry {
+            block: 840000,
+            burned: 0,
+            divisibility: 0,
+            etching: Txid::all_zeros(),
+            mints,
+            number,
+            premine: 0,
+            spaced_rune: ordinals::SpacedRune::new(Rune(name), 0),
+            symbol: Some('$'),
+            terms: Some(ordinals::Terms {
+                amount: Some(100),
+                cap: Some(1000),
+                height: (None, None),
+                offset: (None, None),
+            }),
+            pending_burns: 0,
+            pending_mints: 0,
+            inscription_id: None,
+            timestamp: 0,
+            turbo: false,
+        }
+    }
+
+    fn rune_ids(db: &RocksDB, order: RuneOrder) -> Vec<RuneId> {
+        db.get_rune_ids(&order)
+            .unwrap()
+            .map(|rune_id| rune_id.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rune_orders_follow_updates_and_rollbacks() {
+        let (_dir, db) = RocksDB::open_temp();
+        // Rune(0) is "A", Rune(1) is "B".
+        let a = RuneId::new(840000, 1).unwrap();
+        let b = RuneId::new(840000, 2).unwrap();
+
+        let mut update = BatchUpdate::new(2, 1, 0);
+        update.runes = HashMap::from([(a, rune_entry(0, 0, 5)), (b, rune_entry(1, 1, 1))]);
+        update.rune_ids = HashMap::from([(0, a), (1, b)]);
+        update.rune_numbers = HashMap::from([(0, a), (1, b)]);
+        update.rune_utxo_counts = HashMap::from([(a, 1), (b, 3)]);
+        db.batch_update(&update, false).unwrap();
+
+        assert_eq!(rune_ids(&db, RuneOrder::Newest), vec![b, a]);
+        assert_eq!(rune_ids(&db, RuneOrder::Symbol('$')), vec![b, a]);
+        assert!(rune_ids(&db, RuneOrder::Symbol('€')).is_empty());
+        assert_eq!(rune_ids(&db, RuneOrder::NamePrefix("".into())), vec![a, b]);
+        assert_eq!(rune_ids(&db, RuneOrder::NamePrefix("B".into())), vec![b]);
+        assert_eq!(
+            rune_ids(&db, RuneOrder::Ranking(RuneRanking::Mints)),
+            vec![a, b]
+        );
+        assert_eq!(
+            rune_ids(&db, RuneOrder::Ranking(RuneRanking::UtxoCount)),
+            vec![b, a]
+        );
+
+        // Rankings move with the values instead of listing a rune twice.
+        let mut update = BatchUpdate::new(2, 2, 0);
+        update.runes = HashMap::from([(b, rune_entry(1, 1, 10))]);
+        update.rune_utxo_counts = HashMap::from([(a, 4)]);
+        db.batch_update(&update, false).unwrap();
+
+        assert_eq!(
+            rune_ids(&db, RuneOrder::Ranking(RuneRanking::Supply)),
+            vec![b, a]
+        );
+        assert_eq!(
+            rune_ids(&db, RuneOrder::Ranking(RuneRanking::UtxoCount)),
+            vec![a, b]
+        );
+        assert_eq!(db.get_rune_utxo_counts(&vec![a]).unwrap()[&a], 5);
+
+        // Reverting the etching of B takes it out of every order.
+        let mut rollback = BatchRollback::new(1);
+        rollback.runes_to_delete = vec![b];
+        rollback.runes_ids_to_delete = vec![Rune(1)];
+        rollback.rune_numbers_to_delete = vec![1];
+        rollback.rune_utxo_counts = HashMap::from([(b, -3)]);
+        db.batch_rollback(&rollback, false).unwrap();
+
+        for order in [
+            RuneOrder::Newest,
+            RuneOrder::Symbol('$'),
+            RuneOrder::NamePrefix("".into()),
+            RuneOrder::Ranking(RuneRanking::Mints),
+            RuneOrder::Ranking(RuneRanking::Supply),
+            RuneOrder::Ranking(RuneRanking::UtxoCount),
+        ] {
+            assert_eq!(rune_ids(&db, order), vec![a]);
+        }
+    }
+
+    #[test]
+    fn rune_supply_totals_follow_updates_and_rollbacks() {
+        let (_dir, db) = RocksDB::open_temp();
+        let rune_id = RuneId::new(840000, 1).unwrap();
+        let issued = |issued| {
+            RuneSupplyDelta::credit(RuneSupply {
+                issued,
+                ..Default::default()
+            })
+        };
+        let totals = |issued, burned| RuneSupply {
+            issued,
+            burned,
+            ..Default::default()
+        };
+
+        let mut update = BatchUpdate::new(1, 840001, 0);
+        update.rune_supply_blocks = HashMap::from([
+            ((rune_id, 840000), issued(100)),
+            ((rune_id, 840001), issued(10)),
+        ]);
+        db.batch_update(&update, false).unwrap();
+
+        // A later batch builds on the totals already stored.
+        let mut update = BatchUpdate::new(1, 840010, 0);
+        update.rune_supply_blocks =
+            HashMap::from([((rune_id, 840010), RuneSupplyDelta::credit(totals(5, 20)))]);
+        db.batch_update(&update, false).unwrap();
+
+        assert_eq!(
+            db.get_rune_supply_totals(&rune_id, 840001, 840010).unwrap(),
+            vec![(840001, totals(110, 0)), (840010, totals(115, 20))]
+        );
+
+        // Rolling a block back drops its totals with it.
+        let mut rollback = BatchRollback::new(1);
+        rollback.rune_supply_blocks =
+            HashMap::from([((rune_id, 840010), RuneSupplyDelta::debit(totals(5, 20)))]);
+        db.batch_rollback(&rollback, false).unwrap();
+
+        assert_eq!(
+            db.get_rune_supply_totals(&rune_id, 0, 840010).unwrap(),
+            vec![(840000, totals(100, 0)), (840001, totals(110, 0))]
+        );
+        assert!(db
+            .get_rune_supply_blocks(&rune_id, 840002, 840010)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn history_is_only_complete_on_new_indexes() {
+        let (dir, db) = RocksDB::open_temp();
+        assert!(db.has_rune_history().unwrap());
+        assert!(db.has_script_history().unwrap());
+        assert_eq!(db.get_block_summaries_from().unwrap(), 0);
+
+        // An index from before rune and script history and block summaries
+        // were kept.
+        db.set_block_count(840000).unwrap();
+        for key in [
+            RUNE_HISTORY_KEY,
+            SCRIPT_HISTORY_KEY,
+            BLOCK_SUMMARIES_FROM_KEY,
+        ] {
+            db.db
+                .delete_cf(&db.cf_handle(SETTINGS_CF).unwrap(), key)
+                .unwrap();
+        }
+        drop(db);
+
+        let db = RocksDB::open(dir.path().to_str().unwrap()).unwrap();
+        assert!(!db.has_rune_history().unwrap());
+        assert!(!db.has_script_history().unwrap());
+        assert_eq!(db.get_block_summaries_from().unwrap(), 840000);
+    }
+}
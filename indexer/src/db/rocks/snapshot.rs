@@ -0,0 +1,612 @@
+use {
+    super::*,
+    bitcoin::hashes::{sha256, HashEngine},
+    std::io::{self, Read, Write},
+};
+
+const MAGIC: &[u8; 8] = b"TITANSNP";
+const VERSION: u32 = 2;
+
+/// Column families of the rune state, in snapshot order.
+const RUNE_CFS: [&str; 13] = [
+    RUNES_CF,
+    RUNE_IDS_CF,
+    RUNE_NUMBER_CF,
+    RUNE_NAMES_CF,
+    RUNE_SYMBOLS_CF,
+    RUNE_UTXO_COUNTS_CF,
+    RUNE_RANKINGS_CF,
+    RUNE_MINTS_CF,
+    RUNE_SUPPLY_CF,
+    RUNE_SUPPLY_BLOCKS_CF,
+    RUNE_SUPPLY_TOTALS_CF,
+    INSCRIPTIONS_CF,
+    OUTPOINTS_CF,
+];
+
+/// Column families of the address index, included on request.
+const ADDRESS_CFS: [&str; 2] = [SCRIPT_PUBKEYS_CF, OUTPOINT_TO_SCRIPT_PUBKEY_CF];
+
+/// Entries written between batches on import.
+const IMPORT_BATCH_SIZE: usize = 10_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("io error {0}")]
+    Io(#[from] io::Error),
+    #[error("db error {0}")]
+    DB(#[from] RocksDBError),
+    #[error("invalid snapshot: {0}")]
+    Invalid(String),
+    #[error("snapshot hash is {actual}, expected {expected}")]
+    HashMismatch {
+        expected: sha256::Hash,
+        actual: sha256::Hash,
+    },
+    #[error("the index isn't empty, import into a new data dir")]
+    NotEmpty,
+    #[error("addresses aren't indexed")]
+    NoAddresses,
+}
+
+impl From<rocksdb::Error> for SnapshotError {
+    fn from(error: rocksdb::Error) -> Self {
+        Self::DB(error.into())
+    }
+}
+
+type Result<T> = std::result::Result<T, SnapshotError>;
+
+/// What a snapshot holds, and the hash committing to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotInfo {
+    pub chain: String,
+    pub height: u64,
+    pub block_hash: BlockHash,
+    pub addresses: bool,
+    pub hash: sha256::Hash,
+}
+
+struct Header {
+    chain: String,
+    height: u64,
+    block_hash: BlockHash,
+    addresses: bool,
+    rune_history: bool,
+    block: Vec<u8>,
+    runes_count: u64,
+}
+
+impl RocksDB {
+    /// Writes the rune state at the indexed height, and the address index if
+    /// `addresses` is set. State that depends on the mempool or on when
+    /// blocks were flushed is left out, so every index at the same height
+    /// writes the same bytes.
+    ///
+    /// History is left out too: older blocks with their stats and rune
+    /// activity, transactions and spent outputs. An imported index only has
+    /// it for the blocks indexed after the snapshot's, which also means it
+    /// can't roll the snapshot's block back, so only export blocks that are
+    /// deeper than any recoverable reorg.
+    pub fn export_snapshot<W: Write>(
+        &self,
+        chain: &str,
+        addresses: bool,
+        writer: W,
+    ) -> Result<SnapshotInfo> {
+        let block_count = self.get_block_count()?;
+        if block_count == 0 {
+            return Err(SnapshotError::Invalid("no blocks indexed".into()));
+        }
+
+        if addresses && self.is_index_addresses()? != Some(true) {
+            return Err(SnapshotError::NoAddresses);
+        }
+
+        let height = block_count - 1;
+        let block_hash = self.get_block_hash(height)?;
+        let block = self.get_block_by_hash(&block_hash)?;
+
+        let mut writer = HashWriter::new(writer);
+        writer.put(MAGIC)?;
+        writer.put(&VERSION.to_le_bytes())?;
+        writer.put_bytes(chain.as_bytes())?;
+        writer.put(&height.to_le_bytes())?;
+        writer.put(&block_hash.to_byte_array())?;
+        writer.put(&[addresses as u8])?;
+        writer.put(&[self.has_rune_history()? as u8])?;
+        writer.put_bytes(&block.store())?;
+        writer.put(&self.get_runes_count()?.to_le_bytes())?;
+
+        for name in RUNE_CFS {
+            match name {
+                RUNES_CF => self.export_cf(&mut writer, name, |_, value| {
+                    let mut rune_entry = RuneEntry::load(value.to_vec());
+                    rune_entry.pending_burns = 0;
+                    rune_entry.pending_mints = 0;
+                    Ok(Some(rune_entry.store()))
+                })?,
+                // Without the address index, only outputs holding runes are
+                // stored.
+                OUTPOINTS_CF => self.export_cf(&mut writer, name, |_, value| {
+                    let tx_out = TxOutEntry::load(value.to_vec());
+                    let keep = matches!(tx_out.spent, SpentStatus::Unspent)
+                        && (addresses || !tx_out.runes.is_empty());
+                    Ok(keep.then(|| value.to_vec()))
+                })?,
+                _ => self.export_cf(&mut writer, name, |_, value| Ok(Some(value.to_vec())))?,
+            }
+        }
+
+        if addresses {
+            let outpoints_cf_handle = self.cf_handle(OUTPOINTS_CF)?;
+            for name in ADDRESS_CFS {
+                match name {
+                    // Spent outpoints are only removed once their block is
+                    // purged.
+                    OUTPOINT_TO_SCRIPT_PUBKEY_CF => {
+                        self.export_cf(&mut writer, name, |key, value| {
+                            let tx_out: Option<TxOutEntry> = self
+                                .db
+                                .get_cf(&outpoints_cf_handle, key)?
+                                .map(TxOutEntry::load);
+                            let unspent = tx_out
+                                .is_some_and(|tx_out| matches!(tx_out.spent, SpentStatus::Unspent));
+                            Ok(unspent.then(|| value.to_vec()))
+                        })?
+                    }
+                    _ => self.export_cf(&mut writer, name, |_, value| Ok(Some(value.to_vec())))?,
+                }
+            }
+        }
+
+        let hash = writer.finish()?;
+
+        Ok(SnapshotInfo {
+            chain: chain.to_string(),
+            height,
+            block_hash,
+            addresses,
+            hash,
+        })
+    }
+
+    /// Reads a whole snapshot and checks it against the hash at its end,
+    /// without writing anything.
+    pub fn verify_snapshot<R: Read>(reader: R) -> Result<SnapshotInfo> {
+        let mut reader = HashReader::new(reader);
+        let header = read_header(&mut reader)?;
+
+        for name in snapshot_cfs(header.addresses) {
+            read_cf(&mut reader, name, |_, _| Ok(()))?;
+        }
+
+        Ok(header.info(reader.finish()?))
+    }
+
+    /// Writes a snapshot into this index, which has to be empty. Indexing
+    /// continues from the block after the snapshot's. The index is only
+    /// marked as synced to that block once the whole snapshot was read and
+    /// its hash checked.
+    pub fn import_snapshot<R: Read>(&self, reader: R) -> Result<SnapshotInfo> {
+        if self.get_block_count()? != 0 || self.get_runes_count()? != 0 {
+            return Err(SnapshotError::NotEmpty);
+        }
+
+        let mut reader = HashReader::new(reader);
+        let header = read_header(&mut reader)?;
+
+        for name in snapshot_cfs(header.addresses) {
+            let cf_handle = self.cf_handle(name)?;
+            let mut batch = WriteBatch::default();
+            let count = read_cf(&mut reader, name, |key, value| {
+                batch.put_cf(&cf_handle, key, value);
+                if batch.len() >= IMPORT_BATCH_SIZE {
+                    self.db.write(std::mem::take(&mut batch))?;
+                }
+                Ok(())
+            })?;
+            self.db.write(batch)?;
+
+            info!("Imported {} entries into {}", count, name);
+        }
+
+        if header.addresses {
+            let scripts_count = self.index_script_hashes()?;
+            info!("Indexed {} script hashes", scripts_count);
+        }
+
+        let hash = reader.finish()?;
+
+        let mut batch = WriteBatch::default();
+        batch.put_cf(
+            &self.cf_handle(BLOCKS_CF)?,
+            header.block_hash.to_byte_array(),
+            &header.block,
+        );
+        batch.put_cf(
+            &self.cf_handle(BLOCK_HEIGHT_TO_HASH_CF)?,
+            header.height.to_le_bytes(),
+            header.block_hash.to_byte_array(),
+        );
+
+        let stats_cf_handle = self.cf_handle(STATS_CF)?;
+        batch.put_cf(
+            &stats_cf_handle,
+            RUNES_COUNT_KEY,
+            header.runes_count.to_le_bytes(),
+        );
+        batch.put_cf(
+            &stats_cf_handle,
+            BLOCK_COUNT_KEY,
+            (header.height + 1).to_le_bytes(),
+        );
+        // Blocks before the snapshot's aren't stored, so there's nothing to
+        // purge up to it, nor anything to roll back.
+        batch.put_cf(
+            &stats_cf_handle,
+            PURGED_BLOCKS_COUNT_KEY,
+            header.height.to_le_bytes(),
+        );
+
+        batch.put_cf(
+            &self.cf_handle(SETTINGS_CF)?,
+            INDEX_ADDRESSES_KEY,
+            (header.addresses as u64).to_le_bytes(),
+        );
+        // Mint and supply history is only as complete as the exporter's.
+        batch.put_cf(
+            &self.cf_handle(SETTINGS_CF)?,
+            RUNE_HISTORY_KEY,
+            (header.rune_history as u64).to_le_bytes(),
+        );
+        // Spent outputs aren't exported, so scripts that were emptied before
+        // the snapshot's block are missing.
+        batch.put_cf(
+            &self.cf_handle(SETTINGS_CF)?,
+            SCRIPT_HISTORY_KEY,
+            0u64.to_le_bytes(),
+        );
+        // Neither are block stats and rune activity, not even the snapshot
+        // block's.
+        batch.put_cf(
+            &self.cf_handle(SETTINGS_CF)?,
+            BLOCK_SUMMARIES_FROM_KEY,
+            (header.height + 1).to_le_bytes(),
+        );
+
+        self.db.write(batch)?;
+        self.db.flush()?;
+
+        Ok(header.info(hash))
+    }
+
+    fn export_cf<W: Write>(
+        &self,
+        writer: &mut HashWriter<W>,
+        name: &str,
+        mut map: impl FnMut(&[u8], &[u8]) -> Result<Option<Vec<u8>>>,
+    ) -> Result<()> {
+        let cf_handle = self.cf_handle(name)?;
+        writer.put_bytes(name.as_bytes())?;
+
+        let mut count = 0;
+        for item in self.db.iterator_cf(&cf_handle, IteratorMode::Start) {
+            let (key, value) = item?;
+            if let Some(value) = map(&key, &value)? {
+                writer.put(&[1])?;
+                writer.put_bytes(&key)?;
+                writer.put_bytes(&value)?;
+                count += 1;
+            }
+        }
+        writer.put(&[0])?;
+
+        info!("Exported {} entries from {}", count, name);
+        Ok(())
+    }
+}
+
+impl Header {
+    fn info(&self, hash: sha256::Hash) -> SnapshotInfo {
+        SnapshotInfo {
+            chain: self.chain.clone(),
+            height: self.height,
+            block_hash: self.block_hash,
+            addresses: self.addresses,
+            hash,
+        }
+    }
+}
+
+fn snapshot_cfs(addresses: bool) -> Vec<&'static str> {
+    let mut cfs = RUNE_CFS.to_vec();
+    if addresses {
+        cfs.extend(ADDRESS_CFS);
+    }
+    cfs
+}
+
+fn read_header<R: Read>(reader: &mut HashReader<R>) -> Result<Header> {
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(SnapshotError::Invalid("not a snapshot".into()));
+    }
+
+    let version = reader.take_u32()?;
+    if version != VERSION {
+        return Err(SnapshotError::Invalid(format!(
+            "unsupported version {}",
+            version
+        )));
+    }
+
+    let chain = String::from_utf8(reader.take_bytes()?)
+        .map_err(|_| SnapshotError::Invalid("invalid chain".into()))?;
+    let height = reader.take_u64()?;
+    let block_hash = BlockHash::from_slice(&reader.take(32)?)
+        .map_err(|_| SnapshotError::Invalid("invalid block hash".into()))?;
+    let addresses = reader.take(1)?[0] != 0;
+    let rune_history = reader.take(1)?[0] != 0;
+    let block = reader.take_bytes()?;
+    let runes_count = reader.take_u64()?;
+
+    Ok(Header {
+        chain,
+        height,
+        block_hash,
+        addresses,
+        rune_history,
+        block,
+        runes_count,
+    })
+}
+
+/// Reads the entries of the `name` column family, returning how many there
+/// were.
+fn read_cf<R: Read>(
+    reader: &mut HashReader<R>,
+    name: &str,
+    mut entry: impl FnMut(Vec<u8>, Vec<u8>) -> Result<()>,
+) -> Result<u64> {
+    if reader.take_bytes()? != name.as_bytes() {
+        return Err(SnapshotError::Invalid(format!("expected {}", name)));
+    }
+
+    let mut count = 0;
+    loop {
+        match reader.take(1)?[0] {
+            0 => return Ok(count),
+            1 => {
+                let key = reader.take_bytes()?;
+                let value = reader.take_bytes()?;
+                entry(key, value)?;
+                count += 1;
+            }
+            _ => return Err(SnapshotError::Invalid(format!("corrupt entry in {}", name))),
+        }
+    }
+}
+
+/// Writes through to `inner`, hashing everything it writes.
+struct HashWriter<W> {
+    inner: W,
+    engine: sha256::HashEngine,
+}
+
+impl<W: Write> HashWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            engine: sha256::Hash::engine(),
+        }
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.engine.input(bytes);
+        self.inner.write_all(bytes)
+    }
+
+    fn put_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.put(&(bytes.len() as u32).to_le_bytes())?;
+        self.put(bytes)
+    }
+
+    /// Ends the snapshot with the hash of everything before it.
+    fn finish(mut self) -> io::Result<sha256::Hash> {
+        let hash = sha256::Hash::from_engine(self.engine);
+        self.inner.write_all(hash.as_byte_array())?;
+        self.inner.flush()?;
+        Ok(hash)
+    }
+}
+
+/// Reads from `inner`, hashing everything it reads.
+struct HashReader<R> {
+    inner: R,
+    engine: sha256::HashEngine,
+}
+
+impl<R: Read> HashReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            engine: sha256::Hash::engine(),
+        }
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<Vec<u8>> {
+        let mut bytes = vec![0; len];
+        self.inner.read_exact(&mut bytes)?;
+        self.engine.input(&bytes);
+        Ok(bytes)
+    }
+
+    fn take_u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn take_u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn take_bytes(&mut self) -> io::Result<Vec<u8>> {
+        let len = self.take_u32()?;
+        self.take(len as usize)
+    }
+
+    /// Checks the hash the snapshot ends with against the one of what was
+    /// read, and that nothing follows it.
+    fn finish(mut self) -> Result<sha256::Hash> {
+        let actual = sha256::Hash::from_engine(self.engine);
+
+        let mut expected = [0; 32];
+        self.inner.read_exact(&mut expected)?;
+        let expected = sha256::Hash::from_byte_array(expected);
+
+        if expected != actual {
+            return Err(SnapshotError::HashMismatch { expected, actual });
+        }
+
+        if self.inner.read(&mut [0])? != 0 {
+            return Err(SnapshotError::Invalid("trailing data".into()));
+        }
+
+        Ok(actual)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            super::tests::{outpoint, rune_entry},
+            *,
+        },
+        bitcoin::{blockdata::constants::genesis_block, Network},
+        titan_types::RuneAmount,
+    };
+
+    fn cf_entries(db: &RocksDB, name: &str) -> Vec<KeyValue> {
+        let cf_handle = db.cf_handle(name).unwrap();
+        db.db
+            .iterator_cf(&cf_handle, IteratorMode::Start)
+            .map(|item| item.unwrap())
+            .collect()
+    }
+
+    fn export(db: &RocksDB) -> (SnapshotInfo, Vec<u8>) {
+        let mut bytes = Vec::new();
+        let info = db.export_snapshot("regtest", false, &mut bytes).unwrap();
+        (info, bytes)
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let (_dir, db) = RocksDB::open_temp();
+        let a = RuneId::new(840000, 1).unwrap();
+        let b = RuneId::new(840000, 2).unwrap();
+        let header = genesis_block(Network::Regtest).header;
+        let block_hash = header.block_hash();
+
+        let tx_out = |runes: Vec<RuneAmount>, spent: SpentStatus| TxOutEntry {
+            runes,
+            risky_runes: vec![],
+            value: 1000,
+            spent,
+        };
+
+        let mut update = BatchUpdate::new(2, 1, 0);
+        update.blocks = HashMap::from([(block_hash, Block::empty_block(0, header))]);
+        update.block_hashes = HashMap::from([(0, block_hash)]);
+        update.runes = HashMap::from([(a, rune_entry(0, 0, 5)), (b, rune_entry(1, 1, 1))]);
+        update.rune_ids = HashMap::from([(0, a), (1, b)]);
+        update.rune_numbers = HashMap::from([(0, a), (1, b)]);
+        update.rune_utxo_counts = HashMap::from([(a, 1)]);
+        update.txouts = HashMap::from([
+            (
+                outpoint(1),
+                tx_out(vec![RuneAmount::from((a, 10))], SpentStatus::Unspent),
+            ),
+            (outpoint(2), tx_out(vec![], SpentStatus::Unspent)),
+            (
+                outpoint(3),
+                tx_out(
+                    vec![RuneAmount::from((b, 10))],
+                    SpentStatus::Spent(SpenderReference {
+                        txid: outpoint(4).txid,
+                        vin: 0,
+                    }),
+                ),
+            ),
+        ]);
+        db.batch_update(&update, false).unwrap();
+
+        let (info, bytes) = export(&db);
+        assert_eq!(info.height, 0);
+        assert_eq!(info.block_hash, block_hash);
+
+        let (_imported_dir, imported) = RocksDB::open_temp();
+        assert_eq!(imported.import_snapshot(&bytes[..]).unwrap(), info);
+        assert!(matches!(
+            imported.import_snapshot(&bytes[..]),
+            Err(SnapshotError::NotEmpty)
+        ));
+
+        // Exporting again gives the same snapshot.
+        assert_eq!(export(&imported), (info, bytes));
+
+        for name in RUNE_CFS {
+            let entries = cf_entries(&imported, name);
+            match name {
+                // Only unspent outputs holding runes are exported.
+                OUTPOINTS_CF => assert_eq!(
+                    entries.into_iter().map(|(key, _)| key).collect::<Vec<_>>(),
+                    vec![outpoint_to_bytes(&outpoint(1)).into_boxed_slice()]
+                ),
+                _ => assert_eq!(entries, cf_entries(&db, name), "{name}"),
+            }
+        }
+
+        assert_eq!(imported.get_block_count().unwrap(), 1);
+        assert!(imported.has_rune_history().unwrap());
+        assert!(!imported.has_script_history().unwrap());
+        assert_eq!(imported.get_block_summaries_from().unwrap(), 1);
+        assert_eq!(imported.get_block_hash(0).unwrap(), block_hash);
+        assert_eq!(imported.get_runes_count().unwrap(), 2);
+        assert_eq!(
+            imported
+                .get_rune_ids(&RuneOrder::Ranking(RuneRanking::UtxoCount))
+                .unwrap()
+                .map(|rune_id| rune_id.unwrap())
+                .collect::<Vec<_>>(),
+            vec![a, b]
+        );
+        assert_eq!(cf_entries(&imported, BLOCKS_CF), cf_entries(&db, BLOCKS_CF));
+    }
+
+    #[test]
+    fn hash_commits_to_contents() {
+        let mut bytes = Vec::new();
+        let mut writer = HashWriter::new(&mut bytes);
+        writer.put_bytes(b"runes").unwrap();
+        writer.put(&[0]).unwrap();
+        let hash = writer.finish().unwrap();
+
+        let mut reader = HashReader::new(&bytes[..]);
+        assert_eq!(read_cf(&mut reader, "runes", |_, _| Ok(())).unwrap(), 0);
+        assert_eq!(reader.finish().unwrap(), hash);
+
+        bytes[4] ^= 1;
+        let mut reader = HashReader::new(&bytes[..]);
+        assert!(read_cf(&mut reader, "runes", |_, _| Ok(())).is_err());
+
+        bytes[4] ^= 1;
+        bytes[10] ^= 1;
+        let mut reader = HashReader::new(&bytes[..]);
+        read_cf(&mut reader, "runes", |_, _| Ok(())).unwrap();
+        assert!(matches!(
+            reader.finish(),
+            Err(SnapshotError::HashMismatch { .. })
+        ));
+    }
+}
@@ -1,9 +1,14 @@
-use bitcoin::{hashes::Hash, OutPoint, ScriptBuf, Txid};
-use ordinals::RuneId;
+use bitcoin::{
+    hashes::{sha256, Hash},
+    OutPoint, Script, ScriptBuf, Txid,
+};
+use ordinals::{Rune, RuneId};
 use std::convert::TryInto;
 
 use titan_types::InscriptionId;
 
+use crate::models::RuneRanking;
+
 /// Converts an `Txid` to a 32-byte Vec<u8>.
 pub fn txid_to_bytes(txid: &Txid) -> [u8; 32] {
     txid.as_raw_hash().to_byte_array()
@@ -97,6 +102,21 @@ pub fn script_pubkey_search_key(script_pubkey: &ScriptBuf) -> Vec<u8> {
     buffer
 }
 
+/// The sha256 of a script pubkey, what Electrum calls its scripthash before
+/// reversing it for display.
+pub fn script_hash_key(script_pubkey: &Script) -> [u8; 32] {
+    sha256::Hash::hash(script_pubkey.as_bytes()).to_byte_array()
+}
+
+/// The script pubkey a `script_pubkeys` key starts with.
+pub fn parse_script_pubkey_from_script_pubkey_key(key: &[u8]) -> Result<ScriptBuf, &'static str> {
+    if key.len() < 37 || key[key.len() - 37] != b':' {
+        return Err("Invalid key format: missing delimiter");
+    }
+
+    Ok(ScriptBuf::from_bytes(key[..key.len() - 37].to_vec()))
+}
+
 pub fn parse_outpoint_from_script_pubkey_key(key: &[u8]) -> Result<OutPoint, &'static str> {
     // Get the script_pubkey length from the search key
     let script_pubkey_len = key.len() - 36; // total length minus outpoint length
@@ -118,14 +138,129 @@ pub fn rune_index_key(rune_id: &RuneId) -> Vec<u8> {
     v
 }
 
-pub fn rune_transaction_key(rune_id: &RuneId, index: u64) -> Vec<u8> {
+pub fn rune_transaction_search_key(rune_id: &RuneId) -> Vec<u8> {
     let rune_id_bytes = rune_id_to_bytes(rune_id);
-    // Build something like "rune:<id>:\x??\x??...\x??"
+    // "rune:<id>:"
     let mut v = Vec::with_capacity(rune_id_bytes.len() + 6 + 8);
     v.extend_from_slice(b"rune:");
     v.extend_from_slice(&rune_id_bytes);
     v.push(b':');
-    // Now push the index in little-endian
-    v.extend_from_slice(&index.to_le_bytes());
     v
 }
+
+pub fn rune_transaction_key(rune_id: &RuneId, index: u64) -> Vec<u8> {
+    let mut v = rune_transaction_search_key(rune_id);
+    // Big-endian so that a rune's transactions iterate in index order.
+    v.extend_from_slice(&index.to_be_bytes());
+    v
+}
+
+pub fn parse_index_from_rune_transaction_key(key: &[u8]) -> Result<u64, &'static str> {
+    if key.len() != 26 {
+        return Err("Invalid length for rune transaction key, expected 26 bytes");
+    }
+
+    Ok(u64::from_be_bytes(key[18..26].try_into().unwrap()))
+}
+
+/// Key for the rune name index: the rune name without spacers, so that a
+/// forward iteration from any prefix yields every rune starting with it.
+pub fn rune_name_key(rune: &Rune) -> Vec<u8> {
+    rune.to_string().into_bytes()
+}
+
+pub fn rune_symbol_search_key(symbol: char) -> Vec<u8> {
+    let mut buffer = [0u8; 4];
+    symbol.encode_utf8(&mut buffer).as_bytes().to_vec()
+}
+
+pub fn rune_symbol_key(symbol: char, rune_id: &RuneId) -> Vec<u8> {
+    // UTF-8 is prefix free, so the symbol bytes can be used directly as the prefix.
+    let mut v = rune_symbol_search_key(symbol);
+    v.extend_from_slice(&rune_id_to_be_bytes(rune_id));
+    v
+}
+
+pub fn parse_rune_id_from_symbol_key(key: &[u8]) -> Result<RuneId, &'static str> {
+    if key.len() < 12 {
+        return Err("Invalid key format: too short");
+    }
+
+    rune_id_from_be_bytes(&key[key.len() - 12..])
+}
+
+/// Big-endian rune id, for keys that should iterate in etching order.
+fn rune_id_to_be_bytes(rune_id: &RuneId) -> [u8; 12] {
+    let mut buffer = [0u8; 12];
+    buffer[..8].copy_from_slice(&rune_id.block.to_be_bytes());
+    buffer[8..].copy_from_slice(&rune_id.tx.to_be_bytes());
+    buffer
+}
+
+fn rune_id_from_be_bytes(bytes: &[u8]) -> Result<RuneId, &'static str> {
+    if bytes.len() != 12 {
+        return Err("Invalid length for RuneId, expected 12 bytes");
+    }
+
+    Ok(RuneId {
+        block: u64::from_be_bytes(bytes[0..8].try_into().unwrap()),
+        tx: u32::from_be_bytes(bytes[8..12].try_into().unwrap()),
+    })
+}
+
+pub fn rune_ranking_search_key(ranking: RuneRanking) -> Vec<u8> {
+    vec![ranking as u8]
+}
+
+/// Key for the rune rankings: the ranking, then the value and the rune id,
+/// both big-endian, so that iterating a ranking backwards yields the highest
+/// values first and the newest rune first among equal values.
+pub fn rune_ranking_key(ranking: RuneRanking, value: u128, rune_id: &RuneId) -> Vec<u8> {
+    let mut v = rune_ranking_search_key(ranking);
+    v.extend_from_slice(&value.to_be_bytes());
+    v.extend_from_slice(&rune_id_to_be_bytes(rune_id));
+    v
+}
+
+pub fn parse_rune_id_from_ranking_key(key: &[u8]) -> Result<RuneId, &'static str> {
+    if key.len() != 29 {
+        return Err("Invalid length for rune ranking key, expected 29 bytes");
+    }
+
+    rune_id_from_be_bytes(&key[17..])
+}
+
+pub fn rune_block_search_key(rune_id: &RuneId) -> Vec<u8> {
+    rune_id_to_bytes(rune_id)
+}
+
+/// Height is stored big-endian so that keys for a rune iterate in block order.
+pub fn rune_block_key(rune_id: &RuneId, height: u64) -> Vec<u8> {
+    let mut v = rune_block_search_key(rune_id);
+    v.extend_from_slice(&height.to_be_bytes());
+    v
+}
+
+/// Keyed by the replacing txid so that everything a transaction replaced can
+/// be found with a prefix scan.
+pub fn replaced_tx_key(replacing: &Txid, replaced: &Txid) -> Vec<u8> {
+    let mut v = txid_to_bytes(replacing).to_vec();
+    v.extend_from_slice(&txid_to_bytes(replaced));
+    v
+}
+
+pub fn parse_replaced_txid_from_key(key: &[u8]) -> Result<Txid, &'static str> {
+    if key.len() != 64 {
+        return Err("Invalid length for replaced tx key, expected 64 bytes");
+    }
+
+    txid_from_bytes(&key[32..64])
+}
+
+pub fn parse_height_from_rune_block_key(key: &[u8]) -> Result<u64, &'static str> {
+    if key.len() != 20 {
+        return Err("Invalid length for rune block key, expected 20 bytes");
+    }
+
+    Ok(u64::from_be_bytes(key[12..20].try_into().unwrap()))
+}
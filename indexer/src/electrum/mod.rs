@@ -0,0 +1,14 @@
+//! Electrum protocol server backed by the address index, for wallets that sync
+//! over Electrum. Started with `--enable-electrum`, it needs
+//! `--index-addresses`.
+//!
+//! Subscriptions are driven by the event dispatcher: every session registers
+//! for block and mempool events and reports the headers and scripthash
+//! statuses those changed. Events are never waited on: a session that falls
+//! behind rechecks all of its subscriptions, and one that stops reading is
+//! disconnected.
+
+mod server;
+mod session;
+
+pub use server::run_electrum_server;
@@ -0,0 +1,190 @@
+use {
+    super::session::{Changes, Session},
+    crate::{
+        bitcoin_rpc::RpcClientPool,
+        index::Index,
+        subscription::{TcpSubscription, TcpSubscriptionManager},
+    },
+    serde_json::Value,
+    std::{
+        io,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    titan_types::{Event, EventType},
+    tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        net::{
+            tcp::{OwnedReadHalf, OwnedWriteHalf},
+            TcpListener, TcpStream,
+        },
+        sync::{mpsc, watch},
+        task, time,
+    },
+    tracing::{error, info},
+    uuid::Uuid,
+};
+
+/// Events that can move the tip or change a scripthash's history.
+const EVENT_TYPES: [EventType; 3] = [
+    EventType::NewBlock,
+    EventType::Reorg,
+    EventType::AddressModified,
+];
+
+/// Events buffered per session. Beyond this the session is marked as lagged
+/// and rechecks all of its subscriptions instead.
+const EVENT_CHANNEL_SIZE: usize = 1000;
+
+/// Longest request line accepted, which leaves room for large batches and
+/// transaction broadcasts.
+const MAX_LINE_LENGTH: usize = 1 << 20;
+
+/// How long a client may take to accept a response before it's disconnected.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run the Electrum server on the given address, spawning a session for
+/// each connection.
+pub async fn run_electrum_server(
+    addr: &str,
+    index: Arc<Index>,
+    bitcoin_rpc_pool: RpcClientPool,
+    manager: Arc<TcpSubscriptionManager>,
+    mut shutdown_rx: watch::Receiver<()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind(addr).await?;
+    info!("Electrum server listening on {}", addr);
+
+    loop {
+        tokio::select! {
+            accept_result = listener.accept() => {
+                let (socket, remote_addr) = accept_result?;
+                info!("New Electrum connection from {}", remote_addr);
+                let session = Session::new(index.clone(), bitcoin_rpc_pool.clone());
+                let manager_clone = manager.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(socket, session, manager_clone).await {
+                        error!("Error handling Electrum connection from {}: {:?}", remote_addr, e);
+                    }
+                });
+            }
+            _ = shutdown_rx.changed() => {
+                info!("Electrum server shutting down");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Answers the requests of one connection, one JSON-RPC message per line, and
+/// sends notifications as events come in until the client disconnects.
+async fn handle_connection(
+    socket: TcpStream,
+    mut session: Session,
+    manager: Arc<TcpSubscriptionManager>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (reader, mut writer) = socket.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+
+    let (tx, mut rx) = mpsc::channel::<Event>(EVENT_CHANNEL_SIZE);
+    let lagged = Arc::new(AtomicBool::new(false));
+    let subscription = TcpSubscription {
+        id: Uuid::new_v4(),
+        event_types: EVENT_TYPES.into_iter().collect(),
+        sender: tx,
+        lagged: lagged.clone(),
+    };
+    let sub_id = subscription.id;
+    manager.register(subscription).await;
+
+    let result = loop {
+        tokio::select! {
+            line = read_line(&mut reader, &mut buf) => {
+                let line = match line {
+                    Ok(Some(line)) => line,
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(e.into()),
+                };
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let response = task::block_in_place(|| session.handle(&line));
+                if let Err(e) = write_messages(&mut writer, &[response]).await {
+                    break Err(e);
+                }
+            }
+            Some(event) = rx.recv() => {
+                // Events come in bursts, one pass covers all of them.
+                let mut changes = Changes::default();
+                changes.add(&event);
+                while let Ok(event) = rx.try_recv() {
+                    changes.add(&event);
+                }
+
+                // Some events were dropped, so any subscription may have
+                // changed.
+                if lagged.swap(false, Ordering::AcqRel) {
+                    changes.add_all();
+                }
+
+                let notifications = task::block_in_place(|| session.notifications(&changes));
+                if let Err(e) = write_messages(&mut writer, &notifications).await {
+                    break Err(e);
+                }
+            }
+        }
+    };
+
+    manager.unregister(sub_id).await;
+    result
+}
+
+/// Reads the next request line, or `None` once the client disconnects.
+/// Partial lines stay in `buf`, so this can be cancelled and called again.
+async fn read_line(
+    reader: &mut BufReader<OwnedReadHalf>,
+    buf: &mut Vec<u8>,
+) -> io::Result<Option<String>> {
+    let limit = (MAX_LINE_LENGTH + 1).saturating_sub(buf.len()) as u64;
+    (&mut *reader).take(limit).read_until(b'\n', buf).await?;
+
+    if buf.last() != Some(&b'\n') {
+        if buf.len() > MAX_LINE_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request line too long",
+            ));
+        }
+
+        // The connection closed before the line ended.
+        return Ok(None);
+    }
+
+    let line = String::from_utf8_lossy(buf).into_owned();
+    buf.clear();
+    Ok(Some(line))
+}
+
+async fn write_messages(
+    writer: &mut OwnedWriteHalf,
+    messages: &[Value],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut bytes = Vec::new();
+    for message in messages {
+        serde_json::to_writer(&mut bytes, message)?;
+        bytes.push(b'\n');
+    }
+
+    time::timeout(WRITE_TIMEOUT, writer.write_all(&bytes))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "client stopped reading"))??;
+    Ok(())
+}
@@ -0,0 +1,566 @@
+use {
+    crate::{
+        api::{
+            self,
+            electrum::{self, Header},
+            ApiError,
+        },
+        bitcoin_rpc::{RpcClientPool, RpcClientPoolError},
+        index::Index,
+    },
+    bitcoin::Txid,
+    bitcoincore_rpc::jsonrpc::error::Error as JsonRpcError,
+    serde::{Deserialize, Serialize},
+    serde_json::{json, Value},
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
+    titan_types::Event,
+    tracing::error,
+};
+
+const PROTOCOL_VERSION: &str = "1.4";
+
+/// Most scripthashes one connection can subscribe to.
+const MAX_SUBSCRIPTIONS: usize = 10_000;
+
+// JSON-RPC error codes, and the ones ElectrumX uses for bad requests and
+// errors from bitcoind.
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const BAD_REQUEST: i32 = 1;
+const DAEMON_ERROR: i32 = 2;
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Vec<Value>,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<ApiError> for RpcError {
+    fn from(error: ApiError) -> Self {
+        match error {
+            ApiError::RpcError(bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(error))) => {
+                Self::new(DAEMON_ERROR, error.message)
+            }
+            ApiError::RpcError(error) => Self::new(DAEMON_ERROR, error),
+            error => Self::new(BAD_REQUEST, error),
+        }
+    }
+}
+
+impl From<RpcClientPoolError> for RpcError {
+    fn from(error: RpcClientPoolError) -> Self {
+        Self::new(DAEMON_ERROR, error)
+    }
+}
+
+type RpcResult = Result<Value, RpcError>;
+
+/// What the events since the last notifications may have changed.
+#[derive(Debug, Default)]
+pub(super) struct Changes {
+    header: bool,
+    /// A reorg rolled back blocks without saying which scripts they touched.
+    all_script_hashes: bool,
+    script_hashes: HashSet<[u8; 32]>,
+}
+
+impl Changes {
+    pub(super) fn add(&mut self, event: &Event) {
+        match event {
+            Event::NewBlock { .. } => self.header = true,
+            Event::Reorg { .. } => {
+                self.header = true;
+                self.all_script_hashes = true;
+            }
+            Event::AddressModified { address, .. } => {
+                if let Some(script_hash) = electrum::address_script_hash(address) {
+                    self.script_hashes.insert(script_hash);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// For when events were missed, so anything may have changed.
+    pub(super) fn add_all(&mut self) {
+        self.header = true;
+        self.all_script_hashes = true;
+    }
+
+    fn includes(&self, script_hash: &[u8; 32]) -> bool {
+        self.all_script_hashes || self.script_hashes.contains(script_hash)
+    }
+}
+
+/// State of one Electrum connection: what it subscribed to and what it was
+/// last told.
+pub(super) struct Session {
+    index: Arc<Index>,
+    bitcoin_rpc_pool: RpcClientPool,
+    /// Last header sent, once subscribed to headers.
+    header: Option<Header>,
+    /// Last status sent for each subscribed scripthash, with the scripthash as
+    /// the client sent it.
+    statuses: HashMap<[u8; 32], (String, Option<String>)>,
+}
+
+impl Session {
+    pub(super) fn new(index: Arc<Index>, bitcoin_rpc_pool: RpcClientPool) -> Self {
+        Self {
+            index,
+            bitcoin_rpc_pool,
+            header: None,
+            statuses: HashMap::new(),
+        }
+    }
+
+    /// Answers a request line, or a batch of requests.
+    pub(super) fn handle(&mut self, line: &str) -> Value {
+        match serde_json::from_str(line) {
+            Ok(Value::Array(requests)) => requests
+                .into_iter()
+                .map(|request| self.handle_request(request))
+                .collect(),
+            Ok(request) => self.handle_request(request),
+            Err(e) => response(Value::Null, Err(RpcError::new(PARSE_ERROR, e))),
+        }
+    }
+
+    /// Notifications for the subscriptions `changes` touched, if they changed
+    /// since they were last reported.
+    pub(super) fn notifications(&mut self, changes: &Changes) -> Vec<Value> {
+        let mut notifications = Vec::new();
+
+        if let (true, Some(last_header)) = (changes.header, &self.header) {
+            match electrum::header(&self.index) {
+                Ok(header) if header != *last_header => {
+                    notifications.push(notification("blockchain.headers.subscribe", [&header]));
+                    self.header = Some(header);
+                }
+                Ok(_) => {}
+                Err(e) => error!("Failed to get Electrum header: {:?}", e),
+            }
+        }
+
+        for (parsed, (script_hash, last_status)) in self.statuses.iter_mut() {
+            if !changes.includes(parsed) {
+                continue;
+            }
+
+            match electrum::status(&self.index, parsed) {
+                Ok(status) if status != *last_status => {
+                    notifications.push(notification(
+                        "blockchain.scripthash.subscribe",
+                        (script_hash, &status),
+                    ));
+                    *last_status = status;
+                }
+                Ok(_) => {}
+                Err(e) => error!("Failed to get status of {}: {:?}", script_hash, e),
+            }
+        }
+
+        notifications
+    }
+
+    fn handle_request(&mut self, request: Value) -> Value {
+        match serde_json::from_value::<Request>(request) {
+            Ok(request) => {
+                let result = self.call(&request.method, &request.params);
+                response(request.id, result)
+            }
+            Err(e) => response(Value::Null, Err(RpcError::new(INVALID_REQUEST, e))),
+        }
+    }
+
+    fn call(&mut self, method: &str, params: &[Value]) -> RpcResult {
+        match method {
+            "server.version" => to_value((server_version(), PROTOCOL_VERSION)),
+            "server.ping" => Ok(Value::Null),
+            "server.features" => Ok(json!({
+                "genesis_hash": self.index.get_genesis_block_hash(),
+                "hosts": {},
+                "protocol_min": PROTOCOL_VERSION,
+                "protocol_max": PROTOCOL_VERSION,
+                "server_version": server_version(),
+                "hash_function": "sha256",
+                "pruning": null,
+            })),
+            "server.banner" => to_value(server_version()),
+            "server.donation_address" => Ok(json!("")),
+            "server.peers.subscribe" => Ok(json!([])),
+            "blockchain.relayfee" => to_value(electrum::relay_fee()),
+            "blockchain.estimatefee" => {
+                let target = u64_param(params, 0)?.clamp(1, u16::MAX.into()) as u16;
+                to_value(electrum::estimate_fee(&self.index, target)?)
+            }
+            "mempool.get_fee_histogram" => to_value(electrum::fee_histogram(&self.index)?),
+            "blockchain.block.header" => {
+                let height = u64_param(params, 0)?;
+                match cp_height_param(params, 1)? {
+                    0 => to_value(electrum::block_header(&self.index, height)?),
+                    cp_height => to_value(electrum::proven_block_header(
+                        &self.index,
+                        height,
+                        cp_height,
+                    )?),
+                }
+            }
+            "blockchain.block.headers" => to_value(electrum::block_headers(
+                &self.index,
+                u64_param(params, 0)?,
+                u64_param(params, 1)?,
+                cp_height_param(params, 2)?,
+            )?),
+            "blockchain.headers.subscribe" => {
+                let header = electrum::header(&self.index)?;
+                let result = to_value(&header);
+                self.header = Some(header);
+                result
+            }
+            "blockchain.scripthash.get_balance" => {
+                let script_hash = script_hash_param(params)?;
+                to_value(electrum::balance(&self.index, &script_hash)?)
+            }
+            "blockchain.scripthash.get_history" => {
+                let script_hash = script_hash_param(params)?;
+                to_value(electrum::history(&self.index, &script_hash)?)
+            }
+            "blockchain.scripthash.listunspent" => {
+                let script_hash = script_hash_param(params)?;
+                to_value(electrum::unspent(&self.index, &script_hash)?)
+            }
+            "blockchain.scripthash.subscribe" => {
+                let script_hash = script_hash_param(params)?;
+                if self.statuses.len() >= MAX_SUBSCRIPTIONS
+                    && !self.statuses.contains_key(&script_hash)
+                {
+                    return Err(RpcError::new(
+                        BAD_REQUEST,
+                        format!(
+                            "at most {MAX_SUBSCRIPTIONS} scripthash subscriptions per connection"
+                        ),
+                    ));
+                }
+
+                let status = electrum::status(&self.index, &script_hash)?;
+                self.statuses.insert(
+                    script_hash,
+                    (str_param(params, 0)?.to_string(), status.clone()),
+                );
+                to_value(status)
+            }
+            "blockchain.scripthash.unsubscribe" => {
+                let script_hash = script_hash_param(params)?;
+                to_value(self.statuses.remove(&script_hash).is_some())
+            }
+            "blockchain.transaction.broadcast" => {
+                let txid = api::broadcast_transaction(
+                    self.index.clone(),
+                    self.bitcoin_rpc_pool.get()?,
+                    str_param(params, 0)?,
+                )?;
+                to_value(txid)
+            }
+            "blockchain.transaction.get" => {
+                let txid = txid_param(params, 0)?;
+                if params.get(1).and_then(Value::as_bool).unwrap_or_default() {
+                    return Err(RpcError::new(
+                        BAD_REQUEST,
+                        "verbose transactions aren't supported",
+                    ));
+                }
+
+                to_value(api::bitcoin_transaction_hex(
+                    self.index.clone(),
+                    self.bitcoin_rpc_pool.get()?,
+                    &txid,
+                )?)
+            }
+            "blockchain.transaction.get_merkle" => to_value(electrum::merkle_proof(
+                &self.index,
+                &txid_param(params, 0)?,
+                u64_param(params, 1)?,
+            )?),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method {method}"),
+            )),
+        }
+    }
+}
+
+fn server_version() -> String {
+    format!("Titan {}", env!("CARGO_PKG_VERSION"))
+}
+
+fn str_param(params: &[Value], position: usize) -> Result<&str, RpcError> {
+    params
+        .get(position)
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("missing parameter {position}")))
+}
+
+fn u64_param(params: &[Value], position: usize) -> Result<u64, RpcError> {
+    params
+        .get(position)
+        .and_then(Value::as_u64)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("missing parameter {position}")))
+}
+
+/// The checkpoint height of header methods, zero when left out.
+fn cp_height_param(params: &[Value], position: usize) -> Result<u64, RpcError> {
+    match params.get(position) {
+        Some(_) => u64_param(params, position),
+        None => Ok(0),
+    }
+}
+
+fn txid_param(params: &[Value], position: usize) -> Result<Txid, RpcError> {
+    str_param(params, position)?
+        .parse()
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e))
+}
+
+fn script_hash_param(params: &[Value]) -> Result<[u8; 32], RpcError> {
+    electrum::parse_script_hash(str_param(params, 0)?)
+        .map_err(|_| RpcError::new(INVALID_PARAMS, "invalid scripthash"))
+}
+
+fn to_value(value: impl Serialize) -> RpcResult {
+    serde_json::to_value(value).map_err(|e| RpcError::new(BAD_REQUEST, e))
+}
+
+fn response(id: Value, result: RpcResult) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    }
+}
+
+fn notification(method: &str, params: impl Serialize) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::models::BatchUpdate,
+        bitcoin::{
+            blockdata::constants::genesis_block,
+            consensus,
+            hashes::{sha256d, Hash},
+            merkle_tree, BlockHash, Network, TxMerkleNode,
+        },
+        titan_types::{Block, Location},
+    };
+
+    const ADDRESS: &str = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+
+    fn txid(n: u8) -> Txid {
+        Txid::from_byte_array([n; 32])
+    }
+
+    fn call(session: &mut Session, method: &str, params: Value) -> Value {
+        let request = json!({ "id": 0, "method": method, "params": params });
+        let mut response = session.handle(&request.to_string());
+        assert!(response.get("error").is_none(), "{method}: {response}");
+        response["result"].take()
+    }
+
+    #[test]
+    fn answers_a_wallet_connecting() {
+        let (dir, db, index) = Index::open_temp();
+        let bitcoin_rpc_pool = RpcClientPool::new(Arc::new(Index::temp_settings(dir.path())), 1);
+
+        let mut headers = vec![genesis_block(Network::Regtest).header];
+        for nonce in 1..3 {
+            let mut header = headers[headers.len() - 1];
+            header.prev_blockhash = header.block_hash();
+            header.nonce = nonce;
+            headers.push(header);
+        }
+        let hashes: Vec<BlockHash> = headers.iter().map(|header| header.block_hash()).collect();
+
+        let mut update = BatchUpdate::new(0, 3, 0);
+        for (height, header) in headers.iter().enumerate() {
+            let mut block = Block::empty_block(height as u64, *header);
+            if height == 2 {
+                block.tx_ids = (1..=3).map(|n| txid(n).to_string()).collect();
+            }
+            update.blocks.insert(hashes[height], block);
+            update.block_hashes.insert(height as u64, hashes[height]);
+        }
+        db.batch_update(&update, false).unwrap();
+
+        let mut session = Session::new(Arc::new(index), bitcoin_rpc_pool);
+        let header_hex = |height: usize| consensus::encode::serialize_hex(&headers[height]);
+
+        let version = call(&mut session, "server.version", json!(["Sparrow", "1.4"]));
+        assert_eq!(version[1], PROTOCOL_VERSION);
+
+        let features = call(&mut session, "server.features", json!([]));
+        assert_eq!(features["genesis_hash"], json!(hashes[0]));
+        assert_eq!(features["hash_function"], "sha256");
+
+        assert!(call(&mut session, "server.banner", json!([])).is_string());
+        assert_eq!(call(&mut session, "server.donation_address", json!([])), "");
+        assert_eq!(
+            call(&mut session, "server.peers.subscribe", json!([])),
+            json!([])
+        );
+        assert_eq!(
+            call(&mut session, "blockchain.relayfee", json!([])),
+            0.00001
+        );
+
+        let tip = call(&mut session, "blockchain.headers.subscribe", json!([]));
+        assert_eq!(tip, json!({ "height": 2, "hex": header_hex(2) }));
+
+        let chunk = call(&mut session, "blockchain.block.headers", json!([0, 2016]));
+        assert_eq!(chunk["count"], 3);
+        assert_eq!(chunk["max"], 2016);
+        assert_eq!(
+            chunk["hex"],
+            (0..3).map(header_hex).collect::<String>().as_str()
+        );
+        assert!(chunk.get("root").is_none());
+
+        assert_eq!(
+            call(&mut session, "blockchain.block.header", json!([1])),
+            header_hex(1)
+        );
+
+        let root = merkle_tree::calculate_root(
+            hashes
+                .iter()
+                .map(|hash| TxMerkleNode::from_raw_hash(hash.to_raw_hash())),
+        )
+        .unwrap()
+        .to_string();
+        let proven = call(&mut session, "blockchain.block.header", json!([1, 2]));
+        assert_eq!(proven["header"], header_hex(1));
+        assert_eq!(proven["root"], root.as_str());
+        assert_eq!(
+            proven["branch"],
+            json!([
+                hashes[0].to_raw_hash().to_string(),
+                sha256d::Hash::hash(&[hashes[2].to_byte_array(); 2].concat()).to_string(),
+            ])
+        );
+
+        let proven_chunk = call(&mut session, "blockchain.block.headers", json!([0, 2, 2]));
+        assert_eq!(proven_chunk["count"], 2);
+        assert_eq!(proven_chunk["root"], root.as_str());
+
+        assert_eq!(
+            call(&mut session, "blockchain.estimatefee", json!([2])),
+            0.00001
+        );
+        assert_eq!(
+            call(&mut session, "mempool.get_fee_histogram", json!([])),
+            json!([])
+        );
+
+        let script_hash = "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161";
+        assert_eq!(
+            call(
+                &mut session,
+                "blockchain.scripthash.subscribe",
+                json!([script_hash])
+            ),
+            Value::Null
+        );
+        assert_eq!(
+            call(
+                &mut session,
+                "blockchain.scripthash.get_history",
+                json!([script_hash])
+            ),
+            json!([])
+        );
+
+        let proof = call(
+            &mut session,
+            "blockchain.transaction.get_merkle",
+            json!([txid(2), 2]),
+        );
+        assert_eq!(proof["block_height"], 2);
+        assert_eq!(proof["pos"], 1);
+        assert_eq!(proof["merkle"].as_array().unwrap().len(), 2);
+
+        assert_eq!(call(&mut session, "server.ping", json!([])), Value::Null);
+
+        let unknown = session.handle(r#"{"id": 1, "method": "blockchain.unknown"}"#);
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn changes_only_include_modified_script_hashes() {
+        let modified = electrum::address_script_hash(ADDRESS).unwrap();
+        let other = [0; 32];
+
+        let mut changes = Changes::default();
+        changes.add(&Event::AddressModified {
+            address: ADDRESS.to_string(),
+            location: Location::mempool(),
+        });
+        assert!(!changes.header);
+        assert!(changes.includes(&modified));
+        assert!(!changes.includes(&other));
+
+        changes.add(&Event::NewBlock {
+            block_hash: BlockHash::from_byte_array([1; 32]),
+            block_height: 840000,
+        });
+        assert!(changes.header);
+        assert!(!changes.includes(&other));
+    }
+
+    #[test]
+    fn lagging_includes_every_script_hash() {
+        let mut changes = Changes::default();
+        changes.add_all();
+        assert!(changes.header);
+        assert!(changes.includes(&[0; 32]));
+    }
+
+    #[test]
+    fn reorgs_include_every_script_hash() {
+        let mut changes = Changes::default();
+        changes.add(&Event::Reorg {
+            height: 840000,
+            depth: 2,
+        });
+        assert!(changes.header);
+        assert!(changes.includes(&[0; 32]));
+    }
+}
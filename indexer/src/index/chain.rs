@@ -1,5 +1,5 @@
 use {
-    bitcoin::{address::FromScriptError, Address, Network, Script},
+    bitcoin::{address::FromScriptError, Address, Amount, Network, Script},
     clap::ValueEnum,
     ordinals::Rune,
     serde::{Deserialize, Serialize},
@@ -41,6 +41,21 @@ impl Chain {
     pub(crate) fn address_from_script(self, script: &Script) -> Result<Address, FromScriptError> {
         Address::from_script(script, self.network())
     }
+
+    /// Block subsidy at `height`, in sats.
+    pub(crate) fn subsidy(self, height: u64) -> u64 {
+        let halving_interval = match self {
+            Self::Regtest => 150,
+            _ => 210_000,
+        };
+
+        let halvings = height / halving_interval;
+        if halvings >= 64 {
+            return 0;
+        }
+
+        (Amount::ONE_BTC.to_sat() * 50) >> halvings
+    }
 }
 
 impl From<Chain> for Network {
@@ -104,4 +119,12 @@ mod tests {
             "invalid chain `foo`"
         );
     }
+
+    #[test]
+    fn subsidy() {
+        assert_eq!(Chain::Mainnet.subsidy(0), 5_000_000_000);
+        assert_eq!(Chain::Mainnet.subsidy(840_000), 312_500_000);
+        assert_eq!(Chain::Regtest.subsidy(150), 2_500_000_000);
+        assert_eq!(Chain::Regtest.subsidy(150 * 64), 0);
+    }
 }
@@ -1,6 +1,7 @@
 use {
     super::{
         metrics::Metrics,
+        readiness, rune_search,
         settings::Settings,
         store::{Store, StoreError},
         updater::Updater,
@@ -9,22 +10,32 @@ use {
     crate::{
         bitcoin_rpc::{RpcClientError, RpcClientPool},
         index::updater::{ReorgError, UpdaterError},
-        models::{block_id_to_transaction_status, Inscription, RuneEntry},
+        models::{
+            block_id_to_transaction_status, Inscription, RuneEntry, RuneSupply,
+            TransactionStateChange,
+        },
+    },
+    bitcoin::{
+        blockdata::constants::genesis_block,
+        consensus,
+        hashes::{sha256, Hash},
+        Address, BlockHash, OutPoint, Script, ScriptBuf, Transaction as BitcoinTransaction, Txid,
     },
-    bitcoin::{Address, BlockHash, OutPoint, Transaction as BitcoinTransaction, Txid},
     ordinals::{Rune, RuneId},
     std::{
-        collections::HashMap,
+        collections::{hash_map, HashMap, HashSet},
+        ops::Range,
         sync::{
             atomic::{AtomicBool, Ordering},
             Arc,
         },
-        thread::{self},
         time::Duration,
     },
     titan_types::{
-        AddressData, AddressTxOut, Block, Event, InscriptionId, MempoolEntry, Pagination,
-        PaginationResponse, RuneAmount, Transaction, TransactionStatus, TxOutEntry,
+        AddressData, AddressTxOut, Block, BlockRuneActivity, BlockStats, Event, InscriptionId,
+        MempoolBlocks, MempoolEntry, MempoolFees, MempoolTxPosition, Pagination,
+        PaginationResponse, Readiness, RuneAction, RuneActivity, RuneAmount, RuneSearch,
+        SpentStatus, Transaction, TransactionStatus, TxOutEntry, TxReplacement,
     },
     tokio::{runtime::Runtime, sync::mpsc::Sender},
     tracing::{error, info, warn},
@@ -188,7 +199,9 @@ impl Index {
                 }
             }
 
-            thread::sleep(Duration::from_millis(self.settings.main_loop_interval));
+            self.updater
+                .mempool_sequence()
+                .wait(Duration::from_millis(self.settings.main_loop_interval));
         }
 
         let rt = Runtime::new().expect("Failed to create runtime");
@@ -202,6 +215,17 @@ impl Index {
             .await;
     }
 
+    /// Whether the index is caught up with the node, blocks and mempool.
+    pub fn readiness(&self) -> Result<Readiness> {
+        Ok(readiness::readiness(
+            self.updater.has_synced_once(),
+            self.updater.is_at_tip(),
+            self.updater.is_mempool_synced(),
+            self.db.get_block_count()?,
+            self.updater.node_height(),
+        ))
+    }
+
     pub fn get_block_count(&self) -> Result<u64> {
         Ok(self.db.get_block_count()?)
     }
@@ -210,10 +234,33 @@ impl Index {
         Ok(self.db.get_block_hash(height)?)
     }
 
+    /// Of the chain the index follows, whether or not it indexed the block.
+    pub fn get_genesis_block_hash(&self) -> BlockHash {
+        genesis_block(self.settings.chain.network()).block_hash()
+    }
+
     pub fn get_block_by_hash(&self, hash: &BlockHash) -> Result<Block> {
         Ok(self.db.get_block_by_hash(hash)?)
     }
 
+    /// Height of the first block with stats and rune activity. It's above 0
+    /// on an index created before they were kept or imported from a snapshot.
+    pub fn get_block_summaries_from(&self) -> Result<u64> {
+        Ok(self.db.get_block_summaries_from()?)
+    }
+
+    /// Stored when the block was indexed. Not found for blocks below
+    /// `get_block_summaries_from`.
+    pub fn get_block_stats(&self, hash: &BlockHash) -> Result<BlockStats> {
+        Ok(self.db.get_block_stats(hash)?)
+    }
+
+    /// Every rune etched, minted, burned or transferred in a block, in block
+    /// order. Like the stats, stored when the block was indexed.
+    pub fn get_block_rune_activity(&self, hash: &BlockHash) -> Result<Vec<BlockRuneActivity>> {
+        Ok(self.db.get_block_rune_activity(hash)?)
+    }
+
     pub fn get_mempool_txids(&self) -> Result<Vec<Txid>> {
         Ok(self.db.get_mempool_txids()?.keys().cloned().collect())
     }
@@ -233,6 +280,22 @@ impl Index {
         Ok(self.db.get_mempool_txids()?)
     }
 
+    pub fn get_mempool_fees(&self) -> Result<MempoolFees> {
+        Ok(self.updater.get_mempool_fees()?)
+    }
+
+    pub fn get_mempool_blocks(
+        &self,
+        max_blocks: usize,
+        txid: Option<&Txid>,
+    ) -> Result<MempoolBlocks> {
+        Ok(self.updater.get_mempool_blocks(max_blocks, txid)?)
+    }
+
+    pub fn get_mempool_tx_position(&self, txid: &Txid) -> Result<Option<MempoolTxPosition>> {
+        Ok(self.updater.get_mempool_tx_position(txid)?)
+    }
+
     pub fn get_tx_out(&self, outpoint: &OutPoint) -> Result<TxOutEntry> {
         Ok(self
             .db
@@ -245,15 +308,119 @@ impl Index {
             .get_tx_outs_with_mempool_spent_update(outpoints, None)?)
     }
 
+    /// Spent status of each of `outpoints`, mempool spends included. `None`
+    /// for outputs the index doesn't track that no mempool transaction spends.
+    pub fn get_outspends(
+        &self,
+        outpoints: &Vec<OutPoint>,
+    ) -> Result<HashMap<OutPoint, Option<SpentStatus>>> {
+        let tx_outs = self.db.get_tx_outs(outpoints, None)?;
+        let mempool_spenders = self.db.get_spent_outpoints_in_mempool(outpoints)?;
+
+        Ok(outpoints
+            .iter()
+            .map(|outpoint| {
+                let spent = match mempool_spenders.get(outpoint) {
+                    Some(Some(spender)) => Some(SpentStatus::Spent(spender.clone())),
+                    _ => tx_outs.get(outpoint).map(|tx_out| tx_out.spent.clone()),
+                };
+                (*outpoint, spent)
+            })
+            .collect())
+    }
+
     pub fn get_rune(&self, rune_id: &RuneId) -> Result<RuneEntry> {
         Ok(self.db.get_rune(rune_id)?)
     }
 
-    pub fn get_runes(
+    pub fn get_runes_by_ids(&self, rune_ids: &Vec<RuneId>) -> Result<HashMap<RuneId, RuneEntry>> {
+        Ok(self.db.get_runes_by_ids(rune_ids)?)
+    }
+
+    /// Newest first.
+    pub fn get_runes_by_number(&self, numbers: Range<u64>) -> Result<Vec<(RuneId, RuneEntry)>> {
+        Ok(self.db.get_runes_by_number(numbers)?)
+    }
+
+    pub fn get_rune_utxo_counts(&self, rune_ids: &Vec<RuneId>) -> Result<HashMap<RuneId, u64>> {
+        Ok(self.db.get_rune_utxo_counts(rune_ids)?)
+    }
+
+    pub fn get_rune_mints(&self, rune_id: &RuneId, from: u64, to: u64) -> Result<Vec<(u64, u64)>> {
+        Ok(self.db.get_rune_mints(rune_id, from, to)?)
+    }
+
+    pub fn get_rune_supply(&self, rune_id: &RuneId) -> Result<RuneSupply> {
+        Ok(self.db.get_rune_supply(rune_id)?)
+    }
+
+    pub fn get_rune_supply_totals(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, RuneSupply)>> {
+        Ok(self.db.get_rune_supply_totals(rune_id, from, to)?)
+    }
+
+    /// Whether rune mints and supply were recorded for every indexed block,
+    /// which isn't the case on an index created before they were.
+    pub fn has_rune_history(&self) -> Result<bool> {
+        Ok(self.db.has_rune_history()?)
+    }
+
+    /// Runes matching `search`, with their unspent output count. Runes are
+    /// read in the order of the page until it's full, so a page costs about
+    /// `skip + limit` plus whatever the filters reject.
+    pub fn search_runes(
         &self,
+        search: &RuneSearch,
         pagination: Pagination,
-    ) -> Result<PaginationResponse<(RuneId, RuneEntry)>> {
-        Ok(self.db.get_runes(pagination)?)
+    ) -> Result<PaginationResponse<(RuneId, RuneEntry, u64)>> {
+        let (skip, limit) = pagination.into();
+        let height = self.db.get_block_count()?;
+
+        let mut runes = Vec::new();
+        if let Some(order) = rune_search::order(search) {
+            let mut skipped = 0;
+            for rune_id in self.db.get_rune_ids(&order)? {
+                if runes.len() as u64 >= limit {
+                    break;
+                }
+
+                let rune_id = rune_id?;
+                let rune_entry = self.db.get_rune(&rune_id)?;
+                if !rune_search::matches(search, &order, &rune_entry, height) {
+                    continue;
+                }
+
+                if skipped < skip {
+                    skipped += 1;
+                    continue;
+                }
+
+                runes.push((rune_id, rune_entry));
+            }
+        }
+
+        let utxo_counts = self
+            .db
+            .get_rune_utxo_counts(&runes.iter().map(|(rune_id, _)| *rune_id).collect())?;
+        let items: Vec<_> = runes
+            .into_iter()
+            .map(|(rune_id, rune_entry)| {
+                let utxo_count = utxo_counts.get(&rune_id).cloned().unwrap_or(0);
+                (rune_id, rune_entry, utxo_count)
+            })
+            .collect();
+
+        let offset = skip + items.len() as u64;
+        Ok(PaginationResponse {
+            items,
+            offset,
+            next_cursor: None,
+            prev_cursor: None,
+        })
     }
 
     pub fn get_rune_id(&self, rune: &Rune) -> Result<RuneId> {
@@ -279,17 +446,308 @@ impl Index {
             .get_last_rune_transactions(rune_id, pagination, mempool)?)
     }
 
+    pub fn get_rune_transactions_before(
+        &self,
+        rune_id: &RuneId,
+        before: u64,
+        limit: u64,
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>> {
+        Ok(self
+            .db
+            .get_rune_transactions_before(rune_id, before, limit, mempool)?)
+    }
+
+    pub fn get_rune_transactions_from(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        limit: u64,
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>> {
+        Ok(self
+            .db
+            .get_rune_transactions_from(rune_id, from, limit, mempool)?)
+    }
+
+    /// Describes what each transaction did with the rune. `txids` pairs each
+    /// txid with whether it's in the mempool; the order is kept.
+    pub fn get_rune_activity(
+        &self,
+        rune_id: &RuneId,
+        txids: &[(Txid, bool)],
+    ) -> Result<Vec<RuneActivity>> {
+        let txids_where = |in_mempool: bool| -> Vec<Txid> {
+            txids
+                .iter()
+                .filter(|(_, mempool)| *mempool == in_mempool)
+                .map(|(txid, _)| *txid)
+                .collect()
+        };
+        let mempool_txids = txids_where(true);
+        let confirmed_txids = txids_where(false);
+
+        let mut state_changes = self.db.get_txs_state_changes(&confirmed_txids, false)?;
+        state_changes.extend(self.db.get_txs_state_changes(&mempool_txids, true)?);
+
+        let confirming_blocks = self
+            .db
+            .get_transaction_confirming_blocks(&confirmed_txids)?;
+        let mut block_times: HashMap<BlockHash, Option<u64>> = HashMap::new();
+        for block_id in confirming_blocks.values().flatten() {
+            if block_times.contains_key(&block_id.hash) {
+                continue;
+            }
+
+            // Old blocks may have been purged, the height is still known.
+            let time = match self.db.get_block_by_hash(&block_id.hash) {
+                Ok(block) => Some(block.header.time as u64),
+                Err(StoreError::NotFound(_)) => None,
+                Err(e) => return Err(e.into()),
+            };
+            block_times.insert(block_id.hash, time);
+        }
+
+        let inputs: Vec<OutPoint> = state_changes
+            .values()
+            .flat_map(|state_change| state_change.inputs.iter().cloned())
+            .collect();
+        let spent_outputs = self.db.get_tx_outs(&inputs, None)?;
+
+        let holds_rune =
+            |tx_out: &TxOutEntry| tx_out.runes.iter().any(|rune| rune.rune_id == *rune_id);
+
+        let mut senders: HashMap<Txid, Vec<OutPoint>> = HashMap::new();
+        let mut receivers: HashMap<Txid, Vec<OutPoint>> = HashMap::new();
+        for (txid, state_change) in state_changes.iter() {
+            senders.insert(
+                *txid,
+                state_change
+                    .inputs
+                    .iter()
+                    .filter(|input| spent_outputs.get(input).is_some_and(holds_rune))
+                    .cloned()
+                    .collect(),
+            );
+            receivers.insert(
+                *txid,
+                state_change
+                    .outputs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, output)| holds_rune(output))
+                    .map(|(vout, _)| OutPoint {
+                        txid: *txid,
+                        vout: vout as u32,
+                    })
+                    .collect(),
+            );
+        }
+
+        let outpoints: Vec<OutPoint> = senders
+            .values()
+            .chain(receivers.values())
+            .flatten()
+            .cloned()
+            .collect();
+        let script_pubkeys = self.get_script_pubkeys(&outpoints)?;
+        let addresses = |outpoints: Option<&Vec<OutPoint>>| -> Vec<String> {
+            let mut addresses: Vec<String> = Vec::new();
+            for outpoint in outpoints.into_iter().flatten() {
+                let Some(address) = script_pubkeys.get(outpoint).and_then(|script_pubkey| {
+                    self.settings.chain.address_from_script(script_pubkey).ok()
+                }) else {
+                    continue;
+                };
+
+                let address = address.to_string();
+                if !addresses.contains(&address) {
+                    addresses.push(address);
+                }
+            }
+            addresses
+        };
+
+        let premine = if state_changes
+            .values()
+            .any(|state_change| matches!(state_change.etched, Some((id, _)) if id == *rune_id))
+        {
+            self.db.get_rune(rune_id)?.premine
+        } else {
+            0
+        };
+
+        let mut activity = Vec::with_capacity(txids.len());
+        for (txid, mempool) in txids {
+            let Some(state_change) = state_changes.get(txid) else {
+                continue;
+            };
+
+            let sender_scripts: HashSet<&ScriptBuf> = senders
+                .get(txid)
+                .into_iter()
+                .flatten()
+                .filter_map(|outpoint| script_pubkeys.get(outpoint))
+                .collect();
+            let sent = sent_to_others(
+                rune_id,
+                txid,
+                &state_change.outputs,
+                &script_pubkeys,
+                &sender_scripts,
+            );
+            let burned = state_change
+                .burned
+                .get(rune_id)
+                .map_or(0, |burned| burned.n());
+
+            let (action, amount) = match (state_change.etched, &state_change.minted) {
+                (Some((id, _)), _) if id == *rune_id => (RuneAction::Etch, premine),
+                (_, Some(minted)) if minted.rune_id == *rune_id => {
+                    (RuneAction::Mint, minted.amount)
+                }
+                _ if sent == 0 && burned > 0 => (RuneAction::Burn, burned),
+                _ => (RuneAction::Transfer, sent),
+            };
+
+            let block_id = confirming_blocks
+                .get(txid)
+                .and_then(|block_id| block_id.as_ref());
+
+            activity.push(RuneActivity {
+                txid: *txid,
+                action,
+                amount,
+                burned,
+                senders: addresses(senders.get(txid)),
+                receivers: addresses(receivers.get(txid)),
+                height: block_id.map(|block_id| block_id.height),
+                timestamp: block_id
+                    .and_then(|block_id| block_times.get(&block_id.hash).copied().flatten()),
+                mempool: *mempool,
+            });
+        }
+
+        Ok(activity)
+    }
+
+    /// Scripts come from the address index when enabled, otherwise from the
+    /// stored bitcoin transactions. Outpoints that can't be resolved are left
+    /// out.
+    pub fn get_script_pubkeys(
+        &self,
+        outpoints: &Vec<OutPoint>,
+    ) -> Result<HashMap<OutPoint, ScriptBuf>> {
+        if self.settings.index_addresses {
+            return Ok(self
+                .db
+                .get_outpoints_to_script_pubkey(outpoints, None, true)?);
+        }
+
+        let mut script_pubkeys = HashMap::new();
+        if !self.settings.index_bitcoin_transactions {
+            return Ok(script_pubkeys);
+        }
+
+        let mut transactions: HashMap<Txid, Option<BitcoinTransaction>> = HashMap::new();
+        for outpoint in outpoints {
+            let transaction = match transactions.entry(outpoint.txid) {
+                hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(match self.db.get_transaction_raw(&outpoint.txid, None) {
+                        Ok(raw) => consensus::deserialize(&raw).ok(),
+                        Err(StoreError::NotFound(_)) => None,
+                        Err(e) => return Err(e.into()),
+                    })
+                }
+            };
+
+            if let Some(tx_out) = transaction
+                .as_ref()
+                .and_then(|transaction| transaction.output.get(outpoint.vout as usize))
+            {
+                script_pubkeys.insert(*outpoint, tx_out.script_pubkey.clone());
+            }
+        }
+
+        Ok(script_pubkeys)
+    }
+
     pub fn get_script_pubkey_outpoints(&self, address: &Address) -> Result<AddressData> {
         let script_pubkey = address.script_pubkey();
         let outpoints = self.db.get_script_pubkey_outpoints(&script_pubkey, None)?;
+        self.get_address_data(&script_pubkey, outpoints)
+    }
+
+    /// Like `get_script_pubkey_outpoints`, but keeps the outputs a mempool
+    /// transaction spends, with their spender.
+    pub fn get_script_pubkey_outputs(&self, script_pubkey: &ScriptBuf) -> Result<AddressData> {
+        let mut outpoints = self
+            .db
+            .get_script_pubkey_outpoints(script_pubkey, Some(false))?;
+        outpoints.extend(
+            self.db
+                .get_script_pubkey_outpoints(script_pubkey, Some(true))?,
+        );
+        self.get_address_data(script_pubkey, outpoints)
+    }
+
+    /// Every output `script_pubkey` has had, spent or not, so every
+    /// transaction that funded or spent it. Outputs spent in a block are only
+    /// kept from the version that started keeping them, earlier ones are
+    /// missing.
+    pub fn get_script_pubkey_history(
+        &self,
+        script_pubkey: &ScriptBuf,
+    ) -> Result<Vec<AddressTxOut>> {
+        let mut outpoints = self.db.get_script_pubkey_spent_outpoints(script_pubkey)?;
+        outpoints.extend(
+            self.db
+                .get_script_pubkey_outpoints(script_pubkey, Some(false))?,
+        );
+        outpoints.extend(
+            self.db
+                .get_script_pubkey_outpoints(script_pubkey, Some(true))?,
+        );
+        Ok(self.get_address_data(script_pubkey, outpoints)?.outputs)
+    }
+
+    /// Only scripts that have had an output since `--index-addresses` was on.
+    pub fn get_script_pubkey_by_hash(&self, script_hash: &[u8; 32]) -> Result<ScriptBuf> {
+        Ok(self.db.get_script_pubkey_by_hash(script_hash)?)
+    }
+
+    /// Whether every script that had an output, spent or not, can be found
+    /// by its hash, which isn't the case on an index created before spent
+    /// outputs were kept or imported from a snapshot.
+    pub fn has_script_history(&self) -> Result<bool> {
+        Ok(self.db.has_script_history()?)
+    }
+
+    /// Whether `script_pubkey` has had an output, spent or not, since
+    /// `--index-addresses` was on.
+    pub fn is_script_pubkey_used(&self, script_pubkey: &Script) -> Result<bool> {
+        let script_hash = sha256::Hash::hash(script_pubkey.as_bytes()).to_byte_array();
+        match self.db.get_script_pubkey_by_hash(&script_hash) {
+            Ok(_) => Ok(true),
+            Err(StoreError::NotFound(_)) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn get_address_data(
+        &self,
+        script_pubkey: &Script,
+        outpoints: Vec<OutPoint>,
+    ) -> Result<AddressData> {
         let outpoints_to_tx_out: HashMap<OutPoint, TxOutEntry> = self
             .db
             .get_tx_outs_with_mempool_spent_update(&outpoints, None)?;
 
         if outpoints.len() != outpoints_to_tx_out.len() {
             error!(
-                "Address {} has {} outpoints but {} txouts",
-                address,
+                "Script {} has {} outpoints but {} txouts",
+                script_pubkey,
                 outpoints.len(),
                 outpoints_to_tx_out.len()
             );
@@ -353,6 +811,51 @@ impl Index {
         Ok(self.db.get_transaction(txid, None)?)
     }
 
+    /// Confirmed or mempool. Not found once a confirmed transaction has been
+    /// purged.
+    pub fn get_transaction_state_change(&self, txid: &Txid) -> Result<TransactionStateChange> {
+        Ok(self.db.get_tx_state_changes(txid, None)?)
+    }
+
+    /// `None` for scripts without an address form, like `OP_RETURN`.
+    pub fn get_address(&self, script_pubkey: &Script) -> Option<Address> {
+        self.settings.chain.address_from_script(script_pubkey).ok()
+    }
+
+    /// Mempool replacement history of `txid`. Replaced transactions are
+    /// removed from the index, so this is empty for unknown txids rather than
+    /// an error.
+    pub fn get_transaction_replacement(&self, txid: &Txid) -> Result<TxReplacement> {
+        // Replacement chains rarely go past a handful of bumps.
+        const MAX_CHAIN_LENGTH: usize = 100;
+
+        let replaces = self.db.get_replaced_txids(txid)?;
+        let replacement = self.db.get_replacement(txid)?;
+
+        let mut replacement_chain = Vec::new();
+        let mut seen = HashSet::from([*txid]);
+        let mut next = replacement.as_ref().map(|r| r.replaced_by);
+        while let Some(replacing) = next {
+            if !seen.insert(replacing) || replacement_chain.len() == MAX_CHAIN_LENGTH {
+                break;
+            }
+
+            replacement_chain.push(replacing);
+            next = self
+                .db
+                .get_replacement(&replacing)?
+                .map(|replacement| replacement.replaced_by);
+        }
+
+        Ok(TxReplacement {
+            txid: *txid,
+            replaces,
+            replaced_by: replacement.as_ref().map(|r| r.replaced_by),
+            replaced_at: replacement.map(|r| r.timestamp),
+            replacement_chain,
+        })
+    }
+
     pub fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus> {
         let result = self.db.get_transaction_confirming_block(txid);
         match result {
@@ -368,6 +871,35 @@ impl Index {
         }
     }
 
+    /// `None` for transactions the index doesn't know about.
+    pub fn get_transaction_statuses(
+        &self,
+        txids: &Vec<Txid>,
+    ) -> Result<HashMap<Txid, Option<TransactionStatus>>> {
+        let confirming_blocks = self.db.get_transaction_confirming_blocks(txids)?;
+
+        let not_in_block = confirming_blocks
+            .iter()
+            .filter(|(_, block_id)| block_id.is_none())
+            .map(|(txid, _)| *txid)
+            .collect();
+        let (unconfirmed, _) = self.db.partition_transactions_by_existence(&not_in_block)?;
+        let unconfirmed: HashSet<Txid> = unconfirmed.into_iter().collect();
+
+        Ok(confirming_blocks
+            .into_iter()
+            .map(|(txid, block_id)| {
+                let status = match block_id {
+                    Some(block_id) => Some(block_id.into_transaction_status()),
+                    None => unconfirmed
+                        .contains(&txid)
+                        .then(TransactionStatus::unconfirmed),
+                };
+                (txid, status)
+            })
+            .collect())
+    }
+
     pub fn pre_index_new_submitted_transaction(&self, txid: &Txid) -> Result<()> {
         Ok(self.updater.pre_index_new_submitted_transaction(txid)?)
     }
@@ -392,3 +924,179 @@ impl Index {
         }
     }
 }
+
+/// Amount of the rune held by the outputs of `txid` whose script isn't one of
+/// `sender_scripts`, so change back to the sender isn't counted. Outputs with
+/// an unknown script are counted.
+fn sent_to_others(
+    rune_id: &RuneId,
+    txid: &Txid,
+    outputs: &[TxOutEntry],
+    script_pubkeys: &HashMap<OutPoint, ScriptBuf>,
+    sender_scripts: &HashSet<&ScriptBuf>,
+) -> u128 {
+    outputs
+        .iter()
+        .enumerate()
+        .filter(|(vout, _)| {
+            let outpoint = OutPoint {
+                txid: *txid,
+                vout: *vout as u32,
+            };
+            script_pubkeys
+                .get(&outpoint)
+                .is_none_or(|script_pubkey| !sender_scripts.contains(script_pubkey))
+        })
+        .flat_map(|(_, output)| output.runes.iter())
+        .filter(|rune| rune.rune_id == *rune_id)
+        .map(|rune| rune.amount)
+        .sum()
+}
+
+#[cfg(test)]
+impl Index {
+    /// An index over an empty database in a temporary directory, along with
+    /// the database to set up what the test needs. Nothing connects to a node
+    /// unless a test calls something that does.
+    pub(crate) fn open_temp() -> (tempfile::TempDir, Arc<crate::db::RocksDB>, Self) {
+        let (dir, db) = crate::db::RocksDB::open_temp();
+        let db = Arc::new(db);
+
+        let settings = Self::temp_settings(dir.path());
+        let bitcoin_rpc_pool = RpcClientPool::new(Arc::new(settings.clone()), 1);
+
+        let index = Self::new(db.clone(), bitcoin_rpc_pool, settings, None);
+        (dir, db, index)
+    }
+
+    /// Settings of `open_temp` indexes, pointing at a node that isn't there.
+    pub(crate) fn temp_settings(data_dir: &std::path::Path) -> Settings {
+        Settings {
+            data_dir: data_dir.to_path_buf(),
+            blocks_dir: None,
+            zmq_endpoint: "tcp://127.0.0.1:0".into(),
+            bitcoin_rpc_limit: 1,
+            bitcoin_rpc_url: "http://127.0.0.1:0".into(),
+            bitcoin_rpc_auth: bitcoincore_rpc::Auth::None,
+            chain: super::Chain::Regtest,
+            no_index_inscriptions: true,
+            index_bitcoin_transactions: false,
+            index_addresses: false,
+            commit_interval: 1,
+            main_loop_interval: 1,
+            pipeline_depth: 1,
+            replacement_history_hours: 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::models::{BatchUpdate, BlockId},
+        titan_types::MempoolEntryFee,
+    };
+
+    fn txid(n: u8) -> Txid {
+        Txid::from_byte_array([n; 32])
+    }
+
+    fn mempool_entry() -> MempoolEntry {
+        MempoolEntry {
+            vsize: 100,
+            weight: Some(400),
+            descendant_count: 1,
+            descendant_size: 100,
+            ancestor_count: 1,
+            ancestor_size: 100,
+            fees: MempoolEntryFee {
+                base: 100,
+                descendant: 100,
+                ancestor: 100,
+            },
+            depends: vec![],
+            spent_by: vec![],
+        }
+    }
+
+    #[test]
+    fn transaction_statuses_split_confirmed_unconfirmed_and_unknown() {
+        let (_dir, db, index) = Index::open_temp();
+        let block_id = BlockId {
+            hash: BlockHash::from_byte_array([7; 32]),
+            height: 840000,
+        };
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.transaction_confirming_block = HashMap::from([(txid(1), block_id.clone())]);
+        db.batch_update(&update, false).unwrap();
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.mempool_txs = HashMap::from([(txid(2), mempool_entry())]);
+        db.batch_update(&update, true).unwrap();
+
+        let statuses = index
+            .get_transaction_statuses(&vec![txid(1), txid(2), txid(3)])
+            .unwrap();
+        assert_eq!(statuses.len(), 3);
+
+        let confirmed = statuses[&txid(1)].clone().unwrap();
+        assert!(confirmed.confirmed);
+        assert_eq!(confirmed.block_height, Some(840000));
+        assert_eq!(confirmed.block_hash, Some(block_id.hash));
+
+        let unconfirmed = statuses[&txid(2)].clone().unwrap();
+        assert!(!unconfirmed.confirmed);
+        assert_eq!(unconfirmed.block_height, None);
+
+        assert!(statuses[&txid(3)].is_none());
+    }
+
+    #[test]
+    fn sent_to_others_leaves_out_change_to_the_sender() {
+        let rune_id = RuneId { block: 1, tx: 1 };
+        let output = |amount: u128| TxOutEntry {
+            runes: vec![RuneAmount { rune_id, amount }],
+            risky_runes: vec![],
+            value: 546,
+            spent: SpentStatus::Unspent,
+        };
+        let outputs = vec![output(30), output(70), output(5)];
+        let sender = ScriptBuf::from_bytes(vec![1]);
+        let receiver = ScriptBuf::from_bytes(vec![2]);
+        let outpoint = |vout: u32| OutPoint {
+            txid: txid(1),
+            vout,
+        };
+
+        // The third output's script is unknown, so it counts as sent.
+        let script_pubkeys = HashMap::from([
+            (outpoint(0), receiver.clone()),
+            (outpoint(1), sender.clone()),
+        ]);
+        let sender_scripts = HashSet::from([&sender]);
+        assert_eq!(
+            sent_to_others(
+                &rune_id,
+                &txid(1),
+                &outputs,
+                &script_pubkeys,
+                &sender_scripts
+            ),
+            35
+        );
+
+        // Without the senders' scripts every output counts.
+        assert_eq!(
+            sent_to_others(
+                &rune_id,
+                &txid(1),
+                &outputs,
+                &script_pubkeys,
+                &HashSet::new()
+            ),
+            105
+        );
+    }
+}
@@ -9,6 +9,8 @@ mod chain;
 mod index;
 mod inscription;
 mod metrics;
+mod readiness;
+mod rune_search;
 mod settings;
 mod store;
 mod updater;
@@ -0,0 +1,66 @@
+use titan_types::Readiness;
+
+/// Blocks the index may be behind the node and still be ready. A new block
+/// takes a moment to index, and the node being ahead during that time
+/// shouldn't take the server out of rotation.
+const MAX_BLOCKS_BEHIND: u64 = 2;
+
+/// Readiness of an index that has `block_count` blocks while the node's tip
+/// is at `node_height`.
+pub(super) fn readiness(
+    synced_once: bool,
+    at_tip: bool,
+    mempool_synced: bool,
+    block_count: u64,
+    node_height: u64,
+) -> Readiness {
+    let blocks_behind = (node_height + 1).saturating_sub(block_count);
+
+    Readiness {
+        ready: synced_once && mempool_synced && blocks_behind <= MAX_BLOCKS_BEHIND,
+        at_tip,
+        blocks_behind,
+        mempool_synced,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_ready_before_the_first_sync() {
+        let starting = readiness(false, false, false, 0, 840000);
+        assert!(!starting.ready);
+        assert_eq!(starting.blocks_behind, 840001);
+
+        // Close to the tip but never reached it.
+        assert!(!readiness(false, false, true, 840000, 840000).ready);
+    }
+
+    #[test]
+    fn stays_ready_while_indexing_a_new_block() {
+        assert_eq!(
+            readiness(true, true, true, 840001, 840000),
+            Readiness {
+                ready: true,
+                at_tip: true,
+                blocks_behind: 0,
+                mempool_synced: true,
+            }
+        );
+
+        let indexing = readiness(true, false, true, 840001, 840001);
+        assert!(indexing.ready);
+        assert!(!indexing.at_tip);
+        assert_eq!(indexing.blocks_behind, 1);
+
+        assert!(readiness(true, false, true, 840001, 840002).ready);
+    }
+
+    #[test]
+    fn not_ready_when_falling_behind_or_out_of_mempool_sync() {
+        assert!(!readiness(true, false, true, 840001, 840003).ready);
+        assert!(!readiness(true, true, false, 840001, 840000).ready);
+    }
+}
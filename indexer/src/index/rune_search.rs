@@ -0,0 +1,164 @@
+use {
+    crate::models::{RuneEntry, RuneOrder, RuneRanking},
+    titan_types::{RuneSearch, RuneSortBy},
+};
+
+/// Index to walk for `search`, `None` if no rune can match it. A name prefix
+/// is walked in name order, otherwise the sort decides, and a symbol on its
+/// own narrows the newest-first walk to the runes that have it.
+pub(super) fn order(search: &RuneSearch) -> Option<RuneOrder> {
+    if let Some(q) = &search.q {
+        // Spacers are not part of the rune name.
+        let prefix = q
+            .chars()
+            .filter(|c| *c != '•' && *c != '.')
+            .collect::<String>()
+            .to_uppercase();
+
+        if !prefix.chars().all(|c| c.is_ascii_uppercase()) {
+            return None;
+        }
+
+        return Some(RuneOrder::NamePrefix(prefix));
+    }
+
+    // Rune numbers follow etching order, so both sorts are the newest first.
+    Some(match (search.sort.unwrap_or_default(), search.symbol) {
+        (RuneSortBy::Number | RuneSortBy::EtchHeight, Some(symbol)) => RuneOrder::Symbol(symbol),
+        (RuneSortBy::Number | RuneSortBy::EtchHeight, None) => RuneOrder::Newest,
+        (RuneSortBy::Supply, _) => RuneOrder::Ranking(RuneRanking::Supply),
+        (RuneSortBy::Mints, _) => RuneOrder::Ranking(RuneRanking::Mints),
+        (RuneSortBy::UtxoCount, _) => RuneOrder::Ranking(RuneRanking::UtxoCount),
+    })
+}
+
+/// Whether `entry` passes the filters of `search` that walking `order` doesn't
+/// already apply.
+pub(super) fn matches(
+    search: &RuneSearch,
+    order: &RuneOrder,
+    entry: &RuneEntry,
+    height: u64,
+) -> bool {
+    let symbol = match order {
+        RuneOrder::Symbol(_) => true,
+        _ => search
+            .symbol
+            .is_none_or(|symbol| entry.symbol == Some(symbol)),
+    };
+
+    symbol
+        && search
+            .mintable
+            .is_none_or(|mintable| entry.mintable(height).is_ok() == mintable)
+        && search.turbo.is_none_or(|turbo| entry.turbo == turbo)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bitcoin::{hashes::Hash, Txid},
+        ordinals::{Rune, SpacedRune, Terms},
+    };
+
+    fn entry(symbol: Option<char>, turbo: bool, cap: u128, mints: u128) -> RuneEntry {
+        RuneEntry {
+            block: 840000,
+            burned: 0,
+            divisibility: 0,
+            etching: Txid::all_zeros(),
+            mints,
+            number: 0,
+            premine: 0,
+            spaced_rune: SpacedRune::new(Rune(0), 0),
+            symbol,
+            terms: Some(Terms {
+                amount: Some(1),
+                cap: Some(cap),
+                height: (None, None),
+                offset: (None, None),
+            }),
+            pending_burns: 0,
+            pending_mints: 0,
+            inscription_id: None,
+            timestamp: 0,
+            turbo,
+        }
+    }
+
+    fn search(q: Option<&str>, symbol: Option<char>, sort: RuneSortBy) -> RuneSearch {
+        RuneSearch {
+            q: q.map(str::to_string),
+            symbol,
+            sort: Some(sort),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn order_follows_name_then_sort() {
+        assert_eq!(
+            order(&search(
+                Some("uncommon•goods"),
+                Some('$'),
+                RuneSortBy::Supply
+            )),
+            Some(RuneOrder::NamePrefix("UNCOMMONGOODS".into()))
+        );
+        assert_eq!(
+            order(&search(Some("dog.go"), None, RuneSortBy::Number)),
+            Some(RuneOrder::NamePrefix("DOGGO".into()))
+        );
+        assert_eq!(order(&search(Some("d0g"), None, RuneSortBy::Number)), None);
+        assert_eq!(
+            order(&search(None, Some('$'), RuneSortBy::EtchHeight)),
+            Some(RuneOrder::Symbol('$'))
+        );
+        assert_eq!(
+            order(&search(None, None, RuneSortBy::Number)),
+            Some(RuneOrder::Newest)
+        );
+        assert_eq!(
+            order(&search(None, Some('$'), RuneSortBy::UtxoCount)),
+            Some(RuneOrder::Ranking(RuneRanking::UtxoCount))
+        );
+        assert_eq!(
+            order(&search(None, None, RuneSortBy::Mints)),
+            Some(RuneOrder::Ranking(RuneRanking::Mints))
+        );
+    }
+
+    #[test]
+    fn filters_apply_on_top_of_the_order() {
+        let open = entry(Some('$'), true, 10, 5);
+        let closed = entry(None, false, 10, 10);
+
+        let by_symbol = search(None, Some('$'), RuneSortBy::Mints);
+        let ranking = order(&by_symbol).unwrap();
+        assert!(matches(&by_symbol, &ranking, &open, 840001));
+        assert!(!matches(&by_symbol, &ranking, &closed, 840001));
+
+        // Walking the symbol index already filters on the symbol.
+        assert!(matches(
+            &by_symbol,
+            &RuneOrder::Symbol('$'),
+            &closed,
+            840001
+        ));
+
+        let mintable = RuneSearch {
+            mintable: Some(true),
+            ..Default::default()
+        };
+        assert!(matches(&mintable, &RuneOrder::Newest, &open, 840001));
+        assert!(!matches(&mintable, &RuneOrder::Newest, &closed, 840001));
+
+        let not_turbo = RuneSearch {
+            turbo: Some(false),
+            ..Default::default()
+        };
+        assert!(!matches(&not_turbo, &RuneOrder::Newest, &open, 840001));
+        assert!(matches(&not_turbo, &RuneOrder::Newest, &closed, 840001));
+    }
+}
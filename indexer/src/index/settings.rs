@@ -8,6 +8,7 @@ use {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Settings {
     pub(crate) data_dir: PathBuf,
+    pub(crate) blocks_dir: Option<PathBuf>,
     pub(crate) zmq_endpoint: String,
     pub(crate) bitcoin_rpc_limit: u32,
     pub(crate) bitcoin_rpc_url: String,
@@ -18,6 +19,8 @@ pub struct Settings {
     pub(crate) index_addresses: bool,
     pub(crate) commit_interval: u64,
     pub(crate) main_loop_interval: u64,
+    pub(crate) pipeline_depth: usize,
+    pub(crate) replacement_history_hours: u64,
 }
 
 impl RpcClientProvider for Settings {
@@ -2,17 +2,21 @@ use {
     crate::{
         db::{RocksDB, RocksDBError},
         models::{
-            BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, RuneEntry,
-            TransactionStateChange,
+            BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, Replacement, RuneEntry,
+            RuneOrder, RuneSupply, TransactionStateChange,
         },
     },
     bitcoin::{consensus, hex::HexToArrayError, BlockHash, OutPoint, ScriptBuf, Txid},
     ordinals::{Rune, RuneId},
-    std::collections::{HashMap, HashSet},
+    std::{
+        collections::{HashMap, HashSet},
+        ops::Range,
+    },
     thiserror::Error,
     titan_types::{
-        Block, InscriptionId, MempoolEntry, Pagination, PaginationResponse, SpenderReference,
-        SpentStatus, Transaction, TransactionStatus, TxOutEntry,
+        Block, BlockRuneActivity, BlockStats, InscriptionId, MempoolEntry, Pagination,
+        PaginationResponse, SpenderReference, SpentStatus, Transaction, TransactionStatus,
+        TxOutEntry,
     },
 };
 
@@ -60,6 +64,12 @@ pub trait Store {
 
     fn get_block_by_hash(&self, hash: &BlockHash) -> Result<Block, StoreError>;
     fn delete_block(&self, hash: &BlockHash) -> Result<(), StoreError>;
+    fn get_block_summaries_from(&self) -> Result<u64, StoreError>;
+    fn get_block_stats(&self, hash: &BlockHash) -> Result<BlockStats, StoreError>;
+    fn get_block_rune_activity(
+        &self,
+        hash: &BlockHash,
+    ) -> Result<Vec<BlockRuneActivity>, StoreError>;
 
     // mempool
     fn is_tx_in_mempool(&self, txid: &Txid) -> Result<bool, StoreError>;
@@ -125,6 +135,20 @@ pub trait Store {
         txids: &HashSet<Txid>,
     ) -> Result<(Vec<Txid>, Vec<Txid>), StoreError>;
 
+    // replacements
+    fn get_spent_outpoints_in_mempool(
+        &self,
+        outpoints: &Vec<OutPoint>,
+    ) -> Result<HashMap<OutPoint, Option<SpenderReference>>, StoreError>;
+    fn get_replacement(&self, txid: &Txid) -> Result<Option<Replacement>, StoreError>;
+    fn get_replaced_txids(&self, txid: &Txid) -> Result<Vec<Txid>, StoreError>;
+    fn set_replacements(
+        &self,
+        replacements: &[(Txid, Txid)],
+        timestamp: u64,
+    ) -> Result<(), StoreError>;
+    fn delete_replacements_before(&self, timestamp: u64) -> Result<u64, StoreError>;
+
     // rune transactions
     fn get_last_rune_transactions(
         &self,
@@ -132,6 +156,20 @@ pub trait Store {
         pagination: Option<Pagination>,
         mempool: Option<bool>,
     ) -> Result<PaginationResponse<Txid>, StoreError>;
+    fn get_rune_transactions_before(
+        &self,
+        rune_id: &RuneId,
+        before: u64,
+        limit: u64,
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>, StoreError>;
+    fn get_rune_transactions_from(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        limit: u64,
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>, StoreError>;
 
     // runes
     fn get_runes_count(&self) -> Result<u64, StoreError>;
@@ -141,10 +179,38 @@ pub trait Store {
         &self,
         rune_ids: &Vec<RuneId>,
     ) -> Result<HashMap<RuneId, RuneEntry>, StoreError>;
-    fn get_runes(
+    fn get_runes_by_number(
+        &self,
+        numbers: Range<u64>,
+    ) -> Result<Vec<(RuneId, RuneEntry)>, StoreError>;
+    fn get_rune_ids(
+        &self,
+        order: &RuneOrder,
+    ) -> Result<Box<dyn Iterator<Item = Result<RuneId, StoreError>> + '_>, StoreError>;
+    fn get_rune_utxo_counts(
         &self,
-        pagination: Pagination,
-    ) -> Result<PaginationResponse<(RuneId, RuneEntry)>, StoreError>;
+        rune_ids: &Vec<RuneId>,
+    ) -> Result<HashMap<RuneId, u64>, StoreError>;
+    fn get_rune_mints(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, u64)>, StoreError>;
+    fn get_rune_supply(&self, rune_id: &RuneId) -> Result<RuneSupply, StoreError>;
+    fn get_rune_supply_blocks(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, RuneSupply)>, StoreError>;
+    fn get_rune_supply_totals(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, RuneSupply)>, StoreError>;
+    fn has_rune_history(&self) -> Result<bool, StoreError>;
 
     // inscription
     fn get_inscription(&self, inscription_id: &InscriptionId) -> Result<Inscription, StoreError>;
@@ -155,6 +221,12 @@ pub trait Store {
         script_pubkey: &ScriptBuf,
         mempool: Option<bool>,
     ) -> Result<Vec<OutPoint>, StoreError>;
+    fn get_script_pubkey_spent_outpoints(
+        &self,
+        script_pubkey: &ScriptBuf,
+    ) -> Result<Vec<OutPoint>, StoreError>;
+    fn get_script_pubkey_by_hash(&self, script_hash: &[u8; 32]) -> Result<ScriptBuf, StoreError>;
+    fn has_script_history(&self) -> Result<bool, StoreError>;
     fn get_outpoints_to_script_pubkey(
         &self,
         outpoints: &Vec<OutPoint>,
@@ -213,6 +285,21 @@ impl Store for RocksDB {
         Ok(self.delete_block(&hash)?)
     }
 
+    fn get_block_summaries_from(&self) -> Result<u64, StoreError> {
+        Ok(self.get_block_summaries_from()?)
+    }
+
+    fn get_block_stats(&self, hash: &BlockHash) -> Result<BlockStats, StoreError> {
+        Ok(self.get_block_stats(hash)?)
+    }
+
+    fn get_block_rune_activity(
+        &self,
+        hash: &BlockHash,
+    ) -> Result<Vec<BlockRuneActivity>, StoreError> {
+        Ok(self.get_block_rune_activity(hash)?)
+    }
+
     fn get_runes_by_ids(
         &self,
         rune_ids: &Vec<RuneId>,
@@ -220,28 +307,68 @@ impl Store for RocksDB {
         Ok(self.get_runes_by_ids(rune_ids)?)
     }
 
-    fn get_runes(
+    fn get_runes_by_number(
         &self,
-        pagination: Pagination,
-    ) -> Result<PaginationResponse<(RuneId, RuneEntry)>, StoreError> {
-        let runes_count = self.get_runes_count()?;
-        let (skip, limit) = pagination.into();
-
-        let start = runes_count.saturating_sub(skip);
-        let end = runes_count.saturating_sub(skip).saturating_sub(limit);
-
+        numbers: Range<u64>,
+    ) -> Result<Vec<(RuneId, RuneEntry)>, StoreError> {
         let mut runes = Vec::new();
-        for i in (end..start).rev() {
-            let rune_id = self.get_rune_id_by_number(i)?;
+        for number in numbers.rev() {
+            let rune_id = self.get_rune_id_by_number(number)?;
             let rune_entry = self.get_rune(&rune_id)?;
             runes.push((rune_id, rune_entry));
         }
 
-        let offset = skip + runes.len() as u64;
-        Ok(PaginationResponse {
-            items: runes,
-            offset,
-        })
+        Ok(runes)
+    }
+
+    fn get_rune_ids(
+        &self,
+        order: &RuneOrder,
+    ) -> Result<Box<dyn Iterator<Item = Result<RuneId, StoreError>> + '_>, StoreError> {
+        let rune_ids = RocksDB::get_rune_ids(self, order)?;
+        Ok(Box::new(rune_ids.map(|rune_id| Ok(rune_id?))))
+    }
+
+    fn get_rune_utxo_counts(
+        &self,
+        rune_ids: &Vec<RuneId>,
+    ) -> Result<HashMap<RuneId, u64>, StoreError> {
+        Ok(self.get_rune_utxo_counts(rune_ids)?)
+    }
+
+    fn get_rune_mints(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, u64)>, StoreError> {
+        Ok(self.get_rune_mints(rune_id, from, to)?)
+    }
+
+    fn get_rune_supply(&self, rune_id: &RuneId) -> Result<RuneSupply, StoreError> {
+        Ok(self.get_rune_supply(rune_id)?)
+    }
+
+    fn get_rune_supply_blocks(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, RuneSupply)>, StoreError> {
+        Ok(self.get_rune_supply_blocks(rune_id, from, to)?)
+    }
+
+    fn get_rune_supply_totals(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, RuneSupply)>, StoreError> {
+        Ok(self.get_rune_supply_totals(rune_id, from, to)?)
+    }
+
+    fn has_rune_history(&self) -> Result<bool, StoreError> {
+        Ok(self.has_rune_history()?)
     }
 
     fn get_mempool_txids(&self) -> Result<HashMap<Txid, MempoolEntry>, StoreError> {
@@ -468,6 +595,33 @@ impl Store for RocksDB {
         Ok(self.partition_transactions_by_existence(txids)?)
     }
 
+    fn get_spent_outpoints_in_mempool(
+        &self,
+        outpoints: &Vec<OutPoint>,
+    ) -> Result<HashMap<OutPoint, Option<SpenderReference>>, StoreError> {
+        Ok(self.get_spent_outpoints_in_mempool(outpoints)?)
+    }
+
+    fn get_replacement(&self, txid: &Txid) -> Result<Option<Replacement>, StoreError> {
+        Ok(self.get_replacement(txid)?)
+    }
+
+    fn get_replaced_txids(&self, txid: &Txid) -> Result<Vec<Txid>, StoreError> {
+        Ok(self.get_replaced_txids(txid)?)
+    }
+
+    fn set_replacements(
+        &self,
+        replacements: &[(Txid, Txid)],
+        timestamp: u64,
+    ) -> Result<(), StoreError> {
+        Ok(self.set_replacements(replacements, timestamp)?)
+    }
+
+    fn delete_replacements_before(&self, timestamp: u64) -> Result<u64, StoreError> {
+        Ok(self.delete_replacements_before(timestamp)?)
+    }
+
     fn get_transaction_confirming_block(&self, txid: &Txid) -> Result<BlockId, StoreError> {
         Ok(self.get_transaction_confirming_block(txid)?)
     }
@@ -505,7 +659,8 @@ impl Store for RocksDB {
             Ok(self.get_last_rune_transactions(rune_id, pagination, mempool)?)
         } else {
             // First get mempool transactions
-            let mempool_txids = self.get_last_rune_transactions(rune_id, pagination, true)?;
+            let mempool_txids =
+                self.get_last_rune_transactions(rune_id, pagination.clone(), true)?;
 
             // Then get non-mempool transactions
             // Adapt pagination to offset
@@ -515,10 +670,12 @@ impl Store for RocksDB {
                     limit: pagination
                         .limit
                         .saturating_sub(mempool_txids.items.len() as u64),
+                    cursor: None,
                 },
                 None => Pagination {
                     skip: 0,
                     limit: u64::MAX,
+                    cursor: None,
                 },
             };
 
@@ -534,10 +691,32 @@ impl Store for RocksDB {
                     .chain(non_mempool_txids.items)
                     .collect(),
                 offset: new_offset,
+                next_cursor: None,
+                prev_cursor: None,
             })
         }
     }
 
+    fn get_rune_transactions_before(
+        &self,
+        rune_id: &RuneId,
+        before: u64,
+        limit: u64,
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>, StoreError> {
+        Ok(self.get_rune_transactions_before(rune_id, before, limit, mempool)?)
+    }
+
+    fn get_rune_transactions_from(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        limit: u64,
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>, StoreError> {
+        Ok(self.get_rune_transactions_from(rune_id, from, limit, mempool)?)
+    }
+
     fn get_script_pubkey_outpoints(
         &self,
         script_pubkey: &ScriptBuf,
@@ -566,6 +745,21 @@ impl Store for RocksDB {
         }
     }
 
+    fn get_script_pubkey_spent_outpoints(
+        &self,
+        script_pubkey: &ScriptBuf,
+    ) -> Result<Vec<OutPoint>, StoreError> {
+        Ok(self.get_script_pubkey_spent_outpoints(script_pubkey)?)
+    }
+
+    fn get_script_pubkey_by_hash(&self, script_hash: &[u8; 32]) -> Result<ScriptBuf, StoreError> {
+        Ok(self.get_script_pubkey_by_hash(script_hash)?)
+    }
+
+    fn has_script_history(&self) -> Result<bool, StoreError> {
+        Ok(self.has_script_history()?)
+    }
+
     fn get_outpoints_to_script_pubkey(
         &self,
         outpoints: &Vec<OutPoint>,
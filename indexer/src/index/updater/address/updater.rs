@@ -1,6 +1,6 @@
 use {
     crate::index::{updater::cache::UpdaterCache, StoreError},
-    bitcoin::{OutPoint, ScriptBuf},
+    bitcoin::{OutPoint, ScriptBuf, Transaction, Txid},
     std::collections::{HashMap, HashSet},
     titan_types::SpenderReference,
 };
@@ -34,6 +34,37 @@ impl AddressUpdater {
         self.spent_outpoints.insert(outpoint, spender_reference);
     }
 
+    /// Remember the outpoints `transaction` spends and creates
+    pub fn add_transaction(&mut self, txid: Txid, transaction: &Transaction) {
+        // skip coinbase inputs
+        if !transaction.is_coinbase() {
+            for (vin, input) in transaction.input.iter().enumerate() {
+                self.add_spent_outpoint(
+                    input.previous_output,
+                    SpenderReference {
+                        txid,
+                        vin: vin as u32,
+                    },
+                );
+            }
+        }
+
+        for (vout, txout) in transaction.output.iter().enumerate() {
+            let outpoint = OutPoint {
+                txid,
+                vout: vout as u32,
+            };
+
+            self.add_new_outpoint(outpoint, txout.script_pubkey.clone());
+        }
+    }
+
+    /// Merge in the outpoints remembered by `other`, for a later block
+    pub fn extend(&mut self, other: AddressUpdater) {
+        self.new_outpoints.extend(other.new_outpoints);
+        self.spent_outpoints.extend(other.spent_outpoints);
+    }
+
     pub fn batch_update_script_pubkey(
         &mut self,
         cache: &mut UpdaterCache,
@@ -94,9 +125,12 @@ impl AddressUpdater {
             let entry: &mut (Vec<OutPoint>, Vec<OutPoint>) =
                 spk_map.entry(script_pubkey.clone()).or_default();
 
-            // add it only if it's not already in the spent list
+            // add it only if it's not already in the spent list, otherwise
+            // straight to the spent outputs kept for the script's history
             if !new_spent_outpoints.contains(outpoint) {
                 entry.0.push(*outpoint); // new
+            } else {
+                entry.1.push(*outpoint); // spent
             }
         }
 
@@ -124,12 +158,23 @@ impl AddressUpdater {
             entry.0.push(*outpoint); // new
         }
 
+        // b) Mempool spends stay out of the script pubkey entries, but the
+        // scripts they spend from changed too.
+        let spent_outpoints: Vec<OutPoint> = self
+            .spent_outpoints
+            .keys()
+            .filter(|outpoint| !self.new_outpoints.contains_key(outpoint))
+            .cloned()
+            .collect();
+
+        if !spent_outpoints.is_empty() {
+            let spent_map = cache.get_spent_outpoints_script_pubkeys(&spent_outpoints)?;
+            cache.add_modified_script_pubkeys(spent_map.into_values());
+        }
+
         cache.set_script_pubkey_entries(spk_map);
         cache.batch_set_outpoints_to_script_pubkey(self.new_outpoints.clone());
 
-        // b) Insert spent outpoints
-        cache.batch_set_spent_outpoints_in_mempool(self.spent_outpoints.clone());
-
         Ok(())
     }
 }
@@ -1,12 +1,21 @@
 use {
+    super::block_files::{BlockFiles, BlockLocation},
     crate::bitcoin_rpc::{BitcoinCoreRpcResultExt, RpcClientPool},
     bitcoin::{Block, BlockHash},
     bitcoincore_rpc::{Client, RpcApi},
-    std::{collections::BTreeMap, sync::mpsc, thread, time::Duration},
+    rayon::prelude::*,
+    std::{
+        collections::BTreeMap,
+        sync::{mpsc, Arc},
+        thread,
+        time::Duration,
+    },
     threadpool::ThreadPool,
-    tracing::{error, trace, warn},
+    tracing::{error, info, trace, warn},
 };
 
+const FILE_READ_BATCH: usize = 64;
+
 pub enum BlockLocator {
     Height(u64),
     Hash(BlockHash),
@@ -89,6 +98,99 @@ pub fn fetch_blocks_from(
     Ok(final_rx)
 }
 
+/// Like `fetch_blocks_from`, but reads blocks from the node's `blk*.dat`
+/// files. The last `rpc_depth` blocks, which may still be reorged, are
+/// fetched over RPC, as is everything if the files can't be used.
+pub fn fetch_blocks_from_files(
+    block_files: Arc<BlockFiles>,
+    bitcoin_rpc_pool: RpcClientPool,
+    start_height: u64,
+    limit: u64,
+    rpc_depth: u64,
+) -> Result<mpsc::Receiver<Block>, bitcoincore_rpc::Error> {
+    let files_end = limit.saturating_sub(rpc_depth);
+    if start_height >= files_end {
+        return fetch_blocks_from(bitcoin_rpc_pool, start_height, limit);
+    }
+
+    let (sender, rx) = mpsc::sync_channel(32);
+
+    thread::spawn(move || {
+        let mut next_height = start_height;
+
+        match locate_blocks(&block_files, &bitcoin_rpc_pool, start_height, files_end) {
+            Ok(locations) => {
+                info!(
+                    "Reading blocks {} to {} from {}",
+                    start_height,
+                    files_end - 1,
+                    block_files.dir().display()
+                );
+
+                // Blocks are read and deserialized in parallel, a batch at a
+                // time.
+                'batches: for batch in locations.chunks(FILE_READ_BATCH) {
+                    let blocks: Vec<_> = batch
+                        .par_iter()
+                        .map(|location| block_files.read_block(location))
+                        .collect();
+
+                    for block in blocks {
+                        match block {
+                            Ok(block) => {
+                                if sender.send(block).is_err() {
+                                    trace!("Block receiver disconnected");
+                                    return;
+                                }
+                                next_height += 1;
+                            }
+                            Err(e) => {
+                                warn!(
+                                    "Failed to read block {} from block files, fetching the rest over RPC: {}",
+                                    next_height, e
+                                );
+                                break 'batches;
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => warn!(
+                "Can't read blocks from {}, fetching over RPC: {}",
+                block_files.dir().display(),
+                e
+            ),
+        }
+
+        let rx = match fetch_blocks_from(bitcoin_rpc_pool, next_height, limit) {
+            Ok(rx) => rx,
+            Err(e) => {
+                error!("Failed to fetch blocks from {}: {}", next_height, e);
+                return;
+            }
+        };
+
+        while let Ok(block) = rx.recv() {
+            if sender.send(block).is_err() {
+                trace!("Block receiver disconnected");
+                return;
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+fn locate_blocks(
+    block_files: &BlockFiles,
+    bitcoin_rpc_pool: &RpcClientPool,
+    start_height: u64,
+    end_height: u64,
+) -> Result<Vec<BlockLocation>, Box<dyn std::error::Error>> {
+    let tip = bitcoin_rpc_pool.get()?.get_block_hash(end_height - 1)?;
+    Ok(block_files.locate_chain(tip, start_height, end_height)?)
+}
+
 pub fn get_block_with_retries(
     client: &Client,
     locator: BlockLocator,
@@ -0,0 +1,310 @@
+use {
+    bitcoin::{block::Header, consensus, p2p::Magic, Block, BlockHash, Network},
+    rayon::prelude::*,
+    std::{
+        collections::HashMap,
+        fs::{self, File},
+        io::{self, Read, Seek, SeekFrom},
+        path::{Path, PathBuf},
+        sync::Mutex,
+    },
+    thiserror::Error,
+};
+
+const HEADER_SIZE: usize = 80;
+
+#[derive(Debug, Error)]
+pub enum BlockFilesError {
+    #[error("io error {0}")]
+    Io(#[from] io::Error),
+    #[error("decode error {0}")]
+    Decode(#[from] consensus::encode::Error),
+    #[error("invalid xor key in {0}")]
+    InvalidXorKey(PathBuf),
+    #[error("block {0} not found in block files")]
+    MissingBlock(BlockHash),
+    #[error("mutex error")]
+    Mutex,
+}
+
+type Result<T> = std::result::Result<T, BlockFilesError>;
+
+/// Where a block is stored in the block files.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockLocation {
+    file: u32,
+    offset: u64,
+    size: u32,
+    prev_blockhash: BlockHash,
+}
+
+/// bitcoind's `blk*.dat` files. Since Bitcoin Core 28 they're obfuscated with
+/// the key in `xor.dat`.
+///
+/// Files are only ever appended to or pruned, so the blocks found in them are
+/// kept between syncs and each scan only reads the headers written since the
+/// last one.
+pub struct BlockFiles {
+    dir: PathBuf,
+    magic: Magic,
+    xor_key: [u8; 8],
+    scanned: Mutex<Scanned>,
+}
+
+#[derive(Default)]
+struct Scanned {
+    /// Offset each file was read up to.
+    files: HashMap<u32, u64>,
+    locations: HashMap<BlockHash, BlockLocation>,
+}
+
+impl BlockFiles {
+    pub fn open(dir: &Path, network: Network) -> Result<Self> {
+        let xor_path = dir.join("xor.dat");
+        let xor_key = match fs::read(&xor_path) {
+            Ok(key) => key
+                .try_into()
+                .map_err(|_| BlockFilesError::InvalidXorKey(xor_path))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => [0; 8],
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            magic: network.magic(),
+            xor_key,
+            scanned: Mutex::new(Scanned::default()),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Locations of the blocks at `start_height..end_height`, in height
+    /// order, where `tip` is the one at `end_height - 1`. Blocks are appended
+    /// to the files in the order they were downloaded, so the order is worked
+    /// out by walking back from `tip`.
+    pub fn locate_chain(
+        &self,
+        tip: BlockHash,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<BlockLocation>> {
+        let mut scanned = self.scanned.lock().map_err(|_| BlockFilesError::Mutex)?;
+        self.scan(&mut scanned)?;
+        let locations = &scanned.locations;
+
+        let mut chain = Vec::with_capacity((end_height - start_height) as usize);
+        let mut hash = tip;
+        for _ in start_height..end_height {
+            let location = locations
+                .get(&hash)
+                .ok_or(BlockFilesError::MissingBlock(hash))?;
+            chain.push(*location);
+            hash = location.prev_blockhash;
+        }
+
+        chain.reverse();
+        Ok(chain)
+    }
+
+    pub fn read_block(&self, location: &BlockLocation) -> Result<Block> {
+        let mut file = File::open(self.file_path(location.file))?;
+        let bytes = self.read_at(&mut file, location.offset, location.size as usize)?;
+        Ok(consensus::deserialize(&bytes)?)
+    }
+
+    /// Adds the blocks written since the last scan to `scanned`, and drops the
+    /// ones in pruned files. Only headers are read.
+    fn scan(&self, scanned: &mut Scanned) -> Result<()> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            let Some(number) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("blk"))
+                .and_then(|name| name.strip_suffix(".dat"))
+                .and_then(|number| number.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            files.push(number);
+        }
+
+        scanned.files.retain(|number, _| files.contains(number));
+        scanned
+            .locations
+            .retain(|_, location| files.contains(&location.file));
+
+        let scans = files
+            .par_iter()
+            .map(|number| {
+                let offset = scanned.files.get(number).copied().unwrap_or_default();
+                self.scan_file(*number, offset)
+                    .map(|(end, locations)| (*number, end, locations))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (number, end, locations) in scans {
+            scanned.files.insert(number, end);
+            scanned.locations.extend(locations);
+        }
+
+        Ok(())
+    }
+
+    /// Blocks in file `number` from `offset` on, and the offset the next scan
+    /// starts from.
+    fn scan_file(
+        &self,
+        number: u32,
+        mut offset: u64,
+    ) -> Result<(u64, Vec<(BlockHash, BlockLocation)>)> {
+        let mut file = File::open(self.file_path(number))?;
+        let len = file.metadata()?.len();
+
+        let mut locations = Vec::new();
+        while offset + 8 + HEADER_SIZE as u64 <= len {
+            let prefix = self.read_at(&mut file, offset, 8)?;
+            // The rest of the file is preallocated space.
+            if prefix[..4] != self.magic.to_bytes() {
+                break;
+            }
+
+            let size = u32::from_le_bytes(prefix[4..].try_into().unwrap());
+            // Still being written.
+            if offset + 8 + size as u64 > len {
+                break;
+            }
+
+            let header: Header =
+                consensus::deserialize(&self.read_at(&mut file, offset + 8, HEADER_SIZE)?)?;
+
+            locations.push((
+                header.block_hash(),
+                BlockLocation {
+                    file: number,
+                    offset: offset + 8,
+                    size,
+                    prev_blockhash: header.prev_blockhash,
+                },
+            ));
+
+            offset += 8 + size as u64;
+        }
+
+        Ok((offset, locations))
+    }
+
+    fn read_at(&self, file: &mut File, offset: u64, len: usize) -> Result<Vec<u8>> {
+        let mut bytes = vec![0; len];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes)?;
+        unxor(&mut bytes, offset, &self.xor_key);
+        Ok(bytes)
+    }
+
+    fn file_path(&self, number: u32) -> PathBuf {
+        self.dir.join(format!("blk{:05}.dat", number))
+    }
+}
+
+/// The key is applied by file position, so reads have to start from their
+/// offset in it.
+fn unxor(bytes: &mut [u8], offset: u64, key: &[u8; 8]) {
+    if key == &[0; 8] {
+        return;
+    }
+
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte ^= key[((offset + i as u64) % 8) as usize];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bitcoin::blockdata::constants::genesis_block,
+        std::{fs::OpenOptions, io::Write},
+        tempfile::TempDir,
+    };
+
+    const KEY: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    /// Appends `block` to `blk00000.dat` the way bitcoind writes it.
+    fn append_block(dir: &Path, block: &Block) {
+        let path = dir.join("blk00000.dat");
+        let offset = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+
+        let block = consensus::serialize(block);
+        let mut data = Network::Regtest.magic().to_bytes().to_vec();
+        data.extend((block.len() as u32).to_le_bytes());
+        data.extend(&block);
+        unxor(&mut data, offset, &KEY);
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap()
+            .write_all(&data)
+            .unwrap();
+    }
+
+    #[test]
+    fn reads_obfuscated_block_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("xor.dat"), KEY).unwrap();
+
+        let genesis = genesis_block(Network::Regtest);
+        append_block(dir.path(), &genesis);
+        // Preallocated space.
+        OpenOptions::new()
+            .append(true)
+            .open(dir.path().join("blk00000.dat"))
+            .unwrap()
+            .write_all(&[0; 100])
+            .unwrap();
+
+        let files = BlockFiles::open(dir.path(), Network::Regtest).unwrap();
+        let chain = files.locate_chain(genesis.block_hash(), 0, 1).unwrap();
+        assert_eq!(chain.len(), 1);
+        assert_eq!(files.read_block(&chain[0]).unwrap(), genesis);
+
+        assert!(matches!(
+            files.locate_chain(genesis.block_hash(), 0, 2),
+            Err(BlockFilesError::MissingBlock(_))
+        ));
+    }
+
+    #[test]
+    fn scans_only_what_was_appended() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("xor.dat"), KEY).unwrap();
+
+        let genesis = genesis_block(Network::Regtest);
+        append_block(dir.path(), &genesis);
+
+        let files = BlockFiles::open(dir.path(), Network::Regtest).unwrap();
+        files.locate_chain(genesis.block_hash(), 0, 1).unwrap();
+        let scanned_to = files.scanned.lock().unwrap().files[&0];
+
+        let mut next = genesis.clone();
+        next.header.prev_blockhash = genesis.block_hash();
+        append_block(dir.path(), &next);
+
+        let chain = files.locate_chain(next.block_hash(), 0, 2).unwrap();
+        assert_eq!(files.read_block(&chain[0]).unwrap(), genesis);
+        assert_eq!(files.read_block(&chain[1]).unwrap(), next);
+        assert_eq!(chain[1].offset, scanned_to + 8);
+
+        // Blocks of pruned files are forgotten.
+        fs::remove_file(dir.path().join("blk00000.dat")).unwrap();
+        assert!(matches!(
+            files.locate_chain(genesis.block_hash(), 0, 1),
+            Err(BlockFilesError::MissingBlock(_))
+        ));
+    }
+}
@@ -0,0 +1,166 @@
+use {
+    super::{
+        address::AddressUpdater,
+        cache::{PrefetchedTxOuts, UpdaterCache},
+        store_lock::StoreWithLock,
+    },
+    crate::index::StoreError,
+    bitcoin::{Block, Txid},
+    prometheus::HistogramVec,
+    std::{
+        sync::{mpsc, Arc},
+        thread,
+    },
+    threadpool::ThreadPool,
+    tracing::trace,
+};
+
+/// A block along with the work that doesn't depend on indexing the blocks
+/// before it.
+pub struct PreparedBlock {
+    pub block: Block,
+    pub txids: Vec<Txid>,
+    /// Confirmed outputs the block spends, as stored when it was prepared.
+    /// Outputs created by blocks that weren't flushed yet are missing.
+    pub tx_outs: PrefetchedTxOuts,
+    /// Outpoints the block creates and spends, when indexing addresses.
+    pub address_updater: Option<AddressUpdater>,
+}
+
+/// Prepares fetched blocks on worker threads while earlier ones are being
+/// indexed. Up to `depth` blocks are prepared ahead, and they come out in the
+/// order they were fetched.
+pub struct BlockPipeline {
+    prepared: mpsc::Receiver<mpsc::Receiver<Result<PreparedBlock, StoreError>>>,
+}
+
+impl BlockPipeline {
+    pub fn new(
+        blocks: mpsc::Receiver<Block>,
+        db: Arc<StoreWithLock>,
+        depth: usize,
+        index_addresses: bool,
+        latency: HistogramVec,
+    ) -> Self {
+        let depth = depth.max(1);
+        let (sender, prepared) = mpsc::sync_channel(depth);
+
+        thread::spawn(move || {
+            let workers = thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(depth);
+            let pool = ThreadPool::new(workers);
+
+            while let Ok(block) = blocks.recv() {
+                let (block_sender, block_receiver) = mpsc::sync_channel(1);
+                let db = db.clone();
+                let latency = latency.clone();
+                pool.execute(move || {
+                    let _ = block_sender.send(prepare(block, &db, index_addresses, &latency));
+                });
+
+                // Blocks here once `depth` blocks are waiting to be indexed.
+                if sender.send(block_receiver).is_err() {
+                    trace!("Prepared block receiver disconnected");
+                    return;
+                }
+            }
+        });
+
+        Self { prepared }
+    }
+
+    /// The next block in order, or `None` once all blocks were indexed.
+    pub fn next(&self) -> Option<Result<PreparedBlock, StoreError>> {
+        self.prepared.recv().ok()?.recv().ok()
+    }
+}
+
+fn prepare(
+    block: Block,
+    db: &StoreWithLock,
+    index_addresses: bool,
+    latency: &HistogramVec,
+) -> Result<PreparedBlock, StoreError> {
+    let txids: Vec<Txid> = {
+        let _timer = latency
+            .with_label_values(&["pipeline_compute_txids"])
+            .start_timer();
+        block.txdata.iter().map(|tx| tx.compute_txid()).collect()
+    };
+
+    let tx_outs = {
+        let _timer = latency
+            .with_label_values(&["pipeline_prefetch_tx_outs"])
+            .start_timer();
+        UpdaterCache::prefetch_tx_outs(db, &block.txdata)?
+    };
+
+    let address_updater = index_addresses.then(|| {
+        let _timer = latency
+            .with_label_values(&["pipeline_extract_script_pubkeys"])
+            .start_timer();
+        let mut address_updater = AddressUpdater::new();
+        for (txid, tx) in txids.iter().zip(&block.txdata) {
+            address_updater.add_transaction(*txid, tx);
+        }
+        address_updater
+    });
+
+    Ok(PreparedBlock {
+        block,
+        txids,
+        tx_outs,
+        address_updater,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::db::RocksDB,
+        bitcoin::{blockdata::constants::genesis_block, BlockHash, Network},
+        prometheus::HistogramOpts,
+    };
+
+    fn prepared_hashes(depth: usize) -> (Vec<BlockHash>, Vec<BlockHash>) {
+        let db = Arc::new(StoreWithLock::new(Arc::new(RocksDB::open_temp().1)));
+        let latency =
+            HistogramVec::new(HistogramOpts::new("latency", "latency"), &["method"]).unwrap();
+
+        let (sender, blocks) = mpsc::channel();
+        let pipeline = BlockPipeline::new(blocks, db, depth, true, latency);
+
+        let mut block = genesis_block(Network::Regtest);
+        let mut hashes = Vec::new();
+        for nonce in 0..32 {
+            block.header.nonce = nonce;
+            hashes.push(block.block_hash());
+            sender.send(block.clone()).unwrap();
+        }
+        drop(sender);
+
+        let mut prepared_hashes = Vec::new();
+        while let Some(prepared) = pipeline.next() {
+            let prepared = prepared.unwrap();
+            assert_eq!(
+                prepared.txids,
+                vec![prepared.block.txdata[0].compute_txid()]
+            );
+            assert!(prepared.address_updater.is_some());
+            prepared_hashes.push(prepared.block.block_hash());
+        }
+
+        (hashes, prepared_hashes)
+    }
+
+    #[test]
+    fn blocks_come_out_in_order() {
+        for depth in [0, 1, 8] {
+            let (hashes, prepared_hashes) = prepared_hashes(depth);
+            assert_eq!(prepared_hashes, hashes, "depth {depth}");
+        }
+    }
+}
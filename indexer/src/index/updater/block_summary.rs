@@ -0,0 +1,223 @@
+use {
+    crate::models::TransactionStateChange,
+    bitcoin::{Block as BitcoinBlock, Txid},
+    ordinals::RuneId,
+    std::collections::HashMap,
+    titan_types::{BlockRuneActivity, BlockStats, RuneAction},
+};
+
+/// Block stats and rune activity, worked out while the block is indexed. They
+/// are stored with the block because the transaction state changes they come
+/// from are purged once the block is past reorg depth.
+#[derive(Default)]
+pub(super) struct BlockSummary {
+    rune_tx_count: u64,
+    activity: Vec<BlockRuneActivity>,
+}
+
+impl BlockSummary {
+    /// Adds a transaction, in block order. `premine` is the premine of the
+    /// rune it etches, if any.
+    pub(super) fn add_tx(
+        &mut self,
+        txid: Txid,
+        state_change: &TransactionStateChange,
+        premine: Option<u128>,
+    ) {
+        if state_change.has_rune_updates() {
+            self.rune_tx_count += 1;
+        }
+
+        let mut received: HashMap<RuneId, u128> = HashMap::new();
+        for rune in state_change
+            .outputs
+            .iter()
+            .flat_map(|output| output.runes.iter())
+        {
+            let amount = received.entry(rune.rune_id).or_default();
+            *amount = amount.saturating_add(rune.amount);
+        }
+
+        let mut push = |rune_id: RuneId, action: RuneAction, amount: u128| {
+            self.activity.push(BlockRuneActivity {
+                txid,
+                rune_id,
+                action,
+                amount,
+            });
+        };
+
+        if let Some((rune_id, _)) = state_change.etched {
+            let premine = premine.unwrap_or_default();
+            push(rune_id, RuneAction::Etch, premine);
+            received
+                .entry(rune_id)
+                .and_modify(|amount| *amount = amount.saturating_sub(premine));
+        }
+
+        if let Some(minted) = &state_change.minted {
+            push(minted.rune_id, RuneAction::Mint, minted.amount);
+            received
+                .entry(minted.rune_id)
+                .and_modify(|amount| *amount = amount.saturating_sub(minted.amount));
+        }
+
+        let mut burned: Vec<_> = state_change.burned.iter().collect();
+        burned.sort_by_key(|(rune_id, _)| **rune_id);
+        for (rune_id, amount) in burned {
+            push(*rune_id, RuneAction::Burn, amount.n());
+        }
+
+        let mut received: Vec<_> = received
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0)
+            .collect();
+        received.sort_by_key(|(rune_id, _)| *rune_id);
+        for (rune_id, amount) in received {
+            push(rune_id, RuneAction::Transfer, amount);
+        }
+    }
+
+    pub(super) fn finish(
+        self,
+        block: &BitcoinBlock,
+        height: u64,
+        subsidy: u64,
+    ) -> (BlockStats, Vec<BlockRuneActivity>) {
+        let coinbase_value: u64 = block
+            .txdata
+            .first()
+            .map(|coinbase| {
+                coinbase
+                    .output
+                    .iter()
+                    .map(|output| output.value.to_sat())
+                    .sum()
+            })
+            .unwrap_or_default();
+
+        let stats = BlockStats {
+            height,
+            hash: block.block_hash(),
+            timestamp: block.header.time as u64,
+            tx_count: block.txdata.len() as u64,
+            total_fees: coinbase_value.saturating_sub(subsidy),
+            weight: block.weight().to_wu(),
+            rune_tx_count: self.rune_tx_count,
+        };
+
+        (stats, self.activity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::models::Lot,
+        bitcoin::{
+            absolute::LockTime, block::Header, block::Version, hashes::Hash, transaction, Amount,
+            BlockHash, CompactTarget, ScriptBuf, Transaction, TxMerkleNode, TxOut,
+        },
+        ordinals::Rune,
+        titan_types::{RuneAmount, SpentStatus, TxOutEntry},
+    };
+
+    fn output(runes: Vec<RuneAmount>) -> TxOutEntry {
+        TxOutEntry {
+            runes,
+            risky_runes: vec![],
+            value: 1000,
+            spent: SpentStatus::Unspent,
+        }
+    }
+
+    #[test]
+    fn activity_and_stats() {
+        let etched = RuneId::new(840000, 1).unwrap();
+        let other = RuneId::new(840000, 2).unwrap();
+
+        let etching = TransactionStateChange {
+            inputs: vec![],
+            outputs: vec![output(vec![RuneAmount {
+                rune_id: etched,
+                amount: 150,
+            }])],
+            etched: Some((etched, Rune(0))),
+            minted: Some(RuneAmount {
+                rune_id: etched,
+                amount: 50,
+            }),
+            burned: HashMap::new(),
+            is_coinbase: false,
+        };
+
+        let transfer = TransactionStateChange {
+            inputs: vec![],
+            outputs: vec![output(vec![RuneAmount {
+                rune_id: other,
+                amount: 7,
+            }])],
+            etched: None,
+            minted: None,
+            burned: HashMap::from([(etched, Lot(3))]),
+            is_coinbase: false,
+        };
+
+        let plain = TransactionStateChange {
+            inputs: vec![],
+            outputs: vec![output(vec![])],
+            etched: None,
+            minted: None,
+            burned: HashMap::new(),
+            is_coinbase: true,
+        };
+
+        let mut summary = BlockSummary::default();
+        summary.add_tx(Txid::all_zeros(), &plain, None);
+        summary.add_tx(Txid::from_byte_array([1; 32]), &etching, Some(100));
+        summary.add_tx(Txid::from_byte_array([2; 32]), &transfer, None);
+
+        let coinbase = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![TxOut {
+                value: Amount::from_sat(312_500_000 + 1234),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        let block = BitcoinBlock {
+            header: Header {
+                version: Version::TWO,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 1_700_000_000,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            txdata: vec![coinbase],
+        };
+
+        let (stats, activity) = summary.finish(&block, 840000, 312_500_000);
+
+        assert_eq!(stats.total_fees, 1234);
+        assert_eq!(stats.rune_tx_count, 2);
+        assert_eq!(stats.weight, block.weight().to_wu());
+        assert_eq!(stats.hash, block.block_hash());
+
+        let actions: Vec<_> = activity
+            .iter()
+            .map(|activity| (activity.rune_id, activity.action, activity.amount))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (etched, RuneAction::Etch, 100),
+                (etched, RuneAction::Mint, 50),
+                (etched, RuneAction::Burn, 3),
+                (other, RuneAction::Transfer, 7),
+            ]
+        );
+    }
+}
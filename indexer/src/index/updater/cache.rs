@@ -3,7 +3,8 @@ use {
     crate::{
         index::{store::StoreError, Chain, Settings},
         models::{
-            BatchDelete, BatchUpdate, BlockId, Inscription, RuneEntry, TransactionStateChange,
+            BatchDelete, BatchUpdate, BlockId, Inscription, RuneEntry, RuneSupplyDelta,
+            TransactionStateChange,
         },
     },
     bitcoin::{consensus, BlockHash, OutPoint, ScriptBuf, Transaction, Txid},
@@ -16,7 +17,8 @@ use {
         time::Instant,
     },
     titan_types::{
-        Block, Event, InscriptionId, Location, MempoolEntry, SpenderReference, TxOutEntry,
+        Block, BlockRuneActivity, BlockStats, Event, InscriptionId, Location, MempoolEntry,
+        SpenderReference, TxOutEntry,
     },
     tokio::sync::mpsc,
     tracing::{info, trace},
@@ -38,11 +40,21 @@ impl UpdaterCacheSettings {
     }
 }
 
+/// Outputs read from the store ahead of indexing the block that spends them.
+pub(super) struct PrefetchedTxOuts {
+    /// Blocks stored when the outputs were read.
+    block_count: u64,
+    tx_outs: HashMap<OutPoint, TxOutEntry>,
+}
+
 pub(super) struct UpdaterCache {
     db: Arc<StoreWithLock>,
     update: BatchUpdate,
     delete: BatchDelete,
     events: Vec<Event>,
+    /// Script pubkeys whose outputs were spent or dropped without a script
+    /// pubkey entry of their own, so they still get an address event.
+    modified_script_pubkeys: HashSet<ScriptBuf>,
     first_block_height: u64,
     last_block_height: Option<u64>,
     pub settings: UpdaterCacheSettings,
@@ -64,6 +76,7 @@ impl UpdaterCache {
             update: BatchUpdate::new(rune_count, block_count, purged_blocks_count),
             delete: BatchDelete::new(),
             events: vec![],
+            modified_script_pubkeys: HashSet::new(),
             first_block_height: block_count,
             last_block_height: None,
             settings,
@@ -131,6 +144,16 @@ impl UpdaterCache {
         self.increment_block_count();
     }
 
+    pub fn set_block_summary(
+        &mut self,
+        hash: BlockHash,
+        stats: BlockStats,
+        rune_activity: Vec<BlockRuneActivity>,
+    ) {
+        self.update.block_stats.insert(hash, stats);
+        self.update.block_rune_activity.insert(hash, rune_activity);
+    }
+
     pub fn increment_runes_count(&mut self) -> () {
         self.update.rune_count += 1;
     }
@@ -157,6 +180,45 @@ impl UpdaterCache {
         }
     }
 
+    /// Reads the confirmed outputs spent by `txs` from the store, so it can
+    /// run ahead of the blocks being indexed.
+    pub fn prefetch_tx_outs(db: &StoreWithLock, txs: &[Transaction]) -> Result<PrefetchedTxOuts> {
+        let db = db.read();
+        // Read first, so a flush racing with the outputs can only make them
+        // look older than they are.
+        let block_count = db.get_block_count()?;
+
+        let outpoints: Vec<OutPoint> = txs
+            .iter()
+            .filter(|tx| !tx.is_coinbase())
+            .flat_map(|tx| tx.input.iter().map(|input| input.previous_output))
+            .collect();
+
+        let tx_outs = if outpoints.is_empty() {
+            HashMap::new()
+        } else {
+            db.get_tx_outs(&outpoints, Some(false))?
+        };
+
+        Ok(PrefetchedTxOuts {
+            block_count,
+            tx_outs,
+        })
+    }
+
+    /// Adds prefetched outputs. The ones already cached are newer, so they're
+    /// kept. If the cache was flushed since they were read, what's stored may
+    /// be newer too, so they're all dropped and read again when needed.
+    pub fn add_prefetched_tx_outs(&mut self, prefetched: PrefetchedTxOuts) {
+        if prefetched.block_count != self.first_block_height {
+            return;
+        }
+
+        for (outpoint, tx_out) in prefetched.tx_outs {
+            self.update.txouts.entry(outpoint).or_insert(tx_out);
+        }
+    }
+
     pub fn precache_tx_outs(&mut self, txs: &Vec<Transaction>) -> Result<()> {
         let outpoints: Vec<_> = txs
             .iter()
@@ -291,6 +353,31 @@ impl UpdaterCache {
         self.update.rune_numbers.insert(number, rune_id);
     }
 
+    pub fn add_rune_utxo_count(&mut self, rune_id: RuneId, delta: i64) {
+        *self.update.rune_utxo_counts.entry(rune_id).or_default() += delta;
+    }
+
+    pub fn add_rune_mints(&mut self, rune_id: RuneId, height: u64, delta: i64) {
+        *self.update.rune_mints.entry((rune_id, height)).or_default() += delta;
+    }
+
+    pub fn add_rune_supply(
+        &mut self,
+        rune_id: RuneId,
+        height: Option<u64>,
+        delta: RuneSupplyDelta,
+    ) {
+        *self.update.rune_supply.entry(rune_id).or_default() += delta;
+
+        if let Some(height) = height {
+            *self
+                .update
+                .rune_supply_blocks
+                .entry((rune_id, height))
+                .or_default() += delta;
+        }
+    }
+
     pub fn set_inscription(
         &mut self,
         inscription_id: InscriptionId,
@@ -322,6 +409,17 @@ impl UpdaterCache {
         )?);
     }
 
+    /// Script pubkeys of outpoints spent by mempool transactions, whether
+    /// they were created in a block or in the mempool. Unknown ones are left out.
+    pub fn get_spent_outpoints_script_pubkeys(
+        &self,
+        outpoints: &Vec<OutPoint>,
+    ) -> Result<HashMap<OutPoint, ScriptBuf>> {
+        self.db
+            .read()
+            .get_outpoints_to_script_pubkey(outpoints, None, true)
+    }
+
     pub fn batch_set_outpoints_to_script_pubkey(&mut self, items: HashMap<OutPoint, ScriptBuf>) {
         self.update.script_pubkeys_outpoints = items;
     }
@@ -373,8 +471,23 @@ impl UpdaterCache {
         Ok(())
     }
 
+    pub fn add_modified_script_pubkeys(
+        &mut self,
+        script_pubkeys: impl IntoIterator<Item = ScriptBuf>,
+    ) {
+        self.modified_script_pubkeys.extend(script_pubkeys);
+    }
+
     pub fn add_address_events(&mut self, chain: Chain) {
-        for script_pubkey in self.update.script_pubkeys.keys() {
+        let modified_script_pubkeys = std::mem::take(&mut self.modified_script_pubkeys);
+        let script_pubkeys: HashSet<&ScriptBuf> = self
+            .update
+            .script_pubkeys
+            .keys()
+            .chain(modified_script_pubkeys.iter())
+            .collect();
+
+        for script_pubkey in script_pubkeys {
             let address = chain.address_from_script(script_pubkey);
             if let Ok(address) = address {
                 self.events.push(Event::AddressModified {
@@ -486,3 +599,75 @@ impl UpdaterCache {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::db::RocksDB,
+        bitcoin::{
+            absolute::LockTime, blockdata::constants::genesis_block, hashes::Hash,
+            transaction::Version, Network, TxIn,
+        },
+        titan_types::SpentStatus,
+    };
+
+    fn tx_out(value: u64) -> TxOutEntry {
+        TxOutEntry {
+            runes: vec![],
+            risky_runes: vec![],
+            value,
+            spent: SpentStatus::Unspent,
+        }
+    }
+
+    fn block(height: u64) -> Block {
+        let mut header = genesis_block(Network::Regtest).header;
+        header.nonce = height as u32;
+        Block::empty_block(height, header)
+    }
+
+    #[test]
+    fn prefetched_tx_outs_never_replace_newer_ones() {
+        let db = Arc::new(StoreWithLock::new(Arc::new(RocksDB::open_temp().1)));
+        let settings = UpdaterCacheSettings {
+            max_recoverable_reorg_depth: 10,
+            mempool: false,
+        };
+        let outpoint = OutPoint::new(Txid::all_zeros(), 0);
+        let spending_tx = [Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: outpoint,
+                ..Default::default()
+            }],
+            output: vec![],
+        }];
+
+        let mut cache = UpdaterCache::new(db.clone(), settings).unwrap();
+        cache.set_new_block(block(0));
+        cache.set_tx_out(outpoint, tx_out(1));
+        cache.flush().unwrap();
+
+        // A block indexed after the prefetch changed the output.
+        let prefetched = UpdaterCache::prefetch_tx_outs(&db, &spending_tx).unwrap();
+        assert_eq!(prefetched.tx_outs[&outpoint].value, 1);
+        cache.set_tx_out(outpoint, tx_out(2));
+        cache.add_prefetched_tx_outs(prefetched);
+        assert_eq!(cache.get_tx_out(&outpoint).unwrap().value, 2);
+
+        // Same, but the change was flushed before the prefetched outputs
+        // were added.
+        let prefetched = UpdaterCache::prefetch_tx_outs(&db, &spending_tx).unwrap();
+        cache.set_new_block(block(1));
+        cache.flush().unwrap();
+        cache.add_prefetched_tx_outs(prefetched);
+        assert_eq!(cache.get_tx_out(&outpoint).unwrap().value, 2);
+
+        // Outputs prefetched since the last flush are used.
+        let prefetched = UpdaterCache::prefetch_tx_outs(&db, &spending_tx).unwrap();
+        cache.add_prefetched_tx_outs(prefetched);
+        assert_eq!(cache.update.txouts[&outpoint].value, 2);
+    }
+}
@@ -4,41 +4,50 @@ use {
         *,
     },
     crate::{
-        bitcoin_rpc::{RpcClientError, RpcClientPool, RpcClientPoolError, RpcClientProvider},
+        bitcoin_rpc::{
+            BitcoinCoreRpcResultExt, RpcClientError, RpcClientPool, RpcClientPoolError,
+            RpcClientProvider,
+        },
         index::{metrics::Metrics, store::Store, Settings, StoreError},
         models::{BlockId, RuneEntry},
     },
     address::AddressUpdater,
     bitcoin::{
         constants::SUBSIDY_HALVING_INTERVAL, hashes::Hash, hex::HexToArrayError,
-        Block as BitcoinBlock, Transaction, Txid,
+        Block as BitcoinBlock, OutPoint, ScriptBuf, Transaction, Txid,
     },
     bitcoincore_rpc::{
         json::{GetBlockchainInfoResult, GetMempoolEntryResult},
         Client, RpcApi,
     },
-    block_fetcher::fetch_blocks_from,
+    block_fetcher::{fetch_blocks_from, fetch_blocks_from_files},
+    block_files::BlockFiles,
+    block_pipeline::{BlockPipeline, PreparedBlock},
+    block_summary::BlockSummary,
     cache::{UpdaterCache, UpdaterCacheSettings},
     indicatif::{ProgressBar, ProgressStyle},
     mempool::MempoolError,
+    mempool_projection::{MempoolProjection, PROJECTED_BLOCKS},
+    mempool_sequence::{MempoolChanges, MempoolSequence},
     ordinals::{Rune, RuneId, SpacedRune, Terms},
     prometheus::HistogramVec,
     rollback::{Rollback, RollbackError},
     std::{
         collections::{HashMap, HashSet},
         fmt::{self, Display, Formatter},
+        path::Path,
         str::FromStr,
         sync::{
-            atomic::{AtomicBool, Ordering},
+            atomic::{AtomicBool, AtomicU64, Ordering},
             Arc, Mutex, RwLock,
         },
         time::{SystemTime, UNIX_EPOCH},
     },
     store_lock::StoreWithLock,
     thiserror::Error,
-    titan_types::{Block, Event, MempoolEntry},
+    titan_types::{Block, Event, MempoolBlocks, MempoolEntry, MempoolFees, MempoolTxPosition},
     tokio::sync::mpsc::{error::SendError, Sender},
-    tracing::{debug, error, info},
+    tracing::{debug, error, info, warn},
     transaction_parser::TransactionParser,
     transaction_updater::TransactionUpdater,
 };
@@ -100,6 +109,9 @@ pub struct Updater {
     db: Arc<StoreWithLock>,
     settings: Settings,
     is_at_tip: AtomicBool,
+    synced_once: AtomicBool,
+    node_height: AtomicU64,
+    mempool_synced: AtomicBool,
 
     bitcoin_rpc_pool: RpcClientPool,
 
@@ -109,9 +121,17 @@ pub struct Updater {
     pre_index_submitted_txs: RwLock<HashSet<Txid>>,
 
     zmq_received_txs: RwLock<HashMap<Txid, Transaction>>,
+    mempool_sequence: MempoolSequence,
 
     transaction_update: RwLock<TransactionUpdate>,
 
+    mempool_projection: RwLock<Option<Arc<MempoolProjection>>>,
+    replacements_pruned_at: AtomicU64,
+
+    /// Kept between syncs so the block files aren't scanned from the start
+    /// every time. Opened on the first sync that reads them.
+    block_files: Mutex<Option<Arc<BlockFiles>>>,
+
     sender: Option<Sender<Event>>,
 
     // monitoring
@@ -132,11 +152,18 @@ impl Updater {
             settings,
             bitcoin_rpc_pool,
             is_at_tip: AtomicBool::new(false),
+            synced_once: AtomicBool::new(false),
+            node_height: AtomicU64::new(0),
+            mempool_synced: AtomicBool::new(false),
             broadcast_lock: Mutex::new(()),
             pre_index_submitted_txs: RwLock::new(HashSet::new()),
             zmq_received_txs: RwLock::new(HashMap::new()),
+            mempool_sequence: MempoolSequence::default(),
             shutdown_flag,
             transaction_update: RwLock::new(TransactionUpdate::default()),
+            mempool_projection: RwLock::new(None),
+            replacements_pruned_at: AtomicU64::new(0),
+            block_files: Mutex::new(None),
             sender,
             latency: metrics.histogram_vec(
                 prometheus::HistogramOpts::new("indexer_latency", "Indexer latency"),
@@ -149,6 +176,34 @@ impl Updater {
         self.is_at_tip.load(Ordering::Relaxed)
     }
 
+    /// Whether the index reached the node's tip since it started.
+    pub fn has_synced_once(&self) -> bool {
+        self.synced_once.load(Ordering::Acquire)
+    }
+
+    /// Height of the node's tip when it was last asked.
+    pub fn node_height(&self) -> u64 {
+        self.node_height.load(Ordering::Acquire)
+    }
+
+    /// `None` if the block files can't be opened, in which case blocks are
+    /// fetched over RPC and opening them is tried again on the next sync.
+    fn open_block_files(&self, blocks_dir: &Path) -> Result<Option<Arc<BlockFiles>>> {
+        let mut block_files = self.block_files.lock().map_err(|_| UpdaterError::Mutex)?;
+        if block_files.is_none() {
+            match BlockFiles::open(blocks_dir, self.settings.chain.network()) {
+                Ok(opened) => *block_files = Some(Arc::new(opened)),
+                Err(e) => warn!(
+                    "Can't read blocks from {}, fetching over RPC: {}",
+                    blocks_dir.display(),
+                    e
+                ),
+            }
+        }
+
+        Ok(block_files.clone())
+    }
+
     fn is_chain_synced(
         &self,
         cache: &UpdaterCache,
@@ -184,6 +239,7 @@ impl Updater {
         // Get RPC client and get block height
         let bitcoin_block_client = self.bitcoin_rpc_pool.get()?;
         let mut chain_info = bitcoin_block_client.get_blockchain_info()?;
+        self.node_height.store(chain_info.blocks, Ordering::Release);
 
         let mut first_block = true;
 
@@ -191,19 +247,56 @@ impl Updater {
         while !self.is_chain_synced(&cache, &chain_info)? {
             let was_at_tip = self.is_at_tip.load(Ordering::Relaxed);
             self.is_at_tip.store(false, Ordering::Release);
+            if !was_at_tip {
+                self.mempool_synced.store(false, Ordering::Release);
+            }
 
             let progress_bar =
                 self.open_progress_bar(cache.get_block_height_tip(), chain_info.blocks);
 
-            let rx = fetch_blocks_from(
-                self.bitcoin_rpc_pool.clone(),
-                cache.get_block_count(),
-                chain_info.blocks + 1,
-            )?;
+            let block_files = match &self.settings.blocks_dir {
+                Some(blocks_dir) => self.open_block_files(blocks_dir)?,
+                None => None,
+            };
+
+            let rx = match block_files {
+                Some(block_files) => fetch_blocks_from_files(
+                    block_files,
+                    self.bitcoin_rpc_pool.clone(),
+                    cache.get_block_count(),
+                    chain_info.blocks + 1,
+                    self.settings.max_recoverable_reorg_depth(),
+                )?,
+                None => fetch_blocks_from(
+                    self.bitcoin_rpc_pool.clone(),
+                    cache.get_block_count(),
+                    chain_info.blocks + 1,
+                )?,
+            };
 
             let rpc_client = self.bitcoin_rpc_pool.get()?;
 
-            while let Ok(block) = rx.recv() {
+            let pipeline = BlockPipeline::new(
+                rx,
+                self.db.clone(),
+                self.settings.pipeline_depth,
+                self.settings.index_addresses,
+                self.latency.clone(),
+            );
+
+            loop {
+                let prepared = {
+                    let _timer = self
+                        .latency
+                        .with_label_values(&["pipeline_wait"])
+                        .start_timer();
+                    pipeline.next()
+                };
+                let Some(prepared) = prepared else {
+                    break;
+                };
+                let prepared = prepared?;
+
                 if self.shutdown_flag.load(Ordering::SeqCst) {
                     info!("Updater received shutdown signal, stopping...");
                     break;
@@ -211,7 +304,7 @@ impl Updater {
 
                 if was_at_tip || first_block {
                     match self.detect_reorg(
-                        &block,
+                        &prepared.block,
                         cache.get_block_count(),
                         &rpc_client,
                         self.settings.max_recoverable_reorg_depth(),
@@ -231,7 +324,7 @@ impl Updater {
                 }
 
                 let block = self.index_block(
-                    block,
+                    prepared,
                     cache.get_block_count() as u64,
                     &rpc_client,
                     &mut cache,
@@ -277,6 +370,7 @@ impl Updater {
 
             info!("Synced to tip {}", chain_info.blocks);
             chain_info = bitcoin_block_client.get_blockchain_info()?;
+            self.node_height.store(chain_info.blocks, Ordering::Release);
             progress_bar.finish_and_clear();
         }
 
@@ -295,6 +389,7 @@ impl Updater {
 
         if !self.shutdown_flag.load(Ordering::SeqCst) {
             self.is_at_tip.store(true, Ordering::Release);
+            self.synced_once.store(true, Ordering::Release);
         }
 
         Ok(())
@@ -308,16 +403,47 @@ impl Updater {
 
         let client = self.bitcoin_rpc_pool.get()?;
 
-        // Get current mempool transactions
+        // Changes are taken before the mempool is read, so any that arrive
+        // meanwhile are applied on the next pass.
         let lock = self.broadcast_lock.lock().unwrap();
-        let current_mempool = client.get_raw_mempool_verbose()?;
+        let changes = self.mempool_sequence.take();
+        let synced = self.mempool_synced.load(Ordering::Acquire);
+
+        let (current_mempool, stored_mempool, removed_txs) = match changes {
+            Some(changes) if synced => {
+                let stored_mempool = self.db.read().get_mempool_txids()?;
+                drop(lock);
+
+                if changes.added.is_empty() && changes.removed.is_empty() {
+                    self.prune_replacements()?;
+                    return Ok(());
+                }
 
-        // Get our previously indexed mempool transactions
-        let stored_mempool = {
-            let db = self.db.read();
-            db.get_mempool_txids()?
+                let (current_mempool, removed_txs) =
+                    self.fetch_mempool_changes(&client, &stored_mempool, changes)?;
+                (current_mempool, stored_mempool, removed_txs)
+            }
+            _ => {
+                // Get current mempool transactions
+                let current_mempool = client.get_raw_mempool_verbose()?;
+
+                // Get our previously indexed mempool transactions
+                let stored_mempool = {
+                    let db = self.db.read();
+                    db.get_mempool_txids()?
+                };
+                drop(lock);
+
+                // Find transactions to remove (they're no longer in mempool)
+                let removed_txs: Vec<Txid> = stored_mempool
+                    .keys()
+                    .filter(|txid| !current_mempool.contains_key(*txid))
+                    .cloned()
+                    .collect();
+
+                (current_mempool, stored_mempool, removed_txs)
+            }
         };
-        drop(lock);
 
         // Find new transactions to index
         let (new_txs, new_txs_with_mempool_entry): (Vec<Txid>, Vec<(Txid, MempoolEntry)>) =
@@ -332,12 +458,14 @@ impl Updater {
                 })
                 .unzip();
 
-        // Find transactions to remove (they're no longer in mempool)
-        let removed_txs: Vec<Txid> = stored_mempool
-            .keys()
-            .filter(|txid| !current_mempool.contains_key(*txid))
-            .cloned()
-            .collect();
+        if !synced {
+            info!(
+                "Syncing mempool: {} stored txs, {} to fetch, {} to remove",
+                stored_mempool.len(),
+                new_txs.len(),
+                removed_txs.len()
+            );
+        }
 
         // Index new transactions
         let new_txs_len = new_txs.len();
@@ -350,12 +478,7 @@ impl Updater {
         let updated_txids =
             self.update_mempool_entries(&mut cache, &stored_mempool, &current_mempool);
 
-        self.send_mempool_events(
-            &mut cache,
-            new_txs_with_mempool_entry,
-            removed_txs.clone(),
-            updated_txids,
-        )?;
+        self.send_mempool_events(&mut cache, new_txs_with_mempool_entry, updated_txids)?;
 
         if new_txs_len > 0 {
             let tx_map = self.choose_mempool_transactions_to_index(&new_txs)?;
@@ -385,13 +508,31 @@ impl Updater {
         }
 
         cache.flush()?;
+
+        // Replacements are looked up once the new transactions are stored and
+        // before the removed ones are rolled back, while both spends are known.
+        if !removed_txs.is_empty() {
+            let replacements = self.find_replacements(&removed_txs)?;
+            self.save_replacements(&replacements)?;
+
+            cache.add_event(Event::MempoolTransactionsReplaced {
+                txids: removed_txs.clone(),
+                replaced_by: replacements,
+            });
+        }
+
         cache.send_events(&self.sender)?;
 
         let removed_len = removed_txs.len();
         if removed_txs.len() > 0 {
-            self.remove_txs(&removed_txs, true)?;
+            let modified_script_pubkeys = self.remove_txs(&removed_txs, true)?;
+            cache.add_modified_script_pubkeys(modified_script_pubkeys);
+            cache.add_address_events(self.settings.chain);
+            cache.send_events(&self.sender)?;
         }
 
+        self.prune_replacements()?;
+
         if new_txs_len > 0 || removed_len > 0 {
             info!(
                 "Mempool: New txs: {}. Removed txs: {}",
@@ -412,9 +553,130 @@ impl Updater {
             .map_err(|_| UpdaterError::Mutex)?
             .clear();
 
+        self.refresh_mempool_projection()?;
+
+        if !self.shutdown_flag.load(Ordering::SeqCst) {
+            self.mempool_synced.store(true, Ordering::Release);
+        }
+
         Ok(())
     }
 
+    pub fn mempool_sequence(&self) -> &MempoolSequence {
+        &self.mempool_sequence
+    }
+
+    pub fn is_mempool_synced(&self) -> bool {
+        self.mempool_synced.load(Ordering::Acquire)
+    }
+
+    pub fn get_mempool_fees(&self) -> Result<MempoolFees> {
+        Ok(self.get_mempool_projection()?.fees.clone())
+    }
+
+    pub fn get_mempool_blocks(
+        &self,
+        max_blocks: usize,
+        txid: Option<&Txid>,
+    ) -> Result<MempoolBlocks> {
+        Ok(self
+            .get_mempool_projection()?
+            .mempool_blocks(max_blocks.min(PROJECTED_BLOCKS), txid))
+    }
+
+    pub fn get_mempool_tx_position(&self, txid: &Txid) -> Result<Option<MempoolTxPosition>> {
+        Ok(self
+            .get_mempool_projection()?
+            .position(txid, PROJECTED_BLOCKS))
+    }
+
+    fn get_mempool_projection(&self) -> Result<Arc<MempoolProjection>> {
+        if let Some(projection) = self
+            .mempool_projection
+            .read()
+            .map_err(|_| UpdaterError::Mutex)?
+            .as_ref()
+        {
+            return Ok(projection.clone());
+        }
+
+        self.refresh_mempool_projection()
+    }
+
+    fn refresh_mempool_projection(&self) -> Result<Arc<MempoolProjection>> {
+        let mempool = self.db.read().get_mempool_txids()?;
+        let projection = Arc::new(MempoolProjection::new(&mempool, PROJECTED_BLOCKS)?);
+
+        *self
+            .mempool_projection
+            .write()
+            .map_err(|_| UpdaterError::Mutex)? = Some(projection.clone());
+
+        Ok(projection)
+    }
+
+    /// Entries of the transactions reported added over ZMQ, along with the
+    /// stored ones whose ancestors or descendants changed, so their fee and
+    /// size totals stay current without reading the whole mempool. Also
+    /// returns the reported removals that were indexed.
+    fn fetch_mempool_changes(
+        &self,
+        client: &Client,
+        stored_mempool: &HashMap<Txid, MempoolEntry>,
+        changes: MempoolChanges,
+    ) -> Result<(HashMap<Txid, GetMempoolEntryResult>, Vec<Txid>)> {
+        let removed_txs: Vec<Txid> = changes
+            .removed
+            .into_iter()
+            .filter(|txid| stored_mempool.contains_key(txid))
+            .collect();
+
+        let mut current_mempool = HashMap::new();
+        let mut ancestors = Vec::new();
+        for txid in changes.added {
+            if stored_mempool.contains_key(&txid) {
+                continue;
+            }
+
+            // Already gone if it isn't found.
+            if let Some(entry) = client.get_mempool_entry(&txid).into_option()? {
+                ancestors.extend(entry.depends.iter().copied());
+                current_mempool.insert(txid, entry);
+            }
+        }
+
+        let mut descendants = Vec::new();
+        for txid in &removed_txs {
+            let entry = &stored_mempool[txid];
+            ancestors.extend(entry.depends.iter().copied());
+            descendants.extend(entry.spent_by.iter().copied());
+        }
+
+        let mut affected = HashSet::new();
+        while let Some(txid) = ancestors.pop() {
+            if let Some(entry) = stored_mempool.get(&txid) {
+                if affected.insert(txid) {
+                    ancestors.extend(entry.depends.iter().copied());
+                }
+            }
+        }
+        while let Some(txid) = descendants.pop() {
+            if let Some(entry) = stored_mempool.get(&txid) {
+                if affected.insert(txid) {
+                    descendants.extend(entry.spent_by.iter().copied());
+                }
+            }
+        }
+
+        for txid in affected {
+            if let Some(entry) = client.get_mempool_entry(&txid).into_option()? {
+                current_mempool.insert(txid, entry);
+            }
+        }
+
+        Ok((current_mempool, removed_txs))
+    }
+
     fn update_mempool_entries(
         &self,
         cache: &mut UpdaterCache,
@@ -440,19 +702,12 @@ impl Updater {
         &self,
         cache: &mut UpdaterCache,
         new_txids: Vec<(Txid, MempoolEntry)>,
-        removed_txids: Vec<Txid>,
         updated_txids: Vec<(Txid, MempoolEntry)>,
     ) -> Result<()> {
         if !new_txids.is_empty() {
             cache.add_event(Event::MempoolTransactionsAdded { txids: new_txids });
         }
 
-        if !removed_txids.is_empty() {
-            cache.add_event(Event::MempoolTransactionsReplaced {
-                txids: removed_txids,
-            });
-        }
-
         if !updated_txids.is_empty() {
             cache.add_event(Event::MempoolEntriesUpdated {
                 txids: updated_txids,
@@ -462,6 +717,81 @@ impl Updater {
         Ok(())
     }
 
+    /// Returns `(replaced, replacing)` pairs for the removed transactions
+    /// that had one of their inputs spent again by another mempool
+    /// transaction.
+    fn find_replacements(&self, removed_txids: &Vec<Txid>) -> Result<Vec<(Txid, Txid)>> {
+        let db = self.db.read();
+        let state_changes = db.get_txs_state_changes(removed_txids, true)?;
+
+        let inputs: Vec<(Txid, OutPoint)> = state_changes
+            .iter()
+            .filter(|(_, state_change)| !state_change.is_coinbase)
+            .flat_map(|(txid, state_change)| {
+                state_change
+                    .inputs
+                    .iter()
+                    .map(move |outpoint| (*txid, *outpoint))
+            })
+            .collect();
+
+        let spenders = db.get_spent_outpoints_in_mempool(
+            &inputs.iter().map(|(_, outpoint)| *outpoint).collect(),
+        )?;
+
+        let mut replacements: HashMap<Txid, Txid> = HashMap::new();
+        for (txid, outpoint) in inputs {
+            if let Some(Some(spender)) = spenders.get(&outpoint) {
+                if spender.txid != txid {
+                    replacements.entry(txid).or_insert(spender.txid);
+                }
+            }
+        }
+
+        Ok(replacements.into_iter().collect())
+    }
+
+    fn save_replacements(&self, replacements: &[(Txid, Txid)]) -> Result<()> {
+        if replacements.is_empty() {
+            return Ok(());
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.db.write().set_replacements(replacements, now)?;
+
+        Ok(())
+    }
+
+    /// Drops replacements older than `replacement_history_hours`. Runs at most
+    /// every ten minutes since it scans the whole history.
+    fn prune_replacements(&self) -> Result<()> {
+        const PRUNE_INTERVAL_SECS: u64 = 600;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let pruned_at = self.replacements_pruned_at.load(Ordering::Relaxed);
+        if now.saturating_sub(pruned_at) < PRUNE_INTERVAL_SECS {
+            return Ok(());
+        }
+
+        self.replacements_pruned_at.store(now, Ordering::Relaxed);
+
+        let cutoff = now.saturating_sub(self.settings.replacement_history_hours * 3600);
+        let deleted = self.db.write().delete_replacements_before(cutoff)?;
+        if deleted > 0 {
+            debug!("Pruned {} mempool replacements", deleted);
+        }
+
+        Ok(())
+    }
+
     fn choose_mempool_transactions_to_index(
         &self,
         new_txs: &Vec<Txid>,
@@ -519,7 +849,7 @@ impl Updater {
 
     fn index_block(
         &self,
-        bitcoin_block: BitcoinBlock,
+        prepared: PreparedBlock,
         height: u64,
         rpc_client: &Client,
         cache: &mut UpdaterCache,
@@ -530,8 +860,20 @@ impl Updater {
             .with_label_values(&["index_block"])
             .start_timer();
 
+        let PreparedBlock {
+            block: bitcoin_block,
+            txids,
+            tx_outs,
+            address_updater: block_address_updater,
+        } = prepared;
+
+        cache.add_prefetched_tx_outs(tx_outs);
         cache.precache_tx_outs(&bitcoin_block.txdata)?;
 
+        if let Some(block_address_updater) = block_address_updater {
+            address_updater.extend(block_address_updater);
+        }
+
         let mut transaction_parser =
             TransactionParser::new(&rpc_client, self.settings.chain, height, false)?;
 
@@ -543,18 +885,18 @@ impl Updater {
             .with_label_values(&["parse_block&index_block_txs"])
             .start_timer();
 
-        let mut transaction_updater =
-            TransactionUpdater::new(self.settings.clone().into(), Some(address_updater))?;
+        // Script pubkeys were already extracted with the prepared block.
+        let mut transaction_updater = TransactionUpdater::new(self.settings.clone().into(), None)?;
 
         let mut block = Block::empty_block(height, bitcoin_block.header);
+        let mut summary = BlockSummary::default();
 
         let mut transaction_update = self
             .transaction_update
             .write()
             .map_err(|_| UpdaterError::Mutex)?;
 
-        for (i, tx) in bitcoin_block.txdata.iter().enumerate() {
-            let txid = tx.compute_txid();
+        for (i, (tx, &txid)) in bitcoin_block.txdata.iter().zip(&txids).enumerate() {
             match transaction_parser.parse(cache, u32::try_from(i).unwrap(), tx) {
                 Ok(result) => {
                     debug!("Indexing tx {} in block {}", txid, block_height);
@@ -572,9 +914,14 @@ impl Updater {
                     )?;
                     block.tx_ids.push(txid.to_string());
                     transaction_update.add_block_tx(txid);
-                    if let Some((id, ..)) = result.etched {
-                        block.etched_runes.push(id);
-                    }
+                    let premine = match result.etched {
+                        Some((id, ..)) => {
+                            block.etched_runes.push(id);
+                            Some(cache.get_rune(&id)?.premine)
+                        }
+                        None => None,
+                    };
+                    summary.add_tx(txid, &result, premine);
                 }
                 Err(e) => {
                     panic!("Failed to index transaction {}: {}", txid, e);
@@ -582,6 +929,14 @@ impl Updater {
             }
         }
 
+        let (stats, rune_activity) =
+            summary.finish(&bitcoin_block, height, self.settings.chain.subsidy(height));
+        cache.set_block_summary(bitcoin_block.block_hash(), stats, rune_activity);
+
+        if self.mempool_synced.load(Ordering::Acquire) {
+            self.mempool_sequence.txs_confirmed(txids);
+        }
+
         Ok(block)
     }
 
@@ -696,11 +1051,10 @@ impl Updater {
         Ok(true)
     }
 
-    fn remove_txs(&self, txids: &Vec<Txid>, mempool: bool) -> Result<()> {
+    fn remove_txs(&self, txids: &Vec<Txid>, mempool: bool) -> Result<HashSet<ScriptBuf>> {
         let db = self.db.write();
         let mut rollback_updater = Rollback::new(&db, self.settings.clone().into(), mempool)?;
-        rollback_updater.revert_transactions(txids)?;
-        Ok(())
+        Ok(rollback_updater.revert_transactions(txids)?)
     }
 
     fn open_progress_bar(&self, current_height: u64, total_height: u64) -> ProgressBar {
@@ -863,7 +1217,22 @@ impl Updater {
                         )?
                     };
 
-                    sender.blocking_send(Event::TransactionsReplaced { txids: not_exists })?;
+                    let replaced_by = {
+                        let db = self.db.read();
+                        not_exists
+                            .iter()
+                            .filter_map(|txid| match db.get_replacement(txid) {
+                                Ok(replacement) => replacement
+                                    .map(|replacement| Ok((*txid, replacement.replaced_by))),
+                                Err(e) => Some(Err(e)),
+                            })
+                            .collect::<std::result::Result<Vec<_>, _>>()?
+                    };
+
+                    sender.blocking_send(Event::TransactionsReplaced {
+                        txids: not_exists,
+                        replaced_by,
+                    })?;
                 }
 
                 if !categorized.added.is_empty() {
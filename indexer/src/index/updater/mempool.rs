@@ -81,35 +81,50 @@ pub fn sort_transaction_order(
     mempool_entries: &HashMap<Txid, GetMempoolEntryResult>,
     tx_map: &HashMap<Txid, Transaction>,
 ) -> Result<Vec<Txid>, MempoolError> {
-    // Step 2: Build dependency graph and indegree count
-    let mut graph: HashMap<Txid, Vec<Txid>> = HashMap::new();
-    let mut indegree: HashMap<Txid, usize> = HashMap::new();
-
-    // Initialize graph nodes
-    for &txid in tx_map.keys() {
-        graph.insert(txid, Vec::new());
-        indegree.insert(txid, 0);
-    }
+    // Only consider dependencies that are part of our tx_map and in mempool_entries
+    let parents = tx_map
+        .iter()
+        .map(|(&txid, tx)| {
+            let parents = tx
+                .input
+                .iter()
+                .map(|input| input.previous_output.txid)
+                .filter(|dep_txid| {
+                    tx_map.contains_key(dep_txid) && mempool_entries.contains_key(dep_txid)
+                })
+                .collect();
 
-    // Populate graph with edges and compute indegrees
-    for (&txid, tx) in tx_map.iter() {
-        for input in &tx.input {
-            let dep_txid = input.previous_output.txid;
-            // Only consider dependencies that are part of our tx_map and in mempool_entries
-            if tx_map.contains_key(&dep_txid) && mempool_entries.contains_key(&dep_txid) {
-                graph.entry(dep_txid).or_default().push(txid);
-                *indegree.entry(txid).or_insert(0) += 1;
-            }
+            (txid, parents)
+        })
+        .collect();
+
+    dependency_order(&parents)
+}
+
+/// Orders the transactions in `parents` so that each one comes after all of
+/// its parents. Every parent must itself be a key of `parents`.
+pub fn dependency_order(parents: &HashMap<Txid, Vec<Txid>>) -> Result<Vec<Txid>, MempoolError> {
+    // Build dependency graph and indegree count
+    let mut graph: HashMap<Txid, Vec<Txid>> = HashMap::with_capacity(parents.len());
+    let mut indegree: HashMap<Txid, usize> = HashMap::with_capacity(parents.len());
+
+    for (&txid, tx_parents) in parents {
+        indegree.insert(txid, tx_parents.len());
+        for &parent in tx_parents {
+            graph.entry(parent).or_default().push(txid);
         }
     }
 
-    // Step 3: Topological sort using Kahn's algorithm
-    let mut queue: VecDeque<Txid> = indegree
+    // Topological sort using Kahn's algorithm. Roots are sorted so that the
+    // order doesn't depend on the hash map's.
+    let mut roots: Vec<Txid> = indegree
         .iter()
         .filter_map(|(&txid, &deg)| if deg == 0 { Some(txid) } else { None })
         .collect();
+    roots.sort();
+    let mut queue: VecDeque<Txid> = roots.into();
 
-    let mut sorted = Vec::with_capacity(tx_map.len());
+    let mut sorted = Vec::with_capacity(parents.len());
 
     while let Some(node) = queue.pop_front() {
         sorted.push(node);
@@ -127,7 +142,7 @@ pub fn sort_transaction_order(
     }
 
     // If sorted transactions count doesn't match input transactions count, a cycle exists.
-    if sorted.len() != tx_map.len() {
+    if sorted.len() != parents.len() {
         return Err(MempoolError::CycleDetected);
     }
 
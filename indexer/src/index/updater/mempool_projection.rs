@@ -0,0 +1,564 @@
+use {
+    super::mempool::{dependency_order, MempoolError},
+    bitcoin::Txid,
+    std::{
+        cmp::Ordering,
+        collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque},
+    },
+    titan_types::{
+        FeeRateBucket, MempoolBlock, MempoolBlocks, MempoolEntry, MempoolFees, MempoolTxPosition,
+        RecommendedFees,
+    },
+};
+
+/// Room left for transactions in a block once the coinbase is accounted for.
+const BLOCK_VSIZE: u64 = 999_000;
+
+/// A block counts as full when it has less than this left.
+const BLOCK_FULL_MARGIN: u64 = 50_000;
+
+const MIN_FEE_RATE: f64 = 1.0;
+
+/// Lower bounds of the histogram buckets, in sat/vB.
+const FEE_RATE_BUCKETS: [f64; 39] = [
+    0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0, 12.0, 15.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0,
+    80.0, 90.0, 100.0, 125.0, 150.0, 175.0, 200.0, 250.0, 300.0, 350.0, 400.0, 500.0, 600.0, 700.0,
+    800.0, 900.0, 1000.0, 1200.0, 1400.0, 1600.0, 1800.0, 2000.0,
+];
+
+/// Blocks projected on every mempool pass. The recommended fee rates look
+/// up to six of them ahead.
+pub(crate) const PROJECTED_BLOCKS: usize = 8;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProjectedBlock {
+    pub txids: Vec<Txid>,
+    /// Fee rate of the package each transaction was selected with.
+    pub fee_rates: Vec<f64>,
+    pub vsizes: Vec<u64>,
+    pub vsize: u64,
+    pub weight: u64,
+    pub total_fee: u64,
+}
+
+impl ProjectedBlock {
+    fn push(&mut self, node: &Node, fee_rate: f64) {
+        self.txids.push(node.txid);
+        self.fee_rates.push(fee_rate);
+        self.vsizes.push(node.vsize);
+        self.vsize += node.vsize;
+        self.weight += node.weight;
+        self.total_fee += node.fee;
+    }
+
+    fn is_full(&self) -> bool {
+        self.vsize + BLOCK_FULL_MARGIN >= BLOCK_VSIZE
+    }
+
+    /// Lowest fee rate a transaction got into the block with.
+    fn min_fee_rate(&self) -> f64 {
+        self.fee_rates.iter().copied().fold(f64::INFINITY, f64::min)
+    }
+
+    /// Fee rate that half of the block's vsize pays at least.
+    fn median_fee_rate(&self) -> f64 {
+        let mut rates: Vec<(f64, u64)> = self
+            .fee_rates
+            .iter()
+            .copied()
+            .zip(self.vsizes.iter().copied())
+            .collect();
+        rates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut vsize = 0;
+        for (fee_rate, tx_vsize) in rates {
+            vsize += tx_vsize;
+            if vsize * 2 >= self.vsize {
+                return fee_rate;
+            }
+        }
+
+        MIN_FEE_RATE
+    }
+}
+
+pub(crate) struct MempoolProjection {
+    pub blocks: Vec<ProjectedBlock>,
+    /// Effective fee rate of every mempool transaction: the rate of the
+    /// package it was selected with, or of its current ancestor package if it
+    /// didn't make it into a projected block.
+    pub fee_rates: HashMap<Txid, f64>,
+    /// Projected block of every transaction that made it into one.
+    pub block_indices: HashMap<Txid, usize>,
+    pub fees: MempoolFees,
+}
+
+struct Node {
+    txid: Txid,
+    fee: u64,
+    vsize: u64,
+    weight: u64,
+    children: Vec<usize>,
+    /// Every in-mempool ancestor, not including the transaction itself, in
+    /// dependency order.
+    ancestors: Vec<usize>,
+    /// Fee and vsize of the transaction plus its unselected ancestors.
+    package_fee: u64,
+    package_vsize: u64,
+    selected: bool,
+    /// Bumped whenever the package changes, to skip stale heap entries.
+    version: u32,
+}
+
+#[derive(PartialEq, Eq)]
+struct Candidate {
+    fee: u64,
+    vsize: u64,
+    index: usize,
+    version: u32,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.fee as u128 * other.vsize as u128)
+            .cmp(&(other.fee as u128 * self.vsize as u128))
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+struct PackageGraph {
+    nodes: Vec<Node>,
+}
+
+impl PackageGraph {
+    fn new(entries: &HashMap<Txid, MempoolEntry>) -> Result<Self, MempoolError> {
+        let parents: HashMap<Txid, Vec<Txid>> = entries
+            .iter()
+            .map(|(txid, entry)| {
+                let parents = entry
+                    .depends
+                    .iter()
+                    .filter(|parent| entries.contains_key(*parent))
+                    .copied()
+                    .collect();
+
+                (*txid, parents)
+            })
+            .collect();
+
+        // Nodes are indexed in dependency order, so a node's ancestors all
+        // have lower indices than it.
+        let order = dependency_order(&parents)?;
+        let indices: HashMap<Txid, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(index, txid)| (*txid, index))
+            .collect();
+
+        let mut nodes: Vec<Node> = Vec::with_capacity(order.len());
+        for (index, txid) in order.iter().enumerate() {
+            let entry = &entries[txid];
+
+            let mut ancestors = BTreeSet::new();
+            for parent in &parents[txid] {
+                let parent = indices[parent];
+                ancestors.insert(parent);
+                ancestors.extend(nodes[parent].ancestors.iter().copied());
+                nodes[parent].children.push(index);
+            }
+
+            let vsize = entry.vsize.max(1);
+            let ancestors: Vec<usize> = ancestors.into_iter().collect();
+            let (package_fee, package_vsize) =
+                ancestors
+                    .iter()
+                    .fold((entry.fees.base, vsize), |(fee, vsize), ancestor| {
+                        (fee + nodes[*ancestor].fee, vsize + nodes[*ancestor].vsize)
+                    });
+
+            nodes.push(Node {
+                txid: *txid,
+                fee: entry.fees.base,
+                vsize,
+                weight: entry.weight.unwrap_or(vsize * 4),
+                children: Vec::new(),
+                ancestors,
+                package_fee,
+                package_vsize,
+                selected: false,
+                version: 0,
+            });
+        }
+
+        Ok(Self { nodes })
+    }
+
+    fn candidate(&self, index: usize) -> Candidate {
+        let node = &self.nodes[index];
+        Candidate {
+            fee: node.package_fee,
+            vsize: node.package_vsize,
+            index,
+            version: node.version,
+        }
+    }
+
+    fn package_fee_rate(&self, index: usize) -> f64 {
+        let node = &self.nodes[index];
+        node.package_fee as f64 / node.package_vsize as f64
+    }
+
+    /// Unselected ancestors and the transaction itself, parents first.
+    fn package(&self, index: usize) -> Vec<usize> {
+        let mut package: Vec<usize> = self.nodes[index]
+            .ancestors
+            .iter()
+            .copied()
+            .filter(|ancestor| !self.nodes[*ancestor].selected)
+            .collect();
+        package.push(index);
+        package
+    }
+
+    /// Marks the transaction as mined and takes it out of its descendants'
+    /// packages.
+    fn select(&mut self, index: usize, heap: &mut BinaryHeap<Candidate>) {
+        self.nodes[index].selected = true;
+        let (fee, vsize) = (self.nodes[index].fee, self.nodes[index].vsize);
+
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<usize> = self.nodes[index].children.iter().copied().collect();
+        while let Some(descendant) = queue.pop_front() {
+            if !visited.insert(descendant) || self.nodes[descendant].selected {
+                continue;
+            }
+
+            let node = &mut self.nodes[descendant];
+            node.package_fee -= fee;
+            node.package_vsize -= vsize;
+            node.version += 1;
+            queue.extend(node.children.iter().copied());
+            heap.push(self.candidate(descendant));
+        }
+    }
+}
+
+impl MempoolProjection {
+    /// Fills up to `max_blocks` blocks by repeatedly picking the ancestor
+    /// package with the highest fee rate, the way miners build templates.
+    pub fn new(
+        entries: &HashMap<Txid, MempoolEntry>,
+        max_blocks: usize,
+    ) -> Result<Self, MempoolError> {
+        let mut graph = PackageGraph::new(entries)?;
+        let mut heap: BinaryHeap<Candidate> = (0..graph.nodes.len())
+            .map(|index| graph.candidate(index))
+            .collect();
+
+        let mut fee_rates = HashMap::with_capacity(graph.nodes.len());
+        let mut block_indices = HashMap::new();
+        let mut blocks = Vec::new();
+        while blocks.len() < max_blocks && !heap.is_empty() {
+            let mut block = ProjectedBlock::default();
+            let mut skipped = Vec::new();
+
+            while let Some(candidate) = heap.pop() {
+                let node = &graph.nodes[candidate.index];
+                if node.selected || node.version != candidate.version {
+                    continue;
+                }
+
+                if block.vsize + node.package_vsize > BLOCK_VSIZE {
+                    skipped.push(candidate);
+                    if block.is_full() {
+                        break;
+                    }
+                    continue;
+                }
+
+                let fee_rate = graph.package_fee_rate(candidate.index);
+                for index in graph.package(candidate.index) {
+                    graph.select(index, &mut heap);
+                    let node = &graph.nodes[index];
+                    block.push(node, fee_rate);
+                    fee_rates.insert(node.txid, fee_rate);
+                    block_indices.insert(node.txid, blocks.len());
+                }
+            }
+
+            heap.extend(skipped);
+            if block.txids.is_empty() {
+                break;
+            }
+            blocks.push(block);
+        }
+
+        for index in 0..graph.nodes.len() {
+            if !graph.nodes[index].selected {
+                fee_rates.insert(graph.nodes[index].txid, graph.package_fee_rate(index));
+            }
+        }
+
+        let fees = mempool_fees(entries, &blocks, &fee_rates);
+
+        Ok(Self {
+            blocks,
+            fee_rates,
+            block_indices,
+            fees,
+        })
+    }
+
+    /// The first `max_blocks` projected blocks and, if `txid` is in the
+    /// mempool, where it stands.
+    pub fn mempool_blocks(&self, max_blocks: usize, txid: Option<&Txid>) -> MempoolBlocks {
+        let blocks = self
+            .blocks
+            .iter()
+            .take(max_blocks)
+            .map(|block| MempoolBlock {
+                txids: block.txids.clone(),
+                tx_count: block.txids.len() as u64,
+                vsize: block.vsize,
+                weight: block.weight,
+                total_fee: block.total_fee,
+                min_fee_rate: block.min_fee_rate(),
+                max_fee_rate: block.fee_rates.iter().copied().fold(0.0, f64::max),
+                median_fee_rate: block.median_fee_rate(),
+            })
+            .collect();
+
+        let position = txid.and_then(|txid| self.position(txid, max_blocks));
+
+        MempoolBlocks { blocks, position }
+    }
+
+    /// Where `txid` stands, counting only the first `max_blocks` blocks.
+    pub fn position(&self, txid: &Txid, max_blocks: usize) -> Option<MempoolTxPosition> {
+        self.fee_rates.get(txid).map(|fee_rate| MempoolTxPosition {
+            txid: *txid,
+            block: self
+                .block_indices
+                .get(txid)
+                .filter(|block| **block < max_blocks)
+                .map(|block| *block as u64),
+            fee_rate: *fee_rate,
+        })
+    }
+}
+
+fn mempool_fees(
+    entries: &HashMap<Txid, MempoolEntry>,
+    blocks: &[ProjectedBlock],
+    fee_rates: &HashMap<Txid, f64>,
+) -> MempoolFees {
+    let histogram = fee_rate_histogram(
+        entries
+            .iter()
+            .map(|(txid, entry)| (fee_rates[txid], entry.vsize, entry.fees.base)),
+    );
+
+    let next_block = blocks
+        .first()
+        .map(|block| {
+            fee_rate_histogram(block.txids.iter().zip(block.fee_rates.iter()).map(
+                |(txid, fee_rate)| {
+                    let entry = &entries[txid];
+                    (*fee_rate, entry.vsize, entry.fees.base)
+                },
+            ))
+        })
+        .unwrap_or_default();
+
+    // Getting into the nth block takes about its median fee rate, unless it
+    // isn't full, in which case anything pays.
+    let block_fee_rate = |n: usize| {
+        blocks
+            .get(n)
+            .filter(|block| block.is_full())
+            .map_or(MIN_FEE_RATE, |block| {
+                round_fee_rate(block.median_fee_rate())
+            })
+            .max(MIN_FEE_RATE)
+    };
+
+    let hour = block_fee_rate(5);
+    let half_hour = block_fee_rate(2).max(hour);
+    let fastest = block_fee_rate(0).max(half_hour);
+    // Anything that makes it into the last projected block confirms
+    // eventually, once the mempool is deeper than the projection.
+    let economy = blocks
+        .last()
+        .filter(|block| block.is_full())
+        .map_or(MIN_FEE_RATE, |block| round_fee_rate(block.min_fee_rate()))
+        .clamp(MIN_FEE_RATE, hour);
+
+    MempoolFees {
+        count: entries.len() as u64,
+        vsize: entries.values().map(|entry| entry.vsize).sum(),
+        total_fee: entries.values().map(|entry| entry.fees.base).sum(),
+        histogram,
+        next_block,
+        recommended: RecommendedFees {
+            fastest,
+            half_hour,
+            hour,
+            economy,
+        },
+    }
+}
+
+fn fee_rate_histogram(txs: impl Iterator<Item = (f64, u64, u64)>) -> Vec<FeeRateBucket> {
+    let mut buckets: Vec<FeeRateBucket> = FEE_RATE_BUCKETS
+        .iter()
+        .map(|fee_rate| FeeRateBucket {
+            fee_rate: *fee_rate,
+            count: 0,
+            vsize: 0,
+            total_fee: 0,
+        })
+        .collect();
+
+    for (fee_rate, vsize, fee) in txs {
+        let index = FEE_RATE_BUCKETS
+            .iter()
+            .rposition(|lower| fee_rate >= *lower)
+            .unwrap_or(0);
+        let bucket = &mut buckets[index];
+        bucket.count += 1;
+        bucket.vsize += vsize;
+        bucket.total_fee += fee;
+    }
+
+    buckets.retain(|bucket| bucket.count > 0);
+    buckets
+}
+
+fn round_fee_rate(fee_rate: f64) -> f64 {
+    (fee_rate * 100.0).ceil() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::hashes::Hash, titan_types::MempoolEntryFee};
+
+    fn txid(n: u8) -> Txid {
+        Txid::from_byte_array([n; 32])
+    }
+
+    fn entry(fee: u64, vsize: u64, depends: Vec<Txid>) -> MempoolEntry {
+        MempoolEntry {
+            vsize,
+            weight: Some(vsize * 4),
+            descendant_count: 0,
+            descendant_size: 0,
+            ancestor_count: 0,
+            ancestor_size: 0,
+            fees: MempoolEntryFee {
+                base: fee,
+                descendant: 0,
+                ancestor: 0,
+            },
+            depends,
+            spent_by: vec![],
+        }
+    }
+
+    #[test]
+    fn child_pays_for_parent() {
+        let entries = HashMap::from([
+            (txid(1), entry(100, 100, vec![])),
+            (txid(2), entry(1_900, 100, vec![txid(1)])),
+            (txid(3), entry(500, 100, vec![])),
+        ]);
+
+        let projection = MempoolProjection::new(&entries, 1).unwrap();
+
+        assert_eq!(projection.blocks.len(), 1);
+        assert_eq!(projection.blocks[0].txids, vec![txid(1), txid(2), txid(3)]);
+        assert_eq!(projection.fee_rates[&txid(1)], 10.0);
+        assert_eq!(projection.fee_rates[&txid(2)], 10.0);
+        assert_eq!(projection.fee_rates[&txid(3)], 5.0);
+    }
+
+    #[test]
+    fn fills_blocks_in_fee_rate_order() {
+        let entries = HashMap::from([
+            (txid(1), entry(2_000_000, 400_000, vec![])),
+            (txid(2), entry(4_000_000, 400_000, vec![])),
+            (txid(3), entry(1_200_000, 400_000, vec![])),
+        ]);
+
+        let projection = MempoolProjection::new(&entries, 3).unwrap();
+
+        assert_eq!(projection.blocks.len(), 2);
+        assert_eq!(projection.blocks[0].txids, vec![txid(2), txid(1)]);
+        assert_eq!(projection.blocks[1].txids, vec![txid(3)]);
+    }
+
+    #[test]
+    fn reports_tx_position() {
+        let entries = HashMap::from([
+            (txid(1), entry(4_000_000, 600_000, vec![])),
+            (txid(2), entry(1_000_000, 500_000, vec![])),
+            (txid(3), entry(1_000, 100, vec![txid(2)])),
+        ]);
+
+        let projection = MempoolProjection::new(&entries, 1).unwrap();
+        let blocks = projection.mempool_blocks(PROJECTED_BLOCKS, Some(&txid(3)));
+
+        assert_eq!(blocks.blocks.len(), 1);
+        assert_eq!(blocks.blocks[0].txids, vec![txid(1)]);
+        assert_eq!(blocks.blocks[0].weight, 2_400_000);
+        assert_eq!(blocks.blocks[0].min_fee_rate, 4_000_000.0 / 600_000.0);
+
+        let position = blocks.position.unwrap();
+        assert_eq!(position.block, None);
+        assert_eq!(position.fee_rate, 1_001_000.0 / 500_100.0);
+
+        assert!(projection
+            .mempool_blocks(1, Some(&txid(4)))
+            .position
+            .is_none());
+    }
+
+    #[test]
+    fn recommends_minimum_when_mempool_is_small() {
+        let entries = HashMap::from([(txid(1), entry(5_000, 100, vec![]))]);
+
+        let fees = MempoolProjection::new(&entries, PROJECTED_BLOCKS)
+            .unwrap()
+            .fees;
+
+        assert_eq!(fees.recommended.fastest, MIN_FEE_RATE);
+        assert_eq!(fees.recommended.economy, MIN_FEE_RATE);
+        assert_eq!(fees.histogram.len(), 1);
+        assert_eq!(fees.histogram[0].fee_rate, 50.0);
+        assert_eq!(fees.next_block, fees.histogram);
+    }
+
+    #[test]
+    fn economy_follows_a_congested_mempool() {
+        // Two transactions fill a block: seven blocks at 50 sat/vB, then more
+        // than the rest of the projection at 20 sat/vB.
+        let entries: HashMap<Txid, MempoolEntry> = (0..34)
+            .map(|n| {
+                let fee_rate = if n < 14 { 50 } else { 20 };
+                (txid(n), entry(fee_rate * 490_000, 490_000, vec![]))
+            })
+            .collect();
+
+        let fees = MempoolProjection::new(&entries, PROJECTED_BLOCKS)
+            .unwrap()
+            .fees;
+
+        assert_eq!(fees.recommended.hour, 50.0);
+        assert_eq!(fees.recommended.economy, 20.0);
+    }
+}
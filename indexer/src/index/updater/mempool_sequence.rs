@@ -0,0 +1,176 @@
+use {
+    bitcoin::Txid,
+    std::{
+        collections::HashMap,
+        sync::{Condvar, Mutex},
+        time::Duration,
+    },
+    tracing::{info, warn},
+};
+
+/// Past this many pending changes, resyncing is cheaper than applying them
+/// one by one, like after catching up on blocks.
+const MAX_PENDING_CHANGES: usize = 100_000;
+
+#[derive(Default)]
+struct State {
+    /// Set once `sequence` messages arrive, cleared when the listener stops.
+    active: bool,
+    /// Set when notifications were missed, until the next full resync.
+    gap: bool,
+    /// Set when a removal or a block should be applied without waiting for
+    /// the next loop.
+    wake: bool,
+    /// Whether each transaction was last added to (`true`) or removed from
+    /// (`false`) the mempool.
+    changes: HashMap<Txid, bool>,
+}
+
+/// Mempool changes since the last pass.
+#[derive(Debug, Default)]
+pub struct MempoolChanges {
+    pub added: Vec<Txid>,
+    pub removed: Vec<Txid>,
+}
+
+/// Mempool changes reported by bitcoind's ZMQ `sequence` topic, waiting for
+/// the indexer loop to apply them.
+#[derive(Default)]
+pub struct MempoolSequence {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+impl MempoolSequence {
+    /// Called for every `sequence` message. The first one after the listener
+    /// (re)connects forces a full resync, since anything before it may have
+    /// been missed.
+    pub fn activate(&self) {
+        let mut state = self.state.lock().unwrap();
+        if !state.active {
+            info!("Receiving mempool updates over ZMQ");
+            state.active = true;
+            state.gap = true;
+        }
+    }
+
+    pub fn deactivate(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.active = false;
+        state.changes.clear();
+    }
+
+    pub fn gap(&self) {
+        warn!("Missed ZMQ notifications, resyncing mempool");
+        let mut state = self.state.lock().unwrap();
+        state.gap = true;
+        state.wake = true;
+        self.condvar.notify_all();
+    }
+
+    pub fn tx_added(&self, txid: Txid) {
+        let mut state = self.state.lock().unwrap();
+        state.changes.insert(txid, true);
+        Self::limit(&mut state);
+    }
+
+    pub fn tx_removed(&self, txid: Txid) {
+        let mut state = self.state.lock().unwrap();
+        state.changes.insert(txid, false);
+        Self::limit(&mut state);
+        state.wake = true;
+        self.condvar.notify_all();
+    }
+
+    fn limit(state: &mut State) {
+        if state.changes.len() > MAX_PENDING_CHANGES {
+            state.changes.clear();
+            state.gap = true;
+        }
+    }
+
+    /// bitcoind doesn't send removals for transactions mined in a block, so
+    /// they're queued here once the block is indexed.
+    pub fn txs_confirmed(&self, txids: impl IntoIterator<Item = Txid>) {
+        let mut state = self.state.lock().unwrap();
+        if state.active {
+            state
+                .changes
+                .extend(txids.into_iter().map(|txid| (txid, false)));
+        }
+    }
+
+    pub fn block_changed(&self) {
+        self.state.lock().unwrap().wake = true;
+        self.condvar.notify_all();
+    }
+
+    /// Takes the pending changes. Returns `None` when they can't be trusted
+    /// and the whole mempool has to be resynced instead.
+    pub fn take(&self) -> Option<MempoolChanges> {
+        let mut state = self.state.lock().unwrap();
+        let changes = std::mem::take(&mut state.changes);
+
+        if !state.active || state.gap {
+            state.gap = false;
+            return None;
+        }
+
+        let mut mempool_changes = MempoolChanges::default();
+        for (txid, added) in changes {
+            if added {
+                mempool_changes.added.push(txid);
+            } else {
+                mempool_changes.removed.push(txid);
+            }
+        }
+
+        Some(mempool_changes)
+    }
+
+    /// Sleeps for up to `timeout`, returning early when a removal, a block
+    /// or a gap is reported.
+    pub fn wait(&self, timeout: Duration) {
+        let state = self.state.lock().unwrap();
+        let (mut state, _) = self
+            .condvar
+            .wait_timeout_while(state, timeout, |state| !state.wake)
+            .unwrap();
+        state.wake = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::hashes::Hash};
+
+    fn txid(n: u8) -> Txid {
+        Txid::from_byte_array([n; 32])
+    }
+
+    #[test]
+    fn resyncs_until_active_and_after_gaps() {
+        let sequence = MempoolSequence::default();
+        sequence.tx_added(txid(1));
+        assert!(sequence.take().is_none());
+
+        sequence.activate();
+        assert!(sequence.take().is_none());
+
+        sequence.tx_added(txid(1));
+        sequence.tx_added(txid(2));
+        sequence.tx_removed(txid(2));
+        sequence.txs_confirmed([txid(3)]);
+
+        let changes = sequence.take().unwrap();
+        assert_eq!(changes.added, vec![txid(1)]);
+        let mut removed = changes.removed;
+        removed.sort();
+        assert_eq!(removed, vec![txid(2), txid(3)]);
+
+        sequence.tx_added(txid(4));
+        sequence.gap();
+        assert!(sequence.take().is_none());
+        assert!(sequence.take().unwrap().added.is_empty());
+    }
+}
@@ -6,9 +6,14 @@ pub use {
 
 mod address;
 mod block_fetcher;
+mod block_files;
+mod block_pipeline;
+mod block_summary;
 mod cache;
 mod index_updater;
 mod mempool;
+mod mempool_projection;
+mod mempool_sequence;
 mod rollback;
 mod rollback_cache;
 mod store_lock;
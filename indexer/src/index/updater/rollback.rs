@@ -2,7 +2,7 @@ use {
     super::rollback_cache::RollbackCache,
     crate::{
         index::{store::Store, Settings, StoreError},
-        models::TransactionStateChange,
+        models::{RuneSupply, RuneSupplyDelta, TransactionStateChange},
     },
     bitcoin::{OutPoint, ScriptBuf, Txid},
     ordinals::RuneId,
@@ -41,6 +41,7 @@ pub(super) struct Rollback<'a> {
     store: &'a Arc<dyn Store + Send + Sync>,
     settings: RollbackSettings,
     cache: RollbackCache<'a>,
+    modified_script_pubkeys: HashSet<ScriptBuf>,
 }
 
 impl<'a> Rollback<'a> {
@@ -54,26 +55,47 @@ impl<'a> Rollback<'a> {
             store,
             settings,
             cache,
+            modified_script_pubkeys: HashSet::new(),
         })
     }
 
-    pub(super) fn revert_transactions(&mut self, txids: &Vec<Txid>) -> Result<()> {
+    /// Reverts `txids` and returns the script pubkeys whose outputs changed,
+    /// none unless addresses are indexed.
+    pub(super) fn revert_transactions(&mut self, txids: &Vec<Txid>) -> Result<HashSet<ScriptBuf>> {
         let txs_state_changes = self
             .store
             .get_txs_state_changes(txids, self.cache.mempool)?;
 
         self.precache_transactions(&txs_state_changes)?;
 
+        let confirming_blocks = if self.cache.mempool {
+            HashMap::new()
+        } else {
+            self.store.get_transaction_confirming_blocks(txids)?
+        };
+
+        let mut supply_blocks = HashSet::new();
+
         for txid in txids.iter().rev() {
             info!("Reverting transaction {}", txid);
             let transaction = txs_state_changes.get(txid);
+            let height = confirming_blocks
+                .get(txid)
+                .and_then(|block_id| block_id.as_ref())
+                .map(|block_id| block_id.height);
             // .ok_or(RollbackError::Store(StoreError::NotFound(format!(
             //     "transaction not found: {}",
             //     txid
             // ))))?;
 
             if let Some(transaction) = transaction {
-                self.revert_transaction(txid, transaction)?;
+                self.revert_transaction(txid, transaction, height)?;
+
+                if let Some(height) = height {
+                    for rune_id in transaction.rune_ids() {
+                        supply_blocks.insert((rune_id, height));
+                    }
+                }
             } else {
                 warn!("Transaction to rollback not found: {}", txid);
                 if self.cache.mempool {
@@ -82,13 +104,23 @@ impl<'a> Rollback<'a> {
             }
         }
 
+        // Confirmed transactions are reverted a whole block at a time, so the
+        // supply recorded for each block can be undone as a unit.
+        for (rune_id, height) in supply_blocks {
+            self.cache.revert_rune_supply_block(rune_id, height)?;
+        }
+
+        if self.cache.mempool {
+            self.release_mempool_spends(&txs_state_changes)?;
+        }
+
         if self.settings.index_addresses {
             self.revert_transactions_script_pubkeys_modifications(&txs_state_changes)?;
         }
 
         self.cache.flush()?;
 
-        Ok(())
+        Ok(std::mem::take(&mut self.modified_script_pubkeys))
     }
 
     fn precache_transactions(
@@ -123,19 +155,37 @@ impl<'a> Rollback<'a> {
         &mut self,
         txid: &Txid,
         transaction: &TransactionStateChange,
+        height: Option<u64>,
     ) -> Result<()> {
         // Make spendable the inputs again.
         for tx_in in transaction.inputs.iter() {
-            self.update_spendable_input(&tx_in, SpentStatus::Unspent)?;
+            self.update_spendable_input(txid, &tx_in, SpentStatus::Unspent)?;
         }
 
         // Remove tx_outs
-        for (vout, _tx_out) in transaction.outputs.iter().enumerate() {
+        let mut pending: HashMap<RuneId, RuneSupply> = HashMap::new();
+        for (vout, tx_out) in transaction.outputs.iter().enumerate() {
             let outpoint = OutPoint {
                 txid: txid.clone(),
                 vout: vout as u32,
             };
             self.cache.add_outpoint_to_delete(outpoint);
+
+            if !self.cache.mempool {
+                for rune_amount in tx_out.runes.iter() {
+                    self.cache.add_rune_utxo_count(rune_amount.rune_id, -1);
+                }
+            } else {
+                for rune_amount in tx_out.runes.iter() {
+                    let supply = pending.entry(rune_amount.rune_id).or_default();
+                    supply.pending = supply.pending.saturating_add(rune_amount.amount);
+                }
+            }
+        }
+
+        for (rune_id, supply) in pending {
+            self.cache
+                .add_rune_supply(rune_id, RuneSupplyDelta::debit(supply));
         }
 
         // Remove etched rune if any.
@@ -169,11 +219,15 @@ impl<'a> Rollback<'a> {
         // Remove mints if any.
         if let Some(mint) = transaction.minted.as_ref() {
             self.decrement_mint(&mint.rune_id)?;
+
+            if let Some(height) = height {
+                self.cache.add_rune_mints(mint.rune_id, height, -1);
+            }
         }
 
         // Remove burned if any.
         for (rune_id, amount) in transaction.burned.iter() {
-            self.update_burn_balance(rune_id, -(amount.n() as i128))?;
+            self.revert_burn_balance(rune_id, amount.n())?;
         }
 
         // Finally remove the transaction.
@@ -182,9 +236,27 @@ impl<'a> Rollback<'a> {
         Ok(())
     }
 
-    fn update_spendable_input(&mut self, outpoint: &OutPoint, spent: SpentStatus) -> Result<()> {
+    fn update_spendable_input(
+        &mut self,
+        txid: &Txid,
+        outpoint: &OutPoint,
+        spent: SpentStatus,
+    ) -> Result<()> {
         match self.cache.get_tx_out(outpoint) {
             Ok(tx_out) => {
+                // A replacement already spends it again.
+                if let SpentStatus::Spent(spender) = &tx_out.spent {
+                    if self.cache.mempool && spender.txid != *txid {
+                        return Ok(());
+                    }
+                }
+
+                if !self.cache.mempool && matches!(tx_out.spent, SpentStatus::Spent(_)) {
+                    for rune_amount in tx_out.runes.iter() {
+                        self.cache.add_rune_utxo_count(rune_amount.rune_id, 1);
+                    }
+                }
+
                 let mut tx_out = tx_out;
                 tx_out.spent = spent;
                 self.cache.set_tx_out(outpoint.clone(), tx_out);
@@ -219,23 +291,20 @@ impl<'a> Rollback<'a> {
         Ok(())
     }
 
-    fn update_burn_balance(&mut self, rune_id: &RuneId, amount: i128) -> Result<()> {
+    fn revert_burn_balance(&mut self, rune_id: &RuneId, amount: u128) -> Result<()> {
         let rune_entry = self.cache.get_rune(rune_id);
 
         if let Some(mut rune_entry) = rune_entry {
             if self.cache.mempool {
-                let result = rune_entry
+                rune_entry.pending_burns = rune_entry
                     .pending_burns
-                    .checked_add_signed(amount)
+                    .checked_sub(amount)
                     .ok_or(RollbackError::Overflow("burn".to_string()))?;
-                rune_entry.pending_burns = result;
             } else {
-                let result = rune_entry
-                    .pending_burns
-                    .checked_add_signed(amount)
+                rune_entry.burned = rune_entry
+                    .burned
+                    .checked_sub(amount)
                     .ok_or(RollbackError::Overflow("burn".to_string()))?;
-
-                rune_entry.burned = result;
             }
 
             self.cache.set_rune(rune_id.clone(), rune_entry);
@@ -244,6 +313,32 @@ impl<'a> Rollback<'a> {
         Ok(())
     }
 
+    /// Forgets the mempool spends of the reverted transactions. Outpoints that
+    /// a replacement spends again were already claimed by it and are kept.
+    fn release_mempool_spends(
+        &mut self,
+        tx_to_state_changes: &HashMap<Txid, TransactionStateChange>,
+    ) -> Result<()> {
+        let prev_outpoints = tx_to_state_changes
+            .values()
+            .filter(|tx| !tx.is_coinbase)
+            .flat_map(|tx| tx.inputs.clone())
+            .collect::<Vec<_>>();
+
+        let spenders = self.store.get_spent_outpoints_in_mempool(&prev_outpoints)?;
+        let released = prev_outpoints
+            .into_iter()
+            .filter(|outpoint| match spenders.get(outpoint) {
+                Some(Some(spender)) => tx_to_state_changes.contains_key(&spender.txid),
+                _ => true,
+            })
+            .collect();
+
+        self.cache.add_prev_outpoint_to_delete(&released);
+
+        Ok(())
+    }
+
     fn revert_transactions_script_pubkeys_modifications(
         &mut self,
         tx_to_state_changes: &HashMap<Txid, TransactionStateChange>,
@@ -284,16 +379,26 @@ impl<'a> Rollback<'a> {
             }
         }
 
-        let prev_outpoints = tx_to_state_changes
-            .values()
-            .filter(|tx| !tx.is_coinbase)
-            .flat_map(|tx| tx.inputs.clone())
-            .collect::<Vec<_>>();
-
+        // Spent outpoints in the mempool are released by `release_mempool_spends`,
+        // their scripts still changed.
         if self.cache.mempool {
-            // Remove spent outpoints.
-            self.cache.add_prev_outpoint_to_delete(&prev_outpoints);
+            let prev_outpoints = tx_to_state_changes
+                .values()
+                .filter(|tx| !tx.is_coinbase)
+                .flat_map(|tx| tx.inputs.clone())
+                .collect::<Vec<_>>();
+
+            let released =
+                self.store
+                    .get_outpoints_to_script_pubkey(&prev_outpoints, None, true)?;
+            self.modified_script_pubkeys.extend(released.into_values());
         } else {
+            let prev_outpoints = tx_to_state_changes
+                .values()
+                .filter(|tx| !tx.is_coinbase)
+                .flat_map(|tx| tx.inputs.clone())
+                .collect::<Vec<_>>();
+
             let outpoints_to_script_pubkeys: HashMap<OutPoint, ScriptBuf> = self
                 .cache
                 .get_outpoints_to_script_pubkey(&prev_outpoints, false)?;
@@ -314,6 +419,8 @@ impl<'a> Rollback<'a> {
             }
         }
 
+        self.modified_script_pubkeys
+            .extend(script_pubkey_entries.keys().cloned());
         self.cache.set_script_pubkey_entries(script_pubkey_entries);
 
         Ok(())
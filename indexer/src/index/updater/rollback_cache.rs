@@ -1,7 +1,7 @@
 use {
     crate::{
         index::{store::Store, StoreError},
-        models::{BatchRollback, RuneEntry},
+        models::{BatchRollback, RuneEntry, RuneSupplyDelta},
     },
     bitcoin::{OutPoint, ScriptBuf, Txid},
     ordinals::{Rune, RuneId},
@@ -61,6 +61,34 @@ impl<'a> RollbackCache<'a> {
         self.update.txouts.insert(outpoint, tx_out);
     }
 
+    pub fn add_rune_utxo_count(&mut self, rune_id: RuneId, delta: i64) {
+        *self.update.rune_utxo_counts.entry(rune_id).or_default() += delta;
+    }
+
+    pub fn add_rune_mints(&mut self, rune_id: RuneId, height: u64, delta: i64) {
+        *self.update.rune_mints.entry((rune_id, height)).or_default() += delta;
+    }
+
+    pub fn add_rune_supply(&mut self, rune_id: RuneId, delta: RuneSupplyDelta) {
+        *self.update.rune_supply.entry(rune_id).or_default() += delta;
+    }
+
+    /// Undoes everything recorded for the rune at `height`, taking it out of
+    /// the running totals as well.
+    pub fn revert_rune_supply_block(&mut self, rune_id: RuneId, height: u64) -> Result<()> {
+        for (height, supply) in self.db.get_rune_supply_blocks(&rune_id, height, height)? {
+            let delta = RuneSupplyDelta::debit(supply);
+            *self.update.rune_supply.entry(rune_id).or_default() += delta;
+            *self
+                .update
+                .rune_supply_blocks
+                .entry((rune_id, height))
+                .or_default() += delta;
+        }
+
+        Ok(())
+    }
+
     pub fn decrement_runes_count(&mut self) {
         self.update.runes_count -= 1;
     }
@@ -2,12 +2,16 @@ use {
     super::{address::AddressUpdater, cache::UpdaterCache},
     crate::{
         index::{inscription::index_rune_icon, Settings, StoreError},
-        models::{BlockId, RuneEntry, TransactionStateChange},
+        models::{BlockId, RuneEntry, RuneSupply, RuneSupplyDelta, TransactionStateChange},
+    },
+    bitcoin::{
+        opcodes::{Class, ClassifyContext, Opcode},
+        OutPoint, Script, Transaction, Txid,
     },
-    bitcoin::{OutPoint, Transaction, Txid},
     ordinals::{Artifact, Etching, Rune, RuneId, Runestone, SpacedRune},
+    std::collections::HashMap,
     thiserror::Error,
-    titan_types::{Event, MempoolEntry, SpenderReference, SpentStatus, TxOutEntry},
+    titan_types::{Event, Location, MempoolEntry, SpenderReference, SpentStatus, TxOutEntry},
     tokio::sync::mpsc::error::SendError,
 };
 
@@ -23,6 +27,19 @@ pub enum TransactionUpdaterError {
 
 type Result<T> = std::result::Result<T, TransactionUpdaterError>;
 
+const MINT_PROGRESS_PERCENTS: [u128; 4] = [25, 50, 75, 100];
+
+/// True when no input can ever spend `script_pubkey` because its first opcode
+/// always fails.
+fn is_provably_unspendable(script_pubkey: &Script) -> bool {
+    script_pubkey.as_bytes().first().is_some_and(|byte| {
+        matches!(
+            Opcode::from(*byte).classify(ClassifyContext::Legacy),
+            Class::ReturnOp | Class::IllegalOp
+        )
+    })
+}
+
 #[derive(Debug)]
 pub(super) struct TransactionUpdaterSettings {
     pub(super) index_addresses: bool,
@@ -124,6 +141,12 @@ impl<'a> TransactionUpdater<'a> {
 
             cache.set_tx_out(outpoint, output.clone());
 
+            if !cache.settings.mempool {
+                for rune_amount in output.runes.iter() {
+                    cache.add_rune_utxo_count(rune_amount.rune_id, 1);
+                }
+            }
+
             self.transfer_rune(
                 cache,
                 block_id.as_ref().map(|id| id.height),
@@ -133,6 +156,13 @@ impl<'a> TransactionUpdater<'a> {
             );
         }
 
+        self.update_rune_supply(
+            cache,
+            block_id.as_ref().map(|id| id.height),
+            transaction,
+            transaction_state_change,
+        )?;
+
         // Save transaction state change
         cache.set_tx_state_changes(txid, transaction_state_change.clone());
 
@@ -152,6 +182,27 @@ impl<'a> TransactionUpdater<'a> {
             cache.set_transaction_confirming_block(txid, block_id.unwrap());
         }
 
+        // Kept regardless of index_addresses, replacement tracking finds
+        // double spends through it.
+        if cache.settings.mempool && !transaction.is_coinbase() {
+            cache.batch_set_spent_outpoints_in_mempool(
+                transaction
+                    .input
+                    .iter()
+                    .enumerate()
+                    .map(|(vin, input)| {
+                        (
+                            input.previous_output,
+                            SpenderReference {
+                                txid,
+                                vin: vin as u32,
+                            },
+                        )
+                    })
+                    .collect(),
+            );
+        }
+
         if self.settings.index_addresses {
             self.update_script_pubkeys(txid, transaction);
         }
@@ -165,29 +216,7 @@ impl<'a> TransactionUpdater<'a> {
 
     fn update_script_pubkeys(&mut self, txid: Txid, transaction: &Transaction) -> () {
         if let Some(addr_updater) = self.address_updater.as_mut() {
-            // skip coinbase inputs
-            if !transaction.is_coinbase() {
-                for (vin, input) in transaction.input.iter().enumerate() {
-                    // Add the spent outpoint to the address_updater
-                    addr_updater.add_spent_outpoint(
-                        input.previous_output,
-                        SpenderReference {
-                            txid,
-                            vin: vin as u32,
-                        },
-                    );
-                }
-            }
-
-            // Add new outputs
-            for (vout, txout) in transaction.output.iter().enumerate() {
-                let outpoint = OutPoint {
-                    txid,
-                    vout: vout as u32,
-                };
-
-                addr_updater.add_new_outpoint(outpoint, txout.script_pubkey.clone());
-            }
+            addr_updater.add_transaction(txid, transaction);
         }
     }
 
@@ -199,6 +228,12 @@ impl<'a> TransactionUpdater<'a> {
     ) -> Result<()> {
         match cache.get_tx_out(outpoint) {
             Ok(tx_out) => {
+                if !cache.settings.mempool && matches!(tx_out.spent, SpentStatus::Unspent) {
+                    for rune_amount in tx_out.runes.iter() {
+                        cache.add_rune_utxo_count(rune_amount.rune_id, -1);
+                    }
+                }
+
                 let mut tx_out = tx_out;
                 tx_out.spent = spent;
                 cache.set_tx_out(outpoint.clone(), tx_out);
@@ -222,7 +257,7 @@ impl<'a> TransactionUpdater<'a> {
         rune_id: &RuneId,
         amount: u128,
     ) -> Result<()> {
-        self.update_burn_balance(cache, rune_id, amount as i128)?;
+        self.update_burn_balance(cache, rune_id, amount)?;
 
         cache.add_event(Event::RuneBurned {
             location: height.into(),
@@ -251,6 +286,110 @@ impl<'a> TransactionUpdater<'a> {
             rune_id: *rune_id,
         });
 
+        // Mint activity and progress only count confirmed mints.
+        if let Some(height) = height {
+            cache.add_rune_mints(*rune_id, height, 1);
+            self.mint_progress(cache, height, txid, rune_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn mint_progress(
+        &mut self,
+        cache: &mut UpdaterCache,
+        height: u64,
+        txid: Txid,
+        rune_id: &RuneId,
+    ) -> Result<()> {
+        let rune_entry = cache.get_rune(rune_id)?;
+        let cap = rune_entry
+            .terms
+            .and_then(|terms| terms.cap)
+            .unwrap_or_default();
+
+        if cap == 0 {
+            return Ok(());
+        }
+
+        let before = rune_entry.mints.saturating_sub(1).saturating_mul(100) / cap;
+        let after = rune_entry.mints.saturating_mul(100) / cap;
+
+        for percent in MINT_PROGRESS_PERCENTS {
+            if before < percent && after >= percent {
+                cache.add_event(Event::RuneMintProgress {
+                    location: Location::block(height),
+                    rune_id: *rune_id,
+                    txid,
+                    percent: percent as u8,
+                    mints: rune_entry.mints,
+                    cap,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_rune_supply(
+        &mut self,
+        cache: &mut UpdaterCache,
+        height: Option<u64>,
+        transaction: &Transaction,
+        transaction_state_change: &TransactionStateChange,
+    ) -> Result<()> {
+        let mut supplies: HashMap<RuneId, RuneSupply> = HashMap::new();
+        let mut credit = |rune_id: RuneId, field: fn(&mut RuneSupply) -> &mut u128, amount| {
+            let value = field(supplies.entry(rune_id).or_default());
+            *value = value.saturating_add(amount);
+        };
+
+        if cache.settings.mempool {
+            // Everything an unconfirmed transaction moves is pending until it confirms.
+            for output in transaction_state_change.outputs.iter() {
+                for rune_amount in output.runes.iter() {
+                    credit(
+                        rune_amount.rune_id,
+                        |supply| &mut supply.pending,
+                        rune_amount.amount,
+                    );
+                }
+            }
+        } else {
+            if let Some((id, _)) = transaction_state_change.etched {
+                let rune_entry = cache.get_rune(&id)?;
+                credit(id, |supply| &mut supply.issued, rune_entry.premine);
+            }
+
+            if let Some(minted) = transaction_state_change.minted.as_ref() {
+                credit(minted.rune_id, |supply| &mut supply.issued, minted.amount);
+            }
+
+            for (rune_id, amount) in transaction_state_change.burned.iter() {
+                credit(*rune_id, |supply| &mut supply.burned, amount.n());
+            }
+
+            // OP_RETURN outputs are already counted as burned.
+            for (vout, output) in transaction_state_change.outputs.iter().enumerate() {
+                let script_pubkey = &transaction.output[vout].script_pubkey;
+                if script_pubkey.is_op_return() || !is_provably_unspendable(script_pubkey) {
+                    continue;
+                }
+
+                for rune_amount in output.runes.iter() {
+                    credit(
+                        rune_amount.rune_id,
+                        |supply| &mut supply.unspendable,
+                        rune_amount.amount,
+                    );
+                }
+            }
+        }
+
+        for (rune_id, supply) in supplies {
+            cache.add_rune_supply(rune_id, height, RuneSupplyDelta::credit(supply));
+        }
+
         Ok(())
     }
 
@@ -258,23 +397,22 @@ impl<'a> TransactionUpdater<'a> {
         &mut self,
         cache: &mut UpdaterCache,
         rune_id: &RuneId,
-        amount: i128,
+        amount: u128,
     ) -> Result<()> {
         let mut rune_entry = cache.get_rune(rune_id)?;
 
+        // Confirmed burns add up, pending ones are reverted and replayed as
+        // the mempool changes.
         if cache.settings.mempool {
-            let result = rune_entry
+            rune_entry.pending_burns = rune_entry
                 .pending_burns
-                .checked_add_signed(amount)
+                .checked_add(amount)
                 .ok_or(TransactionUpdaterError::Overflow("burn".to_string()))?;
-            rune_entry.pending_burns = result;
         } else {
-            let result = rune_entry
-                .pending_burns
-                .checked_add_signed(amount)
+            rune_entry.burned = rune_entry
+                .burned
+                .checked_add(amount)
                 .ok_or(TransactionUpdaterError::Overflow("burn".to_string()))?;
-
-            rune_entry.burned = result;
         }
 
         cache.set_rune(*rune_id, rune_entry);
@@ -416,3 +554,283 @@ impl<'a> TransactionUpdater<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            db::RocksDB,
+            index::updater::{cache::UpdaterCacheSettings, store_lock::StoreWithLock},
+            models::Lot,
+        },
+        bitcoin::{
+            absolute::LockTime, hashes::Hash, transaction::Version, Amount, BlockHash, ScriptBuf,
+            TxOut,
+        },
+        std::sync::Arc,
+        titan_types::RuneAmount,
+    };
+
+    fn save(
+        db: &Arc<StoreWithLock>,
+        block_id: Option<BlockId>,
+        transaction: &Transaction,
+        transaction_state_change: &TransactionStateChange,
+    ) {
+        let mut cache = UpdaterCache::new(
+            db.clone(),
+            UpdaterCacheSettings {
+                max_recoverable_reorg_depth: 10,
+                mempool: block_id.is_none(),
+            },
+        )
+        .unwrap();
+
+        TransactionUpdater::new(
+            TransactionUpdaterSettings {
+                index_addresses: false,
+                index_bitcoin_transactions: false,
+            },
+            None,
+        )
+        .unwrap()
+        .save(
+            &mut cache,
+            0,
+            block_id,
+            transaction.compute_txid(),
+            transaction,
+            transaction_state_change,
+            None,
+        )
+        .unwrap();
+
+        cache.flush().unwrap();
+    }
+
+    #[test]
+    fn supply_of_full_range_premine() {
+        let (_dir, rocks) = RocksDB::open_temp();
+        let rocks = Arc::new(rocks);
+        let db = Arc::new(StoreWithLock::new(rocks.clone()));
+
+        let rune = Rune(1_000_000_000_000_000_000);
+        let rune_id = RuneId::new(840_000, 1).unwrap();
+        let output = |runes| TxOutEntry {
+            runes,
+            risky_runes: Vec::new(),
+            value: 546,
+            spent: SpentStatus::Unspent,
+        };
+        let all = || {
+            output(vec![RuneAmount {
+                rune_id,
+                amount: u128::MAX,
+            }])
+        };
+
+        let runestone = Runestone {
+            etching: Some(Etching {
+                premine: Some(u128::MAX),
+                rune: Some(rune),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let etching = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: Vec::new(),
+            output: vec![
+                TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: runestone.encipher(),
+                },
+                TxOut {
+                    value: Amount::from_sat(546),
+                    script_pubkey: ScriptBuf::new(),
+                },
+            ],
+        };
+        let etched = TransactionStateChange {
+            inputs: Vec::new(),
+            outputs: vec![output(Vec::new()), all()],
+            etched: Some((rune_id, rune)),
+            minted: None,
+            burned: HashMap::new(),
+            is_coinbase: false,
+        };
+
+        let block_id = BlockId {
+            hash: BlockHash::all_zeros(),
+            height: 840_000,
+        };
+        save(&db, Some(block_id.clone()), &etching, &etched);
+
+        let supply = rocks.get_rune_supply(&rune_id).unwrap();
+        assert_eq!(supply.issued, u128::MAX);
+        assert_eq!(rocks.get_rune(&rune_id).unwrap().premine, u128::MAX);
+
+        // Moving all of it in the mempool and burning all of it in a block.
+        let transfer = Transaction {
+            output: vec![etching.output[1].clone()],
+            ..etching.clone()
+        };
+        let moved = TransactionStateChange {
+            outputs: vec![all()],
+            etched: None,
+            ..etched.clone()
+        };
+        save(&db, None, &transfer, &moved);
+        assert_eq!(rocks.get_rune_supply(&rune_id).unwrap().pending, u128::MAX);
+
+        let burn = Transaction {
+            lock_time: LockTime::from_consensus(1),
+            ..transfer
+        };
+        let burned = TransactionStateChange {
+            outputs: vec![output(Vec::new())],
+            etched: None,
+            burned: HashMap::from([(rune_id, Lot(u128::MAX))]),
+            ..etched
+        };
+        save(
+            &db,
+            Some(BlockId {
+                height: 840_001,
+                ..block_id
+            }),
+            &burn,
+            &burned,
+        );
+
+        let supply = rocks.get_rune_supply(&rune_id).unwrap();
+        assert_eq!(supply.issued, u128::MAX);
+        assert_eq!(supply.burned, u128::MAX);
+        assert_eq!(rocks.get_rune(&rune_id).unwrap().burned, u128::MAX);
+        assert_eq!(
+            rocks.get_rune_supply_blocks(&rune_id, 0, 840_001).unwrap(),
+            vec![
+                (
+                    840_000,
+                    RuneSupply {
+                        issued: u128::MAX,
+                        ..Default::default()
+                    }
+                ),
+                (
+                    840_001,
+                    RuneSupply {
+                        burned: u128::MAX,
+                        ..Default::default()
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            rocks
+                .get_rune_supply_totals(&rune_id, 840_001, 840_001)
+                .unwrap(),
+            vec![(
+                840_001,
+                RuneSupply {
+                    issued: u128::MAX,
+                    burned: u128::MAX,
+                    ..Default::default()
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn confirmed_burns_add_up() {
+        let (_dir, rocks) = RocksDB::open_temp();
+        let rocks = Arc::new(rocks);
+        let db = Arc::new(StoreWithLock::new(rocks.clone()));
+
+        let rune = Rune(1_000_000_000_000_000_000);
+        let rune_id = RuneId::new(840_000, 1).unwrap();
+        let outputs = |amount| {
+            let output = |runes| TxOutEntry {
+                runes,
+                risky_runes: Vec::new(),
+                value: 546,
+                spent: SpentStatus::Unspent,
+            };
+
+            vec![
+                output(Vec::new()),
+                output(vec![RuneAmount { rune_id, amount }]),
+            ]
+        };
+
+        let runestone = Runestone {
+            etching: Some(Etching {
+                premine: Some(1000),
+                rune: Some(rune),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let etching = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: Vec::new(),
+            output: vec![
+                TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: runestone.encipher(),
+                },
+                TxOut {
+                    value: Amount::from_sat(546),
+                    script_pubkey: ScriptBuf::new(),
+                },
+            ],
+        };
+        let etched = TransactionStateChange {
+            inputs: Vec::new(),
+            outputs: outputs(1000),
+            etched: Some((rune_id, rune)),
+            minted: None,
+            burned: HashMap::new(),
+            is_coinbase: false,
+        };
+        let block_id = |height| {
+            Some(BlockId {
+                hash: BlockHash::all_zeros(),
+                height,
+            })
+        };
+        save(&db, block_id(840_000), &etching, &etched);
+
+        let burn = |lock_time, amount| {
+            (
+                Transaction {
+                    lock_time: LockTime::from_consensus(lock_time),
+                    ..etching.clone()
+                },
+                TransactionStateChange {
+                    outputs: outputs(1000 - amount),
+                    etched: None,
+                    burned: HashMap::from([(rune_id, Lot(amount))]),
+                    ..etched.clone()
+                },
+            )
+        };
+
+        let (transaction, state_change) = burn(1, 100);
+        save(&db, block_id(840_001), &transaction, &state_change);
+
+        // A pending burn is kept apart and doesn't replace the total.
+        let (transaction, state_change) = burn(2, 50);
+        save(&db, None, &transaction, &state_change);
+        let rune_entry = rocks.get_rune(&rune_id).unwrap();
+        assert_eq!(rune_entry.burned, 100);
+        assert_eq!(rune_entry.pending_burns, 50);
+
+        let (transaction, state_change) = burn(3, 200);
+        save(&db, block_id(840_002), &transaction, &state_change);
+        assert_eq!(rocks.get_rune(&rune_id).unwrap().burned, 300);
+    }
+}
@@ -1,13 +1,14 @@
 use {
+    super::sequence::{message_number, SequenceEvent, SequenceTracker},
     crate::index::updater::Updater,
     async_zmq::{
         subscribe, Error as AsyncZmqError, Multipart, RecvError, SocketError, StreamExt,
         SubscribeError,
     },
     bitcoin::{consensus::encode, Transaction},
-    std::{sync::Arc, thread},
+    std::sync::Arc,
     tokio::sync::watch,
-    tracing::{debug, error, info},
+    tracing::{debug, error, info, warn},
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -25,19 +26,37 @@ pub enum ZmqError {
     SubscribeError(#[from] SubscribeError),
 }
 
+const TOPICS: [&str; 3] = ["rawtx", "sequence", "hashblock"];
+
 /// Asynchronous ZMQ listener
 pub async fn zmq_listener(
     updater: Arc<Updater>,
     endpoint: String,
     shutdown_rx: &mut watch::Receiver<bool>,
+) -> Result<(), ZmqError> {
+    let result = listen(&updater, endpoint, shutdown_rx).await;
+
+    // Without the listener, the mempool goes back to being polled.
+    updater.mempool_sequence().deactivate();
+    result
+}
+
+async fn listen(
+    updater: &Arc<Updater>,
+    endpoint: String,
+    shutdown_rx: &mut watch::Receiver<bool>,
 ) -> Result<(), ZmqError> {
     // 1. Create and connect an async subscriber socket
     info!("Connecting to ZMQ at {endpoint}");
     let mut sub = subscribe(&endpoint)?.connect()?;
 
-    // 2. Subscribe to "rawtx"
-    sub.set_subscribe("rawtx")?;
-    debug!("Subscribed to ZMQ topic: rawtx");
+    // 2. Subscribe to the topics
+    for topic in TOPICS {
+        sub.set_subscribe(topic)?;
+        debug!("Subscribed to ZMQ topic: {}", topic);
+    }
+
+    let mut tracker = SequenceTracker::default();
 
     // 3. Main loop
     loop {
@@ -63,7 +82,7 @@ pub async fn zmq_listener(
                         };
 
                         // Handle the received frames
-                        if let Err(e) = process_zmq_message(&updater, frames).await {
+                        if let Err(e) = process_zmq_message(updater, &mut tracker, frames).await {
                             error!("Failed to process message: {:?}", e);
                         }
                     },
@@ -81,9 +100,10 @@ pub async fn zmq_listener(
     Ok(())
 }
 
-/// Process the frames from a rawtx message
+/// Process the frames of a message
 async fn process_zmq_message(
     updater: &Arc<Updater>,
+    tracker: &mut SequenceTracker,
     frames: Multipart,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if frames.len() < 2 {
@@ -95,20 +115,57 @@ async fn process_zmq_message(
     let topic = std::str::from_utf8(&frames[0])?;
     let payload = &frames[1];
 
+    // bitcoind numbers the messages of each topic. Missed mempool updates
+    // mean the mempool has to be resynced, other topics can be caught up
+    // with by polling.
+    if let Some(number) = frames.get(2).and_then(|frame| message_number(frame)) {
+        if !tracker.check(topic, number) {
+            if topic == "sequence" {
+                updater.mempool_sequence().gap();
+            } else {
+                warn!("Missed ZMQ {} notifications", topic);
+                updater.mempool_sequence().block_changed();
+            }
+        }
+    }
+
     match topic {
         "rawtx" => {
             debug!("Received rawtx, size={} bytes", payload.len());
             handle_raw_tx(updater, payload)?;
         }
+        "sequence" => handle_sequence(updater, payload),
+        "hashblock" => {
+            debug!("Received hashblock");
+            updater.mempool_sequence().block_changed();
+        }
         other => {
             error!("Unknown ZMQ topic: {}", other);
         }
     }
 
-    // If there are extra frames (like sequence numbers), you can handle them here or ignore.
     Ok(())
 }
 
+fn handle_sequence(updater: &Arc<Updater>, body: &[u8]) {
+    let Some(event) = SequenceEvent::parse(body) else {
+        error!("Invalid ZMQ sequence message, size={} bytes", body.len());
+        return;
+    };
+
+    debug!("Received sequence {:?}", event);
+
+    let mempool_sequence = updater.mempool_sequence();
+    mempool_sequence.activate();
+    match event {
+        SequenceEvent::TxAdded(txid) => mempool_sequence.tx_added(txid),
+        SequenceEvent::TxRemoved(txid) => mempool_sequence.tx_removed(txid),
+        SequenceEvent::BlockConnected(_) | SequenceEvent::BlockDisconnected(_) => {
+            mempool_sequence.block_changed()
+        }
+    }
+}
+
 /// Decode and store a raw transaction in RocksDB (example)
 fn handle_raw_tx(
     updater: &Arc<Updater>,
@@ -1,5 +1,6 @@
 mod listener;
 mod manager;
+mod sequence;
 
 pub use listener::zmq_listener;
 pub use manager::ZmqManager;
@@ -0,0 +1,102 @@
+use {
+    bitcoin::{hashes::Hash, BlockHash, Txid},
+    std::collections::HashMap,
+};
+
+/// A message of bitcoind's `sequence` topic.
+#[derive(Debug, PartialEq)]
+pub enum SequenceEvent {
+    BlockConnected(BlockHash),
+    BlockDisconnected(BlockHash),
+    TxAdded(Txid),
+    TxRemoved(Txid),
+}
+
+impl SequenceEvent {
+    /// Parses a message body: a hash in RPC byte order, a one-byte label and,
+    /// for mempool events, the 8-byte mempool sequence number.
+    pub fn parse(body: &[u8]) -> Option<Self> {
+        if body.len() < 33 {
+            return None;
+        }
+
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&body[..32]);
+        hash.reverse();
+
+        match (body[32], body.len()) {
+            (b'C', 33) => Some(Self::BlockConnected(BlockHash::from_byte_array(hash))),
+            (b'D', 33) => Some(Self::BlockDisconnected(BlockHash::from_byte_array(hash))),
+            (b'A', 41) => Some(Self::TxAdded(Txid::from_byte_array(hash))),
+            (b'R', 41) => Some(Self::TxRemoved(Txid::from_byte_array(hash))),
+            _ => None,
+        }
+    }
+}
+
+/// Tracks the message number bitcoind appends to every message, per topic,
+/// to notice dropped messages.
+#[derive(Debug, Default)]
+pub struct SequenceTracker {
+    last: HashMap<String, u32>,
+}
+
+impl SequenceTracker {
+    /// Records `number` for `topic`. Returns false if messages were skipped
+    /// since the previous one.
+    pub fn check(&mut self, topic: &str, number: u32) -> bool {
+        match self.last.insert(topic.to_string(), number) {
+            Some(last) => last.wrapping_add(1) == number,
+            None => true,
+        }
+    }
+}
+
+/// Reads the little-endian message number frame.
+pub fn message_number(frame: &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(frame.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sequence_messages() {
+        let mut body = vec![0u8; 32];
+        body[0] = 1;
+        body.push(b'A');
+        body.extend(7u64.to_le_bytes());
+
+        let mut hash = [0u8; 32];
+        hash[31] = 1;
+        assert_eq!(
+            SequenceEvent::parse(&body),
+            Some(SequenceEvent::TxAdded(Txid::from_byte_array(hash)))
+        );
+
+        body[32] = b'C';
+        assert_eq!(SequenceEvent::parse(&body), None);
+        body.truncate(33);
+        assert_eq!(
+            SequenceEvent::parse(&body),
+            Some(SequenceEvent::BlockConnected(BlockHash::from_byte_array(
+                hash
+            )))
+        );
+    }
+
+    #[test]
+    fn detects_gaps() {
+        let mut tracker = SequenceTracker::default();
+        assert!(tracker.check("sequence", 5));
+        assert!(tracker.check("sequence", 6));
+        assert!(tracker.check("rawtx", 0));
+        assert!(!tracker.check("sequence", 8));
+        assert!(tracker.check("sequence", 9));
+        assert!(tracker.check("rawtx", 1));
+
+        assert_eq!(message_number(&3u32.to_le_bytes()), Some(3));
+        assert_eq!(message_number(&[1, 2]), None);
+    }
+}
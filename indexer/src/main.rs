@@ -3,7 +3,7 @@ use bitcoin_rpc::{validate_rpc_connection, RpcClientPool, RpcClientProvider};
 use clap::Parser;
 use db::RocksDB;
 use index::{Index, Settings};
-use options::Options;
+use options::{Command, Options};
 use server::{Server, ServerConfig};
 use std::{io, panic, sync::Arc};
 use subscription::{
@@ -19,10 +19,12 @@ use tracing::{error, info};
 mod api;
 mod bitcoin_rpc;
 mod db;
+mod electrum;
 mod index;
 mod models;
 mod options;
 mod server;
+mod snapshot;
 mod subscription;
 mod util;
 
@@ -39,6 +41,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 3. Prepare and validate configurations
     let settings = setup_settings(&options)?;
     let server_config = setup_server_config(&options)?;
+
+    if options.enable_electrum && !settings.index_addresses {
+        return Err("--enable-electrum requires --index-addresses".into());
+    }
+
+    if let Some(Command::Snapshot(command)) = options.command.clone() {
+        let db_arc = open_rocks_db(&settings)?;
+        snapshot::run(command, &settings, &db_arc)?;
+        db_arc.flush()?;
+        return Ok(());
+    }
+
     validate_rpc(&settings)?;
 
     // 4. Open RocksDB
@@ -76,7 +90,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 7. Spawn background threads (indexer, ZMQ listener, etc.)
     let index_handle = spawn_background_threads(index.clone(), options.enable_zmq_listener).await;
 
-    // 8. Start the HTTP server
+    // 8. Start the Electrum server
+    let electrum_handle = spawn_electrum_server(
+        &options,
+        index.clone(),
+        bitcoin_rpc_pool.clone(),
+        spawn_subscription_result.as_ref(),
+    );
+
+    // 9. Start the HTTP server
     let handle = Handle::new();
     let server = Server;
     let http_server_jh = server.start(
@@ -88,13 +110,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         handle.clone(),
     )?;
 
-    // 9. Wait for SIGINT (Ctrl-C) or SIGTERM
+    // 10. Wait for SIGINT (Ctrl-C) or SIGTERM
     wait_for_signals().await;
 
-    // 10. Graceful shutdown (async)
+    // 11. Graceful shutdown (async)
     graceful_shutdown(
         index,
         spawn_subscription_result,
+        electrum_handle,
         db_arc,
         &handle,
         index_handle,
@@ -178,6 +201,30 @@ async fn spawn_background_threads(
     index_handle
 }
 
+/// Spawn the Electrum server if it's enabled. It stops with the subscription tasks.
+fn spawn_electrum_server(
+    options: &Options,
+    index: Arc<Index>,
+    bitcoin_rpc_pool: RpcClientPool,
+    spawn_subscription_result: Option<&SubscriptionSpawnResult>,
+) -> Option<task::JoinHandle<()>> {
+    let spawn_subscription_result = spawn_subscription_result?;
+    let manager = spawn_subscription_result
+        .electrum_subscription_manager
+        .clone()?;
+    let shutdown_rx = spawn_subscription_result.shutdown_tx.subscribe();
+    let address = options.electrum_address.clone();
+
+    Some(tokio::spawn(async move {
+        if let Err(e) =
+            electrum::run_electrum_server(&address, index, bitcoin_rpc_pool, manager, shutdown_rx)
+                .await
+        {
+            error!("Electrum server error: {:?}", e);
+        }
+    }))
+}
+
 /// Block until either SIGINT or SIGTERM is received
 async fn wait_for_signals() {
     use tokio::select;
@@ -197,16 +244,23 @@ async fn wait_for_signals() {
 async fn graceful_shutdown(
     index: Arc<Index>,
     spawn_subscription_result: Option<SubscriptionSpawnResult>,
+    electrum_handle: Option<task::JoinHandle<()>>,
     db_arc: Arc<RocksDB>,
     handle: &Handle,
     index_handle: std::thread::JoinHandle<()>,
     http_server_jh: task::JoinHandle<io::Result<()>>,
 ) {
-    // 1) Signal the subscription tasks to stop
+    // 1) Signal the subscription tasks and the Electrum server to stop
     if let Some(result) = spawn_subscription_result {
         shutdown_and_wait_subscription_tasks(result).await;
     }
 
+    if let Some(electrum_handle) = electrum_handle {
+        if let Err(e) = electrum_handle.await {
+            error!("Electrum server task join error: {:?}", e);
+        }
+    }
+
     // 2) Tell the Index to shut down
     index.shutdown();
 
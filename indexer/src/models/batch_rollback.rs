@@ -1,5 +1,5 @@
 use {
-    super::RuneEntry,
+    super::{RuneEntry, RuneSupplyDelta},
     bitcoin::{OutPoint, ScriptBuf, Txid},
     ordinals::{Rune, RuneId},
     std::{collections::HashMap, fmt::Display},
@@ -11,6 +11,10 @@ pub struct BatchRollback {
 
     pub rune_entry: HashMap<RuneId, RuneEntry>,
     pub txouts: HashMap<OutPoint, TxOutEntry>,
+    pub rune_utxo_counts: HashMap<RuneId, i64>,
+    pub rune_mints: HashMap<(RuneId, u64), i64>,
+    pub rune_supply: HashMap<RuneId, RuneSupplyDelta>,
+    pub rune_supply_blocks: HashMap<(RuneId, u64), RuneSupplyDelta>,
     pub script_pubkey_entry: HashMap<ScriptBuf, (Vec<OutPoint>, Vec<OutPoint>)>,
 
     pub outpoints_to_delete: Vec<OutPoint>,
@@ -29,6 +33,10 @@ impl BatchRollback {
             runes_count,
             rune_entry: HashMap::new(),
             txouts: HashMap::new(),
+            rune_utxo_counts: HashMap::new(),
+            rune_mints: HashMap::new(),
+            rune_supply: HashMap::new(),
+            rune_supply_blocks: HashMap::new(),
             script_pubkey_entry: HashMap::new(),
             outpoints_to_delete: Vec::new(),
             prev_outpoints_to_delete: Vec::new(),
@@ -47,13 +55,17 @@ impl Display for BatchRollback {
         write!(
             f,
             "BatchRollback: \
-             counts: [runes: {}, txouts: {}, script_pubkeys: {}]
+             counts: [runes: {}, txouts: {}, rune_utxo_counts: {}, rune_mints: {}, rune_supply: {}/{}, script_pubkeys: {}]
              outpoints_to_delete: {}, prev_outpoints_to_delete: {}, runes_to_delete: {}, \
              runes_ids_to_delete: {}, rune_numbers_to_delete: {}, inscriptions_to_delete: {}, \
              delete_all_rune_transactions: {}, txs_to_delete: {}
              ",
             self.runes_count,
             self.txouts.len(),
+            self.rune_utxo_counts.len(),
+            self.rune_mints.len(),
+            self.rune_supply.len(),
+            self.rune_supply_blocks.len(),
             self.script_pubkey_entry.len(),
             self.outpoints_to_delete.len(),
             self.prev_outpoints_to_delete.len(),
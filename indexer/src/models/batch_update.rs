@@ -1,12 +1,15 @@
 use {
-    super::{BlockId, Inscription, RuneEntry, TransactionStateChange},
+    super::{BlockId, Inscription, RuneEntry, RuneSupplyDelta, TransactionStateChange},
     bitcoin::{BlockHash, OutPoint, ScriptBuf, Transaction, Txid},
     ordinals::RuneId,
     std::{
         collections::{HashMap, HashSet},
         fmt::Display,
     },
-    titan_types::{Block, InscriptionId, SpenderReference, TxOutEntry, MempoolEntry},
+    titan_types::{
+        Block, BlockRuneActivity, BlockStats, InscriptionId, MempoolEntry, SpenderReference,
+        TxOutEntry,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -16,12 +19,18 @@ pub struct BatchUpdate {
     pub spent_outpoints_in_mempool: HashMap<OutPoint, SpenderReference>,
     pub blocks: HashMap<BlockHash, Block>,
     pub block_hashes: HashMap<u64, BlockHash>,
+    pub block_stats: HashMap<BlockHash, BlockStats>,
+    pub block_rune_activity: HashMap<BlockHash, Vec<BlockRuneActivity>>,
     pub txouts: HashMap<OutPoint, TxOutEntry>,
     pub tx_state_changes: HashMap<Txid, TransactionStateChange>,
     pub rune_transactions: HashMap<RuneId, Vec<Txid>>,
     pub runes: HashMap<RuneId, RuneEntry>,
     pub rune_ids: HashMap<u128, RuneId>,
     pub rune_numbers: HashMap<u64, RuneId>,
+    pub rune_utxo_counts: HashMap<RuneId, i64>,
+    pub rune_mints: HashMap<(RuneId, u64), i64>,
+    pub rune_supply: HashMap<RuneId, RuneSupplyDelta>,
+    pub rune_supply_blocks: HashMap<(RuneId, u64), RuneSupplyDelta>,
     pub inscriptions: HashMap<InscriptionId, Inscription>,
     pub transactions: HashMap<Txid, Transaction>,
     pub transaction_confirming_block: HashMap<Txid, BlockId>,
@@ -38,6 +47,8 @@ impl BatchUpdate {
             script_pubkeys_outpoints: HashMap::new(),
             spent_outpoints_in_mempool: HashMap::new(),
             blocks: HashMap::new(),
+            block_stats: HashMap::new(),
+            block_rune_activity: HashMap::new(),
             block_hashes: HashMap::new(),
             txouts: HashMap::new(),
             tx_state_changes: HashMap::new(),
@@ -45,6 +56,10 @@ impl BatchUpdate {
             runes: HashMap::new(),
             rune_ids: HashMap::new(),
             rune_numbers: HashMap::new(),
+            rune_utxo_counts: HashMap::new(),
+            rune_mints: HashMap::new(),
+            rune_supply: HashMap::new(),
+            rune_supply_blocks: HashMap::new(),
             inscriptions: HashMap::new(),
             transactions: HashMap::new(),
             transaction_confirming_block: HashMap::new(),
@@ -60,6 +75,8 @@ impl BatchUpdate {
             && self.script_pubkeys_outpoints.is_empty()
             && self.spent_outpoints_in_mempool.is_empty()
             && self.blocks.is_empty()
+            && self.block_stats.is_empty()
+            && self.block_rune_activity.is_empty()
             && self.block_hashes.is_empty()
             && self.txouts.is_empty()
             && self.tx_state_changes.is_empty()
@@ -67,6 +84,10 @@ impl BatchUpdate {
             && self.runes.is_empty()
             && self.rune_ids.is_empty()
             && self.rune_numbers.is_empty()
+            && self.rune_utxo_counts.is_empty()
+            && self.rune_mints.is_empty()
+            && self.rune_supply.is_empty()
+            && self.rune_supply_blocks.is_empty()
             && self.inscriptions.is_empty()
             && self.mempool_txs.is_empty()
             && self.transactions.is_empty()
@@ -84,7 +105,7 @@ impl Display for BatchUpdate {
              addresses: {} , address_outpoints: {}, \
              spent_outpoints_in_mempool: {}, \
              mempool_txs: {}, \
-             runes: txs {}/ runes {}/ ids {}, \
+             runes: txs {}/ runes {}/ ids {}/ utxo counts {}/ mints {}/ supply {}/{}, \
              inscriptions: {}, \
              transactions: {}, \
              transaction_confirming_block: {}]",
@@ -101,6 +122,10 @@ impl Display for BatchUpdate {
             self.rune_transactions.len(),
             self.runes.len(),
             self.rune_ids.len(),
+            self.rune_utxo_counts.len(),
+            self.rune_mints.len(),
+            self.rune_supply.len(),
+            self.rune_supply_blocks.len(),
             self.inscriptions.len(),
             self.transactions.len(),
             self.transaction_confirming_block.len(),
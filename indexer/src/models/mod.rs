@@ -1,8 +1,9 @@
 pub use {
     batch_delete::BatchDelete, batch_rollback::BatchRollback, batch_update::BatchUpdate,
     block::block_id_to_transaction_status, block::BlockId, inscription::Inscription, lot::Lot,
-    media::Media, rune::RuneEntry, transaction_state_change::TransactionStateChange,
-    transaction_state_change::TxRuneIndexRef,
+    media::Media, replacement::Replacement, rune::RuneEntry, rune::RuneOrder, rune::RuneRanking,
+    rune_supply::RuneSupply, rune_supply::RuneSupplyDelta,
+    transaction_state_change::TransactionStateChange, transaction_state_change::TxRuneIndexRef,
 };
 
 mod batch_delete;
@@ -12,5 +13,7 @@ mod block;
 mod inscription;
 mod lot;
 mod media;
+mod replacement;
 mod rune;
+mod rune_supply;
 mod transaction_state_change;
@@ -0,0 +1,56 @@
+use {
+    bitcoin::{hashes::Hash, Txid},
+    borsh::{BorshDeserialize, BorshSerialize},
+    std::io::{Read, Result, Write},
+};
+
+/// Kept for every mempool transaction that was double-spent, under the
+/// replaced txid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub replaced_by: Txid,
+    /// Unix time in seconds at which the replacement was noticed.
+    pub timestamp: u64,
+}
+
+impl BorshSerialize for Replacement {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(&self.replaced_by.as_raw_hash().to_byte_array(), writer)?;
+        BorshSerialize::serialize(&self.timestamp, writer)?;
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for Replacement {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let replaced_by = Txid::from_byte_array(<[u8; 32]>::deserialize_reader(reader)?);
+        let timestamp = u64::deserialize_reader(reader)?;
+        Ok(Self {
+            replaced_by,
+            timestamp,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::str::FromStr};
+
+    #[test]
+    fn borsh_round_trip() {
+        let replacement = Replacement {
+            replaced_by: Txid::from_str(
+                "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+            )
+            .unwrap(),
+            timestamp: 1_700_000_000,
+        };
+
+        let bytes = borsh::to_vec(&replacement).unwrap();
+        assert_eq!(bytes.len(), 40);
+        assert_eq!(
+            borsh::from_slice::<Replacement>(&bytes).unwrap(),
+            replacement
+        );
+    }
+}
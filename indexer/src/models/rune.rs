@@ -120,7 +120,7 @@ impl RuneEntry {
             .or(absolute)
     }
 
-    pub fn to_rune_response(&self, id: RuneId, height: u64) -> RuneResponse {
+    pub fn to_rune_response(&self, id: RuneId, height: u64, utxo_count: u64) -> RuneResponse {
         let mintable = match self.mintable(height) {
             Ok(_) => true,
             Err(_) => false,
@@ -158,10 +158,33 @@ impl RuneEntry {
             inscription_id: self.inscription_id.clone(),
             timestamp: self.timestamp,
             turbo: self.turbo,
+            utxo_count,
         }
     }
 }
 
+/// Rune values kept in an ordered index so the search can sort by them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuneRanking {
+    Mints = 0,
+    Supply = 1,
+    UtxoCount = 2,
+}
+
+/// Order to list rune ids in, each backed by an index so listing a page costs
+/// about the page size.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuneOrder {
+    /// Runes whose name starts with the prefix, in name order.
+    NamePrefix(String),
+    /// Runes with the symbol, newest first.
+    Symbol(char),
+    /// Every rune, newest first.
+    Newest,
+    /// Every rune, highest value first.
+    Ranking(RuneRanking),
+}
+
 impl BorshSerialize for RuneEntry {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         BorshSerialize::serialize(&self.block, writer)?;
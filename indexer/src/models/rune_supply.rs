@@ -0,0 +1,181 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    std::ops::{AddAssign, Neg},
+};
+
+/// A rune's supply breakdown. Stored per block for the supply time series and
+/// as a running total per rune.
+///
+/// Unsigned, since a premine alone can take the whole `u128` range. Existing
+/// entries were stored as non-negative `i128`s, which read back the same.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct RuneSupply {
+    /// Premine at etching plus minted amounts.
+    pub issued: u128,
+    pub burned: u128,
+    /// Held in provably unspendable outputs that the protocol didn't burn.
+    pub unspendable: u128,
+    /// Held in outputs of unconfirmed transactions. Always zero per block.
+    pub pending: u128,
+}
+
+impl RuneSupply {
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Adds the credit and takes away the debit. Saturates rather than
+    /// failing: only `pending` can go past `u128::MAX`, when a chain of
+    /// unconfirmed transactions moves the same runes more than once.
+    pub fn apply(self, delta: &RuneSupplyDelta) -> Self {
+        Self {
+            issued: self
+                .issued
+                .saturating_add(delta.credit.issued)
+                .saturating_sub(delta.debit.issued),
+            burned: self
+                .burned
+                .saturating_add(delta.credit.burned)
+                .saturating_sub(delta.debit.burned),
+            unspendable: self
+                .unspendable
+                .saturating_add(delta.credit.unspendable)
+                .saturating_sub(delta.debit.unspendable),
+            pending: self
+                .pending
+                .saturating_add(delta.credit.pending)
+                .saturating_sub(delta.debit.pending),
+        }
+    }
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            issued: self.issued.saturating_add(rhs.issued),
+            burned: self.burned.saturating_add(rhs.burned),
+            unspendable: self.unspendable.saturating_add(rhs.unspendable),
+            pending: self.pending.saturating_add(rhs.pending),
+        }
+    }
+}
+
+/// Change to a rune's supply breakdown, kept as what is added and what is
+/// taken away so that neither side needs a sign.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuneSupplyDelta {
+    pub credit: RuneSupply,
+    pub debit: RuneSupply,
+}
+
+impl RuneSupplyDelta {
+    pub fn credit(credit: RuneSupply) -> Self {
+        Self {
+            credit,
+            ..Default::default()
+        }
+    }
+
+    pub fn debit(debit: RuneSupply) -> Self {
+        Self {
+            debit,
+            ..Default::default()
+        }
+    }
+}
+
+impl AddAssign for RuneSupplyDelta {
+    fn add_assign(&mut self, rhs: Self) {
+        self.credit = self.credit.saturating_add(rhs.credit);
+        self.debit = self.debit.saturating_add(rhs.debit);
+    }
+}
+
+impl Neg for RuneSupplyDelta {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            credit: self.debit,
+            debit: self.credit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let supply = RuneSupply {
+            issued: 100,
+            burned: 10,
+            unspendable: 5,
+            pending: 0,
+        };
+
+        let mut delta = RuneSupplyDelta::credit(RuneSupply {
+            issued: 50,
+            pending: 20,
+            ..Default::default()
+        });
+        delta += RuneSupplyDelta::debit(RuneSupply {
+            pending: 5,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            supply.apply(&delta),
+            RuneSupply {
+                issued: 150,
+                burned: 10,
+                unspendable: 5,
+                pending: 15,
+            }
+        );
+    }
+
+    #[test]
+    fn neg_reverts_to_zero() {
+        let delta = RuneSupplyDelta::credit(RuneSupply {
+            issued: 1000,
+            burned: 1,
+            unspendable: 2,
+            pending: 3,
+        });
+
+        assert!(RuneSupply::default().apply(&delta).apply(&-delta).is_zero());
+    }
+
+    #[test]
+    fn full_range_amounts() {
+        let max = RuneSupplyDelta::credit(RuneSupply {
+            issued: u128::MAX,
+            pending: u128::MAX,
+            ..Default::default()
+        });
+
+        let mut delta = max;
+        delta += max;
+        assert_eq!(delta.credit.issued, u128::MAX);
+
+        let supply = RuneSupply::default().apply(&max);
+        assert_eq!(supply.issued, u128::MAX);
+        assert!(supply.apply(&-max).is_zero());
+    }
+
+    #[test]
+    fn stored_like_non_negative_i128s() {
+        let mut bytes = Vec::new();
+        (7i128, 0i128, 3i128, 0i128).serialize(&mut bytes).unwrap();
+
+        assert_eq!(
+            RuneSupply::deserialize(&mut &bytes[..]).unwrap(),
+            RuneSupply {
+                issued: 7,
+                burned: 0,
+                unspendable: 3,
+                pending: 0,
+            }
+        );
+    }
+}
@@ -11,7 +11,7 @@ use {
             styling::{AnsiColor, Effects},
             Styles,
         },
-        Parser,
+        Parser, Subcommand,
     },
     std::path::PathBuf,
     tracing::warn,
@@ -81,6 +81,13 @@ pub struct Options {
     )]
     pub(super) data_dir: PathBuf,
 
+    /// Read blocks from bitcoind's blk*.dat files in <BLOCKS_DIR> during initial sync.
+    #[arg(
+        long,
+        help = "Read blocks from bitcoind's blk*.dat files in <BLOCKS_DIR> instead of fetching them over RPC during initial sync. The node must be on the same machine."
+    )]
+    pub(super) blocks_dir: Option<PathBuf>,
+
     /// Do not index inscriptions (rune icons). [default: false]
     #[arg(
         long,
@@ -116,12 +123,21 @@ pub struct Options {
     )]
     pub(super) commit_interval: u64,
 
+    /// Hours to keep mempool replacement history. [default: 72]
+    #[arg(
+        long,
+        help = "Hours to keep mempool replacement history. [default: 72]",
+        default_value = "72"
+    )]
+    pub(super) replacement_history_hours: u64,
+
     /// Enable zmq listener. This optimizes the mempool indexing process because
+    /// mempool changes and new blocks are applied as bitcoind reports them, and
     /// we don't need to fetch transactions from the RPC.
     #[arg(long, default_value = "false")]
     pub(super) enable_zmq_listener: bool,
 
-    /// ZeroMQ endpoint for raw transactions from bitcoind
+    /// ZeroMQ endpoint bitcoind publishes `rawtx`, `sequence` and `hashblock` on
     #[arg(long, default_value = "tcp://127.0.0.1:28332")]
     pub(super) zmq_endpoint: String,
 
@@ -149,10 +165,22 @@ pub struct Options {
     )]
     pub(super) main_loop_interval: u64,
 
+    /// Blocks prepared ahead of the one being indexed. [default: 16]
+    #[arg(
+        long,
+        default_value = "16",
+        help = "Blocks prepared ahead of the one being indexed during sync. [default: 16]"
+    )]
+    pub(super) pipeline_depth: usize,
+
     /// Enable subscription service
     #[arg(long, default_value = "false")]
     pub(super) enable_webhook_subscriptions: bool,
 
+    /// Serve the Esplora REST API under `/esplora`
+    #[arg(long, default_value = "false")]
+    pub(super) enable_esplora: bool,
+
     /// Enable TCP subscription service
     #[arg(long, default_value = "false")]
     pub(super) enable_tcp_subscriptions: bool,
@@ -161,9 +189,46 @@ pub struct Options {
     #[arg(long, default_value = "127.0.0.1:8080")]
     pub(super) tcp_address: String,
 
+    /// Serve the Electrum protocol. Requires --index-addresses
+    #[arg(long, default_value = "false")]
+    pub(super) enable_electrum: bool,
+
+    /// Address the Electrum server listens on
+    #[arg(long, default_value = "127.0.0.1:50001")]
+    pub(super) electrum_address: String,
+
     /// Enable file logging
     #[arg(long, default_value = "false")]
     pub(super) enable_file_logging: bool,
+
+    #[command(subcommand)]
+    pub(super) command: Option<Command>,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Command {
+    /// Export or import the rune state at the indexed height.
+    #[command(subcommand)]
+    Snapshot(SnapshotCommand),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum SnapshotCommand {
+    /// Write the rune state at the indexed height to <OUTPUT>. Stop the indexer first and wait
+    /// for the node to be as many blocks ahead as the deepest reorg the indexer recovers from.
+    Export {
+        output: PathBuf,
+        /// Include the address index. Only for indexes built with --index-addresses.
+        #[arg(long)]
+        addresses: bool,
+    },
+    /// Bootstrap an empty index from the snapshot in <INPUT>, checking it against <HASH>.
+    Import {
+        input: PathBuf,
+        /// Hash the snapshot has to have, as printed by export.
+        #[arg(long)]
+        hash: String,
+    },
 }
 
 impl Options {
@@ -190,6 +255,7 @@ impl From<Options> for Settings {
 
         Self {
             data_dir: options.data_dir,
+            blocks_dir: options.blocks_dir,
             zmq_endpoint: options.zmq_endpoint,
             bitcoin_rpc_limit: options.bitcoin_rpc_limit,
             bitcoin_rpc_url: options.bitcoin_rpc_url,
@@ -200,6 +266,8 @@ impl From<Options> for Settings {
             index_addresses: options.index_addresses,
             commit_interval: options.commit_interval,
             main_loop_interval: options.main_loop_interval,
+            pipeline_depth: options.pipeline_depth,
+            replacement_history_hours: options.replacement_history_hours,
         }
     }
 }
@@ -218,6 +286,7 @@ impl From<Options> for ServerConfig {
 
             index_addresses: options.index_addresses,
             enable_webhook_subscriptions: options.enable_webhook_subscriptions,
+            enable_esplora: options.enable_esplora,
         }
     }
 }
@@ -228,6 +297,7 @@ impl From<Options> for SubscriptionConfig {
             enable_webhook_subscriptions: options.enable_webhook_subscriptions,
             enable_tcp_subscriptions: options.enable_tcp_subscriptions,
             tcp_address: options.tcp_address,
+            enable_electrum: options.enable_electrum,
             enable_file_logging: options.enable_file_logging,
         }
     }
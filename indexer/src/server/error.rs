@@ -3,17 +3,26 @@ use {
         api::{content::ContentError, ApiError},
         bitcoin_rpc::{RpcClientError, RpcClientPoolError},
         index::{IndexError, StoreError},
+        subscription::WebhookStoreError,
     },
-    axum::response::{IntoResponse, Response},
-    http::{header, HeaderValue, StatusCode},
+    axum::{
+        response::{IntoResponse, Response},
+        Json,
+    },
+    bitcoincore_rpc::jsonrpc::error::{Error as JsonRpcError, RpcError},
+    http::{header, HeaderValue},
     std::fmt::Write,
+    titan_types::{ErrorCode, ErrorResponse},
     tracing::error,
 };
 
 #[derive(Debug, thiserror::Error)]
 pub(super) enum ServerError {
-    #[error("bad request: {0}")]
-    BadRequest(String),
+    #[error("borsh serialization error: {0}")]
+    BorshError(std::io::Error),
+
+    #[error("bad request: {1}")]
+    BadRequest(ErrorCode, String),
 
     #[error("content error: {0}")]
     ContentError(#[from] ContentError),
@@ -27,99 +36,235 @@ pub(super) enum ServerError {
     #[error("api error: {0}")]
     ApiError(#[from] ApiError),
 
-    #[error("not found: {0}")]
-    NotFound(String),
+    #[error("not found: {1}")]
+    NotFound(ErrorCode, String),
 }
 
 pub(super) type ServerResult<T = Response> = Result<T, ServerError>;
 
-impl IntoResponse for ServerError {
-    fn into_response(self) -> Response {
+impl ServerError {
+    /// The message of errors that mean what was asked for doesn't exist.
+    fn not_found_message(&self) -> Option<&str> {
         match self {
-            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
-            Self::ApiError(ApiError::IndexError(IndexError::StoreError(StoreError::NotFound(
+            Self::NotFound(_, message)
+            | Self::ApiError(ApiError::IndexError(IndexError::StoreError(StoreError::NotFound(
                 message,
-            )))) => (StatusCode::NOT_FOUND, message).into_response(),
-            Self::ApiError(ApiError::RpcError(error)) => {
-                error!("rpc error: {error}");
-                (StatusCode::BAD_REQUEST, error.to_string()).into_response()
+            ))))
+            | Self::ApiError(ApiError::SubscriptionError(WebhookStoreError::NotFound(message))) => {
+                Some(message)
             }
-            Self::ApiError(error) => {
-                error!("error serving request: {error}");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                        .canonical_reason()
-                        .unwrap_or_default(),
-                )
-                    .into_response()
+            Self::ApiError(ApiError::RpcError(error))
+            | Self::ApiError(ApiError::IndexError(IndexError::RpcApiError(error))) => match error {
+                bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(RpcError {
+                    code: -5,
+                    message,
+                    ..
+                })) => Some(message),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn into_error_response(self) -> ErrorResponse {
+        if let Some(message) = self.not_found_message().map(str::to_string) {
+            let code = match self {
+                Self::NotFound(code, _) => code,
+                Self::ApiError(ApiError::SubscriptionError(_)) => ErrorCode::SubscriptionNotFound,
+                _ => ErrorCode::NotFound,
+            };
+            return ErrorResponse::new(code, message);
+        }
+
+        match self {
+            Self::BadRequest(code, message) | Self::NotFound(code, message) => {
+                ErrorResponse::new(code, message)
             }
-            Self::RpcClientError(error) => {
-                error!("rpc client error: {error}");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                        .canonical_reason()
-                        .unwrap_or_default(),
-                )
-                    .into_response()
+            Self::ApiError(ApiError::InvalidCursor(cursor)) => ErrorResponse::new(
+                ErrorCode::InvalidCursor,
+                format!("invalid cursor: {cursor}"),
+            ),
+            Self::ApiError(
+                error @ (ApiError::BatchTooLarge(_)
+                | ApiError::InvalidRange(_)
+                | ApiError::InvalidSearch(_)),
+            ) => ErrorResponse::new(ErrorCode::InvalidRequest, error.to_string()),
+            Self::ApiError(error @ ApiError::InvalidDescriptor(_)) => {
+                ErrorResponse::new(ErrorCode::InvalidDescriptor, error.to_string())
             }
-            Self::RpcClientPoolError(error) => {
-                error!("rpc client pool error: {error}");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                        .canonical_reason()
-                        .unwrap_or_default(),
-                )
-                    .into_response()
+            Self::ApiError(error @ ApiError::ReindexRequired(_)) => {
+                ErrorResponse::new(ErrorCode::ReindexRequired, error.to_string())
             }
-            Self::ContentError(ContentError::NotAcceptable {
-                accept_encoding,
-                content_encoding,
-            }) => {
-                let mut message = format!(
-                    "inscription content encoding `{}` is not acceptable.",
-                    String::from_utf8_lossy(content_encoding.as_bytes())
-                );
-
-                if let Some(accept_encoding) = accept_encoding.0 {
-                    write!(message, " `Accept-Encoding` header: `{accept_encoding}`").unwrap();
-                } else {
-                    write!(message, " `Accept-Encoding` header not present").unwrap();
-                };
-
-                (StatusCode::NOT_ACCEPTABLE, message).into_response()
+            Self::ApiError(ApiError::HexError(error)) => {
+                ErrorResponse::new(ErrorCode::InvalidTransaction, error.to_string())
             }
-            Self::ContentError(_) => {
-                error!("content error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                        .canonical_reason()
-                        .unwrap_or_default(),
-                )
-                    .into_response()
+            Self::ApiError(ApiError::RpcError(error))
+            | Self::ApiError(ApiError::IndexError(IndexError::RpcApiError(error))) => {
+                rpc_error_response(error)
             }
-            Self::NotFound(message) => (
-                StatusCode::NOT_FOUND,
+            Self::ApiError(ApiError::IndexError(IndexError::RpcClientError(error)))
+            | Self::RpcClientError(error)
+            | Self::RpcClientPoolError(RpcClientPoolError::Provider(error)) => {
+                error!("rpc client error: {error}");
+                ErrorResponse::new(ErrorCode::RpcUnavailable, "bitcoin node unavailable")
+            }
+            Self::ApiError(ApiError::ContentError(error)) | Self::ContentError(error) => {
+                content_error_response(error)
+            }
+            error => {
+                error!("error serving request: {error}");
+                ErrorResponse::new(ErrorCode::Internal, "internal server error")
+            }
+        }
+    }
+}
+
+/// bitcoind's own error codes are in `details`, with its message.
+fn rpc_error_response(error: bitcoincore_rpc::Error) -> ErrorResponse {
+    match error {
+        bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(RpcError { code, message, .. })) => {
+            let response = match code {
+                // RPC_VERIFY_ERROR, RPC_VERIFY_REJECTED and
+                // RPC_VERIFY_ALREADY_IN_CHAIN.
+                -27..=-25 => ErrorResponse::new(ErrorCode::TxRejected, "transaction rejected"),
+                // RPC_DESERIALIZATION_ERROR
+                -22 => ErrorResponse::new(ErrorCode::InvalidTransaction, "invalid transaction"),
+                // RPC_INVALID_PARAMETER
+                -8 => ErrorResponse::new(ErrorCode::InvalidRequest, "invalid parameter"),
+                // RPC_IN_WARMUP
+                -28 => ErrorResponse::new(ErrorCode::RpcUnavailable, "bitcoin node starting up"),
+                _ => {
+                    error!("rpc error {code}: {message}");
+                    ErrorResponse::new(ErrorCode::RpcError, "bitcoin node error")
+                }
+            };
+
+            response.with_details(format!("{message} ({code})"))
+        }
+        bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Transport(error)) => {
+            error!("rpc transport error: {error}");
+            ErrorResponse::new(ErrorCode::RpcUnavailable, "bitcoin node unavailable")
+        }
+        error => {
+            error!("rpc error: {error}");
+            ErrorResponse::new(ErrorCode::RpcError, "bitcoin node error")
+                .with_details(error.to_string())
+        }
+    }
+}
+
+fn content_error_response(error: ContentError) -> ErrorResponse {
+    match error {
+        ContentError::NotAcceptable {
+            accept_encoding,
+            content_encoding,
+        } => {
+            let mut message = format!(
+                "inscription content encoding `{}` is not acceptable.",
+                String::from_utf8_lossy(content_encoding.as_bytes())
+            );
+
+            if let Some(accept_encoding) = accept_encoding.0 {
+                write!(message, " `Accept-Encoding` header: `{accept_encoding}`").unwrap();
+            } else {
+                write!(message, " `Accept-Encoding` header not present").unwrap();
+            };
+
+            ErrorResponse::new(ErrorCode::NotAcceptable, message)
+        }
+        error => {
+            error!("content error: {error}");
+            ErrorResponse::new(ErrorCode::Internal, "internal server error")
+        }
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        let error = self.into_error_response();
+        let status = error.code.status();
+
+        if error.code.is_not_found() {
+            (
+                status,
                 [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
-                message,
+                Json(error),
             )
-                .into_response(),
+                .into_response()
+        } else {
+            (status, Json(error)).into_response()
         }
     }
 }
 
 pub(super) trait OptionExt<T> {
-    fn ok_or_not_found<F: FnOnce() -> S, S: Into<String>>(self, f: F) -> ServerResult<T>;
+    fn ok_or_not_found<F: FnOnce() -> S, S: Into<String>>(
+        self,
+        code: ErrorCode,
+        f: F,
+    ) -> ServerResult<T>;
 }
 
 impl<T> OptionExt<T> for Option<T> {
-    fn ok_or_not_found<F: FnOnce() -> S, S: Into<String>>(self, f: F) -> ServerResult<T> {
+    fn ok_or_not_found<F: FnOnce() -> S, S: Into<String>>(
+        self,
+        code: ErrorCode,
+        f: F,
+    ) -> ServerResult<T> {
         match self {
             Some(value) => Ok(value),
-            None => Err(ServerError::NotFound(f().into() + " not found")),
+            None => Err(ServerError::NotFound(code, f().into() + " not found")),
         }
     }
 }
+
+pub(super) trait ResultExt<T> {
+    /// Reports a missing resource with `code` instead of the generic
+    /// `not_found`.
+    fn or_not_found(self, code: ErrorCode) -> ServerResult<T>;
+}
+
+impl<T, E: Into<ServerError>> ResultExt<T> for Result<T, E> {
+    fn or_not_found(self, code: ErrorCode) -> ServerResult<T> {
+        self.map_err(|error| {
+            let error = error.into();
+            match error.not_found_message() {
+                Some(message) => ServerError::NotFound(code, message.to_string()),
+                None => error,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, http::StatusCode};
+
+    #[test]
+    fn errors_are_json_with_codes() {
+        let error: ServerResult<()> = Err(ApiError::IndexError(IndexError::StoreError(
+            StoreError::NotFound("tx abc".into()),
+        ))
+        .into());
+        let response = error.or_not_found(ErrorCode::TxNotFound).unwrap_err();
+        assert_eq!(
+            response.into_error_response(),
+            ErrorResponse::new(ErrorCode::TxNotFound, "tx abc")
+        );
+
+        let rejected = ServerError::ApiError(ApiError::RpcError(bitcoincore_rpc::Error::JsonRpc(
+            JsonRpcError::Rpc(RpcError {
+                code: -26,
+                message: "txn-mempool-conflict".into(),
+                data: None,
+            }),
+        )))
+        .into_error_response();
+        assert_eq!(rejected.code, ErrorCode::TxRejected);
+        assert_eq!(rejected.status, StatusCode::BAD_REQUEST.as_u16());
+        assert_eq!(
+            rejected.details.as_deref(),
+            Some("txn-mempool-conflict (-26)")
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use {
+    super::{
+        deserialize_from_str::DeserializeFromStr,
+        error::{ResultExt, ServerError, ServerResult},
+        extract::Path,
+        server, Server, ServerConfig,
+    },
+    crate::{
+        api::{
+            self,
+            esplora::{self, ScriptQuery},
+        },
+        bitcoin_rpc::RpcClientPool,
+        index::Index,
+    },
+    axum::{
+        extract::Extension,
+        response::{IntoResponse, Response},
+        routing::{get, post},
+        Json, Router,
+    },
+    bitcoin::{address::NetworkUnchecked, Address, BlockHash, OutPoint, Txid},
+    hex::FromHex,
+    http::header,
+    std::sync::Arc,
+    titan_types::ErrorCode,
+    tokio::task,
+};
+
+/// The Esplora REST API, nested under `/esplora` with `--enable-esplora`.
+pub(super) fn router() -> Router<Arc<ServerConfig>> {
+    Router::new()
+        // Blocks
+        .route("/blocks", get(blocks))
+        .route("/blocks/{start_height}", get(blocks_from))
+        .route("/blocks/tip/height", get(tip_height))
+        .route("/blocks/tip/hash", get(tip_hash))
+        .route("/block-height/{height}", get(block_hash_by_height))
+        .route("/block/{hash}", get(block))
+        .route("/block/{hash}/header", get(block_header))
+        .route("/block/{hash}/status", get(block_status))
+        .route("/block/{query}/txids", get(server::block_txids))
+        // Transactions
+        .route("/tx", post(server::broadcast_transaction))
+        .route("/tx/{txid}", get(transaction))
+        .route("/tx/{txid}/status", get(transaction_status))
+        .route("/tx/{txid}/hex", get(server::transaction_hex))
+        .route("/tx/{txid}/raw", get(server::transaction_raw))
+        .route("/tx/{txid}/outspends", get(outspends))
+        .route("/tx/{txid}/outspend/{vout}", get(outspend))
+        // Addresses and scripthashes
+        .route("/address/{address}", get(address))
+        .route("/address/{address}/txs", get(address_txs))
+        .route(
+            "/address/{address}/txs/chain/{last_seen_txid}",
+            get(address_txs_chain),
+        )
+        .route("/address/{address}/utxo", get(address_utxos))
+        .route("/scripthash/{hash}", get(script_hash))
+        .route("/scripthash/{hash}/txs", get(script_hash_txs))
+        .route(
+            "/scripthash/{hash}/txs/chain/{last_seen_txid}",
+            get(script_hash_txs_chain),
+        )
+        .route("/scripthash/{hash}/utxo", get(script_hash_utxos))
+        // Mempool and fees
+        .route("/mempool/txids", get(server::mempool_txids))
+        .route("/fee-estimates", get(fee_estimates))
+}
+
+fn text(body: impl ToString) -> Response {
+    ([(header::CONTENT_TYPE, "text/plain")], body.to_string()).into_response()
+}
+
+async fn tip_height(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+    task::block_in_place(|| Ok(text(api::tip(index)?.height)))
+}
+
+async fn tip_hash(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+    task::block_in_place(|| Ok(text(api::tip(index)?.hash)))
+}
+
+async fn blocks(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+) -> ServerResult {
+    recent_blocks(&index, &bitcoin_rpc_pool, None)
+}
+
+async fn blocks_from(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(DeserializeFromStr(start_height)): Path<DeserializeFromStr<u64>>,
+) -> ServerResult {
+    recent_blocks(&index, &bitcoin_rpc_pool, Some(start_height))
+}
+
+fn recent_blocks(
+    index: &Index,
+    bitcoin_rpc_pool: &RpcClientPool,
+    start_height: Option<u64>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let blocks = esplora::blocks(index, &bitcoin_rpc_pool.get()?, start_height)?;
+        Ok(Json(blocks).into_response())
+    })
+}
+
+async fn block_hash_by_height(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(height)): Path<DeserializeFromStr<u64>>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let hash =
+            api::block_hash_by_height(index, height).or_not_found(ErrorCode::BlockNotFound)?;
+        Ok(text(hash))
+    })
+}
+
+async fn block(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(hash): Path<BlockHash>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let block = esplora::block(&index, &bitcoin_rpc_pool.get()?, &hash)
+            .or_not_found(ErrorCode::BlockNotFound)?;
+        Ok(Json(block).into_response())
+    })
+}
+
+async fn block_header(
+    Extension(index): Extension<Arc<Index>>,
+    Path(hash): Path<BlockHash>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let header = esplora::block_header(&index, &hash).or_not_found(ErrorCode::BlockNotFound)?;
+        Ok(text(header))
+    })
+}
+
+async fn block_status(
+    Extension(index): Extension<Arc<Index>>,
+    Path(hash): Path<BlockHash>,
+) -> ServerResult {
+    task::block_in_place(|| Ok(Json(esplora::block_status(&index, &hash)?).into_response()))
+}
+
+async fn transaction(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<Txid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let tx = esplora::tx(&index, &bitcoin_rpc_pool.get()?, &txid)
+            .or_not_found(ErrorCode::TxNotFound)?;
+        Ok(Json(tx).into_response())
+    })
+}
+
+async fn transaction_status(
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let status = esplora::tx_status(&index, &txid).or_not_found(ErrorCode::TxNotFound)?;
+        Ok(Json(status).into_response())
+    })
+}
+
+async fn outspends(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<Txid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let outspends = esplora::outspends(&index, &bitcoin_rpc_pool.get()?, &txid)
+            .or_not_found(ErrorCode::TxNotFound)?;
+        Ok(Json(outspends).into_response())
+    })
+}
+
+async fn outspend(
+    Extension(index): Extension<Arc<Index>>,
+    Path((txid, vout)): Path<(Txid, u32)>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let outspend = esplora::outspend(&index, &OutPoint::new(txid, vout))?;
+        Ok(Json(outspend).into_response())
+    })
+}
+
+fn address_query(
+    config: &ServerConfig,
+    address: Address<NetworkUnchecked>,
+) -> ServerResult<ScriptQuery> {
+    Ok(ScriptQuery::Address(Server::require_address(
+        config, address,
+    )?))
+}
+
+fn script_hash_query(config: &ServerConfig, script_hash: &str) -> ServerResult<ScriptQuery> {
+    Server::require_address_index(config)?;
+
+    let script_hash = <[u8; 32]>::from_hex(script_hash).map_err(|err| {
+        ServerError::BadRequest(
+            ErrorCode::InvalidRequest,
+            format!("invalid scripthash: {err}"),
+        )
+    })?;
+
+    Ok(ScriptQuery::ScriptHash(script_hash))
+}
+
+async fn address(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+) -> ServerResult {
+    let query = address_query(&config, address)?;
+    script_stats(&index, &query)
+}
+
+async fn address_txs(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+) -> ServerResult {
+    let query = address_query(&config, address)?;
+    script_txs(&index, &bitcoin_rpc_pool, &query, None)
+}
+
+async fn address_txs_chain(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path((address, last_seen)): Path<(Address<NetworkUnchecked>, Txid)>,
+) -> ServerResult {
+    let query = address_query(&config, address)?;
+    script_txs(&index, &bitcoin_rpc_pool, &query, Some(&last_seen))
+}
+
+async fn address_utxos(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+) -> ServerResult {
+    let query = address_query(&config, address)?;
+    script_utxos(&index, &query)
+}
+
+async fn script_hash(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Path(script_hash): Path<String>,
+) -> ServerResult {
+    let query = script_hash_query(&config, &script_hash)?;
+    script_stats(&index, &query)
+}
+
+async fn script_hash_txs(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(script_hash): Path<String>,
+) -> ServerResult {
+    let query = script_hash_query(&config, &script_hash)?;
+    script_txs(&index, &bitcoin_rpc_pool, &query, None)
+}
+
+async fn script_hash_txs_chain(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path((script_hash, last_seen)): Path<(String, Txid)>,
+) -> ServerResult {
+    let query = script_hash_query(&config, &script_hash)?;
+    script_txs(&index, &bitcoin_rpc_pool, &query, Some(&last_seen))
+}
+
+async fn script_hash_utxos(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Path(script_hash): Path<String>,
+) -> ServerResult {
+    let query = script_hash_query(&config, &script_hash)?;
+    script_utxos(&index, &query)
+}
+
+fn script_stats(index: &Index, query: &ScriptQuery) -> ServerResult {
+    task::block_in_place(|| Ok(Json(esplora::address_stats(index, query)?).into_response()))
+}
+
+fn script_txs(
+    index: &Index,
+    bitcoin_rpc_pool: &RpcClientPool,
+    query: &ScriptQuery,
+    last_seen: Option<&Txid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let txs = esplora::address_txs(index, &bitcoin_rpc_pool.get()?, query, last_seen)?;
+        Ok(Json(txs).into_response())
+    })
+}
+
+fn script_utxos(index: &Index, query: &ScriptQuery) -> ServerResult {
+    task::block_in_place(|| Ok(Json(esplora::address_utxos(index, query)?).into_response()))
+}
+
+async fn fee_estimates(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+    task::block_in_place(|| Ok(Json(esplora::fee_estimates(&index)?).into_response()))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::index::Chain, bitcoin::hashes::Hash, bitcoincore_rpc::Auth,
+        http::StatusCode, tokio::net::TcpListener,
+    };
+
+    /// Every endpoint BDK's Esplora client requests during a full scan and a
+    /// sync is routed, rather than falling through to not found.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn routes_what_bdk_syncs_with() {
+        let (_dir, _db, index) = Index::open_temp();
+        let config = Arc::new(ServerConfig {
+            chain: Chain::Regtest,
+            csp_origin: None,
+            decompress: false,
+            http_listen: String::new(),
+            bitcoin_rpc_url: "http://127.0.0.1:0".into(),
+            bitcoin_rpc_auth: Auth::None,
+            index_addresses: true,
+            enable_webhook_subscriptions: false,
+            enable_esplora: true,
+        });
+
+        let router = Router::new()
+            .nest("/esplora", router())
+            .fallback(|| async { StatusCode::NOT_IMPLEMENTED })
+            .layer(Extension(Arc::new(index)))
+            .layer(Extension(config.clone()))
+            .layer(Extension(RpcClientPool::new(config.clone(), 1)))
+            .with_state(config);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/esplora", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let hash = BlockHash::all_zeros();
+        let txid = Txid::all_zeros();
+        let script_hash = "00".repeat(32);
+        let client = reqwest::Client::new();
+
+        for path in [
+            "/blocks/tip/height".to_string(),
+            "/blocks/tip/hash".to_string(),
+            "/blocks".to_string(),
+            "/blocks/840000".to_string(),
+            "/block-height/840000".to_string(),
+            format!("/block/{hash}"),
+            format!("/block/{hash}/header"),
+            format!("/block/{hash}/status"),
+            format!("/scripthash/{script_hash}/txs"),
+            format!("/scripthash/{script_hash}/txs/chain/{txid}"),
+            format!("/tx/{txid}"),
+            format!("/tx/{txid}/status"),
+            format!("/tx/{txid}/raw"),
+            format!("/tx/{txid}/outspends"),
+            format!("/tx/{txid}/outspend/0"),
+            "/fee-estimates".to_string(),
+        ] {
+            let response = client.get(format!("{url}{path}")).send().await.unwrap();
+            assert_ne!(
+                response.status().as_u16(),
+                StatusCode::NOT_IMPLEMENTED.as_u16(),
+                "{path}"
+            );
+        }
+
+        let broadcast = client
+            .post(format!("{url}/tx"))
+            .body("00")
+            .send()
+            .await
+            .unwrap();
+        assert_ne!(
+            broadcast.status().as_u16(),
+            StatusCode::NOT_IMPLEMENTED.as_u16()
+        );
+    }
+}
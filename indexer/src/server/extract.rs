@@ -0,0 +1,91 @@
+use {
+    super::error::ServerError,
+    axum::{
+        extract::{FromRequest, FromRequestParts, RawPathParams, Request},
+        http::request::Parts,
+    },
+    serde::de::DeserializeOwned,
+    titan_types::ErrorCode,
+};
+
+/// `axum::extract::Path`, rejecting with an error code for the parameter
+/// that failed to parse.
+pub(super) struct Path<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => Ok(Self(value)),
+            Err(rejection) => {
+                // Every route but Esplora's outspend has a single
+                // parameter, so that's the one.
+                let code = RawPathParams::from_request_parts(parts, state)
+                    .await
+                    .ok()
+                    .and_then(|params| params.iter().next().map(|(key, _)| param_code(key)))
+                    .unwrap_or(ErrorCode::InvalidRequest);
+
+                Err(ServerError::BadRequest(code, rejection.body_text()))
+            }
+        }
+    }
+}
+
+fn param_code(key: &str) -> ErrorCode {
+    match key {
+        "txid" => ErrorCode::InvalidTxid,
+        "outpoint" => ErrorCode::InvalidOutpoint,
+        "address" => ErrorCode::InvalidAddress,
+        "rune" => ErrorCode::InvalidRune,
+        "query" | "height" | "hash" => ErrorCode::InvalidBlock,
+        "inscription_id" => ErrorCode::InvalidInscriptionId,
+        "id" => ErrorCode::InvalidSubscriptionId,
+        _ => ErrorCode::InvalidRequest,
+    }
+}
+
+/// `axum::extract::Query`, rejecting with a JSON error.
+pub(super) struct Query<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Query(value)| Self(value))
+            .map_err(|rejection| {
+                ServerError::BadRequest(ErrorCode::InvalidRequest, rejection.body_text())
+            })
+    }
+}
+
+/// `axum::extract::Json` for request bodies, rejecting with a JSON error.
+pub(super) struct JsonBody<T>(pub T);
+
+impl<T, S> FromRequest<S> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ServerError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        axum::Json::<T>::from_request(req, state)
+            .await
+            .map(|axum::Json(value)| Self(value))
+            .map_err(|rejection| {
+                ServerError::BadRequest(ErrorCode::InvalidRequest, rejection.body_text())
+            })
+    }
+}
@@ -0,0 +1,95 @@
+use {
+    super::error::{ServerError, ServerResult},
+    axum::{extract::FromRequestParts, http::request::Parts, response::IntoResponse, Json},
+    borsh::BorshSerialize,
+    http::{header, HeaderValue},
+    serde::Serialize,
+    std::{collections::HashMap, convert::Infallible, fmt::Display},
+    titan_types::BORSH_CONTENT_TYPE,
+};
+
+/// Encoding of a response body, picked from the `Accept` header. Borsh when it
+/// lists `application/x-borsh`, JSON otherwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Format {
+    Json,
+    Borsh,
+}
+
+impl Format {
+    fn from_accept(accept: &str) -> Self {
+        let borsh = accept.split(',').any(|media_range| {
+            media_range
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .eq_ignore_ascii_case(BORSH_CONTENT_TYPE)
+        });
+
+        if borsh {
+            Self::Borsh
+        } else {
+            Self::Json
+        }
+    }
+
+    pub(super) fn respond<T: Serialize + BorshSerialize>(self, value: T) -> ServerResult {
+        let vary = [(header::VARY, HeaderValue::from_static("accept"))];
+
+        match self {
+            Self::Json => Ok((vary, Json(value)).into_response()),
+            Self::Borsh => {
+                let body = borsh::to_vec(&value).map_err(ServerError::BorshError)?;
+                Ok((
+                    vary,
+                    [(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(BORSH_CONTENT_TYPE),
+                    )],
+                    body,
+                )
+                    .into_response())
+            }
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Format {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(Self::from_accept)
+            .find(|format| *format == Self::Borsh)
+            .unwrap_or(Self::Json))
+    }
+}
+
+/// Maps keyed by bitcoin or rune types go out keyed by their string form, so
+/// JSON is unchanged and Borsh has a key it can encode.
+pub(super) fn string_keys<K: Display, V>(map: HashMap<K, V>) -> HashMap<String, V> {
+    map.into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_accept() {
+        assert_eq!(Format::from_accept("application/json"), Format::Json);
+        assert_eq!(Format::from_accept("*/*"), Format::Json);
+        assert_eq!(Format::from_accept("application/x-borsh"), Format::Borsh);
+        assert_eq!(
+            Format::from_accept("application/json;q=0.5, Application/X-Borsh; q=1"),
+            Format::Borsh
+        );
+    }
+}
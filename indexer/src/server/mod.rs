@@ -2,5 +2,9 @@ pub use {server::Server, server_config::ServerConfig};
 
 mod deserialize_from_str;
 mod error;
+mod esplora;
+mod extract;
+mod format;
+mod openapi;
 mod server;
 mod server_config;
@@ -0,0 +1,64 @@
+//! OpenAPI description of the HTTP API.
+//!
+//! Paths come from the `#[utoipa::path]` of each handler in `server.rs`, which
+//! `Server::routes` registers together with the route, so every route is
+//! described. `spec_is_up_to_date` fails when a route or a `titan_types` struct
+//! changes without `openapi.json` being regenerated.
+
+use {
+    titan_types::{ErrorResponse, PaginationResponse, RuneActivity, RuneResponse},
+    utoipa::OpenApi,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Titan",
+        description = "Bitcoin, runes and mempool indexer.\n\nJSON responses are also \
+                       available Borsh encoded by sending `Accept: application/x-borsh`. \
+                       Errors are always JSON."
+    ),
+    components(schemas(
+        ErrorResponse,
+        PaginationResponse<RuneResponse>,
+        PaginationResponse<RuneActivity>,
+        PaginationResponse<String>,
+    )),
+    tags(
+        (name = "status"),
+        (name = "blocks"),
+        (name = "addresses"),
+        (name = "transactions"),
+        (name = "inscriptions"),
+        (name = "runes"),
+        (name = "mempool"),
+        (name = "subscriptions"),
+    )
+)]
+pub(super) struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::super::Server,
+        std::{fs, path::PathBuf},
+    };
+
+    /// Run with `UPDATE_OPENAPI=1` to regenerate `openapi.json`.
+    #[test]
+    fn spec_is_up_to_date() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("openapi.json");
+        let spec = Server::routes().into_openapi().to_pretty_json().unwrap() + "\n";
+
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            fs::write(&path, spec).unwrap();
+            return;
+        }
+
+        assert!(
+            fs::read_to_string(&path).unwrap() == spec,
+            "{} is out of date, rerun with UPDATE_OPENAPI=1",
+            path.display()
+        );
+    }
+}
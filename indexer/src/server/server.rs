@@ -1,7 +1,11 @@
 use {
     super::{
         deserialize_from_str::DeserializeFromStr,
-        error::{OptionExt, ServerError, ServerResult},
+        error::{OptionExt, ResultExt, ServerError, ServerResult},
+        esplora,
+        extract::{JsonBody, Path, Query},
+        format::{string_keys, Format},
+        openapi::ApiDoc,
         ServerConfig,
     },
     crate::{
@@ -12,22 +16,34 @@ use {
     },
     axum::{
         body::Bytes,
-        extract::{DefaultBodyLimit, Extension, FromRef, Json, Path, Query},
+        extract::{DefaultBodyLimit, Extension, FromRef},
         response::IntoResponse,
-        routing::{get, post},
-        Router,
+        routing::get,
+        Json, Router,
     },
     axum_server::Handle,
     bitcoin::{address::NetworkUnchecked, Address, OutPoint, Txid},
     http::{header, StatusCode},
+    ordinals::RuneId,
     std::{io, net::ToSocketAddrs, sync::Arc},
-    titan_types::{query, InscriptionId, Pagination, Subscription},
+    titan_types::{
+        query, AddressData, AddressQuery, Block, BlockRuneActivity, BlockStats, BlockTip,
+        BlocksQuery, BumpInfo, BumpInfoQuery, ErrorCode, ErrorResponse, InscriptionId,
+        MempoolBlocks, MempoolBlocksQuery, MempoolEntry, MempoolFees, OutputRisk, Outspend,
+        Pagination, PaginationResponse, Readiness, RuneActivity, RuneMintsQuery, RuneMintsResponse,
+        RuneResponse, RuneSearch, RuneSupplyQuery, RuneSupplyResponse, Status, Subscription,
+        Transaction, TransactionDetail, TransactionStatus, TxOutEntry, TxReplacement, WalletData,
+        WalletQuery,
+    },
     tokio::task,
     tower_http::{
         compression::CompressionLayer,
         cors::{Any, CorsLayer},
     },
     tracing::{error, info},
+    utoipa::OpenApi,
+    utoipa_axum::{router::OpenApiRouter, routes},
+    utoipa_scalar::{Scalar, Servable},
     uuid::Uuid,
 };
 
@@ -54,42 +70,17 @@ impl Server {
         config: Arc<ServerConfig>,
         handle: Handle,
     ) -> SpawnResult<task::JoinHandle<io::Result<()>>> {
-        let router = Router::new()
-            // Status
-            .route("/status", get(Self::status))
-            // Blocks
-            .route("/tip", get(Self::tip))
-            .route("/block/{query}", get(Self::block))
-            .route("/block/{height}/hash", get(Self::block_hash_by_height))
-            .route("/block/{query}/txids", get(Self::block_txids))
-            // Addresses
-            .route("/address/{address}", get(Self::address))
-            // Transactions
-            .route("/tx/broadcast", post(Self::broadcast_transaction))
-            .route("/tx/{txid}", get(Self::transaction))
-            .route("/tx/{txid}/raw", get(Self::transaction_raw))
-            .route("/tx/{txid}/hex", get(Self::transaction_hex))
-            .route("/tx/{txid}/status", get(Self::transaction_status))
-            .route("/output/{outpoint}", get(Self::output))
-            // Inscriptions
-            .route("/inscription/{inscription_id}", get(Self::inscription))
-            // Runes
-            .route("/runes", get(Self::runes))
-            .route("/rune/{rune}", get(Self::rune))
-            .route("/rune/{rune}/transactions", get(Self::rune_transactions))
-            // Mempool
-            .route("/mempool/txids", get(Self::mempool_txids))
-            // Mempool entries
-            .route("/mempool/entry/{txid}", get(Self::mempool_tx))
-            .route("/mempool/entries", post(Self::mempool_entries))
-            .route("/mempool/entries/all", get(Self::mempool_all_entries))
-            // Subscriptions
-            .route(
-                "/subscription/{id}",
-                get(Self::get_subscription).delete(Self::delete_subscription),
-            )
-            .route("/subscription", post(Self::add_subscription))
-            .route("/subscriptions", get(Self::subscriptions))
+        let (router, spec) = Self::routes().split_for_parts();
+        let mut router = router
+            .merge(Scalar::with_url("/docs", spec.clone()))
+            .route("/openapi.json", get(move || async move { Json(spec) }));
+
+        if config.enable_esplora {
+            router = router.nest("/esplora", esplora::router());
+        }
+
+        let router = router
+            .fallback(Self::not_found)
             .layer(Extension(index))
             .layer(Extension(webhook_subscription_manager))
             .layer(Extension(config.clone()))
@@ -108,6 +99,63 @@ impl Server {
         Ok(jh)
     }
 
+    /// The API routes, each with its OpenAPI description.
+    pub(super) fn routes() -> OpenApiRouter<Arc<ServerConfig>> {
+        OpenApiRouter::with_openapi(ApiDoc::openapi())
+            // Status
+            .routes(routes!(status))
+            .routes(routes!(ready))
+            // Blocks
+            .routes(routes!(tip))
+            .routes(routes!(block))
+            .routes(routes!(block_hash_by_height))
+            .routes(routes!(block_txids))
+            .routes(routes!(block_stats))
+            .routes(routes!(block_rune_activity))
+            .routes(routes!(blocks))
+            // Addresses
+            .routes(routes!(address))
+            .routes(routes!(wallet))
+            // Transactions
+            .routes(routes!(broadcast_transaction))
+            .routes(routes!(transaction))
+            .routes(routes!(transaction_detail))
+            .routes(routes!(transaction_raw))
+            .routes(routes!(transaction_hex))
+            .routes(routes!(transaction_status))
+            .routes(routes!(transaction_outspends))
+            .routes(routes!(transaction_bump_info))
+            .routes(routes!(transaction_replacement))
+            .routes(routes!(transaction_statuses))
+            .routes(routes!(output))
+            .routes(routes!(output_risk))
+            .routes(routes!(output_spender))
+            .routes(routes!(outputs))
+            // Inscriptions
+            .routes(routes!(inscription))
+            // Runes
+            .routes(routes!(runes))
+            .routes(routes!(search_runes))
+            .routes(routes!(runes_by_ids))
+            .routes(routes!(rune))
+            .routes(routes!(rune_transactions))
+            .routes(routes!(rune_activity))
+            .routes(routes!(rune_mints))
+            .routes(routes!(rune_supply))
+            // Mempool
+            .routes(routes!(mempool_txids))
+            // Mempool entries
+            .routes(routes!(mempool_tx))
+            .routes(routes!(mempool_entries))
+            .routes(routes!(mempool_all_entries))
+            .routes(routes!(mempool_fees))
+            .routes(routes!(mempool_blocks))
+            // Subscriptions
+            .routes(routes!(get_subscription, delete_subscription))
+            .routes(routes!(add_subscription))
+            .routes(routes!(subscriptions))
+    }
+
     fn spawn(
         &self,
         config: &ServerConfig,
@@ -130,255 +178,896 @@ impl Server {
         }))
     }
 
-    async fn tip(Extension(index): Extension<Arc<Index>>) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::tip(index)?).into_response()))
+    async fn not_found(uri: http::Uri) -> ServerError {
+        ServerError::NotFound(ErrorCode::NotFound, format!("{} not found", uri.path()))
     }
 
-    async fn status(Extension(index): Extension<Arc<Index>>) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::status(index)?).into_response()))
-    }
+    /// Checks addresses are indexed and `address` is for the configured chain.
+    pub(super) fn require_address(
+        config: &ServerConfig,
+        address: Address<NetworkUnchecked>,
+    ) -> ServerResult<Address> {
+        Self::require_address_index(config)?;
 
-    async fn block(
-        Extension(index): Extension<Arc<Index>>,
-        Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::block(index, &query)?).into_response()))
+        address
+            .require_network(config.chain.network())
+            .map_err(|err| ServerError::BadRequest(ErrorCode::InvalidAddress, err.to_string()))
     }
 
-    async fn block_hash_by_height(
-        Extension(index): Extension<Arc<Index>>,
-        Path(DeserializeFromStr(height)): Path<DeserializeFromStr<u64>>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::block_hash_by_height(index, height)?).into_response()))
-    }
+    pub(super) fn require_address_index(config: &ServerConfig) -> ServerResult<()> {
+        if !config.index_addresses {
+            return Err(ServerError::BadRequest(
+                ErrorCode::AddressIndexDisabled,
+                "addresses are not indexed. Enable --index-addresses to index addresses"
+                    .to_string(),
+            ));
+        }
 
-    async fn block_txids(
-        Extension(index): Extension<Arc<Index>>,
-        Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::block_txids(index, &query)?).into_response()))
+        Ok(())
     }
+}
 
-    async fn broadcast_transaction(
-        Extension(index): Extension<Arc<Index>>,
-        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
-        hex: String,
-    ) -> ServerResult {
-        task::block_in_place(|| {
-            let txid = api::broadcast_transaction(index, bitcoin_rpc_pool.get()?, &hex)?;
-
-            Ok((
-                StatusCode::OK,
-                [(header::CONTENT_TYPE, "text/plain")],
-                txid.to_string(),
-            )
-                .into_response())
-        })
-    }
+#[utoipa::path(get, path = "/tip", tag = "blocks", responses(
+    (status = 200, body = BlockTip),
+))]
+async fn tip(Extension(index): Extension<Arc<Index>>, format: Format) -> ServerResult {
+    task::block_in_place(|| format.respond(api::tip(index)?))
+}
 
-    async fn transaction(
-        Extension(index): Extension<Arc<Index>>,
-        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
-        Path(txid): Path<Txid>,
-    ) -> ServerResult {
-        task::block_in_place(|| {
-            let transaction = api::transaction(index, bitcoin_rpc_pool.get()?, &txid)?;
-            Ok(Json(transaction).into_response())
-        })
-    }
+/// 503 until blocks and mempool are in sync, for load balancer checks.
+#[utoipa::path(get, path = "/ready", tag = "status", responses(
+    (status = 200, body = Readiness),
+    (status = 503, body = Readiness),
+))]
+async fn ready(Extension(index): Extension<Arc<Index>>, format: Format) -> ServerResult {
+    let readiness = task::block_in_place(|| api::readiness(index))?;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
 
-    async fn transaction_raw(
-        Extension(index): Extension<Arc<Index>>,
-        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
-        Path(txid): Path<Txid>,
-    ) -> ServerResult {
-        task::block_in_place(|| {
-            let raw_tx = api::bitcoin_transaction_raw(index, bitcoin_rpc_pool.get()?, &txid)?;
-
-            Ok((
-                StatusCode::OK,
-                [(header::CONTENT_TYPE, "application/octet-stream")],
-                Bytes::from(raw_tx),
-            )
-                .into_response())
-        })
-    }
+    Ok((status, format.respond(readiness)?).into_response())
+}
 
-    async fn transaction_hex(
-        Extension(index): Extension<Arc<Index>>,
-        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
-        Path(txid): Path<Txid>,
-    ) -> ServerResult {
-        task::block_in_place(|| {
-            let hex_string = api::bitcoin_transaction_hex(index, bitcoin_rpc_pool.get()?, &txid)?;
-
-            Ok((
-                StatusCode::OK,
-                [(header::CONTENT_TYPE, "text/plain")],
-                hex_string,
-            )
-                .into_response())
-        })
-    }
+#[utoipa::path(get, path = "/status", tag = "status", responses(
+    (status = 200, body = Status),
+))]
+async fn status(Extension(index): Extension<Arc<Index>>, format: Format) -> ServerResult {
+    task::block_in_place(|| format.respond(api::status(index)?))
+}
 
-    async fn transaction_status(
-        Extension(index): Extension<Arc<Index>>,
-        Path(txid): Path<Txid>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::transaction_status(index, &txid)?).into_response()))
-    }
+#[utoipa::path(get, path = "/block/{query}", tag = "blocks",
+    params(("query" = String, Path, description = "Block height or hash")),
+    responses(
+        (status = 200, body = Block),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn block(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let block = api::block(index, &query).or_not_found(ErrorCode::BlockNotFound)?;
+        format.respond(block)
+    })
+}
 
-    async fn output(
-        Extension(index): Extension<Arc<Index>>,
-        Path(outpoint): Path<OutPoint>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::output(index, &outpoint)?).into_response()))
-    }
+#[utoipa::path(get, path = "/block/{height}/hash", tag = "blocks",
+    params(("height" = u64, Path)),
+    responses(
+        (status = 200, body = String),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn block_hash_by_height(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(height)): Path<DeserializeFromStr<u64>>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let block_hash =
+            api::block_hash_by_height(index, height).or_not_found(ErrorCode::BlockNotFound)?;
+        format.respond(block_hash)
+    })
+}
 
-    async fn runes(
-        Extension(index): Extension<Arc<Index>>,
-        Query(pagination): Query<Pagination>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::runes(index, pagination)?).into_response()))
-    }
+#[utoipa::path(get, path = "/block/{query}/txids", tag = "blocks",
+    params(("query" = String, Path, description = "Block height or hash")),
+    responses(
+        (status = 200, body = Vec<String>),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+pub(super) async fn block_txids(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let txids = api::block_txids(index, &query).or_not_found(ErrorCode::BlockNotFound)?;
+        format.respond(txids)
+    })
+}
 
-    async fn rune(
-        Extension(index): Extension<Arc<Index>>,
-        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::rune(index, &rune)?).into_response()))
-    }
+/// Stored as the block is indexed, so blocks indexed by older versions or
+/// imported with a snapshot answer `reindex_required`.
+#[utoipa::path(get, path = "/block/{query}/stats", tag = "blocks",
+    params(("query" = String, Path, description = "Block height or hash")),
+    responses(
+        (status = 200, body = BlockStats),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn block_stats(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let stats = api::block_stats(index, &query).or_not_found(ErrorCode::BlockNotFound)?;
+        format.respond(stats)
+    })
+}
 
-    async fn rune_transactions(
-        Extension(index): Extension<Arc<Index>>,
-        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
-        Query(pagination): Query<Pagination>,
-    ) -> ServerResult {
-        task::block_in_place(|| {
-            Ok(Json(api::last_rune_transactions(index, &rune, Some(pagination))?).into_response())
-        })
-    }
+/// Rune etchings, mints, burns and transfers of a block, in block order. Blocks
+/// indexed by older versions or imported with a snapshot answer
+/// `reindex_required`.
+#[utoipa::path(get, path = "/block/{query}/runes", tag = "blocks",
+    params(("query" = String, Path, description = "Block height or hash")),
+    responses(
+        (status = 200, body = Vec<BlockRuneActivity>),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn block_rune_activity(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let activity =
+            api::block_rune_activity(index, &query).or_not_found(ErrorCode::BlockNotFound)?;
+        format.respond(activity)
+    })
+}
 
-    async fn inscription(
-        Extension(index): Extension<Arc<Index>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Path(DeserializeFromStr(inscription_id)): Path<DeserializeFromStr<InscriptionId>>,
-        accept_encoding: AcceptEncoding,
-    ) -> ServerResult {
-        task::block_in_place(|| {
-            Ok(api::inscription_content(
-                index,
-                &inscription_id,
-                accept_encoding,
-                config.csp_origin.clone(),
-                config.decompress,
-            )?
-            .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
+/// Stats of blocks, newest first. Answers `reindex_required` rather than
+/// listing fewer when some were indexed by older versions or imported with a
+/// snapshot.
+#[utoipa::path(get, path = "/blocks", tag = "blocks",
+    params(BlocksQuery),
+    responses(
+        (status = 200, body = Vec<BlockStats>),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn blocks(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<BlocksQuery>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(api::blocks(index, query)?))
+}
+
+/// Takes the transaction as hex and returns its txid.
+#[utoipa::path(post, path = "/tx/broadcast", tag = "transactions",
+    request_body(content = String, content_type = "text/plain"),
+    responses(
+        (status = 200, body = String, content_type = "text/plain"),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+pub(super) async fn broadcast_transaction(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    hex: String,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let txid =
+            api::broadcast_transaction(index, bitcoin_rpc_pool.get()?, &hex).map_err(|error| {
+                match error {
+                    api::ApiError::HexError(_) | api::ApiError::ConsensusError(_) => {
+                        ServerError::BadRequest(ErrorCode::InvalidTransaction, error.to_string())
+                    }
+                    error => error.into(),
+                }
+            })?;
+
+        Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/plain")],
+            txid.to_string(),
+        )
             .into_response())
-        })
-    }
+    })
+}
 
-    async fn mempool_txids(Extension(index): Extension<Arc<Index>>) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::mempool_txids(index)?).into_response()))
-    }
+#[utoipa::path(get, path = "/tx/{txid}", tag = "transactions",
+    params(("txid" = String, Path)),
+    responses(
+        (status = 200, body = Transaction),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn transaction(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<Txid>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let transaction = api::transaction(index, bitcoin_rpc_pool.get()?, &txid)
+            .or_not_found(ErrorCode::TxNotFound)?;
+        format.respond(transaction)
+    })
+}
 
-    async fn mempool_tx(
-        Extension(index): Extension<Arc<Index>>,
-        Path(txid): Path<Txid>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::mempool_tx(index, &txid)?).into_response()))
-    }
+/// Inputs are resolved to the outputs they spend, from the index or bitcoind.
+#[utoipa::path(get, path = "/tx/{txid}/detail", tag = "transactions",
+    params(("txid" = String, Path)),
+    responses(
+        (status = 200, body = TransactionDetail),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn transaction_detail(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<Txid>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let transaction = api::transaction_detail(index, bitcoin_rpc_pool.get()?, &txid)
+            .or_not_found(ErrorCode::TxNotFound)?;
+        format.respond(transaction)
+    })
+}
 
-    async fn mempool_entries(
-        Extension(index): Extension<Arc<Index>>,
-        Json(txids): Json<Vec<Txid>>,
-    ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::mempool_entries(index, &txids)?).into_response()))
-    }
+/// One entry per output, mempool spends included.
+#[utoipa::path(get, path = "/tx/{txid}/outspends", tag = "transactions",
+    params(("txid" = String, Path)),
+    responses(
+        (status = 200, body = Vec<Outspend>),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn transaction_outspends(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<Txid>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let outspends = api::outspends(index, bitcoin_rpc_pool.get()?, &txid)
+            .or_not_found(ErrorCode::TxNotFound)?;
+        format.respond(outspends)
+    })
+}
 
-    async fn mempool_all_entries(Extension(index): Extension<Arc<Index>>) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::mempool_entries_all(index)?).into_response()))
-    }
+#[utoipa::path(get, path = "/tx/{txid}/raw", tag = "transactions",
+    params(("txid" = String, Path)),
+    responses(
+        (status = 200, description = "Consensus encoded transaction", content_type = "application/octet-stream"),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+pub(super) async fn transaction_raw(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<Txid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let raw_tx = api::bitcoin_transaction_raw(index, bitcoin_rpc_pool.get()?, &txid)
+            .or_not_found(ErrorCode::TxNotFound)?;
 
-    async fn address(
-        Extension(index): Extension<Arc<Index>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Path(address): Path<Address<NetworkUnchecked>>,
-    ) -> ServerResult {
-        if !config.index_addresses {
-            return Err(ServerError::BadRequest(
-                "addresses are not indexed. Enable --index-addresses to index addresses"
-                    .to_string(),
-            ));
-        }
+        Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/octet-stream")],
+            Bytes::from(raw_tx),
+        )
+            .into_response())
+    })
+}
 
-        let address = address
-            .require_network(config.chain.network())
-            .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+#[utoipa::path(get, path = "/tx/{txid}/hex", tag = "transactions",
+    params(("txid" = String, Path)),
+    responses(
+        (status = 200, body = String, content_type = "text/plain"),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+pub(super) async fn transaction_hex(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<Txid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let hex_string = api::bitcoin_transaction_hex(index, bitcoin_rpc_pool.get()?, &txid)
+            .or_not_found(ErrorCode::TxNotFound)?;
 
-        task::block_in_place(|| Ok(Json(api::address(index, &address)?).into_response()))
-    }
+        Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/plain")],
+            hex_string,
+        )
+            .into_response())
+    })
+}
 
-    async fn subscriptions(
-        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-    ) -> ServerResult {
-        if !config.enable_webhook_subscriptions {
-            return Err(ServerError::BadRequest(
-                "subscriptions are not enabled".to_string(),
-            ));
+#[utoipa::path(get, path = "/tx/{txid}/status", tag = "transactions",
+    params(("txid" = String, Path)),
+    responses(
+        (status = 200, body = TransactionStatus),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn transaction_status(
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let status = api::transaction_status(index, &txid).or_not_found(ErrorCode::TxNotFound)?;
+        format.respond(status)
+    })
+}
+
+#[utoipa::path(get, path = "/tx/{txid}/bump-info", tag = "transactions",
+    params(("txid" = String, Path), BumpInfoQuery),
+    responses(
+        (status = 200, body = BumpInfo),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn transaction_bump_info(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<Txid>,
+    Query(query): Query<BumpInfoQuery>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let bump_info = api::bump_info(index, bitcoin_rpc_pool.get()?, &txid, query)
+            .or_not_found(ErrorCode::TxNotFound)?;
+        format.respond(bump_info)
+    })
+}
+
+#[utoipa::path(get, path = "/tx/{txid}/replacement", tag = "transactions",
+    params(("txid" = String, Path)),
+    responses(
+        (status = 200, body = TxReplacement),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn transaction_replacement(
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let replacement =
+            api::transaction_replacement(index, &txid).or_not_found(ErrorCode::TxNotFound)?;
+        format.respond(replacement)
+    })
+}
+
+/// Statuses by txid, `null` for transactions that aren't indexed. Takes up
+/// to 1000 txids.
+#[utoipa::path(post, path = "/txs/status", tag = "transactions",
+    request_body = Vec<String>,
+    responses(
+        (status = 200, body = HashMap<String, TransactionStatus>),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn transaction_statuses(
+    Extension(index): Extension<Arc<Index>>,
+    format: Format,
+    JsonBody(txids): JsonBody<Vec<Txid>>,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(string_keys(api::transaction_statuses(index, &txids)?)))
+}
+
+#[utoipa::path(get, path = "/output/{outpoint}", tag = "transactions",
+    params(("outpoint" = String, Path, description = "`txid:vout`")),
+    responses(
+        (status = 200, body = TxOutEntry),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn output(
+    Extension(index): Extension<Arc<Index>>,
+    Path(outpoint): Path<OutPoint>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let output = api::output(index, &outpoint).or_not_found(ErrorCode::OutputNotFound)?;
+        format.respond(output)
+    })
+}
+
+#[utoipa::path(get, path = "/output/{outpoint}/spender", tag = "transactions",
+    params(("outpoint" = String, Path, description = "`txid:vout`")),
+    responses(
+        (status = 200, body = Outspend),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn output_spender(
+    Extension(index): Extension<Arc<Index>>,
+    Path(outpoint): Path<OutPoint>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let spender =
+            api::output_spender(index, &outpoint).or_not_found(ErrorCode::OutputNotFound)?;
+        format.respond(spender)
+    })
+}
+
+/// Outputs by outpoint, `null` for outputs that aren't indexed. Takes up to
+/// 1000 outpoints.
+#[utoipa::path(post, path = "/outputs", tag = "transactions",
+    request_body(content = Vec<String>, description = "`txid:vout` outpoints"),
+    responses(
+        (status = 200, body = HashMap<String, TxOutEntry>),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn outputs(
+    Extension(index): Extension<Arc<Index>>,
+    format: Format,
+    JsonBody(outpoints): JsonBody<Vec<OutPoint>>,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(string_keys(api::outputs(index, &outpoints)?)))
+}
+
+#[utoipa::path(get, path = "/output/{outpoint}/risk", tag = "transactions",
+    params(("outpoint" = String, Path, description = "`txid:vout`")),
+    responses(
+        (status = 200, body = OutputRisk),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn output_risk(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(outpoint): Path<OutPoint>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let risk = api::output_risk(index, bitcoin_rpc_pool.get()?, &outpoint)
+            .or_not_found(ErrorCode::OutputNotFound)?;
+        format.respond(risk)
+    })
+}
+
+#[utoipa::path(get, path = "/runes", tag = "runes",
+    params(Pagination),
+    responses(
+        (status = 200, body = PaginationResponse<RuneResponse>),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn runes(
+    Extension(index): Extension<Arc<Index>>,
+    Query(pagination): Query<Pagination>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(api::runes(index, pagination)?))
+}
+
+#[utoipa::path(get, path = "/runes/search", tag = "runes",
+    params(RuneSearch, Pagination),
+    responses(
+        (status = 200, body = PaginationResponse<RuneResponse>),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn search_runes(
+    Extension(index): Extension<Arc<Index>>,
+    Query(search): Query<RuneSearch>,
+    Query(pagination): Query<Pagination>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(api::search_runes(index, &search, pagination)?))
+}
+
+/// Runes by id, `null` for ids without a rune. Takes up to 1000 ids.
+#[utoipa::path(post, path = "/runes/batch", tag = "runes",
+    request_body = Vec<String>,
+    responses(
+        (status = 200, body = HashMap<String, RuneResponse>),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn runes_by_ids(
+    Extension(index): Extension<Arc<Index>>,
+    format: Format,
+    JsonBody(rune_ids): JsonBody<Vec<RuneId>>,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(string_keys(api::runes_by_ids(index, &rune_ids)?)))
+}
+
+#[utoipa::path(get, path = "/rune/{rune}", tag = "runes",
+    params(("rune" = String, Path, description = "Rune id or spaced rune name")),
+    responses(
+        (status = 200, body = RuneResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn rune(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let rune_entry = api::rune(index, &rune).or_not_found(ErrorCode::RuneNotFound)?;
+        format.respond(rune_entry)
+    })
+}
+
+#[utoipa::path(get, path = "/rune/{rune}/transactions", tag = "runes",
+    params(("rune" = String, Path, description = "Rune id or spaced rune name"), Pagination),
+    responses(
+        (status = 200, body = PaginationResponse<String>),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn rune_transactions(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+    Query(pagination): Query<Pagination>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let transactions = api::last_rune_transactions(index, &rune, Some(pagination))
+            .or_not_found(ErrorCode::RuneNotFound)?;
+        format.respond(transactions.map(|txid| txid.to_string()))
+    })
+}
+
+#[utoipa::path(get, path = "/rune/{rune}/activity", tag = "runes",
+    params(("rune" = String, Path, description = "Rune id or spaced rune name"), Pagination),
+    responses(
+        (status = 200, body = PaginationResponse<RuneActivity>),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn rune_activity(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+    Query(pagination): Query<Pagination>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let activity =
+            api::rune_activity(index, &rune, pagination).or_not_found(ErrorCode::RuneNotFound)?;
+        format.respond(activity)
+    })
+}
+
+#[utoipa::path(get, path = "/rune/{rune}/mints", tag = "runes",
+    params(("rune" = String, Path, description = "Rune id or spaced rune name"), RuneMintsQuery),
+    responses(
+        (status = 200, body = RuneMintsResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn rune_mints(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+    Query(range): Query<RuneMintsQuery>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let mints = api::rune_mints(index, &rune, range).or_not_found(ErrorCode::RuneNotFound)?;
+        format.respond(mints)
+    })
+}
+
+#[utoipa::path(get, path = "/rune/{rune}/supply", tag = "runes",
+    params(("rune" = String, Path, description = "Rune id or spaced rune name"), RuneSupplyQuery),
+    responses(
+        (status = 200, body = RuneSupplyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn rune_supply(
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+    Query(range): Query<RuneSupplyQuery>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let supply = api::rune_supply(index, &rune, range).or_not_found(ErrorCode::RuneNotFound)?;
+        format.respond(supply)
+    })
+}
+
+#[utoipa::path(get, path = "/inscription/{inscription_id}", tag = "inscriptions",
+    params(("inscription_id" = String, Path)),
+    responses(
+        (status = 200, description = "Inscription content, with the inscription's content type"),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+        (status = 406, body = ErrorResponse),
+    )
+)]
+async fn inscription(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Path(DeserializeFromStr(inscription_id)): Path<DeserializeFromStr<InscriptionId>>,
+    accept_encoding: AcceptEncoding,
+) -> ServerResult {
+    task::block_in_place(|| {
+        Ok(api::inscription_content(
+            index,
+            &inscription_id,
+            accept_encoding,
+            config.csp_origin.clone(),
+            config.decompress,
+        )
+        .or_not_found(ErrorCode::InscriptionNotFound)?
+        .ok_or_not_found(ErrorCode::InscriptionNotFound, || {
+            format!("inscription {inscription_id} content")
+        })?
+        .into_response())
+    })
+}
+
+#[utoipa::path(get, path = "/mempool/txids", tag = "mempool", responses(
+    (status = 200, body = Vec<String>),
+))]
+pub(super) async fn mempool_txids(
+    Extension(index): Extension<Arc<Index>>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let txids = api::mempool_txids(index)?;
+        format.respond(txids.iter().map(Txid::to_string).collect::<Vec<_>>())
+    })
+}
+
+#[utoipa::path(get, path = "/mempool/entry/{txid}", tag = "mempool",
+    params(("txid" = String, Path)),
+    responses(
+        (status = 200, body = MempoolEntry),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn mempool_tx(
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let entry = api::mempool_tx(index, &txid).or_not_found(ErrorCode::TxNotFound)?;
+        format.respond(entry)
+    })
+}
+
+/// Entries by txid, `null` for txids that aren't in the mempool.
+#[utoipa::path(post, path = "/mempool/entries", tag = "mempool",
+    request_body = Vec<String>,
+    responses(
+        (status = 200, body = HashMap<String, MempoolEntry>),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn mempool_entries(
+    Extension(index): Extension<Arc<Index>>,
+    format: Format,
+    JsonBody(txids): JsonBody<Vec<Txid>>,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(string_keys(api::mempool_entries(index, &txids)?)))
+}
+
+#[utoipa::path(get, path = "/mempool/entries/all", tag = "mempool", responses(
+    (status = 200, body = HashMap<String, MempoolEntry>),
+))]
+async fn mempool_all_entries(
+    Extension(index): Extension<Arc<Index>>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(string_keys(api::mempool_entries_all(index)?)))
+}
+
+#[utoipa::path(get, path = "/mempool/fees", tag = "mempool", responses(
+    (status = 200, body = MempoolFees),
+))]
+async fn mempool_fees(Extension(index): Extension<Arc<Index>>, format: Format) -> ServerResult {
+    task::block_in_place(|| format.respond(api::mempool_fees(index)?))
+}
+
+#[utoipa::path(get, path = "/mempool/blocks", tag = "mempool",
+    params(MempoolBlocksQuery),
+    responses(
+        (status = 200, body = MempoolBlocks),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn mempool_blocks(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<MempoolBlocksQuery>,
+    format: Format,
+) -> ServerResult {
+    task::block_in_place(|| format.respond(api::mempool_blocks(index, query)?))
+}
+
+/// Requires `--index-addresses`.
+#[utoipa::path(get, path = "/address/{address}", tag = "addresses",
+    params(("address" = String, Path), AddressQuery),
+    responses(
+        (status = 200, body = AddressData),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn address(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+    Query(query): Query<AddressQuery>,
+    format: Format,
+) -> ServerResult {
+    let address = Server::require_address(&config, address)?;
+
+    task::block_in_place(|| {
+        let mut address_data = api::address(index.clone(), &address)?;
+        if query.risk.unwrap_or(false) {
+            api::add_address_risk(index, bitcoin_rpc_pool.get()?, &mut address_data)?;
         }
 
-        task::block_in_place(|| Ok(Json(api::subscriptions(subscription_manager)?).into_response()))
+        format.respond(address_data)
+    })
+}
+
+/// Derives the addresses of an xpub or a `pkh`, `wpkh`, `sh(wpkh)` or `tr`
+/// descriptor, scanning each chain until `gap_limit` unused addresses in a
+/// row. Requires `--index-addresses`.
+#[utoipa::path(post, path = "/wallet", tag = "addresses",
+    request_body = WalletQuery,
+    responses(
+        (status = 200, body = WalletData),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn wallet(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    format: Format,
+    JsonBody(query): JsonBody<WalletQuery>,
+) -> ServerResult {
+    Server::require_address_index(&config)?;
+
+    task::block_in_place(|| format.respond(api::wallet(index, config.chain.network(), &query)?))
+}
+
+#[utoipa::path(get, path = "/subscriptions", tag = "subscriptions", responses(
+    (status = 200, body = Vec<Subscription>),
+    (status = 400, body = ErrorResponse),
+))]
+async fn subscriptions(
+    Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    format: Format,
+) -> ServerResult {
+    if !config.enable_webhook_subscriptions {
+        return Err(ServerError::BadRequest(
+            ErrorCode::SubscriptionsDisabled,
+            "subscriptions are not enabled".to_string(),
+        ));
     }
 
-    async fn add_subscription(
-        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Json(subscription): Json<Subscription>,
-    ) -> ServerResult {
-        if !config.enable_webhook_subscriptions {
-            return Err(ServerError::BadRequest(
-                "subscriptions are not enabled".to_string(),
-            ));
-        }
+    task::block_in_place(|| format.respond(api::subscriptions(subscription_manager)?))
+}
 
-        task::block_in_place(|| {
-            Ok(Json(api::add_subscription(subscription_manager, subscription)?).into_response())
-        })
+#[utoipa::path(post, path = "/subscription", tag = "subscriptions",
+    request_body = Subscription,
+    responses(
+        (status = 200, description = "Subscription added"),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn add_subscription(
+    Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    format: Format,
+    JsonBody(subscription): JsonBody<Subscription>,
+) -> ServerResult {
+    if !config.enable_webhook_subscriptions {
+        return Err(ServerError::BadRequest(
+            ErrorCode::SubscriptionsDisabled,
+            "subscriptions are not enabled".to_string(),
+        ));
     }
 
-    async fn delete_subscription(
-        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Path(id): Path<Uuid>,
-    ) -> ServerResult {
-        if !config.enable_webhook_subscriptions {
-            return Err(ServerError::BadRequest(
-                "subscriptions are not enabled".to_string(),
-            ));
-        }
+    task::block_in_place(|| {
+        format.respond(api::add_subscription(subscription_manager, subscription)?)
+    })
+}
 
-        task::block_in_place(|| {
-            Ok(Json(api::delete_subscription(subscription_manager, id)?).into_response())
-        })
+#[utoipa::path(delete, path = "/subscription/{id}", tag = "subscriptions",
+    params(("id" = uuid::Uuid, Path)),
+    responses(
+        (status = 200, description = "Subscription deleted"),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn delete_subscription(
+    Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Path(id): Path<Uuid>,
+    format: Format,
+) -> ServerResult {
+    if !config.enable_webhook_subscriptions {
+        return Err(ServerError::BadRequest(
+            ErrorCode::SubscriptionsDisabled,
+            "subscriptions are not enabled".to_string(),
+        ));
     }
 
-    async fn get_subscription(
-        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Path(id): Path<Uuid>,
-    ) -> ServerResult {
-        if !config.enable_webhook_subscriptions {
-            return Err(ServerError::BadRequest(
-                "subscriptions are not enabled".to_string(),
-            ));
-        }
+    task::block_in_place(|| {
+        api::delete_subscription(subscription_manager, id)
+            .or_not_found(ErrorCode::SubscriptionNotFound)?;
+        format.respond(())
+    })
+}
 
-        task::block_in_place(|| {
-            Ok(Json(api::get_subscription(subscription_manager, id)?).into_response())
-        })
+#[utoipa::path(get, path = "/subscription/{id}", tag = "subscriptions",
+    params(("id" = uuid::Uuid, Path)),
+    responses(
+        (status = 200, body = Subscription),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn get_subscription(
+    Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Path(id): Path<Uuid>,
+    format: Format,
+) -> ServerResult {
+    if !config.enable_webhook_subscriptions {
+        return Err(ServerError::BadRequest(
+            ErrorCode::SubscriptionsDisabled,
+            "subscriptions are not enabled".to_string(),
+        ));
     }
+
+    task::block_in_place(|| {
+        let subscription = api::get_subscription(subscription_manager, id)
+            .or_not_found(ErrorCode::SubscriptionNotFound)?;
+        format.respond(subscription)
+    })
 }
 
 impl<S> axum::extract::FromRequestParts<S> for AcceptEncoding
@@ -19,6 +19,7 @@ pub struct ServerConfig {
 
     pub(crate) index_addresses: bool,
     pub(crate) enable_webhook_subscriptions: bool,
+    pub(crate) enable_esplora: bool,
 }
 
 impl RpcClientProvider for ServerConfig {
@@ -0,0 +1,125 @@
+use {
+    crate::{
+        bitcoin_rpc::RpcClientProvider,
+        db::{RocksDB, SnapshotError},
+        index::Settings,
+        options::SnapshotCommand,
+    },
+    bitcoin::{hashes::sha256, BlockHash},
+    bitcoincore_rpc::RpcApi,
+    std::{
+        fs::File,
+        io::{BufReader, BufWriter},
+        str::FromStr,
+    },
+    tracing::info,
+};
+
+pub fn run(
+    command: SnapshotCommand,
+    settings: &Settings,
+    db: &RocksDB,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let chain = settings.chain.to_string();
+
+    match command {
+        SnapshotCommand::Export { output, addresses } => {
+            let height = db
+                .get_block_count()?
+                .checked_sub(1)
+                .ok_or("no blocks indexed")?;
+            check_buried(settings, height, db.get_block_hash(height)?)?;
+
+            let writer = BufWriter::new(File::create(&output)?);
+            let info = db.export_snapshot(&chain, addresses, writer)?;
+
+            info!(
+                "Exported snapshot at height {} ({}) to {}",
+                info.height,
+                info.block_hash,
+                output.display()
+            );
+            println!("{}", info.hash);
+        }
+        SnapshotCommand::Import { input, hash } => {
+            let expected = sha256::Hash::from_str(&hash)?;
+
+            // Check everything before writing, so a bad snapshot can't leave
+            // a half-imported index behind.
+            let info = RocksDB::verify_snapshot(BufReader::new(File::open(&input)?))?;
+            if info.hash != expected {
+                return Err(SnapshotError::HashMismatch {
+                    expected,
+                    actual: info.hash,
+                }
+                .into());
+            }
+
+            if info.chain != chain {
+                return Err(format!("snapshot is for {}, not {}", info.chain, chain).into());
+            }
+
+            if info.addresses != settings.index_addresses {
+                return Err(format!(
+                    "snapshot {} the address index, run with{} --index-addresses",
+                    if info.addresses {
+                        "has"
+                    } else {
+                        "doesn't have"
+                    },
+                    if info.addresses { "" } else { "out" },
+                )
+                .into());
+            }
+
+            check_buried(settings, info.height, info.block_hash)?;
+
+            let info = db.import_snapshot(BufReader::new(File::open(&input)?))?;
+            if info.hash != expected {
+                return Err(format!("{} changed while importing", input.display()).into());
+            }
+
+            info!(
+                "Imported snapshot at height {} ({}), indexing continues from {}",
+                info.height,
+                info.block_hash,
+                info.height + 1
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Only the snapshot's block is stored, without what's needed to roll it
+/// back. Snapshots are therefore limited to blocks deeper in the node's chain
+/// than the reorgs the indexer recovers from.
+fn check_buried(
+    settings: &Settings,
+    height: u64,
+    block_hash: BlockHash,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let client = settings.get_new_rpc_client()?;
+
+    if client.get_block_hash(height)? != block_hash {
+        return Err(format!(
+            "snapshot block {} isn't in the node's chain at height {}",
+            block_hash, height
+        )
+        .into());
+    }
+
+    let depth = settings.max_recoverable_reorg_depth();
+    let node_height = client.get_block_count()?;
+    if node_height < height + depth {
+        return Err(format!(
+            "the node has {} blocks after the snapshot block at height {}, at least {} are needed",
+            node_height - height,
+            height,
+            depth
+        )
+        .into());
+    }
+
+    Ok(())
+}
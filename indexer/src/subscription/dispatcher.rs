@@ -17,6 +17,7 @@ pub async fn event_dispatcher(
     mut receiver: mpsc::Receiver<Event>,
     subscription_manager: Option<Arc<WebhookSubscriptionManager>>,
     tcp_subscription_manager: Option<Arc<TcpSubscriptionManager>>,
+    electrum_subscription_manager: Option<Arc<TcpSubscriptionManager>>,
     mut shutdown_rx: watch::Receiver<()>,
     enable_file_logging: bool,
 ) {
@@ -49,6 +50,12 @@ pub async fn event_dispatcher(
                         if let Some(manager) = &tcp_subscription_manager {
                             manager.broadcast(&event).await;
                         }
+
+                        // Broadcast to Electrum sessions, which are remote and
+                        // unauthenticated, so never wait on them
+                        if let Some(manager) = &electrum_subscription_manager {
+                            manager.try_broadcast(&event).await;
+                        }
                     },
                     None => {
                         // The sender side was dropped, so no more events
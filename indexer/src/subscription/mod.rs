@@ -4,6 +4,7 @@ mod tcp_subscription;
 mod webhook;
 
 pub use spawn::*;
+pub use tcp_subscription::{TcpSubscription, TcpSubscriptionManager};
 pub use webhook::{
     StoreError as WebhookStoreError, SubscriptionManager as WebhookSubscriptionManager,
 };
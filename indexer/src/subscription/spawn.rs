@@ -34,6 +34,8 @@ pub struct SubscriptionSpawnResult {
     pub dispatcher_handle: task::JoinHandle<()>,
     pub webhook_spawn_result: Option<WebhookSubscriptionSpawnResult>,
     pub tcp_spawn_result: Option<TcpSubscriptionSpawnResult>,
+    /// Fed to the Electrum sessions, which register themselves on it.
+    pub electrum_subscription_manager: Option<Arc<TcpSubscriptionManager>>,
     pub shutdown_tx: watch::Sender<()>,
 }
 
@@ -41,6 +43,7 @@ pub struct SubscriptionConfig {
     pub enable_webhook_subscriptions: bool,
     pub enable_tcp_subscriptions: bool,
     pub tcp_address: String,
+    pub enable_electrum: bool,
     pub enable_file_logging: bool,
 }
 
@@ -49,8 +52,11 @@ pub fn spawn_subscription_tasks(
     db: Arc<RocksDB>,
     config: SubscriptionConfig,
 ) -> Option<SubscriptionSpawnResult> {
-    // If both webhook and TCP subscriptions are disabled, return None
-    if !config.enable_webhook_subscriptions && !config.enable_tcp_subscriptions {
+    // If webhook and TCP subscriptions and Electrum are disabled, return None
+    if !config.enable_webhook_subscriptions
+        && !config.enable_tcp_subscriptions
+        && !config.enable_electrum
+    {
         return None;
     }
 
@@ -108,6 +114,10 @@ pub fn spawn_subscription_tasks(
         None
     };
 
+    let electrum_subscription_manager = config
+        .enable_electrum
+        .then(|| Arc::new(TcpSubscriptionManager::new()));
+
     // Create the event sender
     let (event_sender, event_receiver) = mpsc::channel::<Event>(10000);
 
@@ -121,12 +131,14 @@ pub fn spawn_subscription_tasks(
     let tcp_manager_for_dispatcher = tcp_spawn_result
         .as_ref()
         .map(|r| r.tcp_subscription_manager.clone());
+    let electrum_manager_for_dispatcher = electrum_subscription_manager.clone();
 
     let dispatcher_handle = tokio::spawn(async move {
         event_dispatcher(
             event_receiver,
             webhook_manager_for_dispatcher,
             tcp_manager_for_dispatcher,
+            electrum_manager_for_dispatcher,
             dispatcher_rx,
             config.enable_file_logging,
         )
@@ -140,6 +152,7 @@ pub fn spawn_subscription_tasks(
         dispatcher_handle,
         webhook_spawn_result,
         tcp_spawn_result,
+        electrum_subscription_manager,
         shutdown_tx,
     })
 }
@@ -1,12 +1,18 @@
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 use titan_types::{Event, EventType, TcpSubscriptionRequest};
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
     net::{TcpListener, TcpStream},
-    sync::{mpsc, watch, RwLock},
+    sync::{
+        mpsc::{self, error::TrySendError},
+        watch, RwLock,
+    },
 };
 use tracing::{error, info};
 use uuid::Uuid;
@@ -19,6 +25,9 @@ pub struct TcpSubscription {
     pub event_types: HashSet<EventType>,
     /// Channel sender to deliver events to this client.
     pub sender: mpsc::Sender<Event>,
+    /// Set by `try_broadcast` when an event was dropped because the channel
+    /// was full.
+    pub lagged: Arc<AtomicBool>,
 }
 
 /// Manages all active TCP subscriptions.
@@ -72,6 +81,33 @@ impl TcpSubscriptionManager {
             info!("Unregistered failed subscription with id {}", id);
         }
     }
+
+    /// Like `broadcast`, but never waits on a subscriber. When a channel is
+    /// full the event is dropped and the subscription marked as lagged, so a
+    /// client that stops reading can't hold up the dispatcher.
+    pub async fn try_broadcast(&self, event: &Event) {
+        let event_type: EventType = EventType::from(event.clone());
+
+        let subs = self.subscriptions.read().await;
+        let mut failed_ids = Vec::new();
+
+        for (id, sub) in subs.iter() {
+            if sub.event_types.contains(&event_type) {
+                match sub.sender.try_send(event.clone()) {
+                    Ok(()) => {}
+                    Err(TrySendError::Full(_)) => sub.lagged.store(true, Ordering::Release),
+                    Err(TrySendError::Closed(_)) => failed_ids.push(*id),
+                }
+            }
+        }
+
+        drop(subs);
+
+        for id in failed_ids {
+            self.unregister(id).await;
+            info!("Unregistered closed subscription with id {}", id);
+        }
+    }
 }
 
 /// Run the TCP subscription server on the given address.
@@ -137,6 +173,7 @@ async fn handle_tcp_connection(
         id: Uuid::new_v4(),
         event_types,
         sender: tx,
+        lagged: Arc::default(),
     };
     let sub_id = sub.id;
     manager.register(sub).await;
@@ -183,3 +220,56 @@ async fn handle_tcp_connection(
     info!("Unregistered TCP subscription with id {}", sub_id);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(sender: mpsc::Sender<Event>) -> TcpSubscription {
+        TcpSubscription {
+            id: Uuid::new_v4(),
+            event_types: [EventType::Reorg].into_iter().collect(),
+            sender,
+            lagged: Arc::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn try_broadcast_marks_full_subscriptions_as_lagged() {
+        let manager = TcpSubscriptionManager::new();
+        let (tx, mut rx) = mpsc::channel(1);
+        let sub = subscription(tx);
+        let lagged = sub.lagged.clone();
+        manager.register(sub).await;
+
+        let event = Event::Reorg {
+            height: 840000,
+            depth: 1,
+        };
+        manager.try_broadcast(&event).await;
+        assert!(!lagged.load(Ordering::Acquire));
+
+        // The client isn't reading, so the second event is dropped instead of
+        // waited on.
+        manager.try_broadcast(&event).await;
+        assert!(lagged.load(Ordering::Acquire));
+        assert_eq!(rx.recv().await, Some(event));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn try_broadcast_unregisters_closed_subscriptions() {
+        let manager = TcpSubscriptionManager::new();
+        let (tx, rx) = mpsc::channel(1);
+        manager.register(subscription(tx)).await;
+        drop(rx);
+
+        manager
+            .try_broadcast(&Event::Reorg {
+                height: 840000,
+                depth: 1,
+            })
+            .await;
+        assert!(manager.subscriptions.read().await.is_empty());
+    }
+}
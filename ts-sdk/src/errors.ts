@@ -0,0 +1,32 @@
+import { ErrorCode, ErrorResponse } from './types';
+
+/**
+ * Thrown by `TitanHttpClient` when the server answers with an error.
+ */
+export class TitanApiError extends Error {
+  readonly code: ErrorCode;
+  readonly status: number;
+  readonly details?: string;
+
+  constructor(response: ErrorResponse) {
+    super(response.message);
+    this.name = 'TitanApiError';
+    this.code = response.code;
+    this.status = response.status;
+    this.details = response.details;
+  }
+
+  get isNotFound(): boolean {
+    return this.status === 404;
+  }
+}
+
+export function isErrorResponse(data: unknown): data is ErrorResponse {
+  return (
+    typeof data === 'object' &&
+    data !== null &&
+    typeof (data as ErrorResponse).code === 'string' &&
+    typeof (data as ErrorResponse).status === 'number' &&
+    typeof (data as ErrorResponse).message === 'string'
+  );
+}
@@ -5,14 +5,36 @@ import {
   Pagination,
   PaginationResponse,
   RuneResponse,
+  RuneActivity,
+  RuneMintsQuery,
+  RuneMintsResponse,
+  RuneSearch,
+  RuneSupplyQuery,
+  RuneSupplyResponse,
   Subscription,
   Status,
   Transaction,
+  TransactionDetail,
   TxOutEntry,
   TransactionStatus,
   Block,
+  BlockRuneActivity,
+  BlockStats,
+  BlocksQuery,
   MempoolEntry,
+  MempoolFees,
+  MempoolBlocks,
+  MempoolBlocksQuery,
+  BumpInfo,
+  BumpInfoQuery,
+  TxReplacement,
+  AddressQuery,
+  OutputRisk,
+  Outspend,
+  WalletData,
+  WalletQuery,
 } from './types';
+import { TitanApiError, isErrorResponse } from './errors';
 
 /**
  * A HTTP client class for the Titan API. Each method corresponds
@@ -28,6 +50,14 @@ export class TitanHttpClient {
     this.http = axios.create({
       baseURL: this.baseUrl,
     });
+    // Error responses carry a JSON body with a stable `code`.
+    this.http.interceptors.response.use(undefined, (err) => {
+      const data = err instanceof AxiosError ? err.response?.data : undefined;
+      if (isErrorResponse(data)) {
+        return Promise.reject(new TitanApiError(data));
+      }
+      return Promise.reject(err);
+    });
   }
 
   async getStatus(): Promise<Status> {
@@ -53,14 +83,52 @@ export class TitanHttpClient {
     return await this.get<string[]>(`/block/${query}/txids`);
   }
 
-  async getAddress(address: string): Promise<AddressData> {
-    return await this.getOrFail<AddressData>(`/address/${address}`);
+  async getBlockStats(query: string): Promise<BlockStats | undefined> {
+    return await this.get<BlockStats>(`/block/${query}/stats`);
+  }
+
+  async getBlockRuneActivity(
+    query: string,
+  ): Promise<BlockRuneActivity[] | undefined> {
+    return await this.get<BlockRuneActivity[]>(`/block/${query}/runes`);
+  }
+
+  /**
+   * Stats of blocks, newest first.
+   */
+  async getBlocks(query?: BlocksQuery): Promise<BlockStats[]> {
+    const params = query || {};
+    return await this.getOrFail<BlockStats[]>('/blocks', { params });
+  }
+
+  async getAddress(
+    address: string,
+    query?: AddressQuery,
+  ): Promise<AddressData> {
+    const params = query || {};
+    return await this.getOrFail<AddressData>(`/address/${address}`, {
+      params,
+    });
+  }
+
+  /**
+   * Scans the addresses of an xpub or descriptor for outputs.
+   */
+  async getWallet(query: WalletQuery): Promise<WalletData> {
+    const response = await this.http.post<WalletData>('/wallet', query);
+    return response.data;
   }
 
   async getTransaction(txid: string): Promise<Transaction | undefined> {
     return await this.get<Transaction>(`/tx/${txid}`);
   }
 
+  async getTransactionDetail(
+    txid: string,
+  ): Promise<TransactionDetail | undefined> {
+    return await this.get<TransactionDetail>(`/tx/${txid}/detail`);
+  }
+
   async getTransactionRaw(txid: string): Promise<Uint8Array | undefined> {
     // Request raw binary data using the arraybuffer responseType.
     const response = await this.get<ArrayBuffer>(`/tx/${txid}/raw`, {
@@ -84,6 +152,30 @@ export class TitanHttpClient {
     return await this.get<TransactionStatus>(`/tx/${txid}/status`);
   }
 
+  async getTransactionOutspends(txid: string): Promise<Outspend[]> {
+    return await this.getOrFail<Outspend[]>(`/tx/${txid}/outspends`);
+  }
+
+  async getTransactionStatuses(
+    txids: string[],
+  ): Promise<Map<string, TransactionStatus | undefined>> {
+    return await this.postBatch<TransactionStatus>('/txs/status', txids);
+  }
+
+  async getTransactionBumpInfo(
+    txid: string,
+    query?: BumpInfoQuery,
+  ): Promise<BumpInfo> {
+    const params = query || {};
+    return await this.getOrFail<BumpInfo>(`/tx/${txid}/bump-info`, {
+      params,
+    });
+  }
+
+  async getTransactionReplacement(txid: string): Promise<TxReplacement> {
+    return await this.getOrFail<TxReplacement>(`/tx/${txid}/replacement`);
+  }
+
   async sendTransaction(txHex: string): Promise<string> {
     try {
       const response = await this.http.post<string>('/tx/broadcast', txHex, {
@@ -110,6 +202,23 @@ export class TitanHttpClient {
     return await this.get<TxOutEntry>(`/output/${txid}:${vout}`);
   }
 
+  async getOutputRisk(txid: string, vout: number): Promise<OutputRisk> {
+    return await this.getOrFail<OutputRisk>(`/output/${txid}:${vout}/risk`);
+  }
+
+  async getOutputSpender(txid: string, vout: number): Promise<Outspend> {
+    return await this.getOrFail<Outspend>(`/output/${txid}:${vout}/spender`);
+  }
+
+  /**
+   * Fetches many outputs at once. Takes `txid:vout` outpoints.
+   */
+  async getOutputs(
+    outpoints: string[],
+  ): Promise<Map<string, TxOutEntry | undefined>> {
+    return await this.postBatch<TxOutEntry>('/outputs', outpoints);
+  }
+
   async getInscription(
     inscriptionId: string,
   ): Promise<{ headers: any; data: Uint8Array }> {
@@ -135,10 +244,27 @@ export class TitanHttpClient {
     });
   }
 
+  async searchRunes(
+    search: RuneSearch,
+    pagination?: Pagination,
+  ): Promise<PaginationResponse<RuneResponse>> {
+    const params = { ...search, ...(pagination || {}) };
+    return await this.getOrFail<PaginationResponse<RuneResponse>>(
+      '/runes/search',
+      { params },
+    );
+  }
+
   async getRune(rune: string): Promise<RuneResponse | undefined> {
     return await this.get<RuneResponse>(`/rune/${rune}`);
   }
 
+  async getRunesByIds(
+    runeIds: string[],
+  ): Promise<Map<string, RuneResponse | undefined>> {
+    return await this.postBatch<RuneResponse>('/runes/batch', runeIds);
+  }
+
   async getRuneTransactions(
     rune: string,
     pagination?: Pagination,
@@ -150,6 +276,65 @@ export class TitanHttpClient {
     );
   }
 
+  async getRuneActivity(
+    rune: string,
+    pagination?: Pagination,
+  ): Promise<PaginationResponse<RuneActivity>> {
+    const params = pagination || {};
+    return await this.getOrFail<PaginationResponse<RuneActivity>>(
+      `/rune/${rune}/activity`,
+      { params },
+    );
+  }
+
+  /**
+   * Yields every rune, newest first, fetching pages as they're consumed.
+   */
+  streamRunes(): AsyncGenerator<RuneResponse> {
+    return this.paginate((pagination) => this.getRunes(pagination));
+  }
+
+  /**
+   * Yields every transaction ID of a rune, newest first.
+   */
+  streamRuneTransactions(rune: string): AsyncGenerator<string> {
+    return this.paginate((pagination) =>
+      this.getOrFail<PaginationResponse<string>>(
+        `/rune/${rune}/transactions`,
+        { params: pagination },
+      ),
+    );
+  }
+
+  /**
+   * Yields a rune's whole activity feed, newest first.
+   */
+  streamRuneActivity(rune: string): AsyncGenerator<RuneActivity> {
+    return this.paginate((pagination) =>
+      this.getRuneActivity(rune, pagination),
+    );
+  }
+
+  async getRuneMints(
+    rune: string,
+    range?: RuneMintsQuery,
+  ): Promise<RuneMintsResponse> {
+    const params = range || {};
+    return await this.getOrFail<RuneMintsResponse>(`/rune/${rune}/mints`, {
+      params,
+    });
+  }
+
+  async getRuneSupply(
+    rune: string,
+    range?: RuneSupplyQuery,
+  ): Promise<RuneSupplyResponse> {
+    const params = range || {};
+    return await this.getOrFail<RuneSupplyResponse>(`/rune/${rune}/supply`, {
+      params,
+    });
+  }
+
   async getMempoolTxids(): Promise<string[]> {
     return await this.getOrFail<string[]>(`/mempool/txids`);
   }
@@ -176,6 +361,15 @@ export class TitanHttpClient {
     return new Map(Object.entries(response.data));
   }
 
+  async getMempoolFees(): Promise<MempoolFees> {
+    return await this.getOrFail<MempoolFees>('/mempool/fees');
+  }
+
+  async getMempoolBlocks(query?: MempoolBlocksQuery): Promise<MempoolBlocks> {
+    const params = query || {};
+    return await this.getOrFail<MempoolBlocks>('/mempool/blocks', { params });
+  }
+
   async getSubscription(id: string): Promise<Subscription | undefined> {
     return await this.get<Subscription>(`/subscription/${id}`);
   }
@@ -199,6 +393,35 @@ export class TitanHttpClient {
     }
   }
 
+  /**
+   * Batch endpoints answer with an entry for every id, `null` when there's
+   * nothing for it.
+   */
+  private async postBatch<T>(
+    path: string,
+    ids: string[],
+  ): Promise<Map<string, T | undefined>> {
+    const response = await this.http.post<Record<string, T | null>>(path, ids);
+
+    return new Map(
+      Object.entries(response.data).map(([id, value]) => [
+        id,
+        value ?? undefined,
+      ]),
+    );
+  }
+
+  private async *paginate<T>(
+    fetch: (pagination: Pagination) => Promise<PaginationResponse<T>>,
+  ): AsyncGenerator<T> {
+    let pagination: Pagination | undefined = {};
+    while (pagination) {
+      const page: PaginationResponse<T> = await fetch(pagination);
+      yield* page.items;
+      pagination = page.next_cursor ? { cursor: page.next_cursor } : undefined;
+    }
+  }
+
   private async getOrFail<T>(
     path: string,
     config?: AxiosRequestConfig,
@@ -215,11 +438,11 @@ export class TitanHttpClient {
       const response = await this.http.get<T>(path, config);
       return response.data;
     } catch (err) {
-      if (err instanceof AxiosError) {
-        const axiosError = err as AxiosError;
-        if (axiosError.response?.status === 404) {
-          return undefined;
-        }
+      if (err instanceof TitanApiError && err.isNotFound) {
+        return undefined;
+      }
+      if (err instanceof AxiosError && err.response?.status === 404) {
+        return undefined;
       }
 
       throw err;
@@ -1,3 +1,4 @@
+export * from './errors';
 export * from './http-client';
 export * from './tcp-client';
 export * from './types';
@@ -16,6 +16,24 @@ export interface Block {
   etched_runes: string[];
 }
 
+export interface BlockStats {
+  height: number;
+  hash: string;
+  timestamp: number;
+  tx_count: number;
+  /** What the coinbase claims beyond the block subsidy, in sats. */
+  total_fees: number;
+  weight: number;
+  /** Transactions that etch, mint, burn or transfer runes. */
+  rune_tx_count: number;
+}
+
+/** Heights go down from `from`, which defaults to the tip. */
+export interface BlocksQuery {
+  from?: number;
+  limit?: number;
+}
+
 export interface RuneAmount {
   rune_id: string;
   amount: string;
@@ -31,6 +49,15 @@ export interface SpentStatus {
   vin?: SpenderReference;
 }
 
+export interface Outspend {
+  vout: number;
+  /** Null when the index doesn't track the output and no mempool transaction spends it. */
+  spent: boolean | null;
+  spender: SpenderReference | null;
+  /** Confirmation status of the spender. */
+  status: TransactionStatus | null;
+}
+
 export interface TransactionStatus {
   confirmed: boolean;
   block_height?: number;
@@ -48,6 +75,28 @@ export interface AddressTxOut extends OutPoint {
   risky_runes: RuneAmount[];
   status: TransactionStatus;
   spent: SpentStatus;
+  /** Only present on unconfirmed outputs when requested with `risk: true`, at most 100 per address. */
+  risk?: OutputRisk;
+}
+
+export interface AddressQuery {
+  risk?: boolean;
+}
+
+/**
+ * How likely an unconfirmed output is to disappear before it confirms.
+ * Confirmed outputs report no risk.
+ */
+export interface OutputRisk {
+  outpoint: string;
+  confirmed: boolean;
+  unconfirmed_ancestor_depth: number;
+  unconfirmed_ancestors: string[];
+  signals_rbf: boolean;
+  replaced_txs: string[];
+  /** sat/vB */
+  lowest_ancestor_fee_rate: number | null;
+  risky_runes: RuneAmount[];
 }
 
 export interface AddressData {
@@ -56,6 +105,32 @@ export interface AddressData {
   outputs: AddressTxOut[];
 }
 
+export interface WalletQuery {
+  /**
+   * A `pkh`, `wpkh`, `sh(wpkh)` or `tr` descriptor over one extended public
+   * key, or a bare xpub, ypub or zpub.
+   */
+  descriptor: string;
+  /** Defaults to 20, at most 1000. */
+  gap_limit?: number;
+}
+
+export interface WalletAddress {
+  address: string;
+  chain: number;
+  index: number;
+  value: number;
+  outputs: AddressTxOut[];
+}
+
+export interface WalletData {
+  value: number;
+  runes: RuneAmount[];
+  addresses: WalletAddress[];
+  /** First unused index of each chain. */
+  next_index: number[];
+}
+
 export interface TxOut {
   value: number;
   script_pubkey: string;
@@ -86,6 +161,53 @@ export interface Transaction {
   status: TransactionStatus;
 }
 
+export interface TxInDetail {
+  /** `txid:vout` */
+  previous_output: string;
+  sequence: number;
+  /** Null for coinbase inputs and outputs that can't be resolved. */
+  value: number | null;
+  script_pubkey: string | null;
+  address: string | null;
+  runes: RuneAmount[];
+}
+
+export interface TxOutDetail {
+  value: number;
+  script_pubkey: string;
+  address: string | null;
+  runes: RuneAmount[];
+  risky_runes: RuneAmount[];
+  /** Null for outputs the index doesn't track. */
+  spent: SpentStatus | null;
+}
+
+export interface RuneFlow {
+  rune_id: string;
+  input: string;
+  output: string;
+  minted: string;
+  burned: string;
+  etched: boolean;
+}
+
+export interface TransactionDetail {
+  txid: string;
+  version: number;
+  lock_time: number;
+  size: number;
+  vsize: number;
+  weight: number;
+  input: TxInDetail[];
+  output: TxOutDetail[];
+  /** Null for coinbase transactions and when an input can't be resolved. */
+  fee: number | null;
+  /** sat/vB */
+  fee_rate: number | null;
+  rune_flows: RuneFlow[];
+  status: TransactionStatus;
+}
+
 export interface MintResponse {
   start?: number;
   end?: number;
@@ -113,6 +235,96 @@ export interface RuneResponse {
   inscription_id?: string;
   timestamp: number;
   turbo: boolean;
+  utxo_count: number;
+}
+
+export type RuneSortBy =
+  | 'number'
+  | 'supply'
+  | 'mints'
+  | 'utxo_count'
+  | 'etch_height';
+
+export interface RuneSearch {
+  q?: string;
+  symbol?: string;
+  mintable?: boolean;
+  turbo?: boolean;
+  /** Refused along with `q`. `utxo_count` counts unspent outputs, not distinct holders. */
+  sort?: RuneSortBy;
+}
+
+export interface RuneBlockMints {
+  height: number;
+  mints: number;
+}
+
+export interface RuneMintsResponse {
+  id: string;
+  from: number;
+  to: number;
+  mints: string;
+  cap: string;
+  mintable: boolean;
+  blocks: RuneBlockMints[];
+  velocity: number;
+  estimated_blocks_to_cap: number | null;
+  estimated_seconds_to_cap: number | null;
+}
+
+export interface RuneMintsQuery {
+  from?: number;
+  to?: number;
+}
+
+export interface RuneSupplyBlock {
+  height: number;
+  supply: string;
+  burned: string;
+  unspendable: string;
+  circulating: string;
+}
+
+export interface RuneSupplyResponse {
+  id: string;
+  premine: string;
+  minted: string;
+  supply: string;
+  max_supply: string;
+  burned: string;
+  unspendable: string;
+  circulating: string;
+  pending: string;
+  from: number;
+  to: number;
+  blocks: RuneSupplyBlock[];
+}
+
+export interface RuneSupplyQuery {
+  from?: number;
+  to?: number;
+}
+
+export type RuneAction = 'etch' | 'mint' | 'transfer' | 'burn';
+
+/** A rune etched, minted, burned or transferred by a transaction of a block. */
+export interface BlockRuneActivity {
+  txid: string;
+  rune_id: string;
+  action: RuneAction;
+  amount: string;
+}
+
+export interface RuneActivity {
+  txid: string;
+  action: RuneAction;
+  amount: string;
+  burned: string;
+  senders: string[];
+  receivers: string[];
+  height?: number;
+  timestamp?: number;
+  mempool: boolean;
 }
 
 export interface Subscription {
@@ -125,16 +337,21 @@ export interface Subscription {
 export interface Pagination {
   skip?: number;
   limit?: number;
+  cursor?: string;
 }
 
 export interface PaginationResponse<T> {
   items: T[];
+  /** How many items, newest first, come up to the end of this page. */
   offset: number;
+  next_cursor?: string;
+  prev_cursor?: string;
 }
 
 export enum TitanEventType {
   RuneEtched = 'RuneEtched',
   RuneMinted = 'RuneMinted',
+  RuneMintProgress = 'RuneMintProgress',
   RuneBurned = 'RuneBurned',
   RuneTransferred = 'RuneTransferred',
   AddressModified = 'AddressModified',
@@ -176,6 +393,17 @@ export type TitanEvent =
         txid: string;
       };
     }
+  | {
+      type: TitanEventType.RuneMintProgress;
+      data: {
+        location: Location;
+        rune_id: string;
+        txid: string;
+        percent: number;
+        mints: string;
+        cap: string;
+      };
+    }
   | {
       type: TitanEventType.RuneTransferred;
       data: {
@@ -199,7 +427,8 @@ export type TitanEvent =
     }
   | {
       type: TitanEventType.TransactionsReplaced;
-      data: { txids: string[] };
+      /** `replaced_by` holds `[replaced, replacing]` pairs for double-spent txids. */
+      data: { txids: string[]; replaced_by: [string, string][] };
     }
   | {
       type: TitanEventType.NewBlock;
@@ -242,3 +471,163 @@ export interface MempoolEntry {
   depends: string[];
   spentby: string[];
 }
+
+/**
+ * Mempool transactions whose effective fee rate (sat/vB) is at least
+ * `fee_rate` and below the next bucket's.
+ */
+export interface FeeRateBucket {
+  fee_rate: number;
+  count: number;
+  vsize: number;
+  total_fee: number;
+}
+
+/**
+ * Fee rates in sat/vB to confirm within about one block, three blocks,
+ * six blocks, or whenever the mempool clears.
+ */
+export interface RecommendedFees {
+  fastest: number;
+  half_hour: number;
+  hour: number;
+  economy: number;
+}
+
+export interface MempoolFees {
+  count: number;
+  vsize: number;
+  total_fee: number;
+  histogram: FeeRateBucket[];
+  next_block: FeeRateBucket[];
+  recommended: RecommendedFees;
+}
+
+/**
+ * A block the mempool is projected to produce. Fee rates are effective
+ * ones, in sat/vB.
+ */
+export interface MempoolBlock {
+  txids: string[];
+  tx_count: number;
+  vsize: number;
+  weight: number;
+  total_fee: number;
+  min_fee_rate: number;
+  max_fee_rate: number;
+  median_fee_rate: number;
+}
+
+/**
+ * Where a mempool transaction stands in the projection. `block` is null
+ * when it doesn't make it into any of the returned blocks.
+ */
+export interface MempoolTxPosition {
+  txid: string;
+  block: number | null;
+  fee_rate: number;
+}
+
+export interface MempoolBlocks {
+  blocks: MempoolBlock[];
+  position: MempoolTxPosition | null;
+}
+
+export interface MempoolBlocksQuery {
+  blocks?: number;
+  txid?: string;
+}
+
+/**
+ * An output a CPFP child could spend. A child spending one that holds runes
+ * has to allocate them explicitly, or they end up in its first
+ * non-OP_RETURN output (or burned, with an invalid runestone).
+ */
+export interface BumpOutput {
+  vout: number;
+  value: number;
+  spent: boolean;
+  runes: RuneAmount[];
+  risky_runes: RuneAmount[];
+}
+
+/**
+ * What it takes to get a mempool transaction confirmed at `target_fee_rate`
+ * by replacing it (RBF) or spending it with a high-fee child (CPFP). Fee
+ * rates are in sat/vB, fees in sats.
+ */
+export interface BumpInfo {
+  txid: string;
+  vsize: number;
+  fee: number;
+  fee_rate: number;
+  effective_fee_rate: number;
+  projected_block: number | null;
+  signals_rbf: boolean;
+  replaceable: boolean;
+  ancestors: string[];
+  descendants: string[];
+  target_fee_rate: number;
+  rbf_fee: number;
+  cpfp_child_vsize: number;
+  cpfp_fee: number;
+  outputs: BumpOutput[];
+}
+
+export interface BumpInfoQuery {
+  target_fee_rate?: number;
+  child_vsize?: number;
+}
+
+/**
+ * Mempool replacement history of a transaction. History older than the
+ * indexer's retention window is dropped.
+ */
+export interface TxReplacement {
+  txid: string;
+  replaces: string[];
+  replaced_by: string | null;
+  replaced_at: number | null;
+  replacement_chain: string[];
+}
+
+/**
+ * Machine-readable reason a request failed. New codes may be added, so
+ * don't assume this list is exhaustive.
+ */
+export type ErrorCode =
+  | 'invalid_request'
+  | 'invalid_txid'
+  | 'invalid_outpoint'
+  | 'invalid_address'
+  | 'invalid_rune'
+  | 'invalid_block'
+  | 'invalid_inscription_id'
+  | 'invalid_subscription_id'
+  | 'invalid_cursor'
+  | 'invalid_transaction'
+  | 'invalid_descriptor'
+  | 'tx_rejected'
+  | 'address_index_disabled'
+  | 'subscriptions_disabled'
+  | 'reindex_required'
+  | 'not_found'
+  | 'tx_not_found'
+  | 'block_not_found'
+  | 'output_not_found'
+  | 'rune_not_found'
+  | 'inscription_not_found'
+  | 'subscription_not_found'
+  | 'not_acceptable'
+  | 'rpc_unavailable'
+  | 'rpc_error'
+  | 'internal';
+
+/** Body of every error response. */
+export interface ErrorResponse {
+  code: ErrorCode;
+  status: number;
+  message: string;
+  /** The underlying error, like the reason bitcoind rejected a transaction. */
+  details?: string;
+}
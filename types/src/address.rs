@@ -1,18 +1,26 @@
 use {
-    crate::{transaction::TransactionStatus, RuneAmount, SpentStatus, TxOutEntry},
+    crate::{transaction::TransactionStatus, OutputRisk, RuneAmount, SpentStatus, TxOutEntry},
     bitcoin::{OutPoint, Txid},
+    borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AddressData {
     pub value: u64,
     pub runes: Vec<RuneAmount>,
     pub outputs: Vec<AddressTxOut>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AddressTxOut {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
     pub txid: Txid,
     pub vout: u32,
     pub value: u64,
@@ -20,6 +28,18 @@ pub struct AddressTxOut {
     pub risky_runes: Vec<RuneAmount>,
     pub spent: SpentStatus,
     pub status: TransactionStatus,
+    /// Only filled in for unconfirmed outputs, and only when requested with
+    /// `AddressQuery::risk`. At most 100 outputs of an address get one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<OutputRisk>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct AddressQuery {
+    /// Include a risk report for each unconfirmed output, up to 100 of them.
+    pub risk: Option<bool>,
 }
 
 impl From<(OutPoint, TxOutEntry, TransactionStatus)> for AddressTxOut {
@@ -32,6 +52,7 @@ impl From<(OutPoint, TxOutEntry, TransactionStatus)> for AddressTxOut {
             risky_runes: tx_out.risky_runes,
             spent: tx_out.spent,
             status,
+            risk: None,
         }
     }
 }
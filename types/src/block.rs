@@ -1,8 +1,9 @@
 use {
+    crate::rune::RuneAction,
     bitcoin::{
         block::{Header, Version},
         hashes::Hash,
-        BlockHash, CompactTarget, TxMerkleNode,
+        BlockHash, CompactTarget, TxMerkleNode, Txid,
     },
     borsh::{BorshDeserialize, BorshSerialize},
     ordinals::RuneId,
@@ -11,10 +12,13 @@ use {
 };
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Block {
     pub height: u64,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub header: Header,
     pub tx_ids: Vec<String>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
     pub etched_runes: Vec<RuneId>,
 }
 
@@ -122,6 +126,61 @@ impl BorshDeserialize for Block {
     }
 }
 
+/// Summary of a block, as listed by `/blocks`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BlockStats {
+    pub height: u64,
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub hash: BlockHash,
+    pub timestamp: u64,
+    pub tx_count: u64,
+    /// What the coinbase claims beyond the block subsidy, in sats.
+    pub total_fees: u64,
+    pub weight: u64,
+    /// Transactions that etch, mint, burn or transfer runes.
+    pub rune_tx_count: u64,
+}
+
+/// A rune etched, minted, burned or transferred by a transaction of a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BlockRuneActivity {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub txid: Txid,
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub rune_id: RuneId,
+    pub action: RuneAction,
+    /// Premine for etchings, minted amount for mints, burned amount for burns
+    /// and, for transfers, what the outputs received beyond any etched or
+    /// minted amount.
+    pub amount: u128,
+}
+
+/// Query for `/blocks`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct BlocksQuery {
+    /// Height of the first block listed, the others are below it. Defaults to
+    /// the tip.
+    pub from: Option<u64>,
+    /// Number of blocks to return, up to 100. Defaults to 10.
+    pub limit: Option<u64>,
+}
+
 impl Block {
     pub fn empty_block(height: u64, header: Header) -> Self {
         Self {
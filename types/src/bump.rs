@@ -0,0 +1,81 @@
+use {
+    crate::rune::RuneAmount,
+    bitcoin::Txid,
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// What it takes to get a stuck mempool transaction confirmed at
+/// `target_fee_rate`, either by replacing it (RBF) or by spending one of its
+/// outputs with a high-fee child (CPFP). Fee rates are in sat/vB, fees in
+/// sats.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BumpInfo {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub txid: Txid,
+    pub vsize: u64,
+    pub fee: u64,
+    pub fee_rate: f64,
+    /// Fee rate of the ancestor package the transaction is projected to be
+    /// mined with.
+    pub effective_fee_rate: f64,
+    /// Projected block the transaction lands in, 0 being the next one.
+    pub projected_block: Option<u64>,
+    /// Whether one of its inputs signals replaceability (BIP 125).
+    pub signals_rbf: bool,
+    /// Whether it signals itself or inherits signaling from an unconfirmed
+    /// ancestor.
+    pub replaceable: bool,
+    /// Unconfirmed ancestors, nearest first.
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub ancestors: Vec<Txid>,
+    /// Mempool descendants, nearest first. A replacement evicts all of them.
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub descendants: Vec<Txid>,
+    pub target_fee_rate: f64,
+    /// Minimum fee for a replacement of the same vsize. It has to reach the
+    /// target rate and pay for everything it evicts plus its own relay.
+    pub rbf_fee: u64,
+    /// vsize assumed for the CPFP child.
+    pub cpfp_child_vsize: u64,
+    /// Minimum fee for a child that lifts the whole unconfirmed package to
+    /// the target rate.
+    pub cpfp_fee: u64,
+    pub outputs: Vec<BumpOutput>,
+}
+
+/// An output a CPFP child could spend. If it holds runes, the child has to
+/// allocate them explicitly: without a runestone they all go to its first
+/// non-OP_RETURN output, and with an invalid one they are burned.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BumpOutput {
+    pub vout: u32,
+    pub value: u64,
+    pub spent: bool,
+    pub runes: Vec<RuneAmount>,
+    pub risky_runes: Vec<RuneAmount>,
+}
+
+/// Query for `/tx/{txid}/bump-info`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct BumpInfoQuery {
+    /// Defaults to the recommended fastest fee rate.
+    pub target_fee_rate: Option<f64>,
+    pub child_vsize: Option<u64>,
+}
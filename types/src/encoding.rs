@@ -0,0 +1,208 @@
+//! Borsh encoding of field types from other crates, for use with
+//! `#[borsh(serialize_with = "crate::encoding::serialize", deserialize_with =
+//! "crate::encoding::deserialize")]`. Hashes are written as their 32 raw bytes
+//! and rune ids as `block` then `tx`, like the hand-written impls.
+
+use {
+    bitcoin::{consensus, hashes::Hash, BlockHash, OutPoint, ScriptBuf, TxIn, Txid},
+    borsh::{BorshDeserialize, BorshSerialize},
+    ordinals::{Rune, RuneId, SpacedRune},
+    std::io::{Error, ErrorKind, Read, Result, Write},
+};
+
+/// Content type of Borsh encoded HTTP responses.
+pub const BORSH_CONTENT_TYPE: &str = "application/x-borsh";
+
+pub(crate) trait Encode: Sized {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()>;
+    fn decode<R: Read>(reader: &mut R) -> Result<Self>;
+}
+
+pub(crate) fn serialize<T: Encode, W: Write>(value: &T, writer: &mut W) -> Result<()> {
+    value.encode(writer)
+}
+
+pub(crate) fn deserialize<T: Encode, R: Read>(reader: &mut R) -> Result<T> {
+    T::decode(reader)
+}
+
+impl Encode for Txid {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(&self.as_raw_hash().to_byte_array(), writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Txid::from_byte_array(<[u8; 32]>::deserialize_reader(
+            reader,
+        )?))
+    }
+}
+
+impl Encode for BlockHash {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(&self.as_raw_hash().to_byte_array(), writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(BlockHash::from_byte_array(<[u8; 32]>::deserialize_reader(
+            reader,
+        )?))
+    }
+}
+
+impl Encode for OutPoint {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.txid.encode(writer)?;
+        BorshSerialize::serialize(&self.vout, writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(OutPoint {
+            txid: Txid::decode(reader)?,
+            vout: u32::deserialize_reader(reader)?,
+        })
+    }
+}
+
+impl Encode for RuneId {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(&self.block, writer)?;
+        BorshSerialize::serialize(&self.tx, writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(RuneId {
+            block: u64::deserialize_reader(reader)?,
+            tx: u32::deserialize_reader(reader)?,
+        })
+    }
+}
+
+impl Encode for SpacedRune {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(&self.rune.0, writer)?;
+        BorshSerialize::serialize(&self.spacers, writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(SpacedRune {
+            rune: Rune(u128::deserialize_reader(reader)?),
+            spacers: u32::deserialize_reader(reader)?,
+        })
+    }
+}
+
+impl Encode for char {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(&u32::from(*self), writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        char::from_u32(u32::deserialize_reader(reader)?)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "invalid char"))
+    }
+}
+
+/// As a byte vector, like `TxOut` does.
+impl Encode for ScriptBuf {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(self.as_bytes(), writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(ScriptBuf::from_bytes(Vec::<u8>::deserialize_reader(
+            reader,
+        )?))
+    }
+}
+
+/// Consensus encoded, as a byte vector.
+impl Encode for TxIn {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(&consensus::serialize(self), writer)
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        consensus::deserialize(&Vec::<u8>::deserialize_reader(reader)?)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        match self {
+            Some(value) => {
+                BorshSerialize::serialize(&1u8, writer)?;
+                value.encode(writer)
+            }
+            None => BorshSerialize::serialize(&0u8, writer),
+        }
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        match u8::deserialize_reader(reader)? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(reader)?)),
+            _ => Err(Error::new(ErrorKind::InvalidData, "invalid option tag")),
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let len = u32::try_from(self.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "too many items"))?;
+        BorshSerialize::serialize(&len, writer)?;
+        for value in self {
+            value.encode(writer)?;
+        }
+        Ok(())
+    }
+
+    fn decode<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        // Not trusting `len` for the allocation.
+        let mut values = Vec::new();
+        for _ in 0..len {
+            values.push(T::decode(reader)?);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::str::FromStr};
+
+    #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+    struct Fields {
+        #[borsh(
+            serialize_with = "crate::encoding::serialize",
+            deserialize_with = "crate::encoding::deserialize"
+        )]
+        txids: Vec<Txid>,
+        #[borsh(
+            serialize_with = "crate::encoding::serialize",
+            deserialize_with = "crate::encoding::deserialize"
+        )]
+        spaced_rune: SpacedRune,
+        #[borsh(
+            serialize_with = "crate::encoding::serialize",
+            deserialize_with = "crate::encoding::deserialize"
+        )]
+        symbol: Option<char>,
+    }
+
+    #[test]
+    fn fields_round_trip() {
+        let fields = Fields {
+            txids: vec![Txid::from_byte_array([7; 32])],
+            spaced_rune: SpacedRune::from_str("UNCOMMON•GOODS").unwrap(),
+            symbol: Some('⧉'),
+        };
+
+        let bytes = borsh::to_vec(&fields).unwrap();
+        assert_eq!(bytes.len(), 4 + 32 + 16 + 4 + 1 + 4);
+        assert_eq!(Fields::try_from_slice(&bytes).unwrap(), fields);
+    }
+}
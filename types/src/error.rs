@@ -0,0 +1,163 @@
+use {
+    http::StatusCode,
+    serde::{Deserialize, Serialize},
+    std::fmt::{self, Display, Formatter},
+};
+
+/// Machine-readable reason a request failed. New codes may be added, so
+/// clients should handle `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ErrorCode {
+    InvalidRequest,
+    InvalidTxid,
+    InvalidOutpoint,
+    InvalidAddress,
+    InvalidRune,
+    InvalidBlock,
+    InvalidInscriptionId,
+    InvalidSubscriptionId,
+    InvalidCursor,
+    InvalidTransaction,
+    InvalidDescriptor,
+    /// The node rejected a broadcast transaction.
+    TxRejected,
+    AddressIndexDisabled,
+    SubscriptionsDisabled,
+    /// The index was created before the data the request needs was kept.
+    ReindexRequired,
+    NotFound,
+    TxNotFound,
+    BlockNotFound,
+    OutputNotFound,
+    RuneNotFound,
+    InscriptionNotFound,
+    SubscriptionNotFound,
+    NotAcceptable,
+    /// bitcoind can't be reached.
+    RpcUnavailable,
+    /// bitcoind answered with an error.
+    RpcError,
+    Internal,
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidRequest
+            | Self::InvalidTxid
+            | Self::InvalidOutpoint
+            | Self::InvalidAddress
+            | Self::InvalidRune
+            | Self::InvalidBlock
+            | Self::InvalidInscriptionId
+            | Self::InvalidSubscriptionId
+            | Self::InvalidCursor
+            | Self::InvalidTransaction
+            | Self::InvalidDescriptor
+            | Self::TxRejected
+            | Self::AddressIndexDisabled
+            | Self::SubscriptionsDisabled
+            | Self::ReindexRequired => StatusCode::BAD_REQUEST,
+            Self::NotFound
+            | Self::TxNotFound
+            | Self::BlockNotFound
+            | Self::OutputNotFound
+            | Self::RuneNotFound
+            | Self::InscriptionNotFound
+            | Self::SubscriptionNotFound => StatusCode::NOT_FOUND,
+            Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
+            Self::RpcUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::RpcError => StatusCode::BAD_GATEWAY,
+            Self::Internal | Self::Unknown => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// The code as it appears in responses.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidRequest => "invalid_request",
+            Self::InvalidTxid => "invalid_txid",
+            Self::InvalidOutpoint => "invalid_outpoint",
+            Self::InvalidAddress => "invalid_address",
+            Self::InvalidRune => "invalid_rune",
+            Self::InvalidBlock => "invalid_block",
+            Self::InvalidInscriptionId => "invalid_inscription_id",
+            Self::InvalidSubscriptionId => "invalid_subscription_id",
+            Self::InvalidCursor => "invalid_cursor",
+            Self::InvalidTransaction => "invalid_transaction",
+            Self::InvalidDescriptor => "invalid_descriptor",
+            Self::TxRejected => "tx_rejected",
+            Self::AddressIndexDisabled => "address_index_disabled",
+            Self::SubscriptionsDisabled => "subscriptions_disabled",
+            Self::ReindexRequired => "reindex_required",
+            Self::NotFound => "not_found",
+            Self::TxNotFound => "tx_not_found",
+            Self::BlockNotFound => "block_not_found",
+            Self::OutputNotFound => "output_not_found",
+            Self::RuneNotFound => "rune_not_found",
+            Self::InscriptionNotFound => "inscription_not_found",
+            Self::SubscriptionNotFound => "subscription_not_found",
+            Self::NotAcceptable => "not_acceptable",
+            Self::RpcUnavailable => "rpc_unavailable",
+            Self::RpcError => "rpc_error",
+            Self::Internal => "internal",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == StatusCode::NOT_FOUND
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Body of every error response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    /// The HTTP status code, repeated for clients that only see the body.
+    pub status: u16,
+    pub message: String,
+    /// The underlying error, like the reason bitcoind gave for rejecting a
+    /// transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl ErrorResponse {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            status: code.status().as_u16(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(self, details: impl Into<String>) -> Self {
+        Self {
+            details: Some(details.into()),
+            ..self
+        }
+    }
+}
+
+impl Display for ErrorResponse {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.code, self.status, self.message)?;
+        if let Some(details) = &self.details {
+            write!(f, ": {details}")?;
+        }
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ use {
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
 )]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum EventType {
     RuneEtched,
     RuneBurned,
@@ -24,6 +25,9 @@ pub enum EventType {
     MempoolEntriesUpdated,
     NewBlock,
     Reorg,
+    // Borsh encodes the variant index and subscriptions are persisted, so new
+    // variants go at the end.
+    RuneMintProgress,
 }
 
 impl From<Event> for EventType {
@@ -42,6 +46,7 @@ impl From<Event> for EventType {
             Event::MempoolEntriesUpdated { .. } => EventType::MempoolEntriesUpdated,
             Event::NewBlock { .. } => EventType::NewBlock,
             Event::Reorg { .. } => EventType::Reorg,
+            Event::RuneMintProgress { .. } => EventType::RuneMintProgress,
         }
     }
 }
@@ -63,6 +68,7 @@ impl fmt::Display for EventType {
             EventType::MempoolEntriesUpdated => write!(f, "MempoolEntriesUpdated"),
             EventType::NewBlock => write!(f, "NewBlock"),
             EventType::Reorg => write!(f, "Reorg"),
+            EventType::RuneMintProgress => write!(f, "RuneMintProgress"),
         }
     }
 }
@@ -130,6 +136,15 @@ pub enum Event {
         rune_id: RuneId,
         txid: Txid,
     },
+    /// Fired when a confirmed mint takes a rune to 25, 50, 75 or 100 percent of its cap.
+    RuneMintProgress {
+        location: Location,
+        rune_id: RuneId,
+        txid: Txid,
+        percent: u8,
+        mints: u128,
+        cap: u128,
+    },
     RuneTransferred {
         amount: u128,
         location: Location,
@@ -150,12 +165,20 @@ pub enum Event {
     },
     TransactionsReplaced {
         txids: Vec<Txid>,
+        /// `(replaced, replacing)` pairs for the txids that were double-spent
+        /// rather than dropped.
+        #[serde(default)]
+        replaced_by: Vec<(Txid, Txid)>,
     },
     MempoolTransactionsAdded {
         txids: Vec<(Txid, MempoolEntry)>,
     },
     MempoolTransactionsReplaced {
         txids: Vec<Txid>,
+        /// `(replaced, replacing)` pairs for the txids that were double-spent
+        /// rather than dropped.
+        #[serde(default)]
+        replaced_by: Vec<(Txid, Txid)>,
     },
     MempoolEntriesUpdated {
         txids: Vec<(Txid, MempoolEntry)>,
@@ -169,3 +192,45 @@ pub enum Event {
         depth: u64,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_type_borsh_discriminants_are_stable() {
+        // Subscriptions are stored with these bytes, so existing indexes must
+        // keep decoding to the same variants.
+        let stored = [
+            (0u8, EventType::RuneEtched),
+            (1, EventType::RuneBurned),
+            (2, EventType::RuneMinted),
+            (3, EventType::RuneTransferred),
+            (4, EventType::AddressModified),
+            (5, EventType::TransactionSubmitted),
+            (6, EventType::TransactionsAdded),
+            (7, EventType::TransactionsReplaced),
+            (8, EventType::MempoolTransactionsAdded),
+            (9, EventType::MempoolTransactionsReplaced),
+            (10, EventType::MempoolEntriesUpdated),
+            (11, EventType::NewBlock),
+            (12, EventType::Reorg),
+        ];
+
+        for (byte, event_type) in stored {
+            assert_eq!(borsh::from_slice::<EventType>(&[byte]).unwrap(), event_type);
+            assert_eq!(borsh::to_vec(&event_type).unwrap(), vec![byte]);
+        }
+    }
+
+    #[test]
+    fn event_type_list_written_by_baseline_decodes() {
+        // A `Vec<EventType>` of `[RuneTransferred, Reorg]`.
+        let bytes = [2, 0, 0, 0, 3, 12];
+
+        assert_eq!(
+            borsh::from_slice::<Vec<EventType>>(&bytes).unwrap(),
+            vec![EventType::RuneTransferred, EventType::Reorg],
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// Mempool transactions whose effective fee rate falls in
+/// `[fee_rate, next bucket's fee_rate)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeeRateBucket {
+    /// sat/vB.
+    pub fee_rate: f64,
+    pub count: u64,
+    pub vsize: u64,
+    pub total_fee: u64,
+}
+
+/// Fee rates in sat/vB to confirm within about one block, three blocks, six
+/// blocks, or eventually: `economy` gets into the last projected block,
+/// which is the minimum unless the mempool is deeper than the projection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecommendedFees {
+    pub fastest: f64,
+    pub half_hour: f64,
+    pub hour: f64,
+    pub economy: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MempoolFees {
+    pub count: u64,
+    pub vsize: u64,
+    pub total_fee: u64,
+    /// Whole mempool by effective fee rate, i.e. the rate of the ancestor
+    /// package a transaction is mined with. Empty buckets are left out.
+    pub histogram: Vec<FeeRateBucket>,
+    /// Transactions projected to be in the next block, bucketed like
+    /// `histogram`.
+    pub next_block: Vec<FeeRateBucket>,
+    pub recommended: RecommendedFees,
+}
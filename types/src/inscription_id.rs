@@ -11,7 +11,9 @@ use {
 };
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct InscriptionId {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
     pub txid: Txid,
     pub index: u32,
 }
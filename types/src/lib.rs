@@ -1,26 +1,48 @@
 pub use {
-    address::{AddressData, AddressTxOut},
-    block::Block,
+    address::{AddressData, AddressQuery, AddressTxOut},
+    block::{Block, BlockRuneActivity, BlockStats, BlocksQuery},
+    bump::{BumpInfo, BumpInfoQuery, BumpOutput},
+    encoding::BORSH_CONTENT_TYPE,
+    error::{ErrorCode, ErrorResponse},
     event::{Event, EventType, Location},
+    fees::{FeeRateBucket, MempoolFees, RecommendedFees},
     inscription_id::InscriptionId,
+    mempool_block::{MempoolBlock, MempoolBlocks, MempoolBlocksQuery, MempoolTxPosition},
     mempool_entry::{MempoolEntry, MempoolEntryFee},
     pagination::{Pagination, PaginationResponse},
-    rune::{MintResponse, RuneAmount, RuneResponse},
-    stats::{BlockTip, Status},
+    replacement::TxReplacement,
+    risk::OutputRisk,
+    rune::{
+        MintResponse, RuneAction, RuneActivity, RuneAmount, RuneBlockMints, RuneMintsQuery,
+        RuneMintsResponse, RuneResponse, RuneSearch, RuneSortBy, RuneSupplyBlock, RuneSupplyQuery,
+        RuneSupplyResponse,
+    },
+    stats::{BlockTip, Readiness, Status},
     subscription::{Subscription, TcpSubscriptionRequest},
-    transaction::{Transaction, TransactionStatus, TxOut},
-    tx_out::{SpenderReference, SpentStatus, TxOutEntry},
+    transaction::{
+        RuneFlow, Transaction, TransactionDetail, TransactionStatus, TxInDetail, TxOut, TxOutDetail,
+    },
+    tx_out::{Outspend, SpenderReference, SpentStatus, TxOutEntry},
+    wallet::{WalletAddress, WalletData, WalletQuery},
 };
 
 mod address;
 mod block;
+mod bump;
+mod encoding;
+mod error;
 mod event;
+mod fees;
 mod inscription_id;
+mod mempool_block;
 mod mempool_entry;
 mod pagination;
 pub mod query;
+mod replacement;
+mod risk;
 mod rune;
 mod stats;
 mod subscription;
 mod transaction;
 mod tx_out;
+mod wallet;
@@ -0,0 +1,62 @@
+use {
+    bitcoin::Txid,
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// A block the indexed mempool is projected to produce. Fee rates are the
+/// effective ones, in sat/vB.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MempoolBlock {
+    /// In the order they were selected, parents before children.
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub txids: Vec<Txid>,
+    pub tx_count: u64,
+    pub vsize: u64,
+    pub weight: u64,
+    pub total_fee: u64,
+    pub min_fee_rate: f64,
+    pub max_fee_rate: f64,
+    pub median_fee_rate: f64,
+}
+
+/// Where a mempool transaction stands in the projection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MempoolTxPosition {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub txid: Txid,
+    /// Projected block the transaction lands in, 0 being the next one. `None`
+    /// if it doesn't make it into any of the returned blocks.
+    pub block: Option<u64>,
+    /// Fee rate of the ancestor package the transaction is mined with.
+    pub fee_rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MempoolBlocks {
+    pub blocks: Vec<MempoolBlock>,
+    /// Set when the query asked for a txid that is in the mempool.
+    pub position: Option<MempoolTxPosition>,
+}
+
+/// Query for `/mempool/blocks`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct MempoolBlocksQuery {
+    /// Number of blocks to return, up to 8.
+    pub blocks: Option<u64>,
+    #[cfg_attr(feature = "openapi", param(value_type = Option<String>))]
+    pub txid: Option<Txid>,
+}
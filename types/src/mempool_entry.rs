@@ -7,6 +7,7 @@ use {
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct MempoolEntryFee {
     pub base: u64,
     pub descendant: u64,
@@ -14,6 +15,7 @@ pub struct MempoolEntryFee {
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct MempoolEntry {
     pub vsize: u64,
     pub weight: Option<u64>,
@@ -22,8 +24,10 @@ pub struct MempoolEntry {
     pub ancestor_count: u64,
     pub ancestor_size: u64,
     pub fees: MempoolEntryFee,
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
     pub depends: Vec<Txid>,
     #[serde(rename = "spentby")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
     pub spent_by: Vec<Txid>,
 }
 
@@ -1,11 +1,20 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Deserializer, Serialize},
+};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
 pub struct Pagination {
     #[serde(default = "default_skip")]
     pub skip: u64,
     #[serde(default = "default_limit", deserialize_with = "clamp_limit")]
     pub limit: u64,
+    /// Opaque position returned as `next_cursor` or `prev_cursor` by endpoints
+    /// that support it. When set, `skip` is ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
 }
 
 impl Default for Pagination {
@@ -13,6 +22,7 @@ impl Default for Pagination {
         Pagination {
             skip: 0,
             limit: default_limit(),
+            cursor: None,
         }
     }
 }
@@ -29,7 +39,7 @@ fn clamp_limit<'de, D>(deserializer: D) -> Result<u64, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let limit = u64::deserialize(deserializer)?;
+    let limit = <u64 as Deserialize>::deserialize(deserializer)?;
 
     // Clamp the limit to a maximum of 50 entries.
     Ok(limit.min(default_limit()))
@@ -40,6 +50,7 @@ impl Into<Pagination> for (u64, u64) {
         Pagination {
             skip: self.0,
             limit: self.1,
+            cursor: None,
         }
     }
 }
@@ -50,8 +61,27 @@ impl Into<(u64, u64)> for Pagination {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PaginationResponse<T> {
     pub items: Vec<T>,
+    /// How many items, newest first, come up to the end of this page.
     pub offset: u64,
+    /// Pass as `cursor` to get the next page. `None` once there are no more items.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Pass as `cursor` to get the previous page. `None` on the first page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
+}
+
+impl<T> PaginationResponse<T> {
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> PaginationResponse<U> {
+        PaginationResponse {
+            items: self.items.into_iter().map(f).collect(),
+            offset: self.offset,
+            next_cursor: self.next_cursor,
+            prev_cursor: self.prev_cursor,
+        }
+    }
 }
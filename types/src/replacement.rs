@@ -0,0 +1,41 @@
+use {
+    bitcoin::Txid,
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// Mempool replacement history of a transaction. Replacements are kept for a
+/// limited window, so older history may be missing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TxReplacement {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub txid: Txid,
+    /// Transactions this one double-spent out of the mempool.
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub replaces: Vec<Txid>,
+    /// Transaction that double-spent this one, if any.
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub replaced_by: Option<Txid>,
+    /// Unix time in seconds at which the replacement was seen.
+    pub replaced_at: Option<u64>,
+    /// Every later replacement in order, ending with the latest one.
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub replacement_chain: Vec<Txid>,
+}
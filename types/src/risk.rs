@@ -0,0 +1,48 @@
+use {
+    crate::rune::RuneAmount,
+    bitcoin::{OutPoint, Txid},
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// How likely an unconfirmed output is to disappear before it confirms, for
+/// deciding whether to accept it at zero confirmations. Confirmed outputs
+/// report no risk.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OutputRisk {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub outpoint: OutPoint,
+    pub confirmed: bool,
+    /// Longest chain of unconfirmed transactions ending at the one that
+    /// created the output, that one included.
+    pub unconfirmed_ancestor_depth: u64,
+    /// Unconfirmed ancestors of the creating transaction, nearest first.
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub unconfirmed_ancestors: Vec<Txid>,
+    /// Whether the creating transaction or one of its unconfirmed ancestors
+    /// signals replaceability (BIP 125).
+    pub signals_rbf: bool,
+    /// Transactions that the creating transaction or one of its unconfirmed
+    /// ancestors replaced in the mempool, a sign that someone else tried to
+    /// spend the same inputs.
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub replaced_txs: Vec<Txid>,
+    /// Lowest fee rate among the creating transaction and its unconfirmed
+    /// ancestors, in sat/vB.
+    pub lowest_ancestor_fee_rate: Option<f64>,
+    /// Rune balances that only exist if the unconfirmed ancestry confirms.
+    pub risky_runes: Vec<RuneAmount>,
+}
@@ -7,7 +7,8 @@ use {
     std::io::{Read, Result, Write},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct MintResponse {
     pub start: Option<u64>,
     pub end: Option<u64>,
@@ -17,18 +18,39 @@ pub struct MintResponse {
     pub mints: u128,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RuneResponse {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
     pub id: RuneId,
     pub block: u64,
     pub burned: u128,
     pub divisibility: u8,
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
     pub etching: Txid,
     pub number: u64,
     pub premine: u128,
     pub supply: u128,
     pub max_supply: u128,
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
     pub spaced_rune: SpacedRune,
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
     pub symbol: Option<char>,
     pub mint: Option<MintResponse>,
     pub burns: u128,
@@ -37,10 +59,183 @@ pub struct RuneResponse {
     pub inscription_id: Option<InscriptionId>,
     pub timestamp: u64,
     pub turbo: bool,
+    #[serde(default)]
+    /// Unspent outputs holding the rune. Several can belong to the same holder.
+    pub utxo_count: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RuneSortBy {
+    #[default]
+    Number,
+    Supply,
+    Mints,
+    /// Unspent outputs holding the rune. Distinct holders aren't indexed, so
+    /// this stands in for them.
+    UtxoCount,
+    EtchHeight,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuneBlockMints {
+    pub height: u64,
+    pub mints: u64,
+}
+
+/// Mint activity of a rune over a range of blocks.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuneMintsResponse {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub id: RuneId,
+    pub from: u64,
+    pub to: u64,
+    pub mints: u128,
+    pub cap: u128,
+    pub mintable: bool,
+    /// Only blocks with at least one mint are listed.
+    pub blocks: Vec<RuneBlockMints>,
+    /// Average mints per block over `from..=to`.
+    pub velocity: f64,
+    /// Blocks left until the cap is reached at the current velocity. `None` if
+    /// the rune is not mintable or nobody minted in the range.
+    pub estimated_blocks_to_cap: Option<u64>,
+    pub estimated_seconds_to_cap: Option<u64>,
+}
+
+/// Query for `/rune/{rune}/mints`. Both ends are inclusive block heights,
+/// at most 2016 blocks apart. `to` defaults to the tip and `from` to 143
+/// blocks before `to`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct RuneMintsQuery {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
+/// Supply breakdown of a rune at the end of a block.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuneSupplyBlock {
+    pub height: u64,
+    pub supply: u128,
+    pub burned: u128,
+    pub unspendable: u128,
+    pub circulating: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuneSupplyResponse {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub id: RuneId,
+    pub premine: u128,
+    pub minted: u128,
+    /// Premine plus minted amount.
+    pub supply: u128,
+    pub max_supply: u128,
+    pub burned: u128,
+    /// Held in provably unspendable outputs other than OP_RETURN, which the
+    /// protocol burns.
+    pub unspendable: u128,
+    /// Supply minus burned and unspendable.
+    pub circulating: u128,
+    /// Held in outputs of unconfirmed transactions.
+    pub pending: u128,
+    pub from: u64,
+    pub to: u64,
+    /// Only blocks where the breakdown changed are listed.
+    pub blocks: Vec<RuneSupplyBlock>,
+}
+
+/// Query for `/rune/{rune}/supply`. Both ends are inclusive block heights,
+/// at most 2016 blocks apart. `to` defaults to the tip and `from` to 143
+/// blocks before `to`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct RuneSupplyQuery {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RuneAction {
+    Etch,
+    Mint,
+    Transfer,
+    Burn,
+}
+
+/// A transaction in a rune's activity feed.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuneActivity {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub txid: Txid,
+    pub action: RuneAction,
+    /// Premine for etchings, minted amount for mints, burned amount for burns
+    /// and, for transfers, the amount sent to outputs that don't go back to
+    /// the senders' addresses.
+    pub amount: u128,
+    /// Amount of the rune burned by the transaction, whatever the action.
+    pub burned: u128,
+    /// Addresses of the inputs that held the rune. Empty when the indexer
+    /// can't resolve the input scripts.
+    pub senders: Vec<String>,
+    /// Addresses of the outputs that hold the rune.
+    pub receivers: Vec<String>,
+    pub height: Option<u64>,
+    /// Block time. `None` while in the mempool.
+    pub timestamp: Option<u64>,
+    pub mempool: bool,
+}
+
+/// Query for `/runes/search`. Every field is optional; when neither `q` nor
+/// `symbol` is given all runes are matched and only the filters apply.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct RuneSearch {
+    /// Rune name prefix. Spacers (`•` or `.`) are ignored and the match is
+    /// case insensitive. Matches are returned in name order, so `sort` can't
+    /// be given along with it.
+    pub q: Option<String>,
+    /// Exact rune symbol.
+    #[cfg_attr(feature = "openapi", param(value_type = Option<String>))]
+    pub symbol: Option<char>,
+    /// Only runes whose mint is open (`true`) or closed (`false`).
+    pub mintable: Option<bool>,
+    pub turbo: Option<bool>,
+    /// Results are sorted in descending order of this field, rune number by
+    /// default. Refused along with `q`.
+    pub sort: Option<RuneSortBy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RuneAmount {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
     pub rune_id: RuneId,
     pub amount: u128,
 }
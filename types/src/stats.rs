@@ -1,12 +1,33 @@
-use serde::{Deserialize, Serialize};
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct BlockTip {
     pub height: u64,
     pub hash: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Served by `/ready`, with a 503 status while `ready` is false.
+#[derive(Debug, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Readiness {
+    /// The index reached the node's tip once since it started, is at most a
+    /// couple of blocks behind it and the mempool is synced.
+    pub ready: bool,
+    /// Every block up to the node's tip is indexed.
+    pub at_tip: bool,
+    /// Blocks the node has that are not indexed yet.
+    pub blocks_behind: u64,
+    /// The mempool was synced with the node since the last block was
+    /// indexed.
+    pub mempool_synced: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Status {
     pub block_tip: BlockTip,
     pub runes_count: u64,
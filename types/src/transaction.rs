@@ -1,18 +1,25 @@
 use {
     crate::rune::RuneAmount,
     crate::tx_out::SpentStatus,
-    bitcoin::{BlockHash, ScriptBuf, TxIn, Txid},
+    bitcoin::{BlockHash, OutPoint, ScriptBuf, TxIn, Txid},
     borsh::{BorshDeserialize, BorshSerialize},
+    ordinals::RuneId,
     serde::{Deserialize, Serialize},
     std::io::{Read, Result, Write},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TransactionStatus {
     pub confirmed: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub block_height: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
     pub block_hash: Option<BlockHash>,
 }
 
@@ -34,11 +41,22 @@ impl TransactionStatus {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Transaction {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
     pub txid: Txid,
     pub version: i32,
     pub lock_time: u32,
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
     pub input: Vec<TxIn>,
     pub output: Vec<TxOut>,
     pub status: TransactionStatus,
@@ -67,9 +85,99 @@ impl From<(bitcoin::Transaction, TransactionStatus)> for Transaction {
     }
 }
 
+/// A transaction with the outputs its inputs spend, its fee and how it moves
+/// runes.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TransactionDetail {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub txid: Txid,
+    pub version: i32,
+    pub lock_time: u32,
+    pub size: u64,
+    pub vsize: u64,
+    pub weight: u64,
+    pub input: Vec<TxInDetail>,
+    pub output: Vec<TxOutDetail>,
+    /// `None` for coinbase transactions and when an input can't be resolved.
+    pub fee: Option<u64>,
+    /// In sat/vB.
+    pub fee_rate: Option<f64>,
+    /// One per rune the transaction touches, by rune id.
+    pub rune_flows: Vec<RuneFlow>,
+    pub status: TransactionStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TxInDetail {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub previous_output: OutPoint,
+    pub sequence: u32,
+    /// Value of the spent output. `None` for coinbase inputs and outputs that
+    /// can't be resolved, like confirmed ones on a node without `-txindex`.
+    pub value: Option<u64>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub script_pubkey: Option<ScriptBuf>,
+    pub address: Option<String>,
+    pub runes: Vec<RuneAmount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TxOutDetail {
+    pub value: u64,
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub script_pubkey: ScriptBuf,
+    pub address: Option<String>,
+    pub runes: Vec<RuneAmount>,
+    pub risky_runes: Vec<RuneAmount>,
+    /// `None` for outputs the index doesn't track: those without runes,
+    /// unless addresses are indexed.
+    pub spent: Option<SpentStatus>,
+}
+
+/// How a transaction moves one rune. `input` plus `minted` and any premine
+/// equals `output` plus `burned`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuneFlow {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    #[borsh(
+        serialize_with = "crate::encoding::serialize",
+        deserialize_with = "crate::encoding::deserialize"
+    )]
+    pub rune_id: RuneId,
+    /// Held by the resolved inputs.
+    pub input: u128,
+    pub output: u128,
+    pub minted: u128,
+    pub burned: u128,
+    /// Whether the transaction etched the rune.
+    pub etched: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TxOut {
     pub value: u64,
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
     pub script_pubkey: ScriptBuf,
     pub runes: Vec<RuneAmount>,
     pub risky_runes: Vec<RuneAmount>,
@@ -1,5 +1,5 @@
 use {
-    crate::rune::RuneAmount,
+    crate::{rune::RuneAmount, transaction::TransactionStatus},
     bitcoin::{hashes::Hash, Txid},
     borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
@@ -7,7 +7,9 @@ use {
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SpenderReference {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
     pub txid: Txid,
     pub vin: u32,
 }
@@ -35,8 +37,28 @@ pub enum SpentStatus {
     Spent(SpenderReference),
 }
 
+#[cfg(feature = "openapi")]
+impl utoipa::PartialSchema for SpentStatus {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        SpentStatusJson::schema()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl utoipa::ToSchema for SpentStatus {
+    fn schemas(
+        schemas: &mut Vec<(
+            String,
+            utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>,
+        )>,
+    ) {
+        SpentStatusJson::schemas(schemas);
+    }
+}
+
 // Intermediate structure for JSON serialization
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 struct SpentStatusJson {
     spent: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -79,6 +101,7 @@ impl<'de> Deserialize<'de> for SpentStatus {
 }
 
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TxOutEntry {
     pub runes: Vec<RuneAmount>,
     pub risky_runes: Vec<RuneAmount>,
@@ -91,3 +114,17 @@ impl TxOutEntry {
         !self.runes.is_empty()
     }
 }
+
+/// Whether an output is spent, mempool spends included, and by what.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Outspend {
+    pub vout: u32,
+    /// `None` when the index doesn't track the output and no mempool
+    /// transaction spends it: outputs without runes, unless addresses are
+    /// indexed.
+    pub spent: Option<bool>,
+    pub spender: Option<SpenderReference>,
+    /// Confirmation status of the spender.
+    pub status: Option<TransactionStatus>,
+}
@@ -0,0 +1,45 @@
+use {
+    crate::{AddressTxOut, RuneAmount},
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
+
+/// An HD wallet to scan for used addresses.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WalletQuery {
+    /// A `pkh`, `wpkh`, `sh(wpkh)` or `tr` descriptor over one extended
+    /// public key, ending in `/*` or in `/<a;b>/*` for two chains. A bare xpub
+    /// or tpub is scanned as `pkh`, ypub and upub as `sh(wpkh)`, zpub and
+    /// vpub as `wpkh`, each with a receive (`0/*`) and a change (`1/*`)
+    /// chain. Wallets using addresses past index 10000 of a chain are
+    /// refused.
+    pub descriptor: String,
+    /// Unused addresses in a row that end the scan of a chain. Defaults to 20,
+    /// at most 1000.
+    pub gap_limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WalletData {
+    pub value: u64,
+    pub runes: Vec<RuneAmount>,
+    /// Addresses that have had outputs, in derivation order.
+    pub addresses: Vec<WalletAddress>,
+    /// First unused index of each chain, where the next address comes from.
+    pub next_index: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WalletAddress {
+    pub address: String,
+    /// Position of the chain in the descriptor, receive (0) or change (1) for
+    /// a bare extended key.
+    pub chain: u32,
+    pub index: u32,
+    pub value: u64,
+    /// Unspent outputs, as `/address/{address}` reports them.
+    pub outputs: Vec<AddressTxOut>,
+}